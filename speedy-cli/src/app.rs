@@ -0,0 +1,190 @@
+// ========================= Program Lookup =========================
+
+use std::collections::HashSet; // PATH often lists a folder twice
+use std::env; // For PATH and PATHEXT
+use std::fmt; // For describing where a program was found
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // Nothing found is reported through the exit status
+
+use crate::SpeedyError;
+use crate::filters;
+
+// Where a program was found
+#[derive(Debug)]
+enum Source {
+    Path,                   // A folder listed in PATH
+    #[cfg_attr(not(windows), allow(dead_code))]
+    AppPaths(&'static str), // The App Paths registry key of this hive (Windows)
+}
+
+impl fmt::Display for Source {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Source::Path => write!(f, "PATH"),
+            Source::AppPaths(hive) => write!(f, "App Paths, {}", hive),
+        }
+    }
+}
+
+// `speedy search:app <name> [--all]`: find the program a shell would start for <name>,
+// looking through PATH (with PATHEXT on Windows) and then the App Paths registry key,
+// and print where it really lives once links are followed
+pub fn search_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let Some(name) = args.first().filter(|n| !n.is_empty()) else {
+        return Err(SpeedyError::Argument("Usage: speedy search:app <name> [--all]".to_string()));
+    };
+    if name.contains(['/', '\\']) {
+        return Err(SpeedyError::Argument(format!(
+            "search:app takes a program name, not a path: {}",
+            name
+        )));
+    }
+    let mut all = false;
+    for arg in &args[1..] {
+        match arg.as_str() {
+            "--all" => all = true,
+            other => return Err(SpeedyError::Argument(format!("Unknown argument: {}", other))),
+        }
+    }
+
+    let mut found = on_path(name);
+    found.extend(in_app_paths(name));
+    let Some((path, source)) = found.first() else {
+        let places = if cfg!(windows) { "on PATH or in App Paths" } else { "on PATH" };
+        println!("❌ No program named \"{}\" {}", name, places);
+        return Ok(ExitCode::FAILURE);
+    };
+
+    println!("🚀 \"{}\" starts {}", name, launch_target(path).display());
+    if launch_target(path) != *path {
+        println!("   via {} ({})", path.display(), source);
+    } else {
+        println!("   found through {}", source);
+    }
+    if all && found.len() > 1 {
+        println!("Also found (not used, shadowed by the one above):");
+        for (path, source) in &found[1..] {
+            let target = launch_target(path);
+            if target != *path {
+                println!("   {} → {}  ({})", path.display(), target.display(), source);
+            } else {
+                println!("   {}  ({})", path.display(), source);
+            }
+        }
+    } else if found.len() > 1 {
+        println!("   ({} more match(es) further down; --all lists them)", found.len() - 1);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// The file a link points to in the end; the path itself if it isn't a link
+fn launch_target(path: &Path) -> PathBuf {
+    path.canonicalize()
+        .map(|target| crate::longpath::for_display(&target))
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+// Names the shell tries for `name`: on Windows the name itself if it has an extension,
+// then the name with each PATHEXT extension
+fn candidate_names(name: &str) -> Vec<String> {
+    if !cfg!(windows) {
+        return vec![name.to_string()];
+    }
+    let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let mut names = Vec::new();
+    if Path::new(name).extension().is_some() {
+        names.push(name.to_string());
+    }
+    names.extend(
+        pathext
+            .split(';')
+            .filter(|ext| !ext.is_empty())
+            .map(|ext| format!("{}{}", name, ext.to_lowercase())),
+    );
+    names
+}
+
+// Executables named like `name` in the folders of PATH, in PATH order
+fn on_path(name: &str) -> Vec<(PathBuf, Source)> {
+    let Some(path) = env::var_os("PATH") else {
+        return Vec::new();
+    };
+    let names = candidate_names(name);
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for dir in env::split_paths(&path).filter(|dir| !dir.as_os_str().is_empty()) {
+        if !seen.insert(dir.clone()) {
+            continue;
+        }
+        for candidate in &names {
+            let file = dir.join(candidate);
+            if file.is_file() && filters::is_executable(&file) {
+                found.push((file, Source::Path));
+            }
+        }
+    }
+    found
+}
+
+// ========================= App Paths (Windows) =========================
+
+// Programs registered under App Paths, which the Run dialog, Start and ShellExecute use
+// for names that aren't on PATH (chrome.exe, excel.exe, ...). The current user's key is
+// read before the machine-wide one.
+#[cfg(windows)]
+fn in_app_paths(name: &str) -> Vec<(PathBuf, Source)> {
+    use windows_sys::Win32::System::Registry::{HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE};
+
+    // App Paths keys are named after the file, with its extension
+    let key_name = match Path::new(name).extension() {
+        Some(_) => name.to_string(),
+        None => format!("{}.exe", name),
+    };
+    let key = format!("SOFTWARE\\Microsoft\\Windows\\CurrentVersion\\App Paths\\{}", key_name);
+    [(HKEY_CURRENT_USER, "HKCU"), (HKEY_LOCAL_MACHINE, "HKLM")]
+        .into_iter()
+        .filter_map(|(hive, hive_name)| {
+            // The default value holds the program's full path, sometimes in quotes
+            let value = registry_string(hive, &key)?;
+            let path = PathBuf::from(value.trim().trim_matches('"'));
+            path.is_file().then_some((path, Source::AppPaths(hive_name)))
+        })
+        .collect()
+}
+
+#[cfg(not(windows))]
+fn in_app_paths(_name: &str) -> Vec<(PathBuf, Source)> {
+    Vec::new()
+}
+
+// Default value of a registry key as a string, with %VARIABLES% expanded
+#[cfg(windows)]
+fn registry_string(hive: windows_sys::Win32::System::Registry::HKEY, key: &str) -> Option<String> {
+    use windows_sys::Win32::Foundation::{ERROR_MORE_DATA, ERROR_SUCCESS};
+    use windows_sys::Win32::System::Registry::{RRF_RT_REG_EXPAND_SZ, RRF_RT_REG_SZ, RegGetValueW};
+
+    let key: Vec<u16> = key.encode_utf16().chain(std::iter::once(0)).collect();
+    let mut buffer = vec![0u16; 512];
+    loop {
+        let mut size = (buffer.len() * 2) as u32;
+        let status = unsafe {
+            RegGetValueW(
+                hive,
+                key.as_ptr(),
+                std::ptr::null(), // The key's default value
+                RRF_RT_REG_SZ | RRF_RT_REG_EXPAND_SZ,
+                std::ptr::null_mut(),
+                buffer.as_mut_ptr().cast(),
+                &mut size,
+            )
+        };
+        match status {
+            ERROR_SUCCESS => {
+                let len = buffer.iter().position(|&c| c == 0).unwrap_or(buffer.len());
+                return Some(String::from_utf16_lossy(&buffer[..len]));
+            }
+            ERROR_MORE_DATA => buffer.resize(size as usize / 2 + 1, 0),
+            _ => return None,
+        }
+    }
+}
@@ -0,0 +1,361 @@
+// ========================= Traversal Backends =========================
+
+use std::collections::VecDeque; // Directories waiting to be listed in breadth-first order
+use std::fmt; // For displaying backend names
+use std::iter; // For the breadth-first iterator
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::sync::Arc; // The visited set is shared by all walks
+
+use walkdir::{DirEntry, WalkDir}; // Portable directory traversal
+
+use crate::identity::{self, VisitedDirs};
+use crate::index;
+use crate::{SearchOptions, SpeedyError};
+use crate::longpath;
+use crate::schedule::ScheduledWalk;
+use crate::tuning;
+
+// A failed directory read, with a description of how to walk it again if it's transient
+pub type WalkFailure = (walkdir::Error, Option<Box<ScheduledWalk>>);
+
+// One result of a walk: an entry with its depth below the search root, or a failure
+pub type WalkItem = Result<(DirEntry, usize), Box<WalkFailure>>;
+
+// Ways of enumerating a directory tree. Each scheduled walk picks one for the volume it
+// starts on, so a single --global search can mix fast and fallback strategies.
+// Further strategies are added as new variants.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Backend {
+    WalkDir,       // Portable recursive directory listing; works everywhere
+    Mft,           // Reading the NTFS master file table directly (not implemented yet)
+    Everything,    // Asking a running Everything (voidtools) instance; --backend everything
+    WindowsSearch, // Asking the Windows Search indexer; --backend windows-search
+}
+
+impl fmt::Display for Backend {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Backend::WalkDir => write!(f, "directory walk"),
+            Backend::Mft => write!(f, "NTFS MFT reader"),
+            Backend::Everything => write!(f, "Everything index"),
+            Backend::WindowsSearch => write!(f, "Windows Search index"),
+        }
+    }
+}
+
+impl Backend {
+    // `--backend <name>`; None for "auto", which picks per volume
+    pub fn parse(value: &str) -> Result<Option<Backend>, SpeedyError> {
+        match value.to_lowercase().as_str() {
+            "auto" => Ok(None),
+            "walk" => Ok(Some(Backend::WalkDir)),
+            "everything" => Ok(Some(Backend::Everything)),
+            "windows-search" => Ok(Some(Backend::WindowsSearch)),
+            _ => Err(SpeedyError::Argument(format!(
+                "Unknown backend '{}' (use auto, walk, everything or windows-search)",
+                value
+            ))),
+        }
+    }
+
+    // The fastest strategy for a file system, whether or not it's available
+    fn preferred(filesystem: Option<&str>) -> Backend {
+        match filesystem {
+            Some("ntfs") if cfg!(windows) => Backend::Mft,
+            _ => Backend::WalkDir,
+        }
+    }
+
+    // Whether this build can use the backend. Index backends also need the indexer to be
+    // running, which is only found out when it's queried.
+    pub fn is_available(self) -> bool {
+        match self {
+            Backend::WalkDir => true,
+            Backend::Mft => false,
+            Backend::Everything | Backend::WindowsSearch => cfg!(windows),
+        }
+    }
+
+    // Pick the backend for a walk starting at `dir`: the one chosen with --backend if
+    // given, otherwise the fastest for its file system; the directory walk is the fallback
+    pub fn for_dir(dir: &Path, chosen: Option<Backend>) -> Backend {
+        let filesystem = tuning::detect_filesystem(dir);
+        let preferred = chosen.unwrap_or_else(|| Backend::preferred(filesystem.as_deref()));
+        let backend = if preferred.is_available() {
+            preferred
+        } else {
+            Backend::WalkDir
+        };
+        log::debug!(
+            "Using {} for {} (file system: {}{})",
+            backend,
+            longpath::for_display(dir).display(),
+            filesystem.as_deref().unwrap_or("unknown"),
+            if backend != preferred {
+                format!(", {} not available", preferred)
+            } else {
+                String::new()
+            }
+        );
+        backend
+    }
+
+    // Enumerate the entries of a scheduled walk
+    pub fn walk<'a>(
+        self,
+        walk: ScheduledWalk,
+        options: &'a SearchOptions,
+        visited: Arc<VisitedDirs>,
+    ) -> Box<dyn Iterator<Item = WalkItem> + Send + 'a> {
+        let query = match self {
+            // for_dir never selects an unavailable backend; the directory walk is the fallback
+            Backend::WalkDir | Backend::Mft => return Box::new(walk_dir(walk, options, visited)),
+            Backend::Everything => index::query_everything,
+            Backend::WindowsSearch => index::query_windows_search,
+        };
+
+        // Indexes store names as they are, so accent-insensitive matching needs a real walk
+        if options.ignore_accents {
+            log::info!("{} can't match names ignoring accents, walking instead", self);
+            return Box::new(walk_dir(walk, options, visited));
+        }
+        match query(&walk.dir, &options.target) {
+            Ok(mut paths) => {
+                log::info!("{} answered with {} candidate(s)", self, paths.len());
+                if options.first {
+                    paths.sort(); // Indexes answer in no particular order
+                }
+                if options.strategy == Strategy::BreadthFirst {
+                    paths.sort_by_key(|p| p.components().count());
+                }
+                Box::new(indexed_entries(walk, paths, options))
+            }
+            Err(e) => {
+                log::warn!("{} is not available ({}), walking the folder instead", self, e);
+                Box::new(walk_dir(walk, options, visited))
+            }
+        }
+    }
+}
+
+// Order in which a directory walk visits entries (--strategy)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Strategy {
+    #[default]
+    DepthFirst,   // Finish each folder's subtree before its next sibling
+    BreadthFirst, // Every entry at one depth before any entry below it
+}
+
+impl fmt::Display for Strategy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Strategy::DepthFirst => write!(f, "depth-first"),
+            Strategy::BreadthFirst => write!(f, "breadth-first"),
+        }
+    }
+}
+
+impl Strategy {
+    // `--strategy <name>`
+    pub fn parse(value: &str) -> Result<Strategy, SpeedyError> {
+        match value.to_lowercase().as_str() {
+            "dfs" | "depth" => Ok(Strategy::DepthFirst),
+            "bfs" | "breadth" => Ok(Strategy::BreadthFirst),
+            _ => Err(SpeedyError::Argument(format!(
+                "Unknown strategy '{}' (use bfs or dfs)",
+                value
+            ))),
+        }
+    }
+}
+
+// Entries for paths reported by an index, limited to what a walk of the same scheduled
+// directory would have reached. Paths that no longer exist (a stale index) are dropped.
+fn indexed_entries<'a>(
+    walk: ScheduledWalk,
+    paths: Vec<PathBuf>,
+    options: &'a SearchOptions,
+) -> impl Iterator<Item = WalkItem> + Send + 'a {
+    paths.into_iter().filter_map(move |path| {
+        let relative = path.strip_prefix(&walk.dir).ok()?;
+        let depth = relative.components().count();
+        if depth > walk.max_depth || walk.exclude.iter().any(|x| path.starts_with(x)) {
+            return None;
+        }
+        // Folders between the walk's start and the entry must not be skipped by the rules
+        let skipped = path
+            .ancestors()
+            .skip(1)
+            .take_while(|a| a.starts_with(&walk.dir) && *a != walk.dir)
+            .any(|a| options.skip.should_skip(a));
+        if skipped {
+            return None;
+        }
+
+        match WalkDir::new(&path).max_depth(0).into_iter().next()? {
+            Ok(entry) => Some(Ok((entry, walk.base_depth + depth))),
+            Err(e) => {
+                log::debug!("Dropping stale index entry {}: {}", longpath::for_display(&path).display(), e);
+                None
+            }
+        }
+    })
+}
+
+// The WalkDir backend, in the order chosen with --strategy
+fn walk_dir<'a>(
+    walk: ScheduledWalk,
+    options: &'a SearchOptions,
+    visited: Arc<VisitedDirs>,
+) -> Box<dyn Iterator<Item = WalkItem> + Send + 'a> {
+    match options.strategy {
+        Strategy::DepthFirst => Box::new(depth_first(walk, options, visited)),
+        Strategy::BreadthFirst => Box::new(breadth_first(walk, options, visited)),
+    }
+}
+
+// Depth-first: a single recursive WalkDir that prunes skipped, excluded and already
+// visited directories and pairs each entry with its depth below the search root
+fn depth_first<'a>(
+    walk: ScheduledWalk,
+    options: &'a SearchOptions,
+    visited: Arc<VisitedDirs>,
+) -> impl Iterator<Item = WalkItem> + Send + 'a {
+    let ScheduledWalk { dir, base_depth, max_depth, exclude, attempt } = walk;
+    let retry_exclude = exclude.clone();
+    sorted_if_first(WalkDir::new(dir), options)
+        .max_depth(max_depth)
+        .follow_links(options.follow_links)
+        .same_file_system(options.one_file_system)
+        .into_iter()
+        .filter_entry(move |e| {
+            // A retried directory was already marked visited by the failed attempt
+            let retry_root = attempt > 0 && e.depth() == 0;
+            keep_entry(e, options, &exclude, &visited, retry_root)
+        })
+        .map(move |e| match e {
+            Ok(entry) => {
+                let depth = base_depth + entry.depth();
+                Ok((entry, depth))
+            }
+            Err(err) => {
+                let depth = err.depth();
+                let retry = retry_walk(&err, base_depth + depth, max_depth - depth, &retry_exclude, attempt);
+                Err(Box::new((err, retry)))
+            }
+        })
+}
+
+// Breadth-first: list one directory at a time and queue its subdirectories, so shallow
+// matches are reached before the walk disappears into a deep subtree
+fn breadth_first<'a>(
+    walk: ScheduledWalk,
+    options: &'a SearchOptions,
+    visited: Arc<VisitedDirs>,
+) -> impl Iterator<Item = WalkItem> + Send + 'a {
+    let ScheduledWalk { dir, base_depth, max_depth, exclude, attempt } = walk;
+    let list = move |dir: &Path, depth: usize| {
+        sorted_if_first(WalkDir::new(dir), options).max_depth(depth).follow_links(options.follow_links).into_iter()
+    };
+
+    let mut queue: VecDeque<(PathBuf, usize)> = VecDeque::new(); // (folder, depth below `dir`)
+    let mut listing = Some((list(&dir, max_depth.min(1)), 0));
+    let mut root_device = None; // For --one-file-system
+    iter::from_fn(move || {
+        loop {
+            let Some((entries, offset)) = listing.as_mut() else {
+                let (next, depth) = queue.pop_front()?;
+                listing = Some((list(&next, 1), depth));
+                continue;
+            };
+            let offset = *offset;
+            let entry = match entries.next() {
+                None => {
+                    listing = None;
+                    continue;
+                }
+                Some(Ok(entry)) => entry,
+                Some(Err(err)) => {
+                    let depth = offset + err.depth();
+                    let retry = retry_walk(&err, base_depth + depth, max_depth - depth, &exclude, attempt);
+                    return Some(Err(Box::new((err, retry))));
+                }
+            };
+
+            // Each listing starts with the folder itself, already produced by its parent
+            if offset > 0 && entry.depth() == 0 {
+                continue;
+            }
+            let depth = offset + entry.depth();
+            let retry_root = attempt > 0 && depth == 0;
+            if !keep_entry(&entry, options, &exclude, &visited, retry_root) {
+                continue;
+            }
+            if depth == 0 && options.one_file_system {
+                root_device = identity::file_id(&entry).map(|(device, _)| device);
+            }
+            if depth > 0 && depth < max_depth && entry.file_type().is_dir() {
+                let same_device = root_device
+                    .is_none_or(|root| identity::file_id(&entry).is_none_or(|(device, _)| device == root));
+                if same_device {
+                    queue.push_back((entry.path().to_path_buf(), depth));
+                }
+            }
+            return Some(Ok((entry, base_depth + depth)));
+        }
+    })
+}
+
+// --first needs the same order on every run, so folders are then read sorted by name
+// rather than in the order the file system lists them
+fn sorted_if_first(walk: WalkDir, options: &SearchOptions) -> WalkDir {
+    if options.first { walk.sort_by_file_name() } else { walk }
+}
+
+// Whether a walk produces an entry and, for a folder, descends into it
+fn keep_entry(
+    e: &DirEntry,
+    options: &SearchOptions,
+    exclude: &[PathBuf],
+    visited: &VisitedDirs,
+    retry_root: bool,
+) -> bool {
+    if options.skip.should_skip(e.path()) {
+        log::debug!(
+            "Skipping directory by skip rules: {}",
+            longpath::for_display(e.path()).display()
+        );
+        return false;
+    }
+    if exclude.iter().any(|x| x == e.path()) {
+        return false; // Walked separately by an earlier scheduled walk
+    }
+    // Skip directories reached again through a bind mount or junction
+    if !retry_root && !visited.first_visit(e) {
+        log::info!(
+            "Skipping already visited directory: {}",
+            longpath::for_display(e.path()).display()
+        );
+        return false;
+    }
+    true
+}
+
+// Describe how to walk a failed path again if the error turns out to be transient
+fn retry_walk(
+    err: &walkdir::Error,
+    base_depth: usize,
+    max_depth: usize,
+    exclude: &[PathBuf],
+    attempt: u32,
+) -> Option<Box<ScheduledWalk>> {
+    err.path().map(|path| {
+        Box::new(ScheduledWalk {
+            dir: path.to_path_buf(),
+            base_depth,
+            max_depth,
+            exclude: exclude.to_vec(),
+            attempt: attempt + 1,
+        })
+    })
+}
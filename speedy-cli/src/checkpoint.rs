@@ -0,0 +1,172 @@
+// ========================= Checkpoints =========================
+
+use std::fs; // For reading and writing checkpoint files
+use std::io; // For I/O errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::sync::Mutex; // The tracker is updated from whichever worker drives the walk
+use std::time::{Duration, Instant}; // For periodic saves
+
+use walkdir::DirEntry; // Entries produced by the directory walker
+
+use crate::SpeedyError;
+
+// How often the checkpoint file is rewritten during a search
+const SAVE_INTERVAL: Duration = Duration::from_secs(10);
+
+// First line of a checkpoint file, to reject unrelated files
+const HEADER: &str = "speedy-checkpoint 1";
+
+// Which subtrees of the search root have been fully walked so far. Directories arrive in
+// depth-first order, so a directory is complete once the walk moves past it; only the
+// outermost completed directories are kept, which keeps the list short.
+#[derive(Debug, Default)]
+struct Tracker {
+    open: Vec<(PathBuf, bool)>, // Directories being walked, and whether nothing in them failed
+    completed: Vec<PathBuf>,    // Fully walked subtrees
+}
+
+impl Tracker {
+    // Close directories the walk has moved past on its way to `path`
+    fn close_until(&mut self, path: &Path) {
+        while let Some((dir, _)) = self.open.last() {
+            if path.starts_with(dir) {
+                break;
+            }
+            let Some((dir, ok)) = self.open.pop() else {
+                break;
+            };
+            if ok {
+                self.completed.retain(|c| !c.starts_with(&dir));
+                self.completed.push(dir);
+            } else if let Some(parent) = self.open.last_mut() {
+                parent.1 = false; // A missing subtree leaves its parent incomplete too
+            }
+        }
+    }
+}
+
+// Persists search progress to a file so an interrupted search can be resumed
+#[derive(Debug)]
+pub struct Checkpoint {
+    file: PathBuf,
+    root: PathBuf,
+    tracker: Mutex<Tracker>,
+    last_save: Mutex<Instant>,
+}
+
+impl Checkpoint {
+    // Start tracking a search of `root`, continuing from `completed` when resuming
+    pub fn new(file: &Path, root: &Path, completed: Vec<PathBuf>) -> Self {
+        Checkpoint {
+            file: file.to_path_buf(),
+            root: root.to_path_buf(),
+            tracker: Mutex::new(Tracker { open: Vec::new(), completed }),
+            last_save: Mutex::new(Instant::now()),
+        }
+    }
+
+    // Note an entry handed out by the walker
+    pub fn entered(&self, entry: &DirEntry) {
+        let Ok(mut tracker) = self.tracker.lock() else {
+            return;
+        };
+        tracker.close_until(entry.path());
+        if entry.file_type().is_dir() {
+            tracker.open.push((entry.path().to_path_buf(), true));
+        }
+    }
+
+    // Note a directory that could not be walked completely (e.g. queued for a retry)
+    pub fn failed(&self, path: &Path) {
+        let Ok(mut tracker) = self.tracker.lock() else {
+            return;
+        };
+        for (dir, ok) in tracker.open.iter_mut() {
+            if path.starts_with(dir.as_path()) {
+                *ok = false;
+            }
+        }
+    }
+
+    // Write the checkpoint if the last save is older than SAVE_INTERVAL
+    pub fn save_if_due(&self) -> io::Result<()> {
+        {
+            let Ok(mut last_save) = self.last_save.lock() else {
+                return Ok(());
+            };
+            if last_save.elapsed() < SAVE_INTERVAL {
+                return Ok(());
+            }
+            *last_save = Instant::now();
+        }
+        self.save()
+    }
+
+    // Write the current state to the checkpoint file
+    pub fn save(&self) -> io::Result<()> {
+        let completed = match self.tracker.lock() {
+            Ok(tracker) => tracker.completed.clone(),
+            Err(_) => return Ok(()),
+        };
+        let mut contents = format!("{}\nroot\t{}\n", HEADER, self.root.display());
+        for dir in &completed {
+            contents.push_str(&format!("done\t{}\n", dir.display()));
+        }
+
+        // Write to a temporary file first so a crash mid-write keeps the previous checkpoint
+        let temp = self.file.with_extension("tmp");
+        fs::write(&temp, contents)?;
+        fs::rename(&temp, &self.file)
+    }
+
+    // Finish the search: a completed search no longer needs its checkpoint, an interrupted
+    // one keeps the final state for --resume
+    pub fn finish(&self, interrupted: bool) -> io::Result<()> {
+        if interrupted {
+            return self.save();
+        }
+        match fs::remove_file(&self.file) {
+            Err(e) if e.kind() != io::ErrorKind::NotFound => Err(e),
+            _ => Ok(()),
+        }
+    }
+
+    // Where the checkpoint is written
+    pub fn file(&self) -> &Path {
+        &self.file
+    }
+
+    // Number of fully searched subtrees recorded so far
+    pub fn completed_count(&self) -> usize {
+        self.tracker.lock().map(|t| t.completed.len()).unwrap_or(0)
+    }
+}
+
+// Subtrees already searched according to a checkpoint file written for `root`
+pub fn load(file: &Path, root: &Path) -> Result<Vec<PathBuf>, SpeedyError> {
+    let contents = fs::read_to_string(file)?;
+    let mut lines = contents.lines();
+    if lines.next() != Some(HEADER) {
+        return Err(SpeedyError::Argument(format!(
+            "{} is not a Speedy checkpoint file",
+            file.display()
+        )));
+    }
+
+    let mut completed = Vec::new();
+    for line in lines {
+        match line.split_once('\t') {
+            Some(("root", saved)) if Path::new(saved) != root => {
+                return Err(SpeedyError::Argument(format!(
+                    "Checkpoint {} was made for a search of {}, not {}",
+                    file.display(),
+                    saved,
+                    root.display()
+                )));
+            }
+            Some(("done", dir)) => completed.push(PathBuf::from(dir)),
+            _ => {}
+        }
+    }
+    Ok(completed)
+}
@@ -0,0 +1,119 @@
+// ========================= Cleanup Actions =========================
+
+use std::fs; // For moving entries
+use std::io::{self, BufRead, IsTerminal, Write}; // For the confirmation prompt
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+
+use crate::{i18n, longpath};
+
+// Matches listed in the confirmation prompt before the rest are summarized
+const PREVIEW_LIMIT: usize = 20;
+
+// What to do with every match once the search is done
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CleanupAction {
+    Trash,           // --delete: move to the recycle bin / trash, never delete permanently
+    MoveTo(PathBuf), // --move-to <dir>
+}
+
+impl CleanupAction {
+    // Question asked before acting, e.g. "Move 3 item(s) to the trash?"
+    fn question(&self, count: usize) -> String {
+        match self {
+            CleanupAction::Trash => i18n::format("cleanup_trash_question", &[&count]),
+            CleanupAction::MoveTo(dir) => {
+                i18n::format("cleanup_move_question", &[&count, &longpath::for_display(dir).display()])
+            }
+        }
+    }
+}
+
+// Drop matches inside other matched folders: acting on the folder already covers them
+pub fn outermost(paths: &[PathBuf]) -> Vec<PathBuf> {
+    let mut sorted = paths.to_vec();
+    sorted.sort();
+    sorted.dedup();
+    let mut kept: Vec<PathBuf> = Vec::new();
+    for path in sorted {
+        if !kept.iter().any(|k| path.starts_with(k)) {
+            kept.push(path);
+        }
+    }
+    kept
+}
+
+// List what is about to happen and ask for a yes. Without a terminal to ask on, only
+// --yes allows the action.
+pub fn confirm(
+    action: &CleanupAction,
+    paths: &[PathBuf],
+    shown: impl Fn(&Path) -> PathBuf,
+) -> io::Result<bool> {
+    println!();
+    for path in paths.iter().take(PREVIEW_LIMIT) {
+        println!("   {}", shown(path).display());
+    }
+    if paths.len() > PREVIEW_LIMIT {
+        println!("{}", i18n::format("cleanup_more", &[&(paths.len() - PREVIEW_LIMIT)]));
+    }
+
+    if !io::stdin().is_terminal() {
+        let question = action.question(paths.len());
+        println!("{}", i18n::format("cleanup_unconfirmed", &[&question]));
+        return Ok(false);
+    }
+    print!("{}", i18n::format("cleanup_prompt", &[&action.question(paths.len())]));
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    // English answers are understood whatever the language of the prompt
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes" | "s" | "si" | "sí"))
+}
+
+// Apply the action to every path, returning how many succeeded and the failures
+pub fn apply(action: &CleanupAction, paths: &[PathBuf]) -> (usize, Vec<(PathBuf, String)>) {
+    let mut done = 0;
+    let mut failures = Vec::new();
+    for path in paths {
+        let result = match action {
+            // The trash integrations don't understand extended-length paths
+            CleanupAction::Trash => {
+                trash::delete(longpath::for_display(path)).map_err(|e| e.to_string())
+            }
+            CleanupAction::MoveTo(dir) => move_into(path, dir).map_err(|e| e.to_string()),
+        };
+        match result {
+            Ok(()) => done += 1,
+            Err(e) => failures.push((path.clone(), e)),
+        }
+    }
+    (done, failures)
+}
+
+// Move an entry into `dir`, picking "name (1).ext" etc. if the name is taken
+fn move_into(path: &Path, dir: &Path) -> io::Result<()> {
+    fs::create_dir_all(dir)?;
+    let name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let mut target = dir.join(name);
+    let mut n = 1;
+    while target.symlink_metadata().is_ok() {
+        let stem = Path::new(name).file_stem().unwrap_or(name).to_string_lossy();
+        let extension = Path::new(name)
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy()))
+            .unwrap_or_default();
+        target = dir.join(format!("{} ({}){}", stem, n, extension));
+        n += 1;
+    }
+
+    match fs::rename(path, &target) {
+        // A rename can't cross file systems; files are copied instead, folders are refused
+        Err(e) if e.kind() == io::ErrorKind::CrossesDevices && path.is_file() => {
+            fs::copy(path, &target)?;
+            fs::remove_file(path)
+        }
+        result => result,
+    }
+}
@@ -0,0 +1,31 @@
+// ========================= Clipboard =========================
+
+use crate::SpeedyError;
+
+// On X11/Wayland the clipboard is served by the owning process, so after setting it we
+// wait briefly for a clipboard manager to take over the contents before exiting
+#[cfg(all(unix, not(target_os = "macos")))]
+const HOLD_AFTER_COPY: std::time::Duration = std::time::Duration::from_secs(2);
+
+// Place text on the system clipboard
+pub fn copy_text(text: &str) -> Result<(), SpeedyError> {
+    let mut clipboard =
+        arboard::Clipboard::new().map_err(|e| SpeedyError::Clipboard(e.to_string()))?;
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use arboard::SetExtLinux;
+        clipboard
+            .set()
+            .wait_until(std::time::Instant::now() + HOLD_AFTER_COPY)
+            .text(text)
+            .map_err(|e| SpeedyError::Clipboard(e.to_string()))
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        clipboard
+            .set_text(text)
+            .map_err(|e| SpeedyError::Clipboard(e.to_string()))
+    }
+}
@@ -0,0 +1,511 @@
+// ========================= Content Search =========================
+
+use std::collections::VecDeque; // Lines kept for --context before a match
+use std::env; // For NO_COLOR
+use std::fs::File; // For reading files
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write}; // For reading lines and output
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // Nothing found is reported through the exit status
+use std::time::Instant; // For timing the run
+
+use flate2::read::MultiGzDecoder; // --search-compressed: .gz
+use memchr::memmem; // SIMD substring search
+use memmap2::Mmap; // Large files are searched without copying them
+use rayon::prelude::*; // Files, and chunks of large files, are searched in parallel
+use regex::bytes::{Regex, RegexBuilder}; // Case-insensitive search
+use walkdir::WalkDir; // For listing the files to search
+use xz2::read::XzDecoder; // --search-compressed: .xz
+use zstd::stream::read::Decoder as ZstdDecoder; // --search-compressed: .zst
+
+use crate::SpeedyError;
+use crate::environment;
+use crate::longpath;
+use crate::profile::Profile;
+use crate::tuning;
+
+// Bytes looked at for a NUL byte when deciding whether a file is binary
+const SNIFF_LEN: usize = 8 * 1024;
+
+// Extensions of executables, archives and media, treated as binary without reading them
+const BINARY_EXTENSIONS: &[&str] = &[
+    "exe", "dll", "so", "dylib", "o", "obj", "a", "lib", "class", "pyc", "wasm", "bin", "iso", "img",
+    "zip", "7z", "rar", "tar", "gz", "bz2", "xz", "zst", "jar", "apk", "msi", "deb", "rpm",
+    "png", "jpg", "jpeg", "gif", "bmp", "ico", "webp", "tif", "tiff", "psd",
+    "mp3", "wav", "flac", "ogg", "m4a", "mp4", "mkv", "avi", "mov", "webm",
+    "pdf", "doc", "xls", "ppt", "docx", "xlsx", "pptx", "odt", "ttf", "otf", "woff", "woff2",
+    "db", "sqlite",
+];
+
+// Files at least this large are memory-mapped and searched in parallel chunks
+const MMAP_MIN_LEN: u64 = 16 * 1024 * 1024;
+
+// Size of the chunks a mapped file is split into (extended to the end of a line)
+const CHUNK_LEN: usize = 4 * 1024 * 1024;
+
+// Highlighting for the matched text on a terminal
+const HIGHLIGHT_START: &str = "\x1b[1;31m";
+const HIGHLIGHT_END: &str = "\x1b[0m";
+
+// What to do with binary files
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum BinaryMode {
+    Skip,   // Default: leave them out
+    Search, // --binary: search them, but only say whether they match
+    Text,   // --text: no detection; every file is read as text
+}
+
+// Options for `speedy search:content`
+#[derive(Debug)]
+struct ContentArgs {
+    needle: String,         // Text to look for
+    matcher: Matcher,       // Finds the needle, honouring --ignore-case
+    root: PathBuf,          // --path; the current folder by default
+    max_depth: usize,       // --depth
+    threads: Option<usize>, // --threads; None picks a count for the volume
+    context: usize,         // --context <n>: lines shown before and after each match
+    binary: BinaryMode,     // --binary / --text
+    compressed: bool,       // --search-compressed: decompress .gz, .zst and .xz files
+    quiet: bool,            // --quiet: no summary line
+}
+
+// What searching one file gave
+#[derive(Debug)]
+enum Outcome {
+    Lines(Vec<Line>), // Matching lines with their context; empty when nothing matched
+    Binary(usize),    // A binary file searched with --binary: its number of matching lines
+    Skipped,          // A binary file left out
+}
+
+// Finds the needle: memchr's memmem, which uses SIMD where the CPU has it, for exact
+// text, and a byte regex (ASCII case folding only, as before) for --ignore-case
+#[derive(Debug)]
+enum Matcher {
+    Exact(Box<memmem::Finder<'static>>),
+    IgnoreCase(Regex),
+}
+
+impl Matcher {
+    fn new(needle: &str, ignore_case: bool) -> Result<Self, SpeedyError> {
+        if !ignore_case {
+            return Ok(Matcher::Exact(Box::new(memmem::Finder::new(needle.as_bytes()).into_owned())));
+        }
+        RegexBuilder::new(&regex::escape(needle))
+            .case_insensitive(true)
+            .unicode(false)
+            .build()
+            .map(Matcher::IgnoreCase)
+            .map_err(|e| SpeedyError::Parse(format!("Invalid search text: {}", e)))
+    }
+
+    // Byte range of the first match at or after `from`
+    fn find_at(&self, haystack: &[u8], from: usize) -> Option<(usize, usize)> {
+        match self {
+            Matcher::Exact(finder) => finder
+                .find(&haystack[from..])
+                .map(|i| (from + i, from + i + finder.needle().len())),
+            Matcher::IgnoreCase(regex) => regex.find_at(haystack, from).map(|m| (m.start(), m.end())),
+        }
+    }
+
+    // Byte ranges of every non-overlapping match
+    fn find_all(&self, haystack: &[u8]) -> Vec<(usize, usize)> {
+        let mut found = Vec::new();
+        let mut from = 0;
+        while from < haystack.len() {
+            let Some((start, end)) = self.find_at(haystack, from) else {
+                break;
+            };
+            found.push((start, end));
+            from = end.max(start + 1);
+        }
+        found
+    }
+}
+
+// A line printed for a file: a match, or context around one
+#[derive(Debug)]
+struct Line {
+    number: usize,                // 1-based line number
+    text: String,                 // Line without its line ending
+    matches: Vec<(usize, usize)>, // Byte ranges of the needle; empty for context lines
+}
+
+// `speedy search:content <text> [--path <dir>] [--context <n>] [-i]`: list the lines of
+// every file below --path that contain <text>. Binary files (a NUL byte near the start,
+// or a known binary extension) are skipped unless --binary or --text is given; with
+// --search-compressed, .gz, .zst and .xz files are searched in their decompressed form.
+pub fn search_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let args = parse_args(args)?;
+    let start = Instant::now();
+
+    let skip = Profile::default().skip_rules();
+    let root = longpath::to_extended(&args.root);
+    let files: Vec<PathBuf> = WalkDir::new(&root)
+        .max_depth(args.max_depth)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| !skip.should_skip(e.path()))
+        .filter_map(Result::ok)
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .collect();
+
+    let threads = args
+        .threads
+        .unwrap_or_else(|| tuning::thread_count(tuning::detect_volume_kind(&root)));
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("speedy-reader-{}", i))
+        .build()?;
+    let results: Vec<_> = pool.install(|| {
+        files.par_iter().map(|path| search_file(path, &args)).collect()
+    });
+
+    let color = io::stdout().is_terminal() && env::var_os("NO_COLOR").is_none();
+    let mut out = io::BufWriter::new(io::stdout().lock());
+    let (mut matched_files, mut matched_lines, mut skipped) = (0, 0, 0);
+    for (path, result) in files.iter().zip(results) {
+        let shown = longpath::for_display(path);
+        match result {
+            Ok(Outcome::Lines(lines)) if lines.is_empty() => {}
+            Ok(Outcome::Lines(lines)) => {
+                matched_files += 1;
+                matched_lines += lines.iter().filter(|l| !l.matches.is_empty()).count();
+                print_file(&mut out, &shown, &lines, color)?;
+            }
+            Ok(Outcome::Binary(0)) => {}
+            Ok(Outcome::Binary(count)) => {
+                matched_files += 1;
+                matched_lines += count;
+                writeln!(out, "{}", shown.display())?;
+                writeln!(out, "  (binary file: {} matching line(s))", count)?;
+            }
+            Ok(Outcome::Skipped) => skipped += 1,
+            Err(e) => log::info!("Could not read {}: {}", shown.display(), e),
+        }
+    }
+    out.flush()?;
+
+    if !args.quiet {
+        if matched_files == 0 {
+            println!("❌ No file contains \"{}\" ({} searched)", args.needle, files.len());
+        } else {
+            println!(
+                "✅ {} line(s) in {} of {} file(s) in {:.2?}",
+                matched_lines,
+                matched_files,
+                files.len(),
+                start.elapsed()
+            );
+        }
+        if skipped > 0 {
+            println!("ℹ️ {} binary file(s) skipped (--binary to search them)", skipped);
+        }
+    }
+    // Like grep: 1 when nothing matched
+    Ok(if matched_files > 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+fn parse_args(args: &[String]) -> Result<ContentArgs, SpeedyError> {
+    let Some(needle) = args.first().filter(|n| !n.is_empty()) else {
+        return Err(SpeedyError::Argument(
+            "Usage: speedy search:content <text> [--path <dir>] [--context <n>]".to_string(),
+        ));
+    };
+    let mut parsed = ContentArgs {
+        needle: needle.clone(),
+        matcher: Matcher::new("", false)?,
+        root: PathBuf::from("."),
+        max_depth: usize::MAX,
+        threads: None,
+        context: 0,
+        binary: BinaryMode::Skip,
+        compressed: false,
+        quiet: false,
+    };
+
+    let mut ignore_case = false;
+    let mut i = 1;
+    while i < args.len() {
+        let value = || {
+            args.get(i + 1)
+                .ok_or_else(|| SpeedyError::Argument(format!("Missing value after {}", args[i])))
+        };
+        let number = |what: &str| {
+            value()?
+                .parse::<usize>()
+                .map_err(|_| SpeedyError::Parse(format!("{} must be a number", what)))
+        };
+        match args[i].as_str() {
+            "--path" => {
+                parsed.root = crate::resolve_search_path(value()?)?;
+                i += 2;
+            }
+            "--depth" => {
+                parsed.max_depth = number("Depth")?;
+                i += 2;
+            }
+            "--threads" => {
+                parsed.threads = Some(number("Thread count")?);
+                i += 2;
+            }
+            "--context" | "-C" => {
+                parsed.context = number("Context")?;
+                i += 2;
+            }
+            "--ignore-case" | "-i" => {
+                ignore_case = true;
+                i += 1;
+            }
+            "--binary" => {
+                parsed.binary = BinaryMode::Search;
+                i += 1;
+            }
+            "--search-compressed" => {
+                parsed.compressed = true;
+                i += 1;
+            }
+            "--text" => {
+                parsed.binary = BinaryMode::Text;
+                i += 1;
+            }
+            "--quiet" => {
+                parsed.quiet = true;
+                i += 1;
+            }
+            other => {
+                return Err(SpeedyError::Argument(format!("Unknown argument: {}", other)));
+            }
+        }
+    }
+    parsed.matcher = Matcher::new(&parsed.needle, ignore_case)?;
+    // SPEEDY_THREADS only when --threads isn't given, so a bad value can't block the flag
+    if parsed.threads.is_none() {
+        parsed.threads = environment::threads()?;
+    }
+    Ok(parsed)
+}
+
+// Matching lines of one file with their context. Large files are memory-mapped and
+// scanned in parallel chunks; smaller ones, and files that can't be mapped, are read
+// line by line.
+fn search_file(path: &Path, args: &ContentArgs) -> io::Result<Outcome> {
+    if args.compressed
+        && let Some(decoder) = decompress(path)?
+    {
+        // Binary detection goes by the name without the compression extension
+        return search_buffered(&path.with_extension(""), decoder, args);
+    }
+    if args.binary == BinaryMode::Skip && has_binary_extension(path) {
+        return Ok(Outcome::Skipped);
+    }
+    let file = File::open(path)?;
+    if file.metadata()?.len() >= MMAP_MIN_LEN {
+        // SAFETY: the map is only read while this function runs. A file truncated by
+        // another process meanwhile can fault the read, a risk shared with every
+        // mmap-based search tool.
+        match unsafe { Mmap::map(&file) } {
+            Ok(map) => return Ok(search_mapped(path, &map, args)),
+            Err(e) => log::debug!("Could not map {}, reading it instead: {}", path.display(), e),
+        }
+    }
+    search_buffered(path, file, args)
+}
+
+// A reader for the decompressed content of a .gz, .zst or .xz file; None for other files.
+// Data is decompressed as it is read, so only a buffer's worth is held at a time.
+fn decompress(path: &Path) -> io::Result<Option<Box<dyn Read>>> {
+    let extension = path.extension().and_then(|e| e.to_str()).map(str::to_ascii_lowercase);
+    let decoder: Box<dyn Read> = match extension.as_deref() {
+        // Rotated logs are often several gzip members appended to one file
+        Some("gz") => Box::new(MultiGzDecoder::new(File::open(path)?)),
+        Some("zst") => Box::new(ZstdDecoder::new(File::open(path)?)?),
+        Some("xz") => Box::new(XzDecoder::new_multi_decoder(File::open(path)?)),
+        _ => return Ok(None),
+    };
+    Ok(Some(decoder))
+}
+
+// Whether the file should be treated as binary, from its extension and first bytes
+fn is_binary(path: &Path, head: &[u8], args: &ContentArgs) -> bool {
+    args.binary != BinaryMode::Text
+        && (has_binary_extension(path) || head.iter().take(SNIFF_LEN).any(|b| *b == 0))
+}
+
+// Read the file a line at a time; only the last --context lines are kept, in case the
+// next line matches
+fn search_buffered(path: &Path, input: impl Read, args: &ContentArgs) -> io::Result<Outcome> {
+    let mut reader = BufReader::with_capacity(SNIFF_LEN, input);
+    let binary = is_binary(path, reader.fill_buf()?, args);
+    if binary && args.binary == BinaryMode::Skip {
+        return Ok(Outcome::Skipped);
+    }
+    let mut before: VecDeque<Line> = VecDeque::with_capacity(args.context);
+    let mut after = 0; // Context lines still to print after the last match
+    let mut lines = Vec::new();
+    let mut buffer = Vec::new();
+    let mut number = 0;
+
+    loop {
+        buffer.clear();
+        if reader.read_until(b'\n', &mut buffer)? == 0 {
+            break;
+        }
+        number += 1;
+        let text = String::from_utf8_lossy(without_line_ending(&buffer));
+        let matches = args.matcher.find_all(text.as_bytes());
+        if !matches.is_empty() {
+            lines.extend(before.drain(..));
+            lines.push(Line { number, text: text.into_owned(), matches });
+            after = args.context;
+        } else if after > 0 {
+            lines.push(Line { number, text: text.into_owned(), matches });
+            after -= 1;
+        } else if args.context > 0 {
+            if before.len() == args.context {
+                before.pop_front();
+            }
+            before.push_back(Line { number, text: text.into_owned(), matches });
+        }
+    }
+    if binary {
+        return Ok(Outcome::Binary(lines.iter().filter(|l| !l.matches.is_empty()).count()));
+    }
+    Ok(Outcome::Lines(lines))
+}
+
+// Whether the extension names an executable, archive or media format
+fn has_binary_extension(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| BINARY_EXTENSIONS.iter().any(|b| b.eq_ignore_ascii_case(e)))
+}
+
+// Search a mapped file: chunks ending on a line boundary are scanned in parallel for
+// the lines that match, then context is taken from the map around each of them
+fn search_mapped(path: &Path, map: &[u8], args: &ContentArgs) -> Outcome {
+    let binary = is_binary(path, map, args);
+    if binary && args.binary == BinaryMode::Skip {
+        return Outcome::Skipped;
+    }
+
+    let mut chunks = Vec::new();
+    let mut start = 0;
+    while start < map.len() {
+        let end = (start + CHUNK_LEN).min(map.len());
+        let end = memchr::memchr(b'\n', &map[end..]).map_or(map.len(), |i| end + i + 1);
+        chunks.push((start, end));
+        start = end;
+    }
+    // Per chunk: (line start, line number within the chunk) of each matching line, and
+    // the number of lines in the chunk
+    let found: Vec<(Vec<(usize, usize)>, usize)> = chunks
+        .par_iter()
+        .map(|&(start, end)| {
+            let chunk = &map[start..end];
+            let mut hits = Vec::new();
+            let (mut counted_to, mut line) = (0, 0);
+            let mut from = 0;
+            while let Some((hit, _)) = args.matcher.find_at(chunk, from) {
+                let line_start = memchr::memrchr(b'\n', &chunk[..hit]).map_or(0, |i| i + 1);
+                line += memchr::memchr_iter(b'\n', &chunk[counted_to..line_start]).count();
+                counted_to = line_start;
+                hits.push((start + line_start, line));
+                // One entry per line: continue after its end
+                match memchr::memchr(b'\n', &chunk[hit..]) {
+                    Some(i) => from = hit + i + 1,
+                    None => break,
+                }
+            }
+            (hits, memchr::memchr_iter(b'\n', chunk).count())
+        })
+        .collect();
+
+    let mut matching = Vec::new(); // (line start, 1-based line number)
+    let mut lines_before = 0;
+    for (hits, count) in found {
+        matching.extend(hits.into_iter().map(|(at, line)| (at, lines_before + line + 1)));
+        lines_before += count;
+    }
+    if binary {
+        return Outcome::Binary(matching.len());
+    }
+
+    // (line start, line number, is a match) of every line to print
+    let mut wanted: Vec<(usize, usize, bool)> = Vec::new();
+    let mut matching = matching.into_iter().peekable();
+    while let Some((at, number)) = matching.next() {
+        // Up to --context lines before, leaving out those already listed
+        let printed = wanted.last().map_or(0, |w| w.1);
+        let mut before = Vec::new();
+        let mut start = at;
+        while before.len() < args.context && start > 0 && number - before.len() - 1 > printed {
+            start = memchr::memrchr(b'\n', &map[..start - 1]).map_or(0, |i| i + 1);
+            before.push((start, number - before.len() - 1, false));
+        }
+        wanted.extend(before.into_iter().rev());
+        wanted.push((at, number, true));
+
+        // Up to --context lines after, stopping at the next match
+        let next = matching.peek().map_or(usize::MAX, |m| m.1);
+        let (mut end, mut after) = (line_end(map, at), number);
+        while after < number + args.context && after + 1 < next && end < map.len() {
+            after += 1;
+            wanted.push((end, after, false));
+            end = line_end(map, end);
+        }
+    }
+    Outcome::Lines(
+        wanted
+            .into_iter()
+            .map(|(start, number, is_match)| {
+                let bytes = &map[start..line_end(map, start)];
+                let text = String::from_utf8_lossy(without_line_ending(bytes)).into_owned();
+                let matches = if is_match { args.matcher.find_all(text.as_bytes()) } else { Vec::new() };
+                Line { number, text, matches }
+            })
+            .collect(),
+    )
+}
+
+// A line without its trailing \n or \r\n
+fn without_line_ending(mut line: &[u8]) -> &[u8] {
+    while let [rest @ .., b'\n' | b'\r'] = line {
+        line = rest;
+    }
+    line
+}
+
+// Start of the line after the one starting at `start` (the end of the map for the last)
+fn line_end(map: &[u8], start: usize) -> usize {
+    memchr::memchr(b'\n', &map[start..]).map_or(map.len(), |i| start + i + 1)
+}
+
+// grep-style output: the file name, then "number: line" for matches and "number- line"
+// for context, with "--" where lines were left out
+fn print_file(out: &mut impl Write, path: &Path, lines: &[Line], color: bool) -> io::Result<()> {
+    writeln!(out, "{}", path.display())?;
+    let width = lines.last().map_or(1, |l| l.number.to_string().len());
+    let mut previous = None;
+    for line in lines {
+        if previous.is_some_and(|p| line.number > p + 1) {
+            writeln!(out, "  {:>width$}", "--", width = width)?;
+        }
+        previous = Some(line.number);
+
+        let separator = if line.matches.is_empty() { '-' } else { ':' };
+        write!(out, "  {:>width$}{} ", line.number, separator, width = width)?;
+        let mut shown = 0;
+        for &(start, end) in &line.matches {
+            let (before, matched) = (&line.text[shown..start], &line.text[start..end]);
+            if color {
+                write!(out, "{}{}{}{}", before, HIGHLIGHT_START, matched, HIGHLIGHT_END)?;
+            } else {
+                write!(out, "{}{}", before, matched)?;
+            }
+            shown = end;
+        }
+        writeln!(out, "{}", &line.text[shown..])?;
+    }
+    Ok(())
+}
@@ -0,0 +1,104 @@
+// ========================= Crash Reports =========================
+
+use std::backtrace::Backtrace; // Where the panic happened
+use std::env; // For the temporary directory and platform details
+use std::fmt::Write as _; // For building the report text
+use std::fs; // For writing the report
+use std::io; // For write errors
+use std::panic::{self, PanicHookInfo}; // For the panic hook
+use std::path::PathBuf; // For the report location
+use std::process; // The report is named after the process
+use std::sync::Mutex; // The last panic is handed from the hook to the main thread
+use std::thread; // For the panicking thread's name
+use std::time::{Duration, SystemTime, UNIX_EPOCH}; // For timestamps
+
+use crate::progress::ProgressUpdate;
+
+// Name of the thread that runs the traversal; its pool workers are "speedy-worker-N"
+pub const SEARCH_THREAD: &str = "speedy-search";
+
+// Description of the most recent panic in a search thread, written by the hook
+static LAST_PANIC: Mutex<Option<String>> = Mutex::new(None);
+
+// Replace the default panic message for search threads: the panic is recorded for the
+// diagnostics report instead, and the main thread tells the user where to find it.
+// Panics anywhere else keep the standard message.
+pub fn install_hook() {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        let thread = thread::current();
+        let name = thread.name().unwrap_or("<unnamed>");
+        if name != SEARCH_THREAD && !name.starts_with("speedy-worker-") {
+            default_hook(info);
+            return;
+        }
+        let report = describe(info, name);
+        if let Ok(mut last) = LAST_PANIC.lock() {
+            // The first panic is the cause; later ones are usually its consequences
+            last.get_or_insert(report);
+        }
+    }));
+}
+
+// Thread, message, location and backtrace of a panic
+fn describe(info: &PanicHookInfo, thread: &str) -> String {
+    let message = info
+        .payload()
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| info.payload().downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "(no message)".to_string());
+    let location = info
+        .location()
+        .map(|l| format!("{}:{}:{}", l.file(), l.line(), l.column()))
+        .unwrap_or_else(|| "unknown location".to_string());
+    format!(
+        "Thread:    {}\nPanic:     {}\nLocation:  {}\n\nBacktrace:\n{}\n",
+        thread,
+        message,
+        location,
+        Backtrace::force_capture()
+    )
+}
+
+// Write the diagnostics report for a crashed search to a temporary file and return its
+// path. `progress` is the last progress update received before the crash.
+pub fn write_report(
+    args: &[String],
+    progress: Option<ProgressUpdate>,
+    elapsed: Duration,
+) -> io::Result<PathBuf> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_secs()).unwrap_or(0);
+    let panic = LAST_PANIC
+        .lock()
+        .ok()
+        .and_then(|mut last| last.take())
+        .unwrap_or_else(|| "The search thread stopped without a panic message.\n".to_string());
+
+    let mut report = String::new();
+    let _ = writeln!(report, "Speedy diagnostics report");
+    let _ = writeln!(report, "=========================");
+    let _ = writeln!(report, "Version:   {}", env!("CARGO_PKG_VERSION"));
+    let _ = writeln!(report, "Time:      {} (seconds since 1970)", now);
+    let _ = writeln!(report, "OS:        {} {} ({})", env::consts::OS, env::consts::ARCH, env::consts::FAMILY);
+    let _ = writeln!(report, "Command:   {}", args.join(" "));
+    let _ = writeln!(report, "Elapsed:   {:.2?}", elapsed);
+    match progress {
+        Some(update) => {
+            let _ = writeln!(
+                report,
+                "Progress:  {} entries scanned, {} match(es) (at the last update)",
+                update.scanned, update.matches
+            );
+        }
+        None => {
+            let _ = writeln!(report, "Progress:  no update before the crash");
+        }
+    }
+    let _ = writeln!(report);
+    report.push_str(&panic);
+
+    let path = env::temp_dir().join(format!("speedy-crash-{}-{}.txt", process::id(), now));
+    fs::write(&path, report)?;
+    Ok(path)
+}
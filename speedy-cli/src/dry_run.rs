@@ -0,0 +1,122 @@
+// ========================= Dry Run =========================
+
+use std::fs; // For listing the root's top-level directories
+use std::path::Path; // For working with filesystem paths
+
+use crate::SearchOptions;
+use crate::backend::Backend;
+use crate::filters;
+use crate::i18n;
+use crate::longpath;
+use crate::profile::Profile;
+use crate::schedule;
+use crate::tuning::VolumeKind;
+
+// Print how a search would traverse `root` without walking the tree
+pub fn print_plan(
+    root: &Path,
+    options: &SearchOptions,
+    profile: Profile,
+    volume_kind: VolumeKind,
+    threads: usize,
+) {
+    println!("{}", i18n::text("dry_run"));
+    let kind = i18n::text(filters::describe_types(&options.types));
+    println!("{}", i18n::format("dry_run_looking_for", &[&kind, &options.target]));
+    println!("{}", i18n::format("dry_run_profile", &[&profile]));
+    println!("{}", i18n::format("dry_run_root", &[&longpath::for_display(root).display()]));
+    for extra in &options.extra_roots {
+        println!("                 {}", longpath::for_display(extra).display());
+    }
+    println!("{}", i18n::format("dry_run_volume", &[&volume_kind]));
+    match &options.io_limit {
+        Some(limit) => {
+            println!("{}", i18n::format("dry_run_threads_auto", &[&limit.limit(), &threads]))
+        }
+        None => println!("{}", i18n::format("dry_run_threads", &[&threads])),
+    }
+
+    let depth = |d: usize| {
+        if d == usize::MAX { i18n::text("dry_run_unlimited").to_string() } else { d.to_string() }
+    };
+    println!("{}", i18n::format("dry_run_max_depth", &[&depth(options.max_depth)]));
+    println!("{}", i18n::format("dry_run_strategy", &[&options.strategy]));
+    if options.first {
+        println!("{}", i18n::text("dry_run_first"));
+    }
+    if let Some(max) = options.max_matches {
+        println!("{}", i18n::format("dry_run_max_matches", &[&max]));
+    }
+    if options.min_depth > 0 {
+        println!("{}", i18n::format("dry_run_min_depth", &[&options.min_depth]));
+    }
+    for filter in &options.time_filters {
+        println!("{}", i18n::format("dry_run_time_filter", &[filter]));
+    }
+    if options.one_file_system {
+        println!("{}", i18n::text("dry_run_one_file_system"));
+    }
+    if options.low_priority {
+        println!("{}", i18n::text("dry_run_low_priority"));
+    }
+    if options.follow_links {
+        println!("{}", i18n::text("dry_run_follow_links"));
+    }
+
+    println!("{}", i18n::format("dry_run_skip", &[&options.skip.describe()]));
+    if options.skip.should_skip(root) {
+        println!("{}", i18n::text("dry_run_root_skipped"));
+    }
+
+    println!();
+    println!("{}", i18n::text("dry_run_walks"));
+    let walks = schedule::plan_walks(
+        root,
+        &options.extra_roots,
+        options.max_depth,
+        options.prioritize(),
+        &options.skip,
+    );
+    for (i, walk) in walks.iter().enumerate() {
+        let backend = Backend::for_dir(&walk.dir, options.backend);
+        let dir = longpath::for_display(&walk.dir);
+        let max_depth = depth(walk.max_depth);
+        println!("{}", i18n::format("dry_run_walk", &[&(i + 1), &dir.display(), &max_depth, &backend]));
+        for excluded in &walk.exclude {
+            let excluded = longpath::for_display(excluded);
+            println!("{}", i18n::format("dry_run_excludes", &[&excluded.display()]));
+        }
+    }
+
+    if options.max_depth == 0 {
+        return;
+    }
+
+    println!();
+    println!("{}", i18n::text("dry_run_top_level"));
+    let mut dirs: Vec<_> = match fs::read_dir(root) {
+        Ok(entries) => entries
+            .filter_map(Result::ok)
+            .filter(|e| e.file_type().is_ok_and(|t| t.is_dir()))
+            .map(|e| e.path())
+            .collect(),
+        Err(e) => {
+            let root = longpath::for_display(root);
+            println!("{}", i18n::format("dry_run_list_failed", &[&root.display(), &e]));
+            return;
+        }
+    };
+    dirs.sort();
+
+    if dirs.is_empty() {
+        println!("{}", i18n::text("dry_run_no_folders"));
+    }
+    for dir in dirs {
+        let name = longpath::for_display(&dir);
+        if options.skip.should_skip(&dir) {
+            println!("{}", i18n::format("dry_run_skipped", &[&name.display()]));
+        } else {
+            println!("  ✓ {}", name.display());
+        }
+    }
+}
@@ -0,0 +1,166 @@
+// ========================= Match Enrichers =========================
+
+use std::fs::File; // For reading image headers
+use std::io::{self, Read}; // For reading image headers
+use std::path::Path; // For working with filesystem paths
+use std::process::Command; // For asking git about a file
+use std::sync::Arc; // Enrichers are shared between the registry and templates
+
+// Adds extra fields to a match before it is formatted. Each field an enricher provides can
+// be used as `{name}` in a --format template; values are only computed for templates that
+// reference one of the enricher's fields.
+pub trait MatchEnricher: Send + Sync + std::fmt::Debug {
+    // Field names this enricher provides
+    fn fields(&self) -> &'static [&'static str];
+
+    // Values for the match at `path`, as (field, value). Fields left out render empty.
+    fn enrich(&self, path: &Path) -> Vec<(&'static str, String)>;
+}
+
+// The enrichers available to --format templates
+#[derive(Debug, Default)]
+pub struct Registry {
+    enrichers: Vec<Arc<dyn MatchEnricher>>,
+}
+
+impl Registry {
+    // The enrichers that ship with Speedy
+    pub fn builtin() -> Self {
+        let mut registry = Registry::default();
+        registry.register(Arc::new(GitStatus));
+        registry.register(Arc::new(ImageDimensions));
+        registry
+    }
+
+    // Make an enricher's fields available. Fields already provided by an earlier
+    // enricher keep their first provider.
+    pub fn register(&mut self, enricher: Arc<dyn MatchEnricher>) {
+        self.enrichers.push(enricher);
+    }
+
+    // The enricher that provides `field`, with the field's name as it declares it
+    pub fn provider(&self, field: &str) -> Option<(Arc<dyn MatchEnricher>, &'static str)> {
+        self.enrichers.iter().find_map(|e| {
+            let name = e.fields().iter().find(|f| **f == field)?;
+            Some((e.clone(), *name))
+        })
+    }
+
+    // Every field name, for error messages
+    pub fn field_names(&self) -> Vec<&'static str> {
+        self.enrichers.iter().flat_map(|e| e.fields().iter().copied()).collect()
+    }
+}
+
+// ========================= Git Status =========================
+
+// {git}: the file's state in its git repository (clean, modified, added, deleted, renamed,
+// untracked, ignored), or empty outside a repository
+#[derive(Debug)]
+pub struct GitStatus;
+
+impl MatchEnricher for GitStatus {
+    fn fields(&self) -> &'static [&'static str] {
+        &["git"]
+    }
+
+    fn enrich(&self, path: &Path) -> Vec<(&'static str, String)> {
+        let (Some(dir), Some(name)) = (path.parent(), path.file_name()) else {
+            return Vec::new();
+        };
+        let output = Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["status", "--porcelain=v1", "--ignored", "--"])
+            .arg(name)
+            .output();
+        let output = match output {
+            Ok(output) if output.status.success() => output,
+            _ => return Vec::new(), // Not in a repository, or git isn't installed
+        };
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let code = stdout.lines().next().map(|line| line.get(..2).unwrap_or(line));
+        let state = match code {
+            None => "clean",
+            Some("??") => "untracked",
+            Some("!!") => "ignored",
+            Some(code) if code.contains('A') => "added",
+            Some(code) if code.contains('D') => "deleted",
+            Some(code) if code.contains('R') => "renamed",
+            Some(_) => "modified",
+        };
+        vec![("git", state.to_string())]
+    }
+}
+
+// ========================= Image Dimensions =========================
+
+// {width}, {height} and {dimensions} ("1920x1080") of PNG, GIF, BMP and JPEG images,
+// read from the file header
+#[derive(Debug)]
+pub struct ImageDimensions;
+
+impl MatchEnricher for ImageDimensions {
+    fn fields(&self) -> &'static [&'static str] {
+        &["width", "height", "dimensions"]
+    }
+
+    fn enrich(&self, path: &Path) -> Vec<(&'static str, String)> {
+        let Some((width, height)) = image_size(path).ok().flatten() else {
+            return Vec::new();
+        };
+        vec![
+            ("width", width.to_string()),
+            ("height", height.to_string()),
+            ("dimensions", format!("{}x{}", width, height)),
+        ]
+    }
+}
+
+// Width and height of an image, or None if it isn't a supported image
+fn image_size(path: &Path) -> io::Result<Option<(u32, u32)>> {
+    let mut file = File::open(path)?;
+    let mut header = [0u8; 26];
+    let read = file.read(&mut header)?;
+    let header = &header[..read];
+    let be16 = |b: &[u8]| u16::from_be_bytes([b[0], b[1]]) as u32;
+    let le16 = |b: &[u8]| u16::from_le_bytes([b[0], b[1]]) as u32;
+
+    if header.len() >= 24 && header.starts_with(b"\x89PNG\r\n\x1a\n") {
+        let width = u32::from_be_bytes([header[16], header[17], header[18], header[19]]);
+        let height = u32::from_be_bytes([header[20], header[21], header[22], header[23]]);
+        return Ok(Some((width, height)));
+    }
+    if header.len() >= 10 && (header.starts_with(b"GIF87a") || header.starts_with(b"GIF89a")) {
+        return Ok(Some((le16(&header[6..]), le16(&header[8..]))));
+    }
+    if header.len() >= 26 && header.starts_with(b"BM") {
+        let width = i32::from_le_bytes([header[18], header[19], header[20], header[21]]);
+        // Negative heights mark top-down bitmaps
+        let height = i32::from_le_bytes([header[22], header[23], header[24], header[25]]);
+        return Ok(Some((width.unsigned_abs(), height.unsigned_abs())));
+    }
+    if header.starts_with(&[0xFF, 0xD8]) {
+        // JPEG: walk the marker segments up to the start-of-frame, which holds the size
+        let mut data = header[2..].to_vec();
+        file.take(1 << 20).read_to_end(&mut data)?;
+        let mut i = 0;
+        while i + 9 <= data.len() {
+            if data[i] != 0xFF {
+                return Ok(None);
+            }
+            let marker = data[i + 1];
+            if marker == 0xFF {
+                i += 1; // Fill byte
+                continue;
+            }
+            let is_frame = (0xC0..=0xCF).contains(&marker) && !matches!(marker, 0xC4 | 0xC8 | 0xCC);
+            if is_frame {
+                return Ok(Some((be16(&data[i + 7..]), be16(&data[i + 5..]))));
+            }
+            i += 2 + be16(&data[i + 2..]) as usize;
+        }
+    }
+    Ok(None)
+}
@@ -0,0 +1,67 @@
+// ========================= Environment Defaults =========================
+
+use std::env; // For reading the variables
+
+use crate::SpeedyError;
+
+// Default for --threads: a number, or "auto"
+pub const THREADS: &str = "SPEEDY_THREADS";
+// Extra folder names to skip, separated by commas, added to every profile's skip rules
+pub const SKIP: &str = "SPEEDY_SKIP";
+// Default for --output: text or json
+pub const OUTPUT: &str = "SPEEDY_OUTPUT";
+// Any value but 0 turns --notify off (for CI machines and other places without a desktop)
+pub const NO_NOTIFY: &str = "SPEEDY_NO_NOTIFY";
+// Release feed for `speedy self-update`, for mirrors and testing
+pub const UPDATE_FEED: &str = "SPEEDY_UPDATE_FEED";
+
+// Value of a variable, if set to something other than blanks
+fn value(name: &str) -> Option<String> {
+    env::var(name).ok().map(|v| v.trim().to_string()).filter(|v| !v.is_empty())
+}
+
+// SPEEDY_THREADS: the thread count to use when --threads isn't given. None (the default)
+// picks a count for the volume, like --threads auto.
+pub fn threads() -> Result<Option<usize>, SpeedyError> {
+    match value(THREADS).as_deref() {
+        None | Some("auto") => Ok(None),
+        Some(n) => n
+            .parse()
+            .ok()
+            .filter(|n| *n > 0)
+            .map(Some)
+            .ok_or_else(|| SpeedyError::Parse(format!("{} must be a number or 'auto', not '{}'", THREADS, n))),
+    }
+}
+
+// SPEEDY_SKIP: folder names pruned from every walk in addition to the profile's rules,
+// compared case-insensitively like the built-in ones
+pub fn skip_names() -> Vec<String> {
+    value(SKIP)
+        .map(|v| {
+            v.split(',')
+                .map(|name| name.trim().to_lowercase())
+                .filter(|name| !name.is_empty())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+// SPEEDY_OUTPUT: whether searches print JSON events when --output isn't given
+pub fn json_output() -> Result<bool, SpeedyError> {
+    match value(OUTPUT).map(|v| v.to_lowercase()).as_deref() {
+        None | Some("text") => Ok(false),
+        Some("json") => Ok(true),
+        Some(other) => Err(SpeedyError::Parse(format!("{} must be text or json, not '{}'", OUTPUT, other))),
+    }
+}
+
+// SPEEDY_UPDATE_FEED: URL of the release description self-update reads instead of GitHub's
+pub fn update_feed() -> Option<String> {
+    value(UPDATE_FEED)
+}
+
+// SPEEDY_NO_NOTIFY: whether desktop notifications are turned off
+pub fn no_notify() -> bool {
+    value(NO_NOTIFY).is_some_and(|v| v != "0")
+}
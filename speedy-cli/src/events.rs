@@ -0,0 +1,89 @@
+// ========================= Structured Output =========================
+
+use std::fmt::Write as _; // For building JSON text
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+
+use log::Level; // How important a warning is in text mode
+
+// What went wrong while walking
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WarningKind {
+    PermissionDenied, // An entry could not be read because of its permissions
+    Traversal,        // Any other failure to read an entry (vanished, I/O error, loop, ...)
+    NetworkRetry,     // A transient network error; the folder is walked again later
+}
+
+impl WarningKind {
+    fn name(self) -> &'static str {
+        match self {
+            WarningKind::PermissionDenied => "permission_denied",
+            WarningKind::Traversal => "traversal",
+            WarningKind::NetworkRetry => "network_retry",
+        }
+    }
+}
+
+// A problem met during the walk, sent from the search thread on its own channel so it
+// never mixes with results: logged in text mode, a `warning` event with --output json
+#[derive(Debug, Clone)]
+pub struct Warning {
+    pub kind: WarningKind,
+    pub path: Option<PathBuf>, // Entry the warning is about, if known
+    pub message: String,       // Human-readable description
+    pub level: Level,          // Log level used in text mode
+}
+
+impl Warning {
+    // Show the warning the way the chosen output mode expects
+    pub fn report(&self, json: bool) {
+        if json {
+            let mut event = format!("{{\"type\":\"warning\",\"kind\":\"{}\"", self.kind.name());
+            if let Some(path) = &self.path {
+                let _ = write!(event, ",\"path\":{}", json_path(path));
+            }
+            let _ = write!(event, ",\"message\":{}}}", json_string(&self.message));
+            println!("{}", event);
+        } else {
+            log::log!(self.level, "{}", self.message);
+        }
+    }
+}
+
+// `match` event for one result
+pub fn match_event(path: &Path) -> String {
+    format!("{{\"type\":\"match\",\"path\":{}}}", json_path(path))
+}
+
+// Closing `summary` event
+pub fn summary_event(matches: usize, scanned: usize, elapsed_ms: u128, cancelled: bool, denied: usize) -> String {
+    format!(
+        "{{\"type\":\"summary\",\"matches\":{},\"scanned\":{},\"elapsed_ms\":{},\"cancelled\":{},\"permission_denied\":{}}}",
+        matches, scanned, elapsed_ms, cancelled, denied
+    )
+}
+
+// A path as a JSON string (paths that aren't valid Unicode are converted lossily)
+fn json_path(path: &Path) -> String {
+    json_string(&path.to_string_lossy())
+}
+
+// A quoted JSON string with the required escapes
+pub fn json_string(value: &str) -> String {
+    let mut out = String::with_capacity(value.len() + 2);
+    out.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(out, "\\u{:04x}", c as u32);
+            }
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
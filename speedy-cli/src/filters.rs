@@ -0,0 +1,395 @@
+// ========================= Entry Filters =========================
+
+use std::path::Path; // For checking entry paths
+use std::sync::atomic::{AtomicBool, Ordering}; // For warning once about missing timestamps
+
+use walkdir::DirEntry; // Entries produced by the directory walker
+
+use crate::SpeedyError;
+
+// Kinds of entries that `--type` can select
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EntryType {
+    File,       // f: regular file (or a link to one)
+    Dir,        // d: directory (or a link to one)
+    Symlink,    // l: the link itself, not followed
+    Executable, // x: file with an executable bit (Unix) or a PATHEXT extension (Windows)
+}
+
+impl EntryType {
+    // Parse a comma-separated `--type` value such as "f" or "f,l"
+    pub fn parse_list(value: &str) -> Result<Vec<EntryType>, SpeedyError> {
+        value
+            .split(',')
+            .map(|t| match t.trim() {
+                "f" | "file" => Ok(EntryType::File),
+                "d" | "dir" | "folder" => Ok(EntryType::Dir),
+                "l" | "link" | "symlink" => Ok(EntryType::Symlink),
+                "x" | "exe" | "executable" => Ok(EntryType::Executable),
+                other => Err(SpeedyError::Argument(format!(
+                    "Unknown type '{}' (expected f, d, l or x)",
+                    other
+                ))),
+            })
+            .collect()
+    }
+
+    // Does this walker entry have this type?
+    pub fn matches(self, entry: &DirEntry) -> bool {
+        let path = entry.path();
+        match self {
+            EntryType::File => path.is_file(),
+            EntryType::Dir => path.is_dir(),
+            EntryType::Symlink => entry.path_is_symlink(),
+            EntryType::Executable => path.is_file() && is_executable(path),
+        }
+    }
+}
+
+// Human-readable description of a type selection, used in status messages
+pub fn describe_types(types: &[EntryType]) -> &'static str {
+    match types {
+        [EntryType::File] => "file",
+        [EntryType::Dir] => "folder",
+        [EntryType::Symlink] => "symlink",
+        [EntryType::Executable] => "executable",
+        _ => "entry",
+    }
+}
+
+// Does the entry match any of the selected types? (An empty selection matches everything.)
+pub fn matches_any_type(types: &[EntryType], entry: &DirEntry) -> bool {
+    types.is_empty() || types.iter().any(|t| t.matches(entry))
+}
+
+// Cheap check using only the type reported by the directory listing (no extra I/O).
+// Used to keep "did you mean" suggestions to entries of roughly the right kind.
+pub fn plausible_type(types: &[EntryType], entry: &DirEntry) -> bool {
+    let file_type = entry.file_type();
+    types.is_empty()
+        || types.iter().any(|t| match t {
+            EntryType::File | EntryType::Executable => !file_type.is_dir(),
+            EntryType::Dir => file_type.is_dir() || file_type.is_symlink(),
+            EntryType::Symlink => file_type.is_symlink(),
+        })
+}
+
+#[cfg(unix)]
+pub fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata()
+        .map(|m| m.permissions().mode() & 0o111 != 0)
+        .unwrap_or(false)
+}
+
+#[cfg(windows)]
+pub fn is_executable(path: &Path) -> bool {
+    // Windows decides executability by extension, listed in PATHEXT
+    let pathext = std::env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let Some(ext) = path.extension().and_then(|e| e.to_str()) else {
+        return false;
+    };
+    pathext
+        .split(';')
+        .filter_map(|e| e.strip_prefix('.'))
+        .any(|e| e.eq_ignore_ascii_case(ext))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn is_executable(_path: &Path) -> bool {
+    false
+}
+
+// ========================= Owner & Permission Filters (Unix) =========================
+
+// How `--perm` compares permission bits, following `find -perm` conventions
+#[derive(Debug, Clone, Copy)]
+pub enum PermFilter {
+    Exact(u32), // 644: permission bits are exactly this
+    AllOf(u32), // -002: all of these bits are set
+    AnyOf(u32), // /022: any of these bits is set
+}
+
+impl PermFilter {
+    // Parse an octal mode with an optional '-' (all bits) or '/' (any bit) prefix
+    pub fn parse(value: &str) -> Result<PermFilter, SpeedyError> {
+        let (digits, make): (&str, fn(u32) -> PermFilter) = match value.as_bytes().first() {
+            Some(b'-') => (&value[1..], PermFilter::AllOf),
+            Some(b'/') => (&value[1..], PermFilter::AnyOf),
+            _ => (value, PermFilter::Exact),
+        };
+        let mode = u32::from_str_radix(digits, 8)
+            .ok()
+            .filter(|m| *m <= 0o7777)
+            .ok_or_else(|| {
+                SpeedyError::Parse(format!(
+                    "Invalid permission mode '{}' (expected octal like 644, -002 or /111)",
+                    value
+                ))
+            })?;
+        Ok(make(mode))
+    }
+
+    pub fn matches(self, mode: u32) -> bool {
+        let mode = mode & 0o7777;
+        match self {
+            PermFilter::Exact(bits) => mode == bits,
+            PermFilter::AllOf(bits) => mode & bits == bits,
+            PermFilter::AnyOf(bits) => bits == 0 || mode & bits != 0,
+        }
+    }
+}
+
+// Resolve `--owner` to a numeric user ID (accepts a user name or a numeric ID)
+#[cfg(unix)]
+pub fn resolve_owner(user: &str) -> Result<u32, SpeedyError> {
+    if let Ok(uid) = user.parse() {
+        return Ok(uid);
+    }
+
+    let name = std::ffi::CString::new(user)
+        .map_err(|_| SpeedyError::Argument(format!("Invalid user name: {}", user)))?;
+    // getpwnam returns a pointer into static storage; we only read the uid immediately
+    let entry = unsafe { libc::getpwnam(name.as_ptr()) };
+    if entry.is_null() {
+        return Err(SpeedyError::Argument(format!("Unknown user: {}", user)));
+    }
+    Ok(unsafe { (*entry).pw_uid })
+}
+
+#[cfg(not(unix))]
+pub fn resolve_owner(_user: &str) -> Result<u32, SpeedyError> {
+    Err(SpeedyError::Argument(
+        "--owner is only supported on Unix systems".to_string(),
+    ))
+}
+
+// Check `--owner` / `--perm` against the entry's own (unfollowed) metadata
+#[cfg(unix)]
+pub fn matches_owner_and_perm(entry: &DirEntry, owner: Option<u32>, perm: Option<PermFilter>) -> bool {
+    use std::os::unix::fs::MetadataExt;
+
+    if owner.is_none() && perm.is_none() {
+        return true;
+    }
+    let Ok(metadata) = entry.metadata() else {
+        return false;
+    };
+    owner.is_none_or(|uid| metadata.uid() == uid) && perm.is_none_or(|p| p.matches(metadata.mode()))
+}
+
+#[cfg(not(unix))]
+pub fn matches_owner_and_perm(_entry: &DirEntry, owner: Option<u32>, perm: Option<PermFilter>) -> bool {
+    owner.is_none() && perm.is_none()
+}
+
+// ========================= Attribute Filters (Windows) =========================
+
+// Win32 FILE_ATTRIBUTE_* values accepted by `--attr`
+const WINDOWS_ATTRIBUTES: [(&str, u32); 6] = [
+    ("readonly", 0x1),
+    ("hidden", 0x2),
+    ("system", 0x4),
+    ("archive", 0x20),
+    ("reparse", 0x400),
+    ("compressed", 0x800),
+];
+
+// Parse a comma-separated `--attr` list into a mask of required attribute bits
+pub fn parse_attributes(value: &str) -> Result<u32, SpeedyError> {
+    value.split(',').try_fold(0, |mask, name| {
+        let name = name.trim().to_lowercase();
+        WINDOWS_ATTRIBUTES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, bit)| mask | bit)
+            .ok_or_else(|| {
+                SpeedyError::Argument(format!(
+                    "Unknown attribute '{}' (expected readonly, hidden, system, archive, reparse or compressed)",
+                    name
+                ))
+            })
+    })
+}
+
+// Does the entry have every attribute in `required`? Uses the entry's own metadata,
+// so junctions and symlinks report their reparse attribute instead of their target's.
+#[cfg(windows)]
+pub fn matches_attributes(entry: &DirEntry, required: u32) -> bool {
+    use std::os::windows::fs::MetadataExt;
+
+    if required == 0 {
+        return true;
+    }
+    entry
+        .metadata()
+        .map(|m| m.file_attributes() & required == required)
+        .unwrap_or(false)
+}
+
+#[cfg(not(windows))]
+pub fn matches_attributes(_entry: &DirEntry, required: u32) -> bool {
+    required == 0
+}
+
+// ========================= Content Type Filters =========================
+
+// How many leading bytes are read to recognize a file's content
+const SNIFF_BYTES: usize = 8192;
+
+// `--mime` selection: content types such as "image/png", or "image/*" for a whole family
+#[derive(Debug, Clone)]
+pub struct MimeFilter {
+    patterns: Vec<String>, // Lowercase; a trailing "/*" matches any subtype
+}
+
+impl MimeFilter {
+    // Parse a comma-separated `--mime` value such as "image/*,application/pdf"
+    pub fn parse(value: &str) -> Result<Self, SpeedyError> {
+        let patterns = value
+            .split(',')
+            .map(|p| p.trim().to_lowercase())
+            .map(|p| match p.split_once('/') {
+                Some((kind, sub)) if !kind.is_empty() && !sub.is_empty() => Ok(p),
+                _ => Err(SpeedyError::Argument(format!(
+                    "Invalid content type '{}' (expected e.g. image/png or image/*)",
+                    p
+                ))),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(MimeFilter { patterns })
+    }
+
+    fn matches_type(&self, mime: &str) -> bool {
+        self.patterns.iter().any(|pattern| match pattern.strip_suffix("/*") {
+            Some(kind) => mime.split_once('/').is_some_and(|(k, _)| k == kind),
+            None => pattern == mime,
+        })
+    }
+}
+
+// Does the file's content (not its extension) have one of the selected types?
+// Only regular files are sniffed; anything unrecognized does not match.
+pub fn matches_mime(entry: &DirEntry, filter: Option<&MimeFilter>) -> bool {
+    let Some(filter) = filter else {
+        return true;
+    };
+    if !entry.path().is_file() {
+        return false;
+    }
+    sniff_mime(entry.path()).is_some_and(|mime| filter.matches_type(mime))
+}
+
+// Content type recognized from the first bytes of a file
+pub fn sniff_mime(path: &Path) -> Option<&'static str> {
+    use std::io::Read;
+
+    let mut head = Vec::with_capacity(SNIFF_BYTES);
+    std::fs::File::open(path)
+        .ok()?
+        .take(SNIFF_BYTES as u64)
+        .read_to_end(&mut head)
+        .ok()?;
+    infer::get(&head).map(|kind| kind.mime_type())
+}
+
+// ========================= Time Filters =========================
+
+// Which timestamp a time filter looks at
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TimeField {
+    Modified, // Last content change (mtime)
+    Created,  // Birth time, where the file system records it
+    Accessed, // Last read (atime); often only updated lazily
+}
+
+impl TimeField {
+    fn name(self) -> &'static str {
+        match self {
+            TimeField::Modified => "modification",
+            TimeField::Created => "creation",
+            TimeField::Accessed => "access",
+        }
+    }
+}
+
+// `--modified-within`, `--created-within` and `--accessed-within`
+#[derive(Debug, Clone, Copy)]
+pub struct TimeFilter {
+    pub field: TimeField,
+    pub within: std::time::Duration,
+}
+
+impl std::fmt::Display for TimeFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        // Largest unit that divides the age evenly, so "7d" isn't shown as "604800s"
+        let secs = self.within.as_secs();
+        let (amount, unit) = [(7 * 86400, "w"), (86400, "d"), (3600, "h"), (60, "m")]
+            .into_iter()
+            .find(|(size, _)| secs > 0 && secs.is_multiple_of(*size))
+            .map_or((secs, "s"), |(size, unit)| (secs / size, unit));
+        write!(f, "{} in the last {}{}", self.field.name(), amount, unit)
+    }
+}
+
+// Set once a missing timestamp has been reported, so the warning appears only once per field
+static MISSING_TIME_WARNED: [AtomicBool; 3] =
+    [AtomicBool::new(false), AtomicBool::new(false), AtomicBool::new(false)];
+
+// Parse an age such as "45s", "30m", "12h", "7d" or "2w" (a bare number means days)
+pub fn parse_age(value: &str) -> Result<std::time::Duration, SpeedyError> {
+    let value = value.trim();
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let (number, unit) = value.split_at(split);
+    let number: u64 = number.parse().map_err(|_| {
+        SpeedyError::Parse(format!("Invalid age '{}' (expected e.g. 30m, 12h, 7d)", value))
+    })?;
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3600,
+        "" | "d" => 86400,
+        "w" => 7 * 86400,
+        _ => {
+            return Err(SpeedyError::Parse(format!(
+                "Unknown unit in age '{}' (use s, m, h, d or w)",
+                value
+            )));
+        }
+    };
+    Ok(std::time::Duration::from_secs(number.saturating_mul(seconds)))
+}
+
+// Does the entry satisfy every time filter? Entries whose file system doesn't record a
+// timestamp never match, and a warning explains why results may be missing.
+pub fn matches_times(entry: &DirEntry, filters: &[TimeFilter]) -> bool {
+    if filters.is_empty() {
+        return true;
+    }
+    let Ok(metadata) = entry.metadata() else {
+        return false;
+    };
+    let now = std::time::SystemTime::now();
+
+    filters.iter().all(|filter| {
+        let time = match filter.field {
+            TimeField::Modified => metadata.modified(),
+            TimeField::Created => metadata.created(),
+            TimeField::Accessed => metadata.accessed(),
+        };
+        match time {
+            Ok(time) => now.duration_since(time).map_or(true, |age| age <= filter.within),
+            Err(_) => {
+                if !MISSING_TIME_WARNED[filter.field as usize].swap(true, Ordering::Relaxed) {
+                    log::warn!(
+                        "This file system does not record {} times (e.g. {}); such entries are not matched",
+                        filter.field.name(),
+                        entry.path().display()
+                    );
+                }
+                false
+            }
+        }
+    })
+}
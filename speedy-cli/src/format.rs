@@ -0,0 +1,338 @@
+// ========================= Output Templates =========================
+
+use std::fs::File; // For --output-file
+use std::io::{self, BufWriter, Write}; // For writing results incrementally
+use std::path::{Component, Path, PathBuf}; // For reading result metadata and rewriting paths
+use std::sync::Arc; // Templates share enrichers with the registry
+use std::time::UNIX_EPOCH; // For converting modification times to timestamps
+
+use crate::SpeedyError;
+use crate::enrich::{MatchEnricher, Registry};
+use crate::longpath;
+
+// A single piece of a parsed template: either fixed text or a result field
+#[derive(Debug)]
+enum Segment {
+    Literal(String),
+    Field(Field),
+}
+
+// Result fields that can be referenced as `{name}` in a template
+#[derive(Debug, Clone, Copy)]
+enum Field {
+    Path,   // Full path of the match
+    Name,   // File or folder name
+    Parent, // Directory containing the match
+    Ext,    // Extension without the dot (empty if none)
+    Size,   // Size in bytes (0 for folders)
+    Mtime,  // Last modification time, seconds since the Unix epoch
+    Type,   // "file" or "folder"
+    Enriched(usize, &'static str), // Field of the template's enricher at this index
+}
+
+impl Field {
+    fn from_name(name: &str) -> Option<Field> {
+        match name {
+            "path" => Some(Field::Path),
+            "name" => Some(Field::Name),
+            "parent" => Some(Field::Parent),
+            "ext" => Some(Field::Ext),
+            "size" => Some(Field::Size),
+            "mtime" => Some(Field::Mtime),
+            "type" => Some(Field::Type),
+            _ => None,
+        }
+    }
+}
+
+// A compiled `--format` template, parsed once and rendered per result
+#[derive(Debug)]
+pub struct OutputTemplate {
+    segments: Vec<Segment>,
+    enrichers: Vec<Arc<dyn MatchEnricher>>, // Enrichers whose fields the template uses
+}
+
+impl OutputTemplate {
+    // Parse a template such as "{path}\t{size}\t{mtime}".
+    // Supports `{{` / `}}` for literal braces and `\t`, `\n`, `\\` escapes,
+    // since shells usually pass those through unexpanded.
+    // Fields not built in are looked up among the registered match enrichers.
+    pub fn parse(template: &str, registry: &Registry) -> Result<Self, SpeedyError> {
+        let mut segments = Vec::new();
+        let mut enrichers: Vec<Arc<dyn MatchEnricher>> = Vec::new();
+        let mut literal = String::new();
+        let mut chars = template.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut name = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some(ch) => name.push(ch),
+                            None => {
+                                return Err(SpeedyError::Template(format!(
+                                    "Unclosed '{{' in format string: {}",
+                                    template
+                                )));
+                            }
+                        }
+                    }
+                    let name = name.trim();
+                    let field = match Field::from_name(name) {
+                        Some(field) => field,
+                        None => {
+                            let (enricher, field) = registry.provider(name).ok_or_else(|| {
+                                SpeedyError::Template(format!(
+                                    "Unknown field {{{}}} (expected one of: path, name, parent, ext, size, mtime, type, {})",
+                                    name,
+                                    registry.field_names().join(", ")
+                                ))
+                            })?;
+                            let index = match enrichers.iter().position(|e| Arc::ptr_eq(e, &enricher)) {
+                                Some(index) => index,
+                                None => {
+                                    enrichers.push(enricher);
+                                    enrichers.len() - 1
+                                }
+                            };
+                            Field::Enriched(index, field)
+                        }
+                    };
+                    if !literal.is_empty() {
+                        segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                    }
+                    segments.push(Segment::Field(field));
+                }
+                '}' => {
+                    return Err(SpeedyError::Template(
+                        "Unmatched '}' in format string (use '}}' for a literal brace)".to_string(),
+                    ));
+                }
+                '\\' => match chars.peek() {
+                    Some('t') => {
+                        chars.next();
+                        literal.push('\t');
+                    }
+                    Some('n') => {
+                        chars.next();
+                        literal.push('\n');
+                    }
+                    Some('\\') => {
+                        chars.next();
+                        literal.push('\\');
+                    }
+                    _ => literal.push('\\'),
+                },
+                _ => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            segments.push(Segment::Literal(literal));
+        }
+
+        Ok(OutputTemplate { segments, enrichers })
+    }
+
+    // Render the template for a single result. `shown` is the path as it should be printed
+    // (see PathStyle); metadata and names come from the real `path`.
+    pub fn render(&self, path: &Path, shown: &Path) -> String {
+        // Metadata is only read when a field actually needs it
+        let needs_metadata = self
+            .segments
+            .iter()
+            .any(|s| matches!(s, Segment::Field(Field::Size | Field::Mtime | Field::Type)));
+        let metadata = if needs_metadata { path.metadata().ok() } else { None };
+        let enriched: Vec<_> = self.enrichers.iter().map(|e| e.enrich(path)).collect();
+
+        let mut out = String::new();
+        for segment in &self.segments {
+            match segment {
+                Segment::Literal(text) => out.push_str(text),
+                Segment::Field(field) => match field {
+                    Field::Path => out.push_str(&shown.display().to_string()),
+                    Field::Name => out.push_str(
+                        &path
+                            .file_name()
+                            .map(|n| n.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                    Field::Parent => out.push_str(
+                        &shown
+                            .parent()
+                            .map(|p| {
+                                // A relative path directly below its base has an empty parent
+                                if p.as_os_str().is_empty() {
+                                    ".".to_string()
+                                } else {
+                                    p.display().to_string()
+                                }
+                            })
+                            .unwrap_or_default(),
+                    ),
+                    Field::Ext => out.push_str(
+                        &path
+                            .extension()
+                            .map(|e| e.to_string_lossy().into_owned())
+                            .unwrap_or_default(),
+                    ),
+                    Field::Size => {
+                        let size = metadata
+                            .as_ref()
+                            .filter(|m| m.is_file())
+                            .map(|m| m.len())
+                            .unwrap_or(0);
+                        out.push_str(&size.to_string());
+                    }
+                    Field::Mtime => {
+                        let mtime = metadata
+                            .as_ref()
+                            .and_then(|m| m.modified().ok())
+                            .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+                            .map(|d| d.as_secs())
+                            .unwrap_or(0);
+                        out.push_str(&mtime.to_string());
+                    }
+                    Field::Type => out.push_str(match &metadata {
+                        Some(m) if m.is_dir() => "folder",
+                        _ => "file",
+                    }),
+                    Field::Enriched(index, name) => {
+                        if let Some((_, value)) = enriched[*index].iter().find(|(f, _)| f == name) {
+                            out.push_str(value);
+                        }
+                    }
+                },
+            }
+        }
+        out
+    }
+}
+
+// ========================= Path Style =========================
+
+// How result paths are printed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PathStyle {
+    #[default]
+    AsWalked,       // Whatever the walker produced (based on the root as given)
+    Absolute,       // Canonical absolute path (--absolute)
+    RelativeToRoot, // Relative to the search root (--relative)
+    RelativeToCwd,  // Relative to the current directory (--relative cwd)
+}
+
+impl PathStyle {
+    // The printable form of a result path found below `root`
+    pub fn shown(self, path: &Path, root: &Path) -> PathBuf {
+        match self {
+            PathStyle::AsWalked => longpath::for_display(path),
+            PathStyle::Absolute => {
+                let absolute = path
+                    .canonicalize()
+                    .or_else(|_| std::path::absolute(path))
+                    .unwrap_or_else(|_| path.to_path_buf());
+                longpath::for_display(&absolute)
+            }
+            PathStyle::RelativeToRoot => match path.strip_prefix(root) {
+                Ok(relative) if relative.as_os_str().is_empty() => PathBuf::from("."),
+                Ok(relative) => relative.to_path_buf(),
+                Err(_) => longpath::for_display(path),
+            },
+            PathStyle::RelativeToCwd => {
+                let absolute = |p: &Path| std::path::absolute(longpath::for_display(p)).ok();
+                match (absolute(path), std::env::current_dir().ok().as_deref().and_then(absolute)) {
+                    (Some(path), Some(cwd)) => relative_path(&path, &cwd),
+                    _ => longpath::for_display(path),
+                }
+            }
+        }
+    }
+}
+
+// `path` expressed relative to `base` (both absolute), using `..` where needed.
+// Paths on different drives have no relative form and are returned unchanged.
+fn relative_path(path: &Path, base: &Path) -> PathBuf {
+    let path_parts: Vec<Component> = path.components().collect();
+    let base_parts: Vec<Component> = base.components().collect();
+    if path_parts.first() != base_parts.first() {
+        return path.to_path_buf();
+    }
+
+    let common = path_parts
+        .iter()
+        .zip(&base_parts)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let mut relative = PathBuf::new();
+    for _ in common..base_parts.len() {
+        relative.push("..");
+    }
+    for part in &path_parts[common..] {
+        relative.push(part);
+    }
+    if relative.as_os_str().is_empty() {
+        relative.push(".");
+    }
+    relative
+}
+
+// ========================= Result Files =========================
+
+// Results written to --output-file as they arrive, one line per match
+#[derive(Debug)]
+pub struct ResultFile {
+    out: BufWriter<File>,
+    count: usize,
+}
+
+impl ResultFile {
+    // Create the file, or add to it when continuing an earlier (resumed) search
+    pub fn create(path: &Path, append: bool) -> io::Result<Self> {
+        let file = File::options()
+            .write(true)
+            .create(true)
+            .append(append)
+            .truncate(!append)
+            .open(path)?;
+        Ok(ResultFile {
+            out: BufWriter::new(file),
+            count: 0,
+        })
+    }
+
+    // Write one result, rendered with the template if one was given
+    pub fn write(
+        &mut self,
+        path: &Path,
+        shown: &Path,
+        template: Option<&OutputTemplate>,
+    ) -> io::Result<()> {
+        match template {
+            Some(template) => writeln!(self.out, "{}", template.render(path, shown))?,
+            None => writeln!(self.out, "{}", shown.display())?,
+        }
+        self.count += 1;
+        Ok(())
+    }
+
+    // Push buffered lines to disk, so the file can be followed (e.g. `tail -f`) during long
+    // searches
+    pub fn flush(&mut self) -> io::Result<()> {
+        self.out.flush()
+    }
+
+    // Number of results written so far
+    pub fn count(&self) -> usize {
+        self.count
+    }
+}
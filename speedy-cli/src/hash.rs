@@ -0,0 +1,301 @@
+// ========================= Hashing and Verification =========================
+
+use std::fs::{self, File}; // For reading files and manifests
+use std::io::{self, BufWriter, Read, Write}; // For streaming file contents and output
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // Verification failures are reported through the exit status
+use std::time::Instant; // For timing the run
+
+use rayon::prelude::*; // Files are hashed in parallel
+use sha2::{Digest, Sha256, Sha512}; // Supported digests
+use walkdir::WalkDir; // For listing the tree to hash
+
+use crate::SpeedyError;
+use crate::environment;
+use crate::longpath;
+use crate::tuning;
+
+// Files are read in chunks of this size
+const BUFFER_SIZE: usize = 64 * 1024;
+
+// Checksum algorithms for `speedy hash --algo`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Algorithm {
+    Sha256,
+    Sha512,
+}
+
+impl Algorithm {
+    fn parse(value: &str) -> Result<Algorithm, SpeedyError> {
+        match value.to_lowercase().replace('-', "").as_str() {
+            "sha256" => Ok(Algorithm::Sha256),
+            "sha512" => Ok(Algorithm::Sha512),
+            _ => Err(SpeedyError::Argument(format!(
+                "Unknown hash algorithm '{}' (use sha256 or sha512)",
+                value
+            ))),
+        }
+    }
+
+    // Guess the algorithm of a manifest line from the length of its hex digest
+    fn for_digest(digest: &str) -> Option<Algorithm> {
+        match digest.len() {
+            64 => Some(Algorithm::Sha256),
+            128 => Some(Algorithm::Sha512),
+            _ => None,
+        }
+    }
+
+    // Hex digest of a file's contents
+    fn hash_file(self, path: &Path) -> io::Result<String> {
+        match self {
+            Algorithm::Sha256 => digest_file::<Sha256>(path),
+            Algorithm::Sha512 => digest_file::<Sha512>(path),
+        }
+    }
+}
+
+impl std::fmt::Display for Algorithm {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Algorithm::Sha256 => write!(f, "SHA-256"),
+            Algorithm::Sha512 => write!(f, "SHA-512"),
+        }
+    }
+}
+
+pub fn digest_file<D: Digest>(path: &Path) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = D::new();
+    let mut buffer = vec![0u8; BUFFER_SIZE];
+    loop {
+        let read = file.read(&mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+    Ok(hasher.finalize().iter().map(|b| format!("{:02x}", b)).collect())
+}
+
+// Options shared by `speedy hash` and `speedy verify`
+#[derive(Debug)]
+struct HashArgs {
+    target: PathBuf,                // Tree to hash, or manifest to verify
+    algorithm: Option<Algorithm>,   // --algo; verify guesses from the digests if absent
+    threads: Option<usize>,         // --threads; None picks a count for the volume
+    output_file: Option<PathBuf>,   // --output-file for the manifest
+    verbose: bool,                  // --verbose: also list files that verified fine
+    quiet: bool,                    // --quiet: only the manifest / failures
+}
+
+fn parse_args(args: &[String], what: &str) -> Result<HashArgs, SpeedyError> {
+    let mut parsed = HashArgs {
+        target: PathBuf::new(),
+        algorithm: None,
+        threads: None,
+        output_file: None,
+        verbose: false,
+        quiet: false,
+    };
+    let mut target = None;
+    let value = |i: usize, flag: &str| {
+        args.get(i + 1)
+            .ok_or_else(|| SpeedyError::Argument(format!("Missing value after {}", flag)))
+    };
+
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--algo" => {
+                parsed.algorithm = Some(Algorithm::parse(value(i, "--algo")?)?);
+                i += 2;
+            }
+            "--threads" => {
+                parsed.threads = Some(value(i, "--threads")?.parse().map_err(|_| {
+                    SpeedyError::Parse("Thread count must be a number".to_string())
+                })?);
+                i += 2;
+            }
+            "--output-file" => {
+                parsed.output_file = Some(PathBuf::from(value(i, "--output-file")?));
+                i += 2;
+            }
+            "--verbose" => {
+                parsed.verbose = true;
+                i += 1;
+            }
+            "--quiet" => {
+                parsed.quiet = true;
+                i += 1;
+            }
+            flag if flag.starts_with("--") => {
+                return Err(SpeedyError::Argument(format!("Unknown option: {}", flag)));
+            }
+            path => {
+                if target.is_some() {
+                    return Err(SpeedyError::Argument(format!("Unexpected argument: {}", path)));
+                }
+                target = Some(PathBuf::from(path));
+                i += 1;
+            }
+        }
+    }
+
+    parsed.target = target.ok_or_else(|| SpeedyError::Argument(format!("Missing {}", what)))?;
+    // SPEEDY_THREADS only when --threads isn't given, so a bad value can't block the flag
+    if parsed.threads.is_none() {
+        parsed.threads = environment::threads()?;
+    }
+    Ok(parsed)
+}
+
+// A pool sized like a search of `root` would be, unless --threads says otherwise
+fn build_pool(root: &Path, threads: Option<usize>) -> Result<rayon::ThreadPool, SpeedyError> {
+    let threads = threads
+        .unwrap_or_else(|| tuning::thread_count(tuning::detect_volume_kind(root)));
+    Ok(rayon::ThreadPoolBuilder::new()
+        .num_threads(threads)
+        .thread_name(|i| format!("speedy-hasher-{}", i))
+        .build()?)
+}
+
+// `speedy hash <path> [--algo sha256|sha512]`: write a checksum manifest for every file
+// below <path>, in the `sha256sum` format ("<digest>  <path>")
+pub fn hash_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let args = parse_args(args, "path to hash")?;
+    let algorithm = args.algorithm.unwrap_or(Algorithm::Sha256);
+    let start = Instant::now();
+
+    let root = longpath::to_extended(&args.target);
+    let mut files = Vec::new();
+    let mut failed = 0;
+    for entry in WalkDir::new(&root).sort_by_file_name() {
+        match entry {
+            Ok(entry) if entry.file_type().is_file() => files.push(entry.into_path()),
+            Ok(_) => {}
+            Err(e) => {
+                eprintln!("⚠️ {}", e);
+                failed += 1;
+            }
+        }
+    }
+
+    let pool = build_pool(&root, args.threads)?;
+    let digests: Vec<_> = pool.install(|| {
+        files.par_iter().map(|path| algorithm.hash_file(path)).collect()
+    });
+
+    let mut out: Box<dyn Write> = match &args.output_file {
+        Some(file) => Box::new(BufWriter::new(File::create(file)?)),
+        None => Box::new(BufWriter::new(io::stdout().lock())),
+    };
+    let mut hashed = 0;
+    for (path, digest) in files.iter().zip(digests) {
+        let shown = longpath::for_display(path);
+        match digest {
+            Ok(digest) => {
+                writeln!(out, "{}  {}", digest, shown.display())?;
+                hashed += 1;
+            }
+            Err(e) => {
+                eprintln!("⚠️ Could not hash {}: {}", shown.display(), e);
+                failed += 1;
+            }
+        }
+    }
+    out.flush()?;
+
+    if !args.quiet {
+        eprintln!(
+            "✅ {} hashed {} file(s) in {:.2?}{}",
+            algorithm,
+            hashed,
+            start.elapsed(),
+            match &args.output_file {
+                Some(file) => format!(", manifest written to {}", file.display()),
+                None => String::new(),
+            }
+        );
+    }
+    if failed > 0 {
+        eprintln!("❌ {} entr(ies) could not be read and are missing from the manifest", failed);
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// `speedy verify <manifest>`: re-hash the files listed in a manifest and report changes
+pub fn verify_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let args = parse_args(args, "manifest to verify")?;
+    let start = Instant::now();
+    let manifest = fs::read_to_string(&args.target)?;
+
+    // "<digest>  <path>", or "<digest> *<path>" as written by sha256sum in binary mode
+    let mut entries = Vec::new();
+    for (n, line) in manifest.lines().enumerate() {
+        if line.trim().is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let parsed = line.split_once(' ').and_then(|(digest, rest)| {
+            let path = rest.strip_prefix(' ').or_else(|| rest.strip_prefix('*'))?;
+            let algorithm = args.algorithm.or_else(|| Algorithm::for_digest(digest))?;
+            Some((digest.to_lowercase(), PathBuf::from(path), algorithm))
+        });
+        match parsed {
+            Some(entry) => entries.push(entry),
+            None => {
+                return Err(SpeedyError::Parse(format!(
+                    "{} line {} is not a checksum line",
+                    args.target.display(),
+                    n + 1
+                )));
+            }
+        }
+    }
+
+    let pool = build_pool(Path::new("."), args.threads)?;
+    let results: Vec<_> = pool.install(|| {
+        entries
+            .par_iter()
+            .map(|(_, path, algorithm)| algorithm.hash_file(&longpath::to_extended(path)))
+            .collect()
+    });
+
+    let (mut ok, mut changed, mut missing) = (0, 0, 0);
+    for ((expected, path, _), result) in entries.iter().zip(results) {
+        match result {
+            Ok(actual) if &actual == expected => {
+                ok += 1;
+                if args.verbose {
+                    println!("✓ {}", path.display());
+                }
+            }
+            Ok(_) => {
+                changed += 1;
+                println!("✗ {}  (contents changed)", path.display());
+            }
+            Err(e) => {
+                missing += 1;
+                println!("✗ {}  ({})", path.display(), e);
+            }
+        }
+    }
+
+    if changed + missing == 0 {
+        if !args.quiet {
+            println!("✅ All {} file(s) verified in {:.2?}", ok, start.elapsed());
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+    if !args.quiet {
+        println!(
+            "❌ {} changed, {} unreadable or missing, {} OK ({:.2?})",
+            changed,
+            missing,
+            ok,
+            start.elapsed()
+        );
+    }
+    Ok(ExitCode::FAILURE)
+}
@@ -0,0 +1,796 @@
+// ========================= Localization =========================
+
+use std::fmt::Display; // Message arguments
+use std::sync::OnceLock; // The language is chosen once per run
+
+// Messages of one language as (key, text). Texts use {0}, {1}, ... for their arguments.
+// Keys a catalog leaves out fall back to English.
+pub type Catalog = &'static [(&'static str, &'static str)];
+
+// Available languages by their code (the part of LANG before '_', e.g. "es" for es_ES.UTF-8).
+// Adding a language means adding its catalog here.
+const CATALOGS: &[(&str, Catalog)] = &[("en", ENGLISH), ("es", SPANISH)];
+
+// Catalog picked by init()
+static ACTIVE: OnceLock<Catalog> = OnceLock::new();
+
+// Choose the language: --lang if given, otherwise LC_ALL, LC_MESSAGES or LANG.
+// Unknown languages use English.
+pub fn init(lang: Option<&str>) {
+    let requested = lang.map(str::to_string).or_else(|| {
+        ["LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|var| std::env::var(var).ok())
+            .find(|value| !value.is_empty())
+    });
+    let catalog = requested
+        .as_deref()
+        .map(language_code)
+        .and_then(|code| CATALOGS.iter().find(|(c, _)| *c == code))
+        .map_or(ENGLISH, |(_, catalog)| *catalog);
+    let _ = ACTIVE.set(catalog);
+}
+
+// "de_DE.UTF-8" -> "de", "pt-BR" -> "pt"
+fn language_code(value: &str) -> String {
+    value
+        .split(['_', '-', '.', '@'])
+        .next()
+        .unwrap_or_default()
+        .to_lowercase()
+}
+
+// The message for `key` in the active language
+pub fn text(key: &str) -> &'static str {
+    let lookup = |catalog: Catalog| catalog.iter().find(|(k, _)| *k == key).map(|(_, t)| *t);
+    ACTIVE
+        .get()
+        .and_then(|catalog| lookup(catalog))
+        .or_else(|| lookup(ENGLISH))
+        .unwrap_or("")
+}
+
+// The message for `key` with {0}, {1}, ... replaced by `args`. The text is read once from
+// left to right, so an argument containing "{1}" (e.g. a file name) is never substituted.
+// Other braces, and placeholders without an argument, are kept as they are.
+pub fn format(key: &str, args: &[&dyn Display]) -> String {
+    let mut rest = text(key);
+    let mut message = String::with_capacity(rest.len());
+    while let Some(open) = rest.find('{') {
+        message.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+        let digits = after.len() - after.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let arg = Some(&after[..digits])
+            .filter(|_| after[digits..].starts_with('}'))
+            .and_then(|index| index.parse::<usize>().ok())
+            .and_then(|index| args.get(index));
+        match arg {
+            Some(arg) => {
+                message.push_str(&arg.to_string());
+                rest = &after[digits + 1..];
+            }
+            None => {
+                message.push('{');
+                rest = after;
+            }
+        }
+    }
+    message.push_str(rest);
+    message
+}
+
+// ========================= English =========================
+
+const ENGLISH: Catalog = &[
+    // Entry kinds, as used in status messages
+    ("entry", "entry"),
+    ("file", "file"),
+    ("folder", "folder"),
+    ("symlink", "symlink"),
+    ("executable", "executable"),
+    // Search status
+    ("searching", "🔍 Searching for {0} \"{1}\" in {2}..."),
+    ("found_one", "🎯 Found matching {0} at:"),
+    ("found_many", "🎯 Found {0} matching {1}(s):"),
+    ("found_many_cancelled", "🎯 Found {0} matching {1}(s) before the search was cancelled:"),
+    ("found_in", "✅ Found \"{0}\" in {1}"),
+    ("not_found", "❌ Could not find \"{0}\" after {1}"),
+    ("did_you_mean", "💡 Did you mean:"),
+    ("cancelled_partial", "🛑 Search cancelled after {0}: partial results, {1} locations scanned"),
+    ("cancelled_none", "🛑 Search cancelled by user after {0} ({1} locations scanned, no match yet)"),
+    ("rerunning", "↻ Re-running #{0}: speedy {1}"),
+    ("elevated", "🛡️ Continuing the search in an elevated window"),
+    ("project_root", "📁 Project root: {0}"),
+    ("root_covered", "   ({0} is already searched as part of {1})"),
+    ("resuming", "⏩ Resuming: {0} folder(s) already searched"),
+    ("auto_threads", "   (Auto threads for {0}: {1}, up to {2} while I/O latency stays low)"),
+    ("also_searching", "   (Also searching {0})"),
+    ("depth_max", "   (Depth limited to {0} levels)"),
+    ("depth_min", "   (Only matches at least {0} levels deep)"),
+    ("depth_between", "   (Only matches between {0} and {1} levels deep)"),
+    ("profile", "   (Profile: {0})"),
+    ("network_retries", "   (Network share: transient errors are retried up to {0} times)"),
+    ("progress_saved", "💾 Progress saved ({0} folder(s) done); continue with --resume {1}"),
+    ("spilled", "💾 Results exceeded --max-memory and were sorted on disk"),
+    ("wrote_results", "💾 Wrote {0} result(s) to {1}"),
+    ("hardlinks_skipped", "🔗 Skipped {0} hard link(s) to files already listed"),
+    ("max_matches_reached", "✋ Stopped after {0} match(es) (--max-matches)"),
+    // --delete and --move-to
+    ("cleanup_trash_question", "Move {0} item(s) to the trash?"),
+    ("cleanup_move_question", "Move {0} item(s) to {1}?"),
+    ("cleanup_more", "   … and {0} more"),
+    ("cleanup_unconfirmed", "⚠️ {0} Not confirmed: pass --yes to run without a prompt"),
+    ("cleanup_prompt", "❓ {0} [y/N] "),
+    ("cleanup_cancelled", "⚠️ The search was cancelled, so no matches were moved"),
+    ("moved_to_trash", "🗑️ Moved {0} item(s) to the trash"),
+    ("moved_to", "📦 Moved {0} item(s) to {1}"),
+    ("move_failed", "⚠️ Could not move {0}: {1}"),
+    ("nothing_moved", "Nothing was moved."),
+    ("copied_results", "📋 Copied the results to the clipboard"),
+    ("copied_path", "📋 Copied the path to the clipboard"),
+    ("tip_verbose", "ℹ️ Tip: Try with --verbose to see search progress or permission issues"),
+    ("permissions_skipped", "🔒 Skipped {0} entries across {1} folder(s) due to permissions"),
+    (
+        "permissions_skipped_elevate",
+        "🔒 Skipped {0} entries across {1} folder(s) due to permissions; rerun with --elevate to include them",
+    ),
+    (
+        "permissions_skipped_sudo",
+        "🔒 Skipped {0} entries across {1} folder(s) due to permissions; rerun with sudo to include them",
+    ),
+    ("crashed", "💥 The search crashed after {0}."),
+    (
+        "crash_report",
+        "   A diagnostics report was written to {0}\n   Please attach it when reporting the problem.",
+    ),
+    ("crash_report_failed", "   Could not write a diagnostics report: {0}"),
+    // Bookmarks and history
+    ("bookmarked", "🔖 Bookmarked @{0} → {1}"),
+    ("bookmark_removed", "🗑️ Removed bookmark @{0}"),
+    ("no_bookmarks", "No bookmarks yet. Add one with 'speedy bookmark add <name> <path>'."),
+    ("no_history", "No searches recorded yet."),
+    ("ago", "{0} ago"),
+    ("rerun_hint", "Run 'speedy rerun <id>' to repeat a search."),
+    // Progress spinner
+    ("progress_bar", "{spinner} Searching... [{bar:30}] {percent}% {msg}"),
+    ("progress_spinner", "{spinner} Searching... {msg}"),
+    ("progress_scanned", "Scanned {0} locations"),
+    ("progress_rate", " · {0}/s"),
+    ("progress_eta", " · ETA {0}"),
+    ("progress_matches", " · {0} match(es)"),
+    // --dry-run
+    ("dry_run", "🧪 Dry run: nothing will be searched"),
+    ("dry_run_looking_for", "   Looking for:  {0} \"{1}\""),
+    ("dry_run_profile", "   Profile:      {0}"),
+    ("dry_run_root", "   Root:         {0}"),
+    ("dry_run_volume", "   Volume:       {0}"),
+    (
+        "dry_run_threads_auto",
+        "   Threads:      {0}, up to {1} while I/O latency stays low (picked for the volume)",
+    ),
+    ("dry_run_threads", "   Threads:      {0}"),
+    ("dry_run_unlimited", "unlimited"),
+    ("dry_run_max_depth", "   Max depth:    {0}"),
+    ("dry_run_strategy", "   Walk order:   {0}"),
+    ("dry_run_first", "   Result:       the first match in name order (--first)"),
+    ("dry_run_max_matches", "   Stop after:   {0} match(es)"),
+    ("dry_run_min_depth", "   Min depth:    {0} (shallower matches are not reported)"),
+    ("dry_run_time_filter", "   Time filter:  {0}"),
+    ("dry_run_one_file_system", "   File systems: stay on the root's file system"),
+    ("dry_run_low_priority", "   Priority:     low (throttled walk)"),
+    ("dry_run_follow_links", "   Links:        symbolic links to folders are followed"),
+    ("dry_run_skip", "   Skip rules:   {0}"),
+    (
+        "dry_run_root_skipped",
+        "   ⚠️ The root itself matches a skip rule, so nothing below it would be searched",
+    ),
+    ("dry_run_walks", "Walk order:"),
+    ("dry_run_walk", "  {0}. {1} (max depth {2}, {3})"),
+    ("dry_run_excludes", "       excludes {0} (already searched)"),
+    ("dry_run_top_level", "Top-level folders:"),
+    ("dry_run_list_failed", "  ⚠️ Could not list {0}: {1}"),
+    ("dry_run_no_folders", "  (none)"),
+    ("dry_run_skipped", "  ✗ {0} (skipped by skip rules)"),
+    // speedy pick
+    ("pick_nothing", "❌ Nothing to pick from: no paths on stdin"),
+    ("pick_action", "[p]rint (default), [o]pen, open [f]older or [c]opy? "),
+    ("pick_unknown_action", "⚠️ Unknown action '{0}'"),
+    ("pick_no_match", "  (no paths contain \"{0}\")"),
+    ("pick_more", "      … and {0} more (type to filter)"),
+    ("pick_hint_one", "Enter to pick it"),
+    ("pick_hint_number", "a number to pick"),
+    ("pick_prompt", "Filter: {0}, text to filter, - to clear, q to quit: "),
+    ("pick_prompt_filtered", "Filter \"{0}\": {1}, text to filter, - to clear, q to quit: "),
+    ("pick_no_entry", "⚠️ There is no entry {0}"),
+    ("pick_opened", "📂 Opened {0}"),
+    ("pick_opened_folder", "📂 Opened the folder containing {0}"),
+    ("pick_copied", "📋 Copied {0} to the clipboard"),
+    // speedy tune
+    ("tune_skipped", "ℹ️ {0} run(s) skipped: cut short or under {1} entries"),
+    ("tune_no_runs", "❌ No complete runs to learn from yet; search with --telemetry-file first"),
+    ("tune_volume", "💽 {0} ({1} run(s))"),
+    ("tune_auto", "   --threads auto       {0} entries/s ({1} run(s))"),
+    ("tune_ratio", "   {0}× CPUs           {1} entries/s ({2} run(s))"),
+    (
+        "tune_suggested",
+        "   ➜ Suggested: --threads {0} on this machine ({1}× its {2} CPUs; the automatic count is {3})",
+    ),
+    ("tune_default", "     To make it the default: SPEEDY_THREADS={0}"),
+    ("tune_keep_auto", "   ➜ Keep --threads auto: no fixed count was clearly faster"),
+    ("tune_only_auto", "   ➜ Only automatic runs so far; search with --threads <n> too to compare"),
+    // Errors
+    ("error", "❌ {0}"),
+    ("error_io", "IO error: {0}"),
+    ("error_parse", "Parse error: {0}"),
+    ("error_argument", "Argument error: {0}"),
+    ("error_walk", "Directory walk error: {0}"),
+    ("error_thread_pool", "Thread pool error: {0}"),
+    ("error_notification", "Notification error: {0}"),
+    ("error_ctrlc", "Ctrl-C handler error: {0}"),
+    ("error_template", "Template error: {0}"),
+    ("error_clipboard", "Clipboard error: {0}"),
+    ("error_update", "Update error: {0}"),
+    // Short usage, shown when a command is incomplete
+    (
+        "usage",
+        r#"Usage:
+  speedy search <name> [--type f|d|l|x] [--global]
+  speedy search:file <name> [--global]
+  speedy search:folder <name> [--global]
+  speedy search:file <name> [--path <custom_path>]
+  speedy history | speedy rerun <id>
+  speedy bookmark add <name> <path>   (then search with --path @name)
+  <command> | speedy pick [--action print|open|folder|copy]
+  speedy hash <path> [--algo sha256|sha512] > manifest | speedy verify <manifest>
+  speedy rename <regex> <replacement> [--path <dir>] [--dry-run]
+  speedy updatedb [--path <root>] | speedy locate <pattern> [-i] [-b]
+  speedy search:recycled <name> [--deleted-within <age>] [--shadow]
+  speedy search:app <name> [--all]
+  speedy search:content <text> [--path <dir>] [--context <n>] [-i] [--search-compressed]
+  eval "$(speedy shell-init bash)"   (then: sdcd <folder name>)
+  speedy self-update [--check]
+  speedy tune <telemetry-file>...
+Options:
+  --verbose       Show all warnings
+  --quiet         Suppress non-essential output
+  --depth <num>   Limit search depth (default: unlimited)
+  --min-depth <num> Only report matches at least this deep
+  --project       Search from the enclosing project (.git, Cargo.toml, package.json)
+  --created-within <age> Only entries created recently, e.g. 7d (also --modified-, --accessed-within)
+  --mime <type>   Match by file content, e.g. image/* or application/pdf
+  --notify        Show desktop notification when found
+  --threads <num> Set number of threads (default: auto)
+  --all           List every match instead of stopping at the first
+  --max-matches <n> List up to n matches, then stop
+  --first         Report the first match in name order (the same on every run)
+  --absolute      Print canonical absolute paths
+  --relative [root|cwd] Print paths relative to the search root or current dir
+  --dedup-hardlinks With --all, list hard-linked files once
+  --copy          Copy the found path(s) to the clipboard
+  --delete | --move-to <dir> Move the matches to the trash / a folder (asks first)
+  --low-priority  Run in the background without slowing the machine
+  --output-file <file> Write results to a file as they are found
+  --max-memory <size> Keep --all results within a memory budget, e.g. 512M (spills to disk)
+  --format <tpl>  Print results using a template, e.g. "{path}\t{size}"
+  --output json   Print matches, warnings and a summary as JSON lines
+  --profile <name> Preset: fast, balanced (default) or thorough
+  --dry-run       Show what would be searched without searching
+  --backend <name> auto, walk, everything or windows-search (Windows indexes)
+  --strategy <s>  Walk order: dfs (default) or bfs (shallow matches first)
+  --log-level <lvl> Log level: off, error, warn, info, debug, trace
+  --log-file <file> Append log output to a file
+  --lang <code>   Language for messages, e.g. en or es (default: from LANG); goes
+                  before the command: speedy --lang es search <name>
+
+For more information, try 'speedy --help'
+"#,
+    ),
+    // Full --help text
+    (
+        "help",
+        r#"Speedy - A fast file and folder search tool
+
+USAGE:
+  speedy search <name> [options]
+  speedy search:file <name> [options]     (same as search --type f)
+  speedy search:folder <name> [options]   (same as search --type d)
+  speedy search:recycled <name>           Find deleted entries in the Recycle Bin / trash
+                                          (also inside deleted folders) with their original
+                                          path and deletion time; --path <dir> limits to
+                                          what was deleted from <dir>, --deleted-within <age>
+                                          to recent deletions, --shadow (Windows, elevated)
+                                          also looks in Volume Shadow Copies
+  speedy self-update [--check]            Install the latest release for this platform
+                                          (SHA-256 checked); --check only reports it
+  speedy tune <telemetry-file>...         Suggest --threads per kind of storage from runs
+                                          recorded with --telemetry-file
+  speedy search:app <name>                Show the program a shell would start for <name>
+                                          (PATH, PATHEXT, and App Paths on Windows) and
+                                          the file it resolves to; --all lists shadowed
+                                          copies too
+  speedy search:content <text>            List the lines of files below --path that contain
+                                          <text>, grep-style, matches highlighted;
+                                          --context <n> (-C) adds n lines before and after
+                                          each match, -i ignores ASCII case; binary files
+                                          are skipped unless --binary (report whether they
+                                          match) or --text (read everything as text);
+                                          --search-compressed also reads .gz, .zst and .xz
+                                          files (e.g. rotated logs) decompressed
+  speedy history                          List recent searches
+  speedy rerun <id>                       Repeat a search from the history
+  speedy bookmark add <name> <path>       Name a search root, used as --path @name
+  speedy bookmark remove <name> | list    Manage bookmarks
+  speedy hash <path> [--algo <a>]         Checksum every file below <path> (sha256 or
+                                          sha512) in parallel; writes a manifest
+  speedy verify <manifest>                Re-hash the files in a manifest and list changes
+  speedy rename <regex> <replacement>     Rename matching entries below --path, using $1,
+                                          ${name} for capture groups (--dry-run to preview)
+  speedy updatedb [--path <root>]         Index <root> (default /) into an mlocate database
+  speedy locate <pattern> [-i] [-b] [-l n] Print indexed paths containing <pattern>;
+                                          --database <file> reads another (e.g. mlocate's)
+  <command> | speedy pick [--action <a>]  Pick one of the paths piped in and print,
+                                          open, open the folder of or copy it
+                                          (--select-1 takes a lone path without asking)
+  speedy shell-init <shell>               Print the sdcd function for bash, zsh, fish or
+                                          powershell: `sdcd <name>` finds folders named
+                                          <name>, lets you pick one and cds into it
+
+OPTIONS:
+  --type <f|d|l|x>   Only match files, directories, symlinks or executables
+                     (comma-separated for several, e.g. --type f,l)
+  --owner <user>     Only match entries owned by a user name or ID (Unix)
+  --perm <mode>      Match permission bits: 644 exact, -002 all set, /022 any set (Unix)
+  --attr <list>      Require Windows attributes: hidden,readonly,system,archive,reparse,compressed
+  --modified-within <age>
+                     Only entries modified in the last <age>, e.g. 30m, 12h, 7d, 2w
+  --created-within <age>
+                     Only entries created in the last <age> (where the file system records it)
+  --accessed-within <age>
+                     Only entries read in the last <age> (access times may be updated lazily)
+  --mime <types>     Match by file content (magic bytes), not extension, e.g.
+                     image/*, application/pdf (comma-separated for several)
+  --ignore-accents   Match names regardless of accents (Munchen finds München,
+                     strasse finds Straße)
+  --global           Search the entire system (default: current directory)
+  --path <path>      Search in a specific directory, or @name for a bookmark;
+                     repeat it to search several (overlapping ones are walked once)
+  --project          Search from the nearest folder above the current one with a
+                     .git, Cargo.toml or package.json (the project root)
+  --verbose          Show detailed search information, skipped folders and timings
+  --quiet            Suppress non-essential output
+  --depth <num>      Limit search depth (default: unlimited)
+  --min-depth <num>  Only report matches at least this many levels deep
+  --elevate          Rerun the search as administrator via UAC (Windows)
+  --one-file-system  Don't descend into other drives or mounted file systems
+  --notify           Show desktop notification when found (with Open / Open folder /
+                     Copy path actions where the desktop supports them)
+  --threads <num>    Set number of threads, or 'auto' to tune per volume (default: auto)
+  --stop-after-match Stop searching after first match is found
+  --max-matches <n>  List up to n matches, then stop searching (exactly n when
+                     there are that many)
+  --first            Report the first match in name order, the same on every run
+                     (folders are read sorted; for scripts that need reproducibility)
+  --all              List every match instead of stopping at the first
+  --absolute         Print results as canonical absolute paths
+  --relative [base]  Print results relative to the search root (default) or, with
+                     'cwd', to the current directory
+  --dedup-hardlinks  With --all, report a file with several hard links only once
+  --copy             Copy the found path (or all paths with --all) to the clipboard
+  --delete           Move the matches to the recycle bin / trash (after confirming)
+  --move-to <dir>    Move the matches into <dir> (after confirming)
+  --yes              Don't ask before --delete / --move-to
+  --low-priority     Lower CPU/I/O priority and throttle the search
+  --checkpoint <f>   Save progress to a file so an interrupted search can resume
+  --resume <f>       Continue a search from its checkpoint file (same command)
+  --telemetry-file <f> Append how fast the walk went (storage kind, threads,
+                     entries/s; no paths or names) to <f> for `speedy tune`
+  --output-file <f>  Write results to a file as they are found (uses --format)
+  --max-memory <sz>  Memory budget for --all results, e.g. 512M or 2G; beyond it they
+                     are sorted on disk in temporary files
+  --format <tpl>     Print each result using a template; fields:
+                     {path} {name} {parent} {ext} {size} {mtime} {type}
+                     plus {git} (git status) and {width} {height} {dimensions} (images)
+  --output <mode>    text (default) or json: one JSON object per line, with "match",
+                     "warning" (permission_denied, traversal, network_retry) and a
+                     closing "summary" event; warnings no longer go to stderr
+  --profile <name>   Search preset: fast (prunes build/cache and hidden folders, stops
+                     at the first match), balanced (default) or thorough (no skip
+                     list, follows symbolic links)
+  --dry-run          Print the search plan (roots, skip rules, depth, top-level folders)
+                     without searching
+  --backend <name>   How folders are enumerated: auto (default), walk, or on Windows
+                     everything / windows-search to ask an existing index
+  --strategy <s>     Walk order: dfs (default, depth-first) or bfs (breadth-first, finds
+                     shallow matches sooner with --stop-after-match)
+  --log-level <lvl>  Log verbosity: off, error, warn, info, debug, trace
+                     (default: warn, info with --verbose or --log-file)
+  --log-file <file>  Append log output to a file instead of the terminal
+  --lang <code>      Language for messages: en or es (default: LC_ALL, LC_MESSAGES or LANG);
+                     it goes before the command, e.g. speedy --lang es history
+  --help             Show this help message
+
+ENVIRONMENT (defaults; command-line flags win):
+  SPEEDY_THREADS     Thread count or 'auto' when --threads isn't given
+  SPEEDY_SKIP        Extra folder names to skip, comma-separated (e.g. dist,.cache)
+  SPEEDY_OUTPUT      text or json when neither --output nor --format is given
+  SPEEDY_NO_NOTIFY   Set (to anything but 0) to turn --notify off, e.g. in CI
+  SPEEDY_UPDATE_FEED Release description self-update reads instead of GitHub's
+
+EXAMPLES:
+  speedy search:file document.txt --global
+  speedy search:folder Projects --path ~/work
+  speedy search python3 --type x --path /usr
+  speedy search:file config.ini --depth 3 --notify
+  speedy search:file report.pdf --format "{path}\t{size}\t{mtime}"
+
+PERFORMANCE TIPS:
+  - Use --global only when necessary
+  - Limit search depth with --depth for faster results
+  - For large searches, use --threads to control CPU usage
+  - Use --stop-after-match when you only need the first result
+  - Use --low-priority for long --global searches while you keep working
+"#,
+    ),
+];
+
+// ========================= Spanish =========================
+
+const SPANISH: Catalog = &[
+    // Entry kinds, as used in status messages
+    ("entry", "entrada"),
+    ("file", "archivo"),
+    ("folder", "carpeta"),
+    ("symlink", "enlace simbólico"),
+    ("executable", "ejecutable"),
+    // Search status
+    ("searching", "🔍 Buscando {0} \"{1}\" en {2}..."),
+    ("found_one", "🎯 Se encontró {0} en:"),
+    ("found_many", "🎯 Se encontraron {0} coincidencias ({1}):"),
+    ("found_many_cancelled", "🎯 Se encontraron {0} coincidencias ({1}) antes de cancelar la búsqueda:"),
+    ("found_in", "✅ \"{0}\" encontrado en {1}"),
+    ("not_found", "❌ No se encontró \"{0}\" tras {1}"),
+    ("did_you_mean", "💡 ¿Quisiste decir?"),
+    ("cancelled_partial", "🛑 Búsqueda cancelada tras {0}: resultados parciales, {1} ubicaciones revisadas"),
+    ("cancelled_none", "🛑 Búsqueda cancelada tras {0} ({1} ubicaciones revisadas, sin coincidencias)"),
+    ("rerunning", "↻ Repitiendo #{0}: speedy {1}"),
+    ("elevated", "🛡️ La búsqueda continúa en una ventana con permisos de administrador"),
+    ("project_root", "📁 Raíz del proyecto: {0}"),
+    ("root_covered", "   ({0} ya se busca como parte de {1})"),
+    ("resuming", "⏩ Reanudando: {0} carpeta(s) ya revisada(s)"),
+    ("auto_threads", "   (Hilos automáticos para {0}: {1}, hasta {2} mientras la latencia de E/S sea baja)"),
+    ("also_searching", "   (También se busca en {0})"),
+    ("depth_max", "   (Profundidad limitada a {0} niveles)"),
+    ("depth_min", "   (Solo coincidencias con al menos {0} niveles de profundidad)"),
+    ("depth_between", "   (Solo coincidencias entre {0} y {1} niveles de profundidad)"),
+    ("profile", "   (Perfil: {0})"),
+    ("network_retries", "   (Recurso de red: los errores transitorios se reintentan hasta {0} veces)"),
+    ("progress_saved", "💾 Progreso guardado ({0} carpeta(s) terminada(s)); continúa con --resume {1}"),
+    ("spilled", "💾 Los resultados superaron --max-memory y se ordenaron en disco"),
+    ("wrote_results", "💾 Se escribieron {0} resultado(s) en {1}"),
+    ("hardlinks_skipped", "🔗 Se omitieron {0} enlace(s) duro(s) a archivos ya listados"),
+    ("max_matches_reached", "✋ Se paró tras {0} coincidencia(s) (--max-matches)"),
+    // --delete and --move-to
+    ("cleanup_trash_question", "¿Mover {0} elemento(s) a la papelera?"),
+    ("cleanup_move_question", "¿Mover {0} elemento(s) a {1}?"),
+    ("cleanup_more", "   … y {0} más"),
+    ("cleanup_unconfirmed", "⚠️ {0} Sin confirmar: usa --yes para hacerlo sin preguntar"),
+    ("cleanup_prompt", "❓ {0} [s/N] "),
+    ("cleanup_cancelled", "⚠️ La búsqueda se canceló, así que no se movió ninguna coincidencia"),
+    ("moved_to_trash", "🗑️ Se movieron {0} elemento(s) a la papelera"),
+    ("moved_to", "📦 Se movieron {0} elemento(s) a {1}"),
+    ("move_failed", "⚠️ No se pudo mover {0}: {1}"),
+    ("nothing_moved", "No se movió nada."),
+    ("copied_results", "📋 Resultados copiados al portapapeles"),
+    ("copied_path", "📋 Ruta copiada al portapapeles"),
+    ("tip_verbose", "ℹ️ Consejo: usa --verbose para ver el avance de la búsqueda o problemas de permisos"),
+    ("permissions_skipped", "🔒 Se omitieron {0} entradas en {1} carpeta(s) por falta de permisos"),
+    (
+        "permissions_skipped_elevate",
+        "🔒 Se omitieron {0} entradas en {1} carpeta(s) por falta de permisos; repite con --elevate para incluirlas",
+    ),
+    (
+        "permissions_skipped_sudo",
+        "🔒 Se omitieron {0} entradas en {1} carpeta(s) por falta de permisos; repite con sudo para incluirlas",
+    ),
+    ("crashed", "💥 La búsqueda falló tras {0}."),
+    (
+        "crash_report",
+        "   Se escribió un informe de diagnóstico en {0}\n   Adjúntalo al informar del problema.",
+    ),
+    ("crash_report_failed", "   No se pudo escribir un informe de diagnóstico: {0}"),
+    // Bookmarks and history
+    ("bookmarked", "🔖 Marcador @{0} → {1}"),
+    ("bookmark_removed", "🗑️ Marcador @{0} eliminado"),
+    ("no_bookmarks", "Aún no hay marcadores. Añade uno con 'speedy bookmark add <nombre> <ruta>'."),
+    ("no_history", "Aún no hay búsquedas registradas."),
+    ("ago", "hace {0}"),
+    ("rerun_hint", "Usa 'speedy rerun <id>' para repetir una búsqueda."),
+    // Progress spinner
+    ("progress_bar", "{spinner} Buscando... [{bar:30}] {percent}% {msg}"),
+    ("progress_spinner", "{spinner} Buscando... {msg}"),
+    ("progress_scanned", "{0} ubicaciones revisadas"),
+    ("progress_rate", " · {0}/s"),
+    ("progress_eta", " · faltan {0}"),
+    ("progress_matches", " · {0} coincidencia(s)"),
+    // --dry-run
+    ("dry_run", "🧪 Simulación: no se buscará nada"),
+    ("dry_run_looking_for", "   Se busca:        {0} \"{1}\""),
+    ("dry_run_profile", "   Perfil:          {0}"),
+    ("dry_run_root", "   Raíz:            {0}"),
+    ("dry_run_volume", "   Volumen:         {0}"),
+    (
+        "dry_run_threads_auto",
+        "   Hilos:           {0}, hasta {1} mientras la latencia de E/S sea baja (elegidos para el volumen)",
+    ),
+    ("dry_run_threads", "   Hilos:           {0}"),
+    ("dry_run_unlimited", "ilimitada"),
+    ("dry_run_max_depth", "   Profundidad máx: {0}"),
+    ("dry_run_strategy", "   Orden:           {0}"),
+    ("dry_run_first", "   Resultado:       la primera coincidencia por nombre (--first)"),
+    ("dry_run_max_matches", "   Parar tras:      {0} coincidencia(s)"),
+    ("dry_run_min_depth", "   Profundidad mín: {0} (no se muestran coincidencias menos profundas)"),
+    ("dry_run_time_filter", "   Filtro de fecha: {0}"),
+    ("dry_run_one_file_system", "   Sistemas:        solo el sistema de archivos de la raíz"),
+    ("dry_run_low_priority", "   Prioridad:       baja (recorrido limitado)"),
+    ("dry_run_follow_links", "   Enlaces:         se siguen los enlaces simbólicos a carpetas"),
+    ("dry_run_skip", "   Exclusiones:     {0}"),
+    (
+        "dry_run_root_skipped",
+        "   ⚠️ La propia raíz coincide con una regla de exclusión, así que no se buscaría nada dentro",
+    ),
+    ("dry_run_walks", "Orden de recorrido:"),
+    ("dry_run_walk", "  {0}. {1} (profundidad máx. {2}, {3})"),
+    ("dry_run_excludes", "       excluye {0} (ya buscada)"),
+    ("dry_run_top_level", "Carpetas de primer nivel:"),
+    ("dry_run_list_failed", "  ⚠️ No se pudo listar {0}: {1}"),
+    ("dry_run_no_folders", "  (ninguna)"),
+    ("dry_run_skipped", "  ✗ {0} (excluida por las reglas)"),
+    // speedy pick
+    ("pick_nothing", "❌ Nada que elegir: no llegaron rutas por stdin"),
+    ("pick_action", "[p] mostrar (por defecto), [o] abrir, abrir [f] carpeta o [c] copiar? "),
+    ("pick_unknown_action", "⚠️ Acción desconocida '{0}'"),
+    ("pick_no_match", "  (ninguna ruta contiene \"{0}\")"),
+    ("pick_more", "      … y {0} más (escribe para filtrar)"),
+    ("pick_hint_one", "Intro para elegirla"),
+    ("pick_hint_number", "un número para elegir"),
+    ("pick_prompt", "Filtro: {0}, texto para filtrar, - para borrar, q para salir: "),
+    (
+        "pick_prompt_filtered",
+        "Filtro \"{0}\": {1}, texto para filtrar, - para borrar, q para salir: ",
+    ),
+    ("pick_no_entry", "⚠️ No existe la entrada {0}"),
+    ("pick_opened", "📂 Se abrió {0}"),
+    ("pick_opened_folder", "📂 Se abrió la carpeta que contiene {0}"),
+    ("pick_copied", "📋 {0} copiado al portapapeles"),
+    // speedy tune
+    ("tune_skipped", "ℹ️ {0} ejecución(es) omitida(s): interrumpidas o con menos de {1} entradas"),
+    (
+        "tune_no_runs",
+        "❌ Aún no hay ejecuciones completas de las que aprender; busca con --telemetry-file primero",
+    ),
+    ("tune_volume", "💽 {0} ({1} ejecución(es))"),
+    ("tune_auto", "   --threads auto       {0} entradas/s ({1} ejecución(es))"),
+    ("tune_ratio", "   {0}× CPU             {1} entradas/s ({2} ejecución(es))"),
+    (
+        "tune_suggested",
+        "   ➜ Sugerencia: --threads {0} en este equipo ({1}× sus {2} CPU; el valor automático es {3})",
+    ),
+    ("tune_default", "     Para usarlo por defecto: SPEEDY_THREADS={0}"),
+    ("tune_keep_auto", "   ➜ Mantén --threads auto: ningún valor fijo fue claramente más rápido"),
+    (
+        "tune_only_auto",
+        "   ➜ Solo hay ejecuciones automáticas; busca también con --threads <n> para comparar",
+    ),
+    // Errors
+    ("error_io", "Error de E/S: {0}"),
+    ("error_parse", "Error de formato: {0}"),
+    ("error_argument", "Error en los argumentos: {0}"),
+    ("error_walk", "Error al recorrer carpetas: {0}"),
+    ("error_thread_pool", "Error del grupo de hilos: {0}"),
+    ("error_notification", "Error de notificación: {0}"),
+    ("error_ctrlc", "Error del manejador de Ctrl-C: {0}"),
+    ("error_template", "Error de plantilla: {0}"),
+    ("error_clipboard", "Error del portapapeles: {0}"),
+    ("error_update", "Error de actualización: {0}"),
+    // Short usage, shown when a command is incomplete
+    (
+        "usage",
+        r#"Uso:
+  speedy search <nombre> [--type f|d|l|x] [--global]
+  speedy search:file <nombre> [--global]
+  speedy search:folder <nombre> [--global]
+  speedy search:file <nombre> [--path <ruta>]
+  speedy history | speedy rerun <id>
+  speedy bookmark add <nombre> <ruta>   (después busca con --path @nombre)
+  <comando> | speedy pick [--action print|open|folder|copy]
+  speedy hash <ruta> [--algo sha256|sha512] > manifiesto | speedy verify <manifiesto>
+  speedy rename <regex> <reemplazo> [--path <carpeta>] [--dry-run]
+  speedy updatedb [--path <raíz>] | speedy locate <patrón> [-i] [-b]
+  speedy search:recycled <nombre> [--deleted-within <antigüedad>] [--shadow]
+  speedy search:app <nombre> [--all]
+  speedy search:content <texto> [--path <carpeta>] [--context <n>] [-i] [--search-compressed]
+  eval "$(speedy shell-init bash)"   (después: sdcd <nombre de carpeta>)
+  speedy self-update [--check]
+  speedy tune <archivo-de-telemetría>...
+Opciones:
+  --verbose       Muestra todos los avisos
+  --quiet         Omite los mensajes no esenciales
+  --depth <num>   Limita la profundidad de búsqueda (por defecto: sin límite)
+  --min-depth <num> Solo coincidencias con al menos esta profundidad
+  --project       Busca desde el proyecto que la contiene (.git, Cargo.toml, package.json)
+  --created-within <antigüedad> Solo entradas creadas hace poco, p. ej. 7d (también --modified-, --accessed-within)
+  --mime <tipo>   Busca por contenido, p. ej. image/* o application/pdf
+  --notify        Muestra una notificación de escritorio al encontrar
+  --threads <num> Número de hilos (por defecto: auto)
+  --all           Lista todas las coincidencias en lugar de parar en la primera
+  --max-matches <n> Lista hasta n coincidencias y después para
+  --first         Informa de la primera coincidencia por orden de nombre (igual en cada ejecución)
+  --absolute      Muestra rutas absolutas canónicas
+  --relative [root|cwd] Muestra rutas relativas a la raíz de búsqueda o a la carpeta actual
+  --dedup-hardlinks Con --all, lista una sola vez los archivos con enlaces duros
+  --copy          Copia la(s) ruta(s) encontrada(s) al portapapeles
+  --delete | --move-to <carpeta> Mueve las coincidencias a la papelera / a una carpeta (pregunta antes)
+  --low-priority  Se ejecuta en segundo plano sin ralentizar el equipo
+  --output-file <archivo> Escribe los resultados en un archivo a medida que se encuentran
+  --max-memory <tamaño> Mantiene los resultados de --all dentro de un límite de memoria, p. ej. 512M (usa el disco)
+  --format <plantilla> Muestra los resultados con una plantilla, p. ej. "{path}\t{size}"
+  --output json   Muestra coincidencias, avisos y un resumen como líneas JSON
+  --profile <nombre> Perfil: fast, balanced (por defecto) o thorough
+  --dry-run       Muestra qué se buscaría sin buscar
+  --backend <nombre> auto, walk, everything o windows-search (índices de Windows)
+  --strategy <s>  Orden del recorrido: dfs (por defecto) o bfs (primero las coincidencias poco profundas)
+  --log-level <nivel> Nivel de registro: off, error, warn, info, debug, trace
+  --log-file <archivo> Añade el registro a un archivo
+  --lang <código> Idioma de los mensajes, p. ej. en o es (por defecto: según LANG); va
+                  antes del comando: speedy --lang es search <nombre>
+
+Para más información, prueba 'speedy --help'
+"#,
+    ),
+    // Full --help text
+    (
+        "help",
+        r#"Speedy - Una herramienta rápida para buscar archivos y carpetas
+
+USO:
+  speedy search <nombre> [opciones]
+  speedy search:file <nombre> [opciones]     (igual que search --type f)
+  speedy search:folder <nombre> [opciones]   (igual que search --type d)
+  speedy search:recycled <nombre>            Busca entradas eliminadas en la Papelera de reciclaje /
+                                             papelera (también dentro de carpetas eliminadas) con su
+                                             ruta original y la fecha de eliminación; --path <carpeta>
+                                             se limita a lo eliminado de <carpeta>, --deleted-within
+                                             <antigüedad> a eliminaciones recientes, --shadow (Windows,
+                                             como administrador) también mira en las instantáneas de volumen
+  speedy self-update [--check]               Instala la última versión para esta plataforma
+                                             (comprobada con SHA-256); --check solo la informa
+  speedy tune <archivo-de-telemetría>...     Sugiere --threads por tipo de almacenamiento a partir
+                                             de ejecuciones registradas con --telemetry-file
+  speedy search:app <nombre>                 Muestra el programa que un shell iniciaría para <nombre>
+                                             (PATH, PATHEXT y App Paths en Windows) y el archivo
+                                             al que corresponde; --all lista también las copias
+                                             ocultas por él
+  speedy search:content <texto>              Lista las líneas de los archivos bajo --path que
+                                             contienen <texto>, al estilo de grep, resaltadas;
+                                             --context <n> (-C) añade n líneas antes y después de
+                                             cada coincidencia, -i ignora mayúsculas ASCII; los
+                                             archivos binarios se omiten salvo con --binary (indica
+                                             si coinciden) o --text (lo lee todo como texto);
+                                             --search-compressed también lee archivos .gz, .zst y
+                                             .xz (p. ej. registros rotados) descomprimidos
+  speedy history                             Lista las búsquedas recientes
+  speedy rerun <id>                          Repite una búsqueda del historial
+  speedy bookmark add <nombre> <ruta>        Da nombre a una raíz de búsqueda, usada como --path @nombre
+  speedy bookmark remove <nombre> | list     Gestiona los marcadores
+  speedy hash <ruta> [--algo <a>]            Calcula en paralelo la suma de cada archivo bajo <ruta>
+                                             (sha256 o sha512); escribe un manifiesto
+  speedy verify <manifiesto>                 Vuelve a calcular las sumas de un manifiesto y lista los cambios
+  speedy rename <regex> <reemplazo>          Renombra las entradas coincidentes bajo --path, con $1 o
+                                             ${nombre} para los grupos de captura (--dry-run para previsualizar)
+  speedy updatedb [--path <raíz>]            Indexa <raíz> (por defecto /) en una base de datos mlocate
+  speedy locate <patrón> [-i] [-b] [-l n]    Muestra las rutas indexadas que contienen <patrón>;
+                                             --database <archivo> lee otra (p. ej. la de mlocate)
+  <comando> | speedy pick [--action <a>]     Elige una de las rutas recibidas y la muestra, la abre,
+                                             abre su carpeta o la copia
+                                             (--select-1 toma una ruta única sin preguntar)
+  speedy shell-init <shell>                  Muestra la función sdcd para bash, zsh, fish o
+                                             powershell: `sdcd <nombre>` busca carpetas llamadas
+                                             <nombre>, te deja elegir una y entra en ella
+
+OPCIONES:
+  --type <f|d|l|x>   Solo archivos, carpetas, enlaces simbólicos o ejecutables
+                     (separados por comas para varios, p. ej. --type f,l)
+  --owner <usuario>  Solo entradas de un nombre o ID de usuario (Unix)
+  --perm <modo>      Bits de permiso: 644 exacto, -002 todos, /022 alguno (Unix)
+  --attr <lista>     Atributos de Windows requeridos: hidden,readonly,system,archive,reparse,compressed
+  --modified-within <antigüedad>
+                     Solo entradas modificadas en el último periodo, p. ej. 30m, 12h, 7d, 2w
+  --created-within <antigüedad>
+                     Solo entradas creadas en el último periodo (si el sistema de archivos lo registra)
+  --accessed-within <antigüedad>
+                     Solo entradas leídas en el último periodo (el acceso puede registrarse con retraso)
+  --mime <tipos>     Busca por contenido (bytes mágicos), no por extensión, p. ej.
+                     image/*, application/pdf (separados por comas para varios)
+  --ignore-accents   Compara nombres sin tener en cuenta los acentos (Munchen encuentra
+                     München, strasse encuentra Straße)
+  --global           Busca en todo el sistema (por defecto: la carpeta actual)
+  --path <ruta>      Busca en una carpeta concreta, o @nombre para un marcador;
+                     repítela para buscar en varias (las que se solapan se recorren una vez)
+  --project          Busca desde la carpeta más cercana por encima de la actual que tenga
+                     .git, Cargo.toml o package.json (la raíz del proyecto)
+  --verbose          Muestra información detallada, carpetas omitidas y tiempos
+  --quiet            Omite los mensajes no esenciales
+  --depth <num>      Limita la profundidad de búsqueda (por defecto: sin límite)
+  --min-depth <num>  Solo coincidencias con al menos esta profundidad
+  --elevate          Repite la búsqueda como administrador mediante UAC (Windows)
+  --one-file-system  No entra en otras unidades ni sistemas de archivos montados
+  --notify           Muestra una notificación de escritorio al encontrar (con acciones
+                     Abrir / Abrir carpeta / Copiar ruta donde el escritorio las admite)
+  --threads <num>    Número de hilos, o 'auto' para ajustarlo al volumen (por defecto: auto)
+  --stop-after-match Deja de buscar tras la primera coincidencia
+  --max-matches <n>  Lista hasta n coincidencias y deja de buscar (exactamente n si
+                     hay tantas)
+  --first            Informa de la primera coincidencia por orden de nombre, igual en cada
+                     ejecución (las carpetas se leen ordenadas; para scripts reproducibles)
+  --all              Lista todas las coincidencias en lugar de parar en la primera
+  --absolute         Muestra los resultados como rutas absolutas canónicas
+  --relative [base]  Muestra los resultados relativos a la raíz de búsqueda (por defecto) o,
+                     con 'cwd', a la carpeta actual
+  --dedup-hardlinks  Con --all, informa una sola vez de un archivo con varios enlaces duros
+  --copy             Copia la ruta encontrada (o todas con --all) al portapapeles
+  --delete           Mueve las coincidencias a la papelera (tras confirmar)
+  --move-to <carpeta> Mueve las coincidencias a <carpeta> (tras confirmar)
+  --yes              No pregunta antes de --delete / --move-to
+  --low-priority     Reduce la prioridad de CPU/E/S y modera la búsqueda
+  --checkpoint <f>   Guarda el progreso en un archivo para reanudar una búsqueda interrumpida
+  --resume <f>       Continúa una búsqueda desde su archivo de punto de control (mismo comando)
+  --telemetry-file <f> Añade a <f> la velocidad del recorrido (tipo de almacenamiento, hilos,
+                     entradas/s; sin rutas ni nombres) para `speedy tune`
+  --output-file <f>  Escribe los resultados en un archivo a medida que se encuentran (usa --format)
+  --max-memory <tam> Límite de memoria para los resultados de --all, p. ej. 512M o 2G; por encima
+                     se ordenan en disco en archivos temporales
+  --format <plant.>  Muestra cada resultado con una plantilla; campos:
+                     {path} {name} {parent} {ext} {size} {mtime} {type}
+                     más {git} (estado de git) y {width} {height} {dimensions} (imágenes)
+  --output <modo>    text (por defecto) o json: un objeto JSON por línea, con eventos "match",
+                     "warning" (permission_denied, traversal, network_retry) y un evento
+                     "summary" final; los avisos ya no van a stderr
+  --profile <nombre> Perfil de búsqueda: fast (omite carpetas de compilación, caché y ocultas,
+                     para en la primera coincidencia), balanced (por defecto) o thorough (sin
+                     lista de omisión, sigue enlaces simbólicos)
+  --dry-run          Muestra el plan de búsqueda (raíces, reglas de omisión, profundidad,
+                     carpetas de primer nivel) sin buscar
+  --backend <nombre> Cómo se enumeran las carpetas: auto (por defecto), walk o, en Windows,
+                     everything / windows-search para consultar un índice existente
+  --strategy <s>     Orden del recorrido: dfs (por defecto, en profundidad) o bfs (en anchura,
+                     encuentra antes las coincidencias poco profundas con --stop-after-match)
+  --log-level <nivel> Detalle del registro: off, error, warn, info, debug, trace
+                     (por defecto: warn, info con --verbose o --log-file)
+  --log-file <archivo> Añade el registro a un archivo en lugar de la terminal
+  --lang <código>    Idioma de los mensajes: en o es (por defecto: LC_ALL, LC_MESSAGES o LANG);
+                     va antes del comando, p. ej. speedy --lang es history
+  --help             Muestra este mensaje de ayuda
+
+ENTORNO (valores por defecto; las opciones de la línea de comandos tienen prioridad):
+  SPEEDY_THREADS     Número de hilos o 'auto' cuando no se indica --threads
+  SPEEDY_SKIP        Más nombres de carpeta que omitir, separados por comas (p. ej. dist,.cache)
+  SPEEDY_OUTPUT      text o json cuando no se indica ni --output ni --format
+  SPEEDY_NO_NOTIFY   Si tiene valor (distinto de 0) desactiva --notify, p. ej. en CI
+  SPEEDY_UPDATE_FEED Descripción de versiones que self-update lee en lugar de la de GitHub
+
+EJEMPLOS:
+  speedy search:file documento.txt --global
+  speedy search:folder Proyectos --path ~/trabajo
+  speedy search python3 --type x --path /usr
+  speedy search:file config.ini --depth 3 --notify
+  speedy search:file informe.pdf --format "{path}\t{size}\t{mtime}"
+
+CONSEJOS DE RENDIMIENTO:
+  - Usa --global solo cuando haga falta
+  - Limita la profundidad con --depth para obtener resultados antes
+  - En búsquedas grandes, usa --threads para controlar el uso de CPU
+  - Usa --stop-after-match cuando solo necesites el primer resultado
+  - Usa --low-priority en búsquedas --global largas mientras sigues trabajando
+"#,
+    ),
+];
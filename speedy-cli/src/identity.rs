@@ -0,0 +1,111 @@
+// ========================= File Identity =========================
+
+use std::collections::HashSet; // For remembering visited directories
+use std::sync::Mutex; // The set is shared with the walker closure
+use std::sync::atomic::{AtomicUsize, Ordering}; // For counting collapsed hard links
+
+use walkdir::DirEntry; // Entries produced by the directory walker
+
+// (volume/device, file index/inode): identifies a file regardless of the path used to reach it
+pub type FileId = (u64, u64);
+
+// Identity of the entry itself (links are not followed)
+#[cfg(unix)]
+pub fn file_id(entry: &DirEntry) -> Option<FileId> {
+    use std::os::unix::fs::MetadataExt;
+
+    entry.metadata().ok().map(|m| (m.dev(), m.ino()))
+}
+
+#[cfg(windows)]
+pub fn file_id(entry: &DirEntry) -> Option<FileId> {
+    use std::os::windows::fs::OpenOptionsExt;
+    use std::os::windows::io::AsRawHandle;
+    use windows_sys::Win32::Storage::FileSystem::{
+        BY_HANDLE_FILE_INFORMATION, FILE_FLAG_BACKUP_SEMANTICS, FILE_FLAG_OPEN_REPARSE_POINT,
+        GetFileInformationByHandle,
+    };
+
+    const FILE_READ_ATTRIBUTES: u32 = 0x80;
+
+    // Backup semantics are required to open directories; only attribute access is requested
+    let file = std::fs::OpenOptions::new()
+        .access_mode(FILE_READ_ATTRIBUTES)
+        .custom_flags(FILE_FLAG_BACKUP_SEMANTICS | FILE_FLAG_OPEN_REPARSE_POINT)
+        .open(entry.path())
+        .ok()?;
+
+    let mut info: BY_HANDLE_FILE_INFORMATION = unsafe { std::mem::zeroed() };
+    if unsafe { GetFileInformationByHandle(file.as_raw_handle(), &mut info) } == 0 {
+        return None;
+    }
+    let index = ((info.nFileIndexHigh as u64) << 32) | info.nFileIndexLow as u64;
+    Some((info.dwVolumeSerialNumber as u64, index))
+}
+
+#[cfg(not(any(unix, windows)))]
+pub fn file_id(_entry: &DirEntry) -> Option<FileId> {
+    None
+}
+
+// ========================= Cycle Protection =========================
+
+// Directories already entered during a search. Bind mounts, junctions and mounted
+// volumes can expose the same directory under several paths; each is walked once.
+#[derive(Debug, Default)]
+pub struct VisitedDirs {
+    seen: Mutex<HashSet<FileId>>,
+}
+
+impl VisitedDirs {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // True the first time a directory is seen (and for anything that is not a directory)
+    pub fn first_visit(&self, entry: &DirEntry) -> bool {
+        if !entry.file_type().is_dir() {
+            return true;
+        }
+        let Some(id) = file_id(entry) else {
+            return true; // Can't tell, so don't hide it
+        };
+        self.seen.lock().map(|mut seen| seen.insert(id)).unwrap_or(true)
+    }
+}
+
+// ========================= Hard-Link Deduplication =========================
+
+// Files already reported during a search. Hard links give one file several names; with
+// --dedup-hardlinks only the first one found is reported.
+#[derive(Debug, Default)]
+pub struct SeenFiles {
+    seen: Mutex<HashSet<FileId>>,
+    duplicates: AtomicUsize, // Results dropped because their file was already reported
+}
+
+impl SeenFiles {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // True the first time a file is seen (and for directories, which can't be hard-linked)
+    pub fn first_sighting(&self, entry: &DirEntry) -> bool {
+        if entry.file_type().is_dir() {
+            return true;
+        }
+        let Some(id) = file_id(entry) else {
+            return true; // Can't tell, so don't hide it
+        };
+        let first = self.seen.lock().map(|mut seen| seen.insert(id)).unwrap_or(true);
+        if !first {
+            self.duplicates.fetch_add(1, Ordering::Relaxed);
+        }
+        first
+    }
+
+    // How many results were collapsed into an earlier one
+    pub fn duplicates(&self) -> usize {
+        self.duplicates.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,87 @@
+// ========================= Existing Search Indexes =========================
+
+use std::io; // For query errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+#[cfg(windows)]
+use std::process::Command; // The indexes are queried through their command-line front ends
+
+#[cfg(windows)]
+use crate::longpath;
+
+// Paths named `name` below `scope` according to Everything (voidtools). The query goes
+// through es.exe, Everything's command-line interface, which talks to the running
+// Everything service over its IPC window.
+#[cfg(windows)]
+pub fn query_everything(scope: &Path, name: &str) -> io::Result<Vec<PathBuf>> {
+    let scope = longpath::for_display(scope);
+    let output = Command::new("es.exe")
+        .arg("-path")
+        .arg(&scope)
+        .arg(format!("wfn:\"{}\"", name))
+        .output()
+        .map_err(|e| io::Error::new(e.kind(), format!("es.exe not found on PATH ({})", e)))?;
+    if !output.status.success() {
+        // es.exe exits with 8 when Everything itself isn't running
+        return Err(io::Error::other(format!(
+            "es.exe failed ({}): {}",
+            output.status,
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(paths_from(&output.stdout))
+}
+
+// Paths named `name` below `scope` according to the Windows Search indexer, queried
+// through its OLE DB provider (Search.CollatorDSO) from PowerShell
+#[cfg(windows)]
+pub fn query_windows_search(scope: &Path, name: &str) -> io::Result<Vec<PathBuf>> {
+    // Windows Search SQL quotes strings with '...' and escapes a quote by doubling it
+    let quote = |s: &str| s.replace('\'', "''");
+    let scope = longpath::for_display(scope).to_string_lossy().replace('\\', "/");
+    let sql = format!(
+        "SELECT System.ItemPathDisplay FROM SYSTEMINDEX WHERE SCOPE='file:{}' AND System.FileName = '{}'",
+        quote(&scope),
+        quote(name)
+    );
+    // The SQL travels inside a PowerShell single-quoted string, so quote it once more
+    let script = format!(
+        "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+         $c = New-Object -ComObject ADODB.Connection; \
+         $c.Open(\"Provider=Search.CollatorDSO;Extended Properties='Application=Windows';\"); \
+         $r = $c.Execute('{}'); \
+         while (-not $r.EOF) {{ $r.Fields.Item('System.ItemPathDisplay').Value; $r.MoveNext() }}",
+        sql.replace('\'', "''")
+    );
+
+    let output = Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "Windows Search query failed: {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(paths_from(&output.stdout))
+}
+
+#[cfg(not(windows))]
+pub fn query_everything(_scope: &Path, _name: &str) -> io::Result<Vec<PathBuf>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Everything is only available on Windows"))
+}
+
+#[cfg(not(windows))]
+pub fn query_windows_search(_scope: &Path, _name: &str) -> io::Result<Vec<PathBuf>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "Windows Search is only available on Windows"))
+}
+
+// One path per output line, in the same extended form the walker uses
+#[cfg(windows)]
+fn paths_from(stdout: &[u8]) -> Vec<PathBuf> {
+    String::from_utf8_lossy(stdout)
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| longpath::to_extended(Path::new(line)))
+        .collect()
+}
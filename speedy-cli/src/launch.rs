@@ -0,0 +1,44 @@
+// ========================= Opening Results =========================
+
+use std::io; // For launch errors
+use std::path::Path; // For working with filesystem paths
+use std::process::Command; // For handing paths to the platform opener
+
+use crate::longpath;
+
+// Open a file or folder with its default application
+pub fn open_path(path: &Path) -> io::Result<()> {
+    // Shell openers don't understand extended-length paths
+    let path = longpath::for_display(path);
+
+    #[cfg(windows)]
+    let mut command = {
+        let mut c = Command::new("cmd");
+        c.args(["/C", "start", ""]).arg(&path);
+        c
+    };
+
+    #[cfg(target_os = "macos")]
+    let mut command = {
+        let mut c = Command::new("open");
+        c.arg(&path);
+        c
+    };
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = {
+        let mut c = Command::new("xdg-open");
+        c.arg(&path);
+        c
+    };
+
+    command.spawn().map(|_| ())
+}
+
+// Open the folder that contains `path`
+pub fn open_containing_folder(path: &Path) -> io::Result<()> {
+    match path.parent() {
+        Some(parent) => open_path(parent),
+        None => open_path(path),
+    }
+}
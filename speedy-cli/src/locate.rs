@@ -0,0 +1,378 @@
+// ========================= locate Database =========================
+
+use std::fs::{self, File}; // For reading directories and database files
+use std::io::{self, BufReader, BufWriter, Read, Write}; // For the binary database format
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // locate exits with 1 when nothing matches
+use std::time::{Instant, UNIX_EPOCH}; // For directory timestamps and timing
+
+use rayon::prelude::*; // Directories are read in parallel
+
+use crate::SpeedyError;
+use crate::environment;
+use crate::storage;
+
+// mlocate database format, as read by `locate` (see mlocate.db(5))
+const MAGIC: &[u8; 8] = b"\0mlocate";
+const FORMAT_VERSION: u8 = 0;
+
+// Entry types inside a directory record
+const ENTRY_FILE: u8 = 0;
+const ENTRY_DIR: u8 = 1;
+const ENTRY_END: u8 = 2;
+
+// Virtual file systems that updatedb never descends into (its PRUNEPATHS)
+const PRUNE_PATHS: &[&str] = &["/dev", "/proc", "/run", "/sys"];
+
+// Where the database lives unless --database is given
+const DATABASE_FILE: &str = "locate.db";
+
+// One directory and the names directly inside it
+#[derive(Debug)]
+struct DirRecord {
+    path: PathBuf,
+    time: (u64, u32),            // Change time of the directory (seconds, nanoseconds)
+    entries: Vec<(bool, Vec<u8>)>, // (is a directory, name), sorted by name
+}
+
+// Default database file in the Speedy data folder
+fn default_database() -> Result<PathBuf, SpeedyError> {
+    storage::data_dir()
+        .map(|dir| dir.join(DATABASE_FILE))
+        .ok_or_else(|| SpeedyError::Argument("No data folder; pass --database <file>".to_string()))
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(not(unix))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+// ========================= updatedb =========================
+
+// `speedy updatedb [--path <root>] [--database <file>] [--threads <num>]`: index every
+// entry below <root> (default /) into an mlocate-compatible database
+pub fn updatedb_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let mut root = PathBuf::from("/");
+    let mut database = None;
+    let mut threads = None;
+    let mut i = 0;
+    while i < args.len() {
+        let value = args.get(i + 1).ok_or_else(|| {
+            SpeedyError::Argument(format!("Missing value after {}", args[i]))
+        });
+        match args[i].as_str() {
+            "--path" => root = crate::resolve_search_path(value?)?,
+            "--database" => database = Some(PathBuf::from(value?)),
+            "--threads" => {
+                threads = Some(value?.parse().map_err(|_| {
+                    SpeedyError::Parse("Thread count must be a number".to_string())
+                })?)
+            }
+            other => return Err(SpeedyError::Argument(format!("Unknown argument: {}", other))),
+        }
+        i += 2;
+    }
+    // SPEEDY_THREADS only when --threads isn't given, so a bad value can't block the flag
+    if threads.is_none() {
+        threads = environment::threads()?;
+    }
+    let database = match database {
+        Some(file) => file,
+        None => default_database()?,
+    };
+
+    // locate joins names onto directory paths, so they must be absolute
+    let root = root.canonicalize()?;
+    let start = Instant::now();
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(threads.unwrap_or_else(num_cpus::get))
+        .thread_name(|i| format!("speedy-updatedb-{}", i))
+        .build()?;
+    let records = pool.install(|| scan(&root));
+
+    if let Some(parent) = database.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    // Write to a temporary file first so a running locate never sees half a database
+    let temp = database.with_extension("tmp");
+    write_database(&temp, &root, &records)?;
+    fs::rename(&temp, &database)?;
+
+    let entries: usize = records.iter().map(|r| r.entries.len()).sum();
+    println!(
+        "🗂️ Indexed {} entries in {} folders below {} in {:.2?}",
+        entries,
+        records.len(),
+        root.display(),
+        start.elapsed()
+    );
+    println!("   Database: {}", database.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+// Records for `dir` and everything below it, each directory before its subdirectories
+fn scan(dir: &Path) -> Vec<DirRecord> {
+    let time = fs::metadata(dir).map(|m| change_time(&m)).unwrap_or((0, 0));
+    let mut entries = Vec::new();
+    let mut subdirs = Vec::new();
+    match fs::read_dir(dir) {
+        Ok(list) => {
+            for entry in list.filter_map(Result::ok) {
+                // file_type() doesn't follow symbolic links, so linked folders stay files
+                let is_dir = entry.file_type().is_ok_and(|t| t.is_dir());
+                let path = entry.path();
+                if is_dir && !PRUNE_PATHS.iter().any(|p| path == Path::new(p)) {
+                    subdirs.push(path.clone());
+                }
+                entries.push((is_dir, path_bytes(Path::new(&entry.file_name()))));
+            }
+        }
+        Err(e) => log::debug!("Could not read {}: {}", dir.display(), e),
+    }
+    entries.sort_by(|a, b| a.1.cmp(&b.1));
+    subdirs.sort();
+
+    let mut records = vec![DirRecord { path: dir.to_path_buf(), time, entries }];
+    let below: Vec<Vec<DirRecord>> = subdirs.par_iter().map(|d| scan(d)).collect();
+    records.extend(below.into_iter().flatten());
+    records
+}
+
+// The later of a directory's modification and status change times, as updatedb stores it
+fn change_time(metadata: &fs::Metadata) -> (u64, u32) {
+    let modified = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| (d.as_secs(), d.subsec_nanos()))
+        .unwrap_or((0, 0));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::MetadataExt;
+        let changed = (metadata.ctime().max(0) as u64, metadata.ctime_nsec().max(0) as u32);
+        modified.max(changed)
+    }
+    #[cfg(not(unix))]
+    modified
+}
+
+fn write_database(file: &Path, root: &Path, records: &[DirRecord]) -> io::Result<()> {
+    let mut out = BufWriter::new(File::create(file)?);
+
+    // Configuration block: variables with their values, each list ended by an empty string
+    let mut config = Vec::new();
+    for (name, values) in [
+        ("prune_bind_mounts", vec!["0"]),
+        ("prunefs", vec![]),
+        ("prunenames", vec![]),
+        ("prunepaths", PRUNE_PATHS.to_vec()),
+    ] {
+        for value in std::iter::once(name).chain(values) {
+            config.extend_from_slice(value.as_bytes());
+            config.push(0);
+        }
+        config.push(0);
+    }
+
+    out.write_all(MAGIC)?;
+    out.write_all(&(config.len() as u32).to_be_bytes())?;
+    out.write_all(&[FORMAT_VERSION, 0, 0, 0])?; // version, require_visibility off, padding
+    out.write_all(&path_bytes(root))?;
+    out.write_all(&[0])?;
+    out.write_all(&config)?;
+
+    for record in records {
+        out.write_all(&record.time.0.to_be_bytes())?;
+        out.write_all(&record.time.1.to_be_bytes())?;
+        out.write_all(&[0; 4])?;
+        out.write_all(&path_bytes(&record.path))?;
+        out.write_all(&[0])?;
+        for (is_dir, name) in &record.entries {
+            out.write_all(&[if *is_dir { ENTRY_DIR } else { ENTRY_FILE }])?;
+            out.write_all(name)?;
+            out.write_all(&[0])?;
+        }
+        out.write_all(&[ENTRY_END])?;
+    }
+    out.flush()
+}
+
+// ========================= locate =========================
+
+// `speedy locate <pattern> [--database <file>] [-i] [-b] [-l <num>]`: print indexed paths
+// matching <pattern>. As with locate, a pattern without * or ? matches anywhere in the
+// path, and a pattern with them must match the whole path (or name with -b).
+pub fn locate_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let mut pattern = None;
+    let mut database = None;
+    let mut ignore_case = false;
+    let mut basename = false;
+    let mut limit = usize::MAX;
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--database" | "-d" => {
+                let value = args.get(i + 1).ok_or_else(|| {
+                    SpeedyError::Argument("Missing file after --database".to_string())
+                })?;
+                database = Some(PathBuf::from(value));
+                i += 2;
+            }
+            "--limit" | "-l" => {
+                limit = args
+                    .get(i + 1)
+                    .and_then(|n| n.parse().ok())
+                    .ok_or_else(|| SpeedyError::Parse("--limit needs a number".to_string()))?;
+                i += 2;
+            }
+            "--ignore-case" | "-i" => {
+                ignore_case = true;
+                i += 1;
+            }
+            "--basename" | "-b" => {
+                basename = true;
+                i += 1;
+            }
+            flag if flag.starts_with('-') => {
+                return Err(SpeedyError::Argument(format!("Unknown argument: {}", flag)));
+            }
+            value => {
+                pattern = Some(value.to_string());
+                i += 1;
+            }
+        }
+    }
+    let pattern = pattern.ok_or_else(|| SpeedyError::Argument("Missing pattern for locate".to_string()))?;
+    let database = match database {
+        Some(file) => file,
+        None => default_database()?,
+    };
+
+    let fold = |s: &str| if ignore_case { s.to_lowercase() } else { s.to_string() };
+    let needle = fold(&pattern);
+    let glob = needle.contains(['*', '?']);
+    let matches = |path: &str| {
+        let subject = if basename {
+            path.rsplit('/').next().unwrap_or(path)
+        } else {
+            path
+        };
+        let subject = fold(subject);
+        if glob {
+            glob_match(needle.as_bytes(), subject.as_bytes())
+        } else {
+            subject.contains(&needle)
+        }
+    };
+
+    let mut found = 0;
+    let mut stdout = BufWriter::new(io::stdout().lock());
+    read_database(&database, |path| {
+        if found < limit && matches(path) {
+            found += 1;
+            let _ = writeln!(stdout, "{}", path);
+        }
+        found < limit
+    })?;
+    stdout.flush()?;
+
+    Ok(if found > 0 { ExitCode::SUCCESS } else { ExitCode::FAILURE })
+}
+
+// Call `visit` with every path in the database until it returns false
+fn read_database(file: &Path, mut visit: impl FnMut(&str) -> bool) -> Result<(), SpeedyError> {
+    let mut input = BufReader::new(File::open(file).map_err(|e| {
+        SpeedyError::Argument(format!(
+            "Could not open {} ({}); run 'speedy updatedb' first",
+            file.display(),
+            e
+        ))
+    })?);
+    let not_mlocate = || SpeedyError::Parse(format!("{} is not an mlocate database", file.display()));
+
+    let mut header = [0u8; 16];
+    input.read_exact(&mut header).map_err(|_| not_mlocate())?;
+    if &header[..8] != MAGIC || header[12] != FORMAT_VERSION {
+        return Err(not_mlocate());
+    }
+    let config_size = u32::from_be_bytes([header[8], header[9], header[10], header[11]]);
+    let require_visibility = header[13] != 0;
+    let root = read_string(&mut input)?;
+    io::copy(&mut (&mut input).take(config_size as u64), &mut io::sink())?;
+    if !visit(&root) {
+        return Ok(());
+    }
+
+    let mut time = [0u8; 16];
+    loop {
+        match input.read_exact(&mut time) {
+            Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(()),
+            result => result?,
+        }
+        let dir = read_string(&mut input)?;
+        // System databases ask locate to hide folders the user couldn't list themselves
+        let visible = !require_visibility || fs::read_dir(&dir).is_ok();
+        loop {
+            let mut kind = [0u8; 1];
+            input.read_exact(&mut kind)?;
+            if kind[0] == ENTRY_END {
+                break;
+            }
+            let name = read_string(&mut input)?;
+            if !visible {
+                continue;
+            }
+            let path = if dir.ends_with('/') {
+                format!("{}{}", dir, name)
+            } else {
+                format!("{}/{}", dir, name)
+            };
+            if !visit(&path) {
+                return Ok(());
+            }
+        }
+    }
+}
+
+// A NUL-terminated string
+fn read_string(input: &mut impl io::BufRead) -> io::Result<String> {
+    let mut bytes = Vec::new();
+    input.read_until(0, &mut bytes)?;
+    if bytes.pop() != Some(0) {
+        return Err(io::Error::new(io::ErrorKind::UnexpectedEof, "truncated locate database"));
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+// Shell-style wildcard match: * matches any run of bytes, ? a single byte
+fn glob_match(pattern: &[u8], text: &[u8]) -> bool {
+    let (mut p, mut t) = (0, 0);
+    let mut backtrack = None; // (pattern index after the last *, text index it matched up to)
+    while t < text.len() {
+        match pattern.get(p) {
+            Some(b'*') => {
+                backtrack = Some((p + 1, t));
+                p += 1;
+            }
+            Some(&c) if c == b'?' || c == text[t] => {
+                p += 1;
+                t += 1;
+            }
+            _ => match backtrack {
+                Some((bp, bt)) => {
+                    p = bp;
+                    t = bt + 1;
+                    backtrack = Some((bp, bt + 1));
+                }
+                None => return false,
+            },
+        }
+    }
+    pattern[p..].iter().all(|&c| c == b'*')
+}
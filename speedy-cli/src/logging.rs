@@ -0,0 +1,106 @@
+// ========================= Logging =========================
+
+use std::fs::{File, OpenOptions}; // For --log-file
+use std::io::Write; // For writing log lines
+use std::path::Path; // For the log file location
+use std::sync::Mutex; // The log file is shared between threads
+use std::time::Instant; // For relative timestamps
+
+use log::{Level, LevelFilter, Log, Metadata, Record}; // Logging facade
+
+use crate::SpeedyError;
+
+// Logger behind the `log` macros: writes to stderr, or to a file with --log-file
+struct SpeedyLogger {
+    level: LevelFilter,
+    file: Option<Mutex<File>>,
+    started: Instant,
+}
+
+impl Log for SpeedyLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        match &self.file {
+            // Files get a full line with a timestamp for later diagnosis
+            Some(file) => {
+                if let Ok(mut file) = file.lock() {
+                    let _ = writeln!(
+                        file,
+                        "[{:>9.3}s] {:<5} {}",
+                        self.started.elapsed().as_secs_f64(),
+                        record.level(),
+                        record.args()
+                    );
+                }
+            }
+            // The terminal keeps the familiar emoji style
+            None => {
+                let prefix = match record.level() {
+                    Level::Error => "❌",
+                    Level::Warn => "⚠️",
+                    Level::Info => "ℹ️",
+                    Level::Debug | Level::Trace => "·",
+                };
+                eprintln!("{} {}", prefix, record.args());
+            }
+        }
+    }
+
+    fn flush(&self) {
+        if let Some(file) = &self.file
+            && let Ok(mut file) = file.lock()
+        {
+            let _ = file.flush();
+        }
+    }
+}
+
+// Parse a --log-level value
+pub fn parse_level(value: &str) -> Result<LevelFilter, SpeedyError> {
+    value.parse().map_err(|_| {
+        SpeedyError::Parse(format!(
+            "Invalid log level '{}' (expected off, error, warn, info, debug or trace)",
+            value
+        ))
+    })
+}
+
+// Install the global logger. Without an explicit --log-level the terminal shows warnings
+// (plus skipped directories and timings with --verbose, nothing with --quiet), and a log
+// file records everything from info up.
+pub fn init(
+    level: Option<LevelFilter>,
+    log_file: Option<&Path>,
+    verbose: bool,
+    quiet: bool,
+) -> Result<(), SpeedyError> {
+    let file = log_file
+        .map(|path| OpenOptions::new().create(true).append(true).open(path))
+        .transpose()?;
+
+    let level = level.unwrap_or(if file.is_some() || verbose {
+        LevelFilter::Info
+    } else if quiet {
+        LevelFilter::Off
+    } else {
+        LevelFilter::Warn
+    });
+
+    let logger = SpeedyLogger {
+        level,
+        file: file.map(Mutex::new),
+        started: Instant::now(),
+    };
+    // Only the first search in a process installs the logger; later ones keep it
+    if log::set_boxed_logger(Box::new(logger)).is_ok() {
+        log::set_max_level(level);
+    }
+    Ok(())
+}
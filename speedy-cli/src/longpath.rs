@@ -0,0 +1,44 @@
+// ========================= Long Path Support =========================
+
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+
+// Convert a search root to an extended-length path (`\\?\C:\...` or `\\?\UNC\server\share\...`)
+// so entries deeper than MAX_PATH (260 characters) can still be opened on Windows.
+// Child paths produced by the walker inherit the prefix. A no-op on other platforms.
+#[cfg(windows)]
+pub fn to_extended(path: &Path) -> PathBuf {
+    let Ok(absolute) = std::path::absolute(path) else {
+        return path.to_path_buf();
+    };
+    let text = absolute.to_string_lossy();
+    if text.starts_with(r"\\?\") || text.starts_with(r"\\.\") {
+        absolute
+    } else if let Some(unc) = text.strip_prefix(r"\\") {
+        PathBuf::from(format!(r"\\?\UNC\{}", unc))
+    } else {
+        PathBuf::from(format!(r"\\?\{}", text))
+    }
+}
+
+#[cfg(not(windows))]
+pub fn to_extended(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
+
+// Convert an extended-length path back to the familiar form for printing
+#[cfg(windows)]
+pub fn for_display(path: &Path) -> PathBuf {
+    let text = path.to_string_lossy();
+    if let Some(unc) = text.strip_prefix(r"\\?\UNC\") {
+        PathBuf::from(format!(r"\\{}", unc))
+    } else if let Some(local) = text.strip_prefix(r"\\?\") {
+        PathBuf::from(local)
+    } else {
+        path.to_path_buf()
+    }
+}
+
+#[cfg(not(windows))]
+pub fn for_display(path: &Path) -> PathBuf {
+    path.to_path_buf()
+}
@@ -1,22 +1,78 @@
+// ========================= Modules =========================
+
+mod app; // `speedy search:app`: the program a shell would start for a name
+mod backend; // Traversal strategies, chosen per volume
+mod checkpoint; // --checkpoint / --resume for long searches
+mod cleanup; // --delete / --move-to actions on matches
+mod clipboard; // System clipboard access
+mod content; // `speedy search:content`: lines of files that contain a text
+mod crash; // Diagnostics report when the search thread panics
+mod dry_run; // --dry-run: describe the traversal without searching
+mod enrich; // Extra --format fields attached to matches ({git}, {dimensions}, ...)
+mod environment; // SPEEDY_* variables: defaults under the command-line flags
+mod events; // Warnings channel and --output json events
+mod filters; // Entry type and metadata filters (--type, ...)
+mod format; // Custom output templates for --format
+mod hash; // `speedy hash` / `speedy verify`: parallel checksum manifests
+mod i18n; // Translated messages, chosen with --lang or LANG
+mod identity; // File IDs and visited-directory tracking
+mod index; // Querying Everything / Windows Search for --backend
+mod launch; // Opening results with the default application
+mod locate; // `speedy updatedb` / `speedy locate` with an mlocate-compatible database
+mod logging; // Leveled logging to the terminal or a --log-file
+mod longpath; // Extended-length (\\?\) paths on Windows
+mod matching; // Unicode-aware name comparison
+mod network; // Timeouts and retries for network shares
+mod notifications; // Desktop notifications with click-to-open actions
+mod permissions; // Permission-denied summary and --elevate
+mod pick; // `speedy pick`: interactive picker for paths piped in on stdin
+mod priority; // Process priority and walker throttling for --low-priority
+mod profile; // --profile presets and folder skip rules
+mod progress; // Progress updates, throughput and ETA for the spinner
+mod recycled; // `speedy search:recycled`: deleted entries in the trash and shadow copies
+mod rename; // `speedy rename`: regex renames of matching entries
+mod results; // Result storage with a --max-memory budget and spill-to-disk
+mod schedule; // Prioritized walk order for --stop-after-match
+mod shell; // `speedy shell-init`: the sdcd jump-to-folder function for each shell
+mod storage; // State persisted between runs (entry counts, recent folders, ...)
+mod suggest; // "Did you mean" suggestions when nothing matches
+mod telemetry; // --telemetry-file samples and `speedy tune`
+mod tuning; // Volume detection and per-volume thread counts for --threads auto
+mod update; // `speedy self-update`: install the latest release
+
 // ========================= Imports =========================
 
 // Standard library modules
+use std::collections::{HashSet, VecDeque}; // For reported matches and the queue of pending directory walks
 use std::env; // For accessing command-line arguments and environment variables
 use std::error::Error; // For implementing error handling
 use std::io; // For I/O operations
 use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // For reporting cancellation in the exit status
 use std::sync::atomic::{AtomicBool, Ordering}; // For atomic operations (used for cancellation)
-use std::sync::Arc; // For shared ownership in multi-threading
+use std::sync::{Arc, Mutex, OnceLock}; // For shared ownership in multi-threading
 use std::time::Instant; // For measuring elapsed time
 
 // External crates
-use crossbeam_channel::{bounded, unbounded}; // For channel-based communication between threads
-use ctrlc; // To handle Ctrl+C interrupts gracefully
+use crossbeam_channel::bounded; // For channel-based communication between threads
 use indicatif::{ProgressBar, ProgressStyle}; // For command-line progress spinners
-use notify_rust::Notification; // For desktop notifications
 use rayon::prelude::*; // For parallel iteration
-use walkdir::WalkDir; // For walking directories recursively
-use num_cpus; // To get number of logical CPU cores
+
+// Local modules
+use backend::{Backend, Strategy};
+use checkpoint::Checkpoint;
+use cleanup::CleanupAction;
+use events::{Warning, WarningKind};
+use filters::{EntryType, MimeFilter, PermFilter, TimeField, TimeFilter}; // For --type, --perm, --mime and --*-within filters
+use format::{OutputTemplate, PathStyle, ResultFile}; // For rendering results with --format
+use identity::{SeenFiles, VisitedDirs}; // For mount-point and junction cycle protection
+use matching::NameMatcher; // For normalized, case-insensitive name matching
+use permissions::DeniedSummary;
+use profile::{Profile, SkipRules};
+use progress::{ProgressUpdate, RateMeter}; // For the progress spinner's rate and ETA
+use results::ResultStore; // Matches kept for printing, --copy and --notify
+use suggest::Suggestions; // For collecting near-miss names
+use tuning::VolumeKind; // For per-volume thread counts and network handling
 
 // ========================= Custom Error Type =========================
 
@@ -31,20 +87,24 @@ enum SpeedyError {
     Notification(notify_rust::error::Error),
     Ctrlc(ctrlc::Error),
     Template(String),
+    Clipboard(String),
+    Update(String),
 }
 
 // Implement display formatting for our error type
 impl std::fmt::Display for SpeedyError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
-            SpeedyError::Io(e) => write!(f, "IO error: {}", e),
-            SpeedyError::Parse(s) => write!(f, "Parse error: {}", s),
-            SpeedyError::Argument(s) => write!(f, "Argument error: {}", s),
-            SpeedyError::WalkDir(e) => write!(f, "Directory walk error: {}", e),
-            SpeedyError::ThreadPoolBuild(e) => write!(f, "Thread pool error: {}", e),
-            SpeedyError::Notification(e) => write!(f, "Notification error: {}", e),
-            SpeedyError::Ctrlc(e) => write!(f, "Ctrl-C handler error: {}", e),
-            SpeedyError::Template(e) => write!(f, "Template error: {}", e),
+            SpeedyError::Io(e) => write!(f, "{}", i18n::format("error_io", &[e])),
+            SpeedyError::Parse(s) => write!(f, "{}", i18n::format("error_parse", &[s])),
+            SpeedyError::Argument(s) => write!(f, "{}", i18n::format("error_argument", &[s])),
+            SpeedyError::WalkDir(e) => write!(f, "{}", i18n::format("error_walk", &[e])),
+            SpeedyError::ThreadPoolBuild(e) => write!(f, "{}", i18n::format("error_thread_pool", &[e])),
+            SpeedyError::Notification(e) => write!(f, "{}", i18n::format("error_notification", &[e])),
+            SpeedyError::Ctrlc(e) => write!(f, "{}", i18n::format("error_ctrlc", &[e])),
+            SpeedyError::Template(e) => write!(f, "{}", i18n::format("error_template", &[e])),
+            SpeedyError::Clipboard(e) => write!(f, "{}", i18n::format("error_clipboard", &[e])),
+            SpeedyError::Update(e) => write!(f, "{}", i18n::format("error_update", &[e])),
         }
     }
 }
@@ -85,49 +145,159 @@ impl From<ctrlc::Error> for SpeedyError {
 
 // ========================= Main Function =========================
 
-fn main() -> Result<(), SpeedyError> {
-    // Track time taken for the entire search
-    let start_time = Instant::now();
+// Exit status of a search interrupted with Ctrl+C
+const EXIT_CANCELLED: u8 = 130;
+
+// Exit status when the search thread panicked, the same one Rust uses for panics
+const EXIT_CRASHED: u8 = 101;
 
+// Matches that can wait for the main thread before workers block on sending more, so a
+// slow consumer (e.g. a terminal) holds back the walk instead of queueing every result
+const FOUND_CHANNEL_CAPACITY: usize = 1024;
+
+// Progress updates waiting to be shown; when full, newer updates are dropped
+const PROGRESS_CHANNEL_CAPACITY: usize = 16;
+
+// Warnings waiting to be reported; like matches, workers wait when it is full
+const WARNING_CHANNEL_CAPACITY: usize = 256;
+
+fn main() -> ExitCode {
     // Collect command-line arguments
-    let args: Vec<String> = env::args().collect();
+    let mut args: Vec<String> = env::args().collect();
+
+    // --lang applies to every command, so it goes before the command's name; further on it
+    // is left to the command (it could be a pattern or a file name there)
+    let mut lang = None;
+    if args.get(1).is_some_and(|a| a == "--lang") {
+        args.remove(1);
+        if args.len() < 2 {
+            let e = SpeedyError::Argument("Missing language after --lang".to_string());
+            eprintln!("{}", i18n::format("error", &[&e]));
+            return ExitCode::FAILURE;
+        }
+        lang = Some(args.remove(1));
+    }
+    i18n::init(lang.as_deref());
+    crash::install_hook();
+
+    match run_command(args) {
+        Ok(code) => code,
+        Err(e) => {
+            eprintln!("{}", i18n::format("error", &[&e]));
+            ExitCode::FAILURE
+        }
+    }
+}
+
+// Dispatch to the subcommand named by the first argument; anything else is a search
+fn run_command(args: Vec<String>) -> Result<ExitCode, SpeedyError> {
+    match args.get(1).map(String::as_str) {
+        Some("history") => print_history().map(|_| ExitCode::SUCCESS),
+        Some("bookmark") => bookmark_command(&args[2..]).map(|_| ExitCode::SUCCESS),
+        Some("pick") => pick::run(&args[2..]),
+        Some("hash") => hash::hash_command(&args[2..]),
+        Some("verify") => hash::verify_command(&args[2..]),
+        Some("rename") => rename::run(&args[2..]),
+        Some("updatedb") => locate::updatedb_command(&args[2..]),
+        Some("locate") => locate::locate_command(&args[2..]),
+        Some("shell-init") => shell::init_command(&args[2..]),
+        Some("search:recycled") => recycled::search_command(&args[2..]),
+        Some("search:content") => content::search_command(&args[2..]),
+        Some("search:app") => app::search_command(&args[2..]),
+        Some("self-update") => update::self_update_command(&args[2..]),
+        Some("tune") => telemetry::tune_command(&args[2..]),
+        Some("rerun") => {
+            let id: u64 = args
+                .get(2)
+                .ok_or_else(|| SpeedyError::Argument("Missing history id after rerun".to_string()))?
+                .parse()
+                .map_err(|_| SpeedyError::Parse("History id must be a number".to_string()))?;
+            let entry = storage::load_history()
+                .into_iter()
+                .find(|entry| entry.id == id)
+                .ok_or_else(|| SpeedyError::Argument(format!("No search #{} in history", id)))?;
+
+            // Run from the original directory so relative paths mean the same thing
+            env::set_current_dir(&entry.cwd)?;
+            println!("{}", i18n::format("rerunning", &[&id, &entry.args.join(" ")]));
+            let mut rerun_args = vec![args[0].clone()];
+            rerun_args.extend(entry.args);
+            run(rerun_args)
+        }
+        _ => run(args),
+    }
+}
+
+// Run a search described by command-line arguments
+fn run(args: Vec<String>) -> Result<ExitCode, SpeedyError> {
+    // Track time taken for the entire search
+    let start_time = Instant::now();
 
     // Display help if --help is requested or no arguments provided
     if args.len() == 1 || args[1] == "--help" {
         print_help();
-        return Ok(());
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Display usage instructions if there are not enough arguments
     if args.len() < 3 {
-        println!("Usage:");
-        println!("  speedy search:file <name> [--global]");
-        println!("  speedy search:folder <name> [--global]");
-        println!("  speedy search:file <name> [--path <custom_path>]");
-        println!("Options:");
-        println!("  --verbose       Show all warnings");
-        println!("  --quiet         Suppress non-essential output");
-        println!("  --depth <num>   Limit search depth (default: unlimited)");
-        println!("  --notify        Show desktop notification when found");
-        println!("  --threads <num> Set number of threads (default: CPU cores)");
-        println!();
-        println!("For more information, try 'speedy --help'");
-        return Ok(());
+        print!("{}", i18n::text("usage"));
+        return Ok(ExitCode::SUCCESS);
     }
 
     // Parse and initialize argument values
-    let search_type = args[1].clone(); // Either "search:file" or "search:folder"
+    let search_type = args[1].clone(); // "search", or the "search:file" / "search:folder" shorthands
+    let mut types = match search_type.as_str() {
+        "search" => Vec::new(), // Any type unless --type is given
+        "search:file" => vec![EntryType::File],
+        "search:folder" => vec![EntryType::Dir],
+        _ => {
+            return Err(SpeedyError::Argument(format!("Unknown command: {}", search_type)));
+        }
+    };
     let target = args[2].clone(); // Name of the file or folder to search
-    let mut search_path = None;
+    let mut search_paths = Vec::new();
     let mut is_global = false;
     let mut verbose = false;
     let mut quiet = false;
+    let mut json = None; // --output json; SPEEDY_OUTPUT when not given
+    let mut min_depth = 0;
     let mut max_depth = usize::MAX;
     let mut notify = false;
-    let mut num_threads = num_cpus::get(); // Default to number of CPU cores
+    let mut num_threads = None; // --threads; Some(None) for auto, SPEEDY_THREADS when not given
 
     // Add new --stop-after-match flag
     let mut stop_after_match = false;
+    let mut max_matches = None; // --max-matches: stop once this many results are found
+    let mut first = false; // --first: the first match in traversal order, the same every run
+    let mut output_template = None;
+    let mut low_priority = false;
+    let mut owner = None;
+    let mut perm = None;
+    let mut attributes = 0;
+    let mut mime = None;
+    let mut time_filters = Vec::new();
+    let mut one_file_system = false;
+    let mut ignore_accents = false;
+    let mut log_level = None;
+    let mut log_file = None;
+    let mut dry_run = false;
+    let mut profile = Profile::default();
+    let mut all = false;
+    let mut copy = false;
+    let mut cleanup_action = None;
+    let mut backend = None;
+    let mut strategy = Strategy::default();
+    let mut max_memory = None;
+    let mut project = false;
+    let mut assume_yes = false;
+    let mut dedup_hardlinks = false;
+    let mut elevate = false;
+    let mut checkpoint_file = None;
+    let mut resume_file = None;
+    let mut telemetry_file = None;
+    let mut path_style = PathStyle::default();
+    let mut output_file = None;
 
     // Parse remaining flags and arguments
     let mut i = 3;
@@ -141,7 +311,7 @@ fn main() -> Result<(), SpeedyError> {
                 if i + 1 >= args.len() {
                     return Err(SpeedyError::Argument("Missing path after --path".to_string()));
                 }
-                search_path = Some(PathBuf::from(&args[i + 1]));
+                search_paths.push(resolve_search_path(&args[i + 1])?);
                 i += 2;
             }
             "--verbose" => {
@@ -161,6 +331,92 @@ fn main() -> Result<(), SpeedyError> {
                     .map_err(|_| SpeedyError::Parse("Depth must be a number".to_string()))?;
                 i += 2;
             }
+            "--min-depth" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing depth value after --min-depth".to_string()));
+                }
+                min_depth = args[i + 1]
+                    .parse()
+                    .map_err(|_| SpeedyError::Parse("Minimum depth must be a number".to_string()))?;
+                i += 2;
+            }
+            "--type" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing type after --type".to_string()));
+                }
+                types = EntryType::parse_list(&args[i + 1])?;
+                i += 2;
+            }
+            "--owner" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing user after --owner".to_string()));
+                }
+                owner = Some(filters::resolve_owner(&args[i + 1])?);
+                i += 2;
+            }
+            "--perm" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing mode after --perm".to_string()));
+                }
+                if !cfg!(unix) {
+                    return Err(SpeedyError::Argument(
+                        "--perm is only supported on Unix systems".to_string(),
+                    ));
+                }
+                perm = Some(PermFilter::parse(&args[i + 1])?);
+                i += 2;
+            }
+            "--attr" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing attribute list after --attr".to_string()));
+                }
+                if !cfg!(windows) {
+                    return Err(SpeedyError::Argument(
+                        "--attr is only supported on Windows".to_string(),
+                    ));
+                }
+                attributes = filters::parse_attributes(&args[i + 1])?;
+                i += 2;
+            }
+            "--modified-within" | "--created-within" | "--accessed-within" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument(format!("Missing age after {}", args[i])));
+                }
+                let field = match args[i].as_str() {
+                    "--modified-within" => TimeField::Modified,
+                    "--created-within" => TimeField::Created,
+                    _ => TimeField::Accessed,
+                };
+                time_filters.push(TimeFilter {
+                    field,
+                    within: filters::parse_age(&args[i + 1])?,
+                });
+                i += 2;
+            }
+            "--mime" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing content type after --mime".to_string()));
+                }
+                mime = Some(MimeFilter::parse(&args[i + 1])?);
+                i += 2;
+            }
+            "--elevate" => {
+                if !cfg!(windows) {
+                    return Err(SpeedyError::Argument(
+                        "--elevate is only supported on Windows; run with sudo instead".to_string(),
+                    ));
+                }
+                elevate = true;
+                i += 1;
+            }
+            "--one-file-system" => {
+                one_file_system = true;
+                i += 1;
+            }
+            "--ignore-accents" => {
+                ignore_accents = true;
+                i += 1;
+            }
             "--notify" => {
                 notify = true;
                 i += 1;
@@ -169,29 +425,308 @@ fn main() -> Result<(), SpeedyError> {
                 if i + 1 >= args.len() {
                     return Err(SpeedyError::Argument("Missing thread count after --threads".to_string()));
                 }
-                num_threads = args[i + 1]
-                    .parse()
-                    .map_err(|_| SpeedyError::Parse("Thread count must be a number".to_string()))?;
+                num_threads = Some(match args[i + 1].as_str() {
+                    "auto" => None,
+                    n => Some(n.parse().map_err(|_| {
+                        SpeedyError::Parse("Thread count must be a number or 'auto'".to_string())
+                    })?),
+                });
                 i += 2;
             }
+            "--all" => {
+                all = true;
+                i += 1;
+            }
+            "--absolute" => {
+                path_style = PathStyle::Absolute;
+                i += 1;
+            }
+            "--relative" => {
+                // Optional base: `--relative root` (default) or `--relative cwd`
+                path_style = match args.get(i + 1).map(String::as_str) {
+                    Some("cwd") => {
+                        i += 1;
+                        PathStyle::RelativeToCwd
+                    }
+                    Some("root") => {
+                        i += 1;
+                        PathStyle::RelativeToRoot
+                    }
+                    _ => PathStyle::RelativeToRoot,
+                };
+                i += 1;
+            }
+            "--dedup-hardlinks" => {
+                dedup_hardlinks = true;
+                i += 1;
+            }
+            "--copy" => {
+                copy = true;
+                i += 1;
+            }
+            "--project" => {
+                project = true;
+                i += 1;
+            }
+            "--backend" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing name after --backend".to_string()));
+                }
+                backend = Backend::parse(&args[i + 1])?;
+                i += 2;
+            }
+            "--strategy" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing name after --strategy".to_string()));
+                }
+                strategy = Strategy::parse(&args[i + 1])?;
+                i += 2;
+            }
+            "--max-memory" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing size after --max-memory".to_string()));
+                }
+                max_memory = Some(results::parse_budget(&args[i + 1])?);
+                i += 2;
+            }
+            "--delete" => {
+                if cleanup_action.is_some() {
+                    return Err(SpeedyError::Argument(
+                        "--delete and --move-to cannot be used together".to_string(),
+                    ));
+                }
+                cleanup_action = Some(CleanupAction::Trash);
+                i += 1;
+            }
+            "--move-to" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing folder after --move-to".to_string()));
+                }
+                if cleanup_action.is_some() {
+                    return Err(SpeedyError::Argument(
+                        "--delete and --move-to cannot be used together".to_string(),
+                    ));
+                }
+                cleanup_action = Some(CleanupAction::MoveTo(PathBuf::from(&args[i + 1])));
+                i += 2;
+            }
+            "--yes" => {
+                assume_yes = true;
+                i += 1;
+            }
             "--stop-after-match" => {
                 stop_after_match = true;
                 i += 1;
             }
+            "--first" => {
+                first = true;
+                i += 1;
+            }
+            "--max-matches" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing count after --max-matches".to_string()));
+                }
+                let count: usize = args[i + 1]
+                    .parse()
+                    .map_err(|_| SpeedyError::Parse("Match count must be a number".to_string()))?;
+                if count == 0 {
+                    return Err(SpeedyError::Argument("--max-matches must be at least 1".to_string()));
+                }
+                max_matches = Some(count);
+                i += 2;
+            }
+            "--profile" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing name after --profile".to_string()));
+                }
+                profile = Profile::parse(&args[i + 1])?;
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--low-priority" => {
+                low_priority = true;
+                i += 1;
+            }
+            "--log-level" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing level after --log-level".to_string()));
+                }
+                log_level = Some(logging::parse_level(&args[i + 1])?);
+                i += 2;
+            }
+            "--log-file" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing path after --log-file".to_string()));
+                }
+                log_file = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            }
+            "--checkpoint" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing file after --checkpoint".to_string()));
+                }
+                checkpoint_file = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            }
+            "--resume" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing file after --resume".to_string()));
+                }
+                resume_file = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            }
+            "--telemetry-file" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing file after --telemetry-file".to_string()));
+                }
+                telemetry_file = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            }
+            "--output-file" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing path after --output-file".to_string()));
+                }
+                output_file = Some(PathBuf::from(&args[i + 1]));
+                i += 2;
+            }
+            "--output" => {
+                json = match args.get(i + 1).map(String::as_str) {
+                    Some("text") => Some(false),
+                    Some("json") => Some(true),
+                    Some(other) => {
+                        return Err(SpeedyError::Argument(format!(
+                            "Unknown output mode '{}' (use text or json)",
+                            other
+                        )));
+                    }
+                    None => return Err(SpeedyError::Argument("Missing mode after --output".to_string())),
+                };
+                i += 2;
+            }
+            "--format" => {
+                if i + 1 >= args.len() {
+                    return Err(SpeedyError::Argument("Missing template after --format".to_string()));
+                }
+                let enrichers = enrich::Registry::builtin();
+                output_template = Some(OutputTemplate::parse(&args[i + 1], &enrichers)?);
+                i += 2;
+            }
             _ => {
                 return Err(SpeedyError::Argument(format!("Unknown argument: {}", args[i])));
             }
         }
     }
 
-    // Initialize global thread pool with specified thread count
-    rayon::ThreadPoolBuilder::new()
-        .num_threads(num_threads)
-        .build_global()?; // Will error if called twice in the same process
+    // SPEEDY_THREADS is only read, and so only has to be valid, when --threads isn't given.
+    // None = auto-tune for the volume being searched.
+    let num_threads = match num_threads {
+        Some(threads) => threads,
+        None => environment::threads()?,
+    };
+
+    // SPEEDY_OUTPUT applies unless --output or --format chose the output
+    let json = match json {
+        Some(json) => json,
+        None => output_template.is_none() && environment::json_output()?,
+    };
+
+    // JSON events replace every human-readable message
+    if json {
+        if output_template.is_some() {
+            return Err(SpeedyError::Argument(
+                "--format and --output json cannot be used together".to_string(),
+            ));
+        }
+        quiet = true;
+    }
+
+    if max_matches.is_some() && stop_after_match {
+        return Err(SpeedyError::Argument(
+            "--max-matches and --stop-after-match cannot be used together (--stop-after-match is --max-matches 1)".to_string(),
+        ));
+    }
+
+    if all && stop_after_match {
+        return Err(SpeedyError::Argument(
+            "--all and --stop-after-match cannot be used together".to_string(),
+        ));
+    }
+
+    if first && (all || max_matches.is_some()) {
+        return Err(SpeedyError::Argument(
+            "--first reports a single match and cannot be used with --all or --max-matches".to_string(),
+        ));
+    }
+
+    // Up to N results are listed like --all
+    let all = all || max_matches.is_some();
+
+    if dedup_hardlinks && !all {
+        return Err(SpeedyError::Argument(
+            "--dedup-hardlinks only applies to --all searches".to_string(),
+        ));
+    }
+
+    if min_depth > max_depth {
+        return Err(SpeedyError::Argument(format!(
+            "--min-depth ({}) cannot be greater than --depth ({})",
+            min_depth, max_depth
+        )));
+    }
+
+    logging::init(log_level, log_file.as_deref(), verbose, quiet)?;
+
+    if notify && environment::no_notify() {
+        log::info!("{} is set, so no notification will be shown", environment::NO_NOTIFY);
+        notify = false;
+    }
+
+    if let Some(chosen) = backend
+        && !chosen.is_available()
+    {
+        log::warn!("The {} is not available on this system, using the directory walk", chosen);
+    }
+
+    // Hand the whole search over to an elevated copy of ourselves
+    if elevate && !permissions::is_elevated() {
+        permissions::relaunch_elevated(&args[1..])?;
+        if !quiet {
+            println!("{}", i18n::text("elevated"));
+        }
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    // Lower our priority before any worker threads exist so they inherit it
+    if low_priority && let Err(e) = priority::lower_process_priority() {
+        log::warn!("Could not lower process priority: {}", e);
+    }
 
-    // Determine root search directory
-    let root_dir = match search_path {
+    // Determine root search directory. With several --path roots the first is the main
+    // root and the others are walked after it.
+    let mut roots = search_paths.into_iter();
+    let root_dir = match roots.next() {
         Some(path) => path,
+        None if project => {
+            let cwd = env::current_dir()?;
+            match find_project_root(&cwd) {
+                Some(root) => {
+                    if !quiet {
+                        println!("{}", i18n::format("project_root", &[&root.display()]));
+                    }
+                    root
+                }
+                None => {
+                    log::warn!(
+                        "No project marker (.git, Cargo.toml, package.json) above {}; searching it instead",
+                        cwd.display()
+                    );
+                    cwd
+                }
+            }
+        }
         None => {
             if is_global {
                 Path::new("C:\\").to_path_buf()
@@ -201,35 +736,192 @@ fn main() -> Result<(), SpeedyError> {
         }
     };
 
-    // Check if directory exists
-    if !root_dir.exists() {
-        return Err(SpeedyError::Argument(format!(
-            "Path does not exist: {}",
-            root_dir.display()
-        )));
+    // Identify the storage behind the root. Network shares get a timed existence check,
+    // fewer threads and retries on transient errors.
+    let volume_kind = tuning::detect_volume_kind(&root_dir);
+    let mut is_network = volume_kind == VolumeKind::Network;
+
+    // Check that every root exists
+    let all_roots: Vec<PathBuf> = std::iter::once(root_dir).chain(roots).collect();
+    for root in &all_roots {
+        let exists = if tuning::detect_volume_kind(root) == VolumeKind::Network {
+            is_network = true;
+            network::root_exists_within(root, network::ROOT_TIMEOUT).ok_or_else(|| {
+                SpeedyError::Argument(format!(
+                    "Network path did not respond within {}s: {}",
+                    network::ROOT_TIMEOUT.as_secs(),
+                    root.display()
+                ))
+            })?
+        } else {
+            root.exists()
+        };
+        if !exists {
+            return Err(SpeedyError::Argument(format!(
+                "Path does not exist: {}",
+                root.display()
+            )));
+        }
+    }
+
+    // Overlapping roots are walked once: a root that is the same as, or inside, another
+    // one is already covered by it
+    let (distinct, covered) = schedule::distinct_roots(all_roots);
+    for (root, by) in &covered {
+        if !quiet {
+            println!("{}", i18n::format("root_covered", &[&root.display(), &by.display()]));
+        }
+    }
+
+    // Walk using extended-length paths so deep entries aren't silently skipped on Windows;
+    // paths are converted back with `longpath::for_display` whenever they're shown
+    let mut distinct = distinct.iter().map(|root| longpath::to_extended(root));
+    let root_dir = distinct.next().unwrap_or_default();
+    let extra_roots: Vec<PathBuf> = distinct.collect();
+    if !extra_roots.is_empty() && (checkpoint_file.is_some() || resume_file.is_some()) {
+        return Err(SpeedyError::Argument(
+            "--checkpoint and --resume take a single --path".to_string(),
+        ));
+    }
+    // A checkpoint records a folder as done once the walk moves past it, which only holds
+    // for a depth-first walk of the folders themselves
+    let indexed = matches!(backend, Some(Backend::Everything | Backend::WindowsSearch));
+    if (strategy == Strategy::BreadthFirst || indexed) && (checkpoint_file.is_some() || resume_file.is_some()) {
+        return Err(SpeedyError::Argument(
+            "--checkpoint and --resume need a depth-first walk (not --strategy bfs or an index --backend)"
+                .to_string(),
+        ));
+    }
+
+    // Subtrees finished by an earlier run of this search are skipped; progress keeps being
+    // saved to the checkpoint file (the resumed file unless another is given)
+    let already_searched = match &resume_file {
+        Some(file) => checkpoint::load(file, &root_dir)?,
+        None => Vec::new(),
+    };
+    let checkpoint = checkpoint_file
+        .as_ref()
+        .or(resume_file.as_ref())
+        .map(|file| Arc::new(Checkpoint::new(file, &root_dir, already_searched.clone())));
+    if resume_file.is_some() && !quiet {
+        println!("{}", i18n::format("resuming", &[&already_searched.len()]));
+    }
+
+    // Pick the thread count: fixed when --threads <num> is given. Otherwise the pool has
+    // room for the most the volume may take, and a limiter starting from a per-volume
+    // heuristic lets more or fewer I/O requests run at once as their latency allows.
+    let (pool_threads, io_limit) = match num_threads {
+        Some(n) => (n, None),
+        None => {
+            let initial = tuning::thread_count(volume_kind);
+            let max = tuning::max_thread_count(volume_kind);
+            if verbose && !quiet {
+                println!("{}", i18n::format("auto_threads", &[&volume_kind, &initial, &max]));
+            }
+            (max, Some(Arc::new(tuning::Limiter::new(initial, max))))
+        }
+    };
+
+    // Options shared with the search thread
+    let options = SearchOptions {
+        target: target.clone(),
+        ignore_accents,
+        types: types.clone(),
+        owner,
+        perm,
+        attributes,
+        mime,
+        time_filters,
+        min_depth,
+        max_depth,
+        stop_after_match: (stop_after_match || profile.stop_after_match()) && !all,
+        max_matches,
+        first,
+        all,
+        dedup_hardlinks,
+        low_priority,
+        one_file_system,
+        network: is_network,
+        suggest: !quiet,
+        skip: profile.skip_rules(),
+        follow_links: profile.follow_links(),
+        backend,
+        strategy,
+        already_searched,
+        checkpoint: checkpoint.clone(),
+        extra_roots: extra_roots.clone(),
+        io_limit: io_limit.clone(),
+    };
+
+    // Show what would be searched and stop before touching the tree
+    if dry_run {
+        dry_run::print_plan(
+            &root_dir,
+            &options,
+            profile,
+            volume_kind,
+            pool_threads,
+        );
+        return Ok(ExitCode::SUCCESS);
     }
 
+    // A pool of our own rather than rayon's global one, so several searches can run in
+    // one process (e.g. when embedded or called repeatedly)
+    let pool = rayon::ThreadPoolBuilder::new()
+        .num_threads(pool_threads)
+        .thread_name(|i| format!("speedy-worker-{}", i))
+        .build()?;
+
     // Print what we're doing (unless --quiet is used)
     if !quiet {
         println!(
-            "🔍 Searching for {} \"{}\" in {}...",
-            if search_type == "search:file" { "file" } else { "folder" },
-            target,
-            root_dir.display()
+            "{}",
+            i18n::format(
+                "searching",
+                &[
+                    &i18n::text(filters::describe_types(&types)),
+                    &target,
+                    &longpath::for_display(&root_dir).display(),
+                ]
+            )
         );
-        if max_depth != usize::MAX {
-            println!("   (Depth limited to {} levels)", max_depth);
+        for root in &extra_roots {
+            println!("{}", i18n::format("also_searching", &[&longpath::for_display(root).display()]));
+        }
+        match (min_depth, max_depth) {
+            (0, usize::MAX) => {}
+            (0, max) => println!("{}", i18n::format("depth_max", &[&max])),
+            (min, usize::MAX) => println!("{}", i18n::format("depth_min", &[&min])),
+            (min, max) => println!("{}", i18n::format("depth_between", &[&min, &max])),
+        }
+        if profile != Profile::Balanced {
+            println!("{}", i18n::format("profile", &[&profile]));
+        }
+        if is_network {
+            println!("{}", i18n::format("network_retries", &[&network::MAX_RETRIES]));
         }
     }
 
-    // Initialize progress bar if needed
+    // Entry count from a previous run of the same root, used to estimate progress; a
+    // search of several roots doesn't match any saved count
+    let known_total = if extra_roots.is_empty() {
+        storage::load_entry_count(&root_dir, max_depth)
+    } else {
+        None
+    };
+
+    // Initialize progress bar if needed: a percentage bar when the total is known, a spinner otherwise
     let progress = if !quiet {
-        let pb = ProgressBar::new_spinner();
+        let (pb, template) = match known_total {
+            Some(total) => (ProgressBar::new(total), i18n::text("progress_bar")),
+            None => (ProgressBar::new_spinner(), i18n::text("progress_spinner")),
+        };
         pb.set_style(
             ProgressStyle::default_spinner()
                 .tick_strings(&["⠋", "⠙", "⠹", "⠸", "⠼", "⠴", "⠦", "⠧", "⠇", "⠏"])
-                .template("{spinner} Searching... {msg}")
-                .map_err(|e| SpeedyError::Template(e.to_string()))?,
+                .template(template)
+                .map_err(|e| SpeedyError::Template(e.to_string()))?
+                .progress_chars("=> "),
         );
         Some(pb)
     } else {
@@ -237,178 +929,682 @@ fn main() -> Result<(), SpeedyError> {
     };
 
     // Create communication channels
-    let (found_tx, found_rx) = bounded(1); // To send found result
-    let (progress_tx, progress_rx) = unbounded(); // To send progress updates
+    let (found_tx, found_rx) = bounded(FOUND_CHANNEL_CAPACITY); // To send found results
+    let (progress_tx, progress_rx) = bounded(PROGRESS_CHANNEL_CAPACITY); // To send progress updates
+    let (warning_tx, warning_rx) = bounded(WARNING_CHANNEL_CAPACITY); // To send traversal warnings
 
     // Handle Ctrl+C to cancel search
-    let cancelled = Arc::new(AtomicBool::new(false));
-    let c = cancelled.clone();
-    ctrlc::set_handler(move || {
-        c.store(true, Ordering::SeqCst);
-    })?;
+    let cancelled = cancel_flag()?;
 
     // Clone values to be moved into the thread
+    let channels = SearchChannels { found: found_tx, progress: progress_tx, warnings: warning_tx };
     let root_dir_clone = root_dir.clone();
     let cancelled_clone = cancelled.clone();
     let progress_clone = progress.clone();
-    let search_type_clone = search_type.clone();
-    let target_clone = target.clone();
 
     // Spawn search thread
-    let search_thread = std::thread::spawn(move || {
-        let found = match search_type_clone.as_str() {
-            "search:file" => parallel_search(
-                &root_dir_clone, 
-                &target_clone, 
-                true, 
-                verbose, 
-                max_depth, 
-                &cancelled_clone, 
-                &found_tx, 
-                &progress_tx,
-                stop_after_match, // Pass the new flag
-            ),
-            "search:folder" => parallel_search(
-                &root_dir_clone, 
-                &target_clone, 
-                false, 
-                verbose, 
-                max_depth, 
-                &cancelled_clone, 
-                &found_tx, 
-                &progress_tx,
-                stop_after_match, // Pass the new flag
-            ),
-            _ => Ok(false),
-        };
+    let setup_time = start_time.elapsed();
+    let search_started = Instant::now();
+    let search_thread = std::thread::Builder::new().name(crash::SEARCH_THREAD.to_string()).spawn(move || {
+        let found = parallel_search(
+            &root_dir_clone,
+            &options,
+            &cancelled_clone,
+            &channels,
+            &pool,
+        );
         if let Some(pb) = progress_clone {
             pb.finish_and_clear();
         }
         found
-    });
+    })?;
 
-    // Show live progress spinner
-    if let Some(pb) = progress {
-        while !search_thread.is_finished() {
-            if let Ok(count) = progress_rx.try_recv() {
-                pb.set_message(format!("Scanned {} locations", count));
+    // Results are collected while the search runs. With --output-file they are written out
+    // as they arrive; only the first is kept (for --notify), or all of them when --copy
+    // needs them. Past --max-memory, kept results move to temporary files.
+    let mut result_file = output_file
+        .as_deref()
+        .map(|file| ResultFile::create(file, resume_file.is_some()))
+        .transpose()?;
+    let mut found_paths = ResultStore::new(max_memory);
+    let mut take_results = |paths: Vec<PathBuf>| -> Result<(), SpeedyError> {
+        for path in paths {
+            let accepted = result_file.as_ref().map_or(found_paths.len(), ResultFile::count);
+            if !all && accepted > 0 {
+                break; // Another worker matched at the same time; one result is enough
+            }
+            if json {
+                println!("{}", events::match_event(&path_style.shown(&path, &root_dir)));
+            }
+            if let Some(file) = &mut result_file {
+                file.write(&path, &path_style.shown(&path, &root_dir), output_template.as_ref())?;
+                if found_paths.is_empty() || copy {
+                    found_paths.push(path)?;
+                }
+            } else {
+                found_paths.push(path)?;
+            }
+        }
+        if let Some(file) = &mut result_file {
+            file.flush()?;
+        }
+        Ok(())
+    };
+
+    // Show live progress spinner while taking results as they arrive
+    let mut rate_meter = RateMeter::new();
+    let mut last_progress = None; // For the diagnostics report if the search crashes
+    while !search_thread.is_finished() {
+        let mut batch: Vec<PathBuf> = found_rx
+            .recv_timeout(std::time::Duration::from_millis(100))
+            .into_iter()
+            .collect();
+        batch.extend(found_rx.try_iter());
+        take_results(batch)?;
+        report_warnings(&warning_rx, progress.as_ref(), json);
+        // Only the latest count matters; skip any backlog of older updates
+        if let Some(update) = progress_rx.try_iter().last() {
+            last_progress = Some(update);
+        }
+        if let Some(pb) = &progress {
+            if let Some(update) = last_progress {
+                let rate = rate_meter.update(&update);
+                let eta = known_total.and_then(|total| rate_meter.eta(update.scanned, total));
+                if let Some(total) = known_total {
+                    // The tree may have grown since the last run, so never overflow the bar
+                    pb.set_position((update.scanned as u64).min(total));
+                }
+                pb.set_message(progress::message(&update, rate, eta));
             }
             pb.tick();
-            std::thread::sleep(std::time::Duration::from_millis(100));
         }
     }
 
-    // Wait for thread to finish and check result
-    let found = search_thread.join().unwrap()?; // Unwrap join error
+    // Wait for thread to finish and check result. A panic in the traversal leaves a
+    // diagnostics report behind instead of a bare panic message.
+    let outcome = match search_thread.join() {
+        Ok(outcome) => outcome?,
+        Err(_) => {
+            if let Some(pb) = &progress {
+                pb.finish_and_clear();
+            }
+            eprintln!("{}", i18n::format("crashed", &[&format!("{:.2?}", start_time.elapsed())]));
+            match crash::write_report(&args, last_progress, start_time.elapsed()) {
+                Ok(report) => eprintln!("{}", i18n::format("crash_report", &[&report.display()])),
+                Err(e) => eprintln!("{}", i18n::format("crash_report_failed", &[&e])),
+            }
+            return Ok(ExitCode::from(EXIT_CRASHED));
+        }
+    };
+    let found = outcome.found;
+    let was_cancelled = cancelled.load(Ordering::SeqCst);
+    let mut cleanup_failed = false; // Set when --delete / --move-to could not move a match
     let elapsed = start_time.elapsed(); // Calculate duration
 
+    let traversal_time = search_started.elapsed();
+    log::info!(
+        "Timing: setup {:.2?}, traversal {:.2?}, {} entries scanned ({:.0} entries/s)",
+        setup_time,
+        traversal_time,
+        outcome.scanned,
+        outcome.scanned as f64 / traversal_time.as_secs_f64().max(f64::EPSILON)
+    );
+    if let Some(limit) = &io_limit {
+        log::info!("Concurrent I/O requests at the end: {} of up to {}", limit.limit(), pool_threads);
+    }
+
+    // Keep the checkpoint of an interrupted search; a finished one is no longer needed
+    if let Some(checkpoint) = &checkpoint {
+        checkpoint.finish(was_cancelled)?;
+        if was_cancelled && !quiet {
+            println!(
+                "{}",
+                i18n::format("progress_saved", &[&checkpoint.completed_count(), &checkpoint.file().display()])
+            );
+        }
+    }
+
+    // Remember the entry count for future progress estimates. Only a full traversal gives
+    // the real total; an early-terminated run is still useful if it saw more than we knew.
+    let scanned = outcome.scanned as u64;
+    let complete = (all || !found) && !was_cancelled && !outcome.limit_reached;
+    if extra_roots.is_empty() && (complete || known_total.is_some_and(|total| scanned > total)) {
+        let _ = storage::save_entry_count(&root_dir, max_depth, scanned); // Best effort
+    }
+
+    // Opt-in performance sample for `speedy tune`; nothing about what was searched is kept
+    if let Some(file) = &telemetry_file {
+        let sample = telemetry::Sample {
+            volume: volume_kind,
+            filesystem: tuning::detect_filesystem(&root_dir),
+            backend: Backend::for_dir(&root_dir, backend).to_string(),
+            strategy: strategy.to_string(),
+            threads: io_limit.as_ref().map_or(pool_threads, |limit| limit.limit()),
+            auto_threads: num_threads.is_none(),
+            entries: scanned,
+            seconds: traversal_time.as_secs_f64(),
+            complete,
+        };
+        if let Err(e) = telemetry::record(file, &sample) {
+            log::warn!("Could not write telemetry to {}: {}", file.display(), e);
+        }
+    }
+
+    // Short description of the result for the search history
+    let mut history_result = if was_cancelled {
+        "cancelled".to_string()
+    } else {
+        "not found".to_string()
+    };
+
+    // Drain every match the workers sent, including those found before a Ctrl+C.
+    // Single-result searches stop at the first match; --all lists every match in order.
+    take_results(found_rx.try_iter().collect())?;
+    report_warnings(&warning_rx, None, json);
+    let written = result_file.as_ref().map(ResultFile::count);
+    if found_paths.spilled() && !quiet {
+        println!("{}", i18n::text("spilled"));
+    }
+
     if found {
-        if let Ok(path) = found_rx.try_recv() {
+        // Printed and copied paths follow --absolute / --relative
+        let shown = |path: &Path| path_style.shown(path, &root_dir);
+        let count = written.unwrap_or(found_paths.len());
+        history_result = match found_paths.first() {
+            Some(path) if count == 1 => longpath::for_display(path).display().to_string(),
+            _ if was_cancelled => format!("{} matches (cancelled)", count),
+            _ => format!("{} matches", count),
+        };
+
+        if let (Some(count), Some(file)) = (written, &output_file) {
+            // Results went to the file as they were found
             if !quiet {
-                println!(
-                    "\n🎯 Found matching {} at:",
-                    if search_type == "search:file" { "file" } else { "folder" }
-                );
-                println!("   {}", path.display());
+                println!("\n{}", i18n::format("wrote_results", &[&count, &file.display()]));
+            }
+        } else if let Some(template) = &output_template {
+            // Templated output is meant for scripts, so it is printed even with --quiet
+            for path in found_paths.sorted()? {
+                let path = path?;
+                println!("{}", template.render(&path, &shown(&path)));
             }
-            if notify {
-                Notification::new()
-                    .summary("Speedy Search")
-                    .body(&format!("Found {}: {}", target, path.display()))
-                    .show()?;
+        } else if !quiet {
+            if all {
+                let key = if was_cancelled { "found_many_cancelled" } else { "found_many" };
+                println!("\n{}", i18n::format(key, &[&count, &i18n::text(filters::describe_types(&types))]));
+            } else {
+                println!("\n{}", i18n::format("found_one", &[&i18n::text(filters::describe_types(&types))]));
+            }
+            for path in found_paths.sorted()? {
+                println!("   {}", shown(&path?).display());
             }
         }
-        if !quiet {
-            println!("✅ Found \"{}\" in {:.2?}", target, elapsed);
+        if !all
+            && let Some(parent) = found_paths.first().and_then(Path::parent)
+        {
+            let _ = storage::record_recent_dir(parent); // Best effort, feeds prioritized search
+        }
+        if outcome.duplicates > 0 && !quiet {
+            println!("{}", i18n::format("hardlinks_skipped", &[&outcome.duplicates]));
+        }
+        if outcome.limit_reached && !quiet {
+            println!("{}", i18n::format("max_matches_reached", &[&count]));
+        }
+        if was_cancelled && !quiet {
+            println!(
+                "{}",
+                i18n::format("cancelled_partial", &[&format!("{:.2?}", elapsed), &outcome.scanned])
+            );
+        } else if !quiet {
+            println!("{}", i18n::format("found_in", &[&target, &format!("{:.2?}", elapsed)]));
+        }
+
+        if copy && !found_paths.is_empty() {
+            let text = found_paths
+                .sorted()?
+                .map(|path| path.map(|path| shown(&path).display().to_string()))
+                .collect::<io::Result<Vec<_>>>()?
+                .join("\n");
+            // The results are already printed, so a missing clipboard is not fatal
+            match clipboard::copy_text(&text) {
+                Ok(()) if !quiet => {
+                    println!("{}", i18n::text(if all { "copied_results" } else { "copied_path" }));
+                }
+                Ok(()) => {}
+                Err(e) => log::warn!("Could not copy to the clipboard: {}", e),
+            }
+        }
+        // Act on the matches once everything else has used their current paths. Partial
+        // results of a cancelled search are never acted on.
+        if let Some(action) = &cleanup_action {
+            let matches = found_paths.sorted()?.collect::<io::Result<Vec<_>>>()?;
+            let targets = cleanup::outermost(&matches);
+            if was_cancelled {
+                println!("{}", i18n::text("cleanup_cancelled"));
+            } else if assume_yes || cleanup::confirm(action, &targets, shown)? {
+                let (done, failures) = cleanup::apply(action, &targets);
+                if !quiet {
+                    match action {
+                        CleanupAction::Trash => println!("{}", i18n::format("moved_to_trash", &[&done])),
+                        CleanupAction::MoveTo(dir) => println!(
+                            "{}",
+                            i18n::format("moved_to", &[&done, &longpath::for_display(dir).display()])
+                        ),
+                    }
+                }
+                for (path, error) in &failures {
+                    println!("{}", i18n::format("move_failed", &[&shown(path).display(), error]));
+                }
+                cleanup_failed = !failures.is_empty();
+            } else {
+                println!("{}", i18n::text("nothing_moved"));
+            }
+        }
+        // Notify last: the notification may keep us waiting for the user to pick an action.
+        // Moved matches are no longer where the notification would open them.
+        if notify && cleanup_action.is_none() && let Some(path) = found_paths.first() {
+            notifications::notify_found(&target, path)?;
         }
-    } else if cancelled.load(Ordering::SeqCst) {
+    } else if was_cancelled {
         if !quiet {
-            println!("🛑 Search cancelled by user");
+            println!(
+                "{}",
+                i18n::format("cancelled_none", &[&format!("{:.2?}", elapsed), &outcome.scanned])
+            );
         }
     } else {
         if !quiet {
-            println!("❌ Could not find \"{}\" after {:.2?}", target, elapsed);
+            println!("{}", i18n::format("not_found", &[&target, &format!("{:.2?}", elapsed)]));
+            if !outcome.suggestions.is_empty() {
+                println!("{}", i18n::text("did_you_mean"));
+                for (name, path) in &outcome.suggestions {
+                    println!("   {}  ({})", name, longpath::for_display(path).display());
+                }
+            }
             if !verbose && is_global {
-                println!("ℹ️ Tip: Try with --verbose to see search progress or permission issues");
+                println!("{}", i18n::text("tip_verbose"));
             }
         }
     }
 
-    Ok(())
+    // Say what was left out because of permissions when it could explain a missing result
+    let denied_total: usize = outcome.denied.iter().map(|(_, count)| count).sum();
+    if denied_total > 0 && (!found || all) && !quiet {
+        let key = if permissions::is_elevated() {
+            "permissions_skipped"
+        } else if cfg!(windows) {
+            "permissions_skipped_elevate"
+        } else {
+            "permissions_skipped_sudo"
+        };
+        println!(
+            "{}",
+            i18n::format(key, &[&permissions::format_count(denied_total), &outcome.denied.len()])
+        );
+        if verbose {
+            for (dir, count) in outcome.denied.iter().take(5) {
+                println!(
+                    "   {:>8}  {}",
+                    permissions::format_count(*count),
+                    longpath::for_display(dir).display()
+                );
+            }
+        }
+    }
+
+    if json {
+        let matches = written.unwrap_or(found_paths.len());
+        let elapsed_ms = elapsed.as_millis();
+        println!("{}", events::summary_event(matches, outcome.scanned, elapsed_ms, was_cancelled, denied_total));
+    }
+
+    // Best effort: a read-only data directory shouldn't fail the search
+    let _ = storage::record_search(&args[1..], elapsed.as_millis() as u64, &history_result);
+
+    // With --notify, also report long searches that ended without a result, so a global
+    // search can be left running in the background
+    if notify && !found && elapsed >= notifications::LONG_SEARCH {
+        notifications::notify_unsuccessful(
+            &target,
+            elapsed,
+            outcome.scanned,
+            was_cancelled,
+        )?;
+    }
+
+    // Like other command-line tools, report an interrupted run with 128 + SIGINT
+    if was_cancelled {
+        return Ok(ExitCode::from(EXIT_CANCELLED));
+    }
+    if cleanup_failed {
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
 }
 
+// The Ctrl+C handler can only be installed once per process, so every search in the
+// process shares its flag; it is reset when a new search starts
+fn cancel_flag() -> Result<Arc<AtomicBool>, SpeedyError> {
+    static CANCEL_FLAG: OnceLock<Arc<AtomicBool>> = OnceLock::new();
+
+    if let Some(flag) = CANCEL_FLAG.get() {
+        flag.store(false, Ordering::SeqCst);
+        return Ok(flag.clone());
+    }
+    let flag = Arc::new(AtomicBool::new(false));
+    let c = flag.clone();
+    ctrlc::set_handler(move || {
+        c.store(true, Ordering::SeqCst);
+    })?;
+    Ok(CANCEL_FLAG.get_or_init(|| flag).clone())
+}
+
+// Options controlling how a single search matches entries
+#[derive(Debug, Clone)]
+struct SearchOptions {
+    target: String,          // Name to look for (case-insensitive)
+    ignore_accents: bool,    // Match names regardless of diacritics
+    types: Vec<EntryType>,   // Entry types to report (empty = any)
+    owner: Option<u32>,      // Only entries owned by this user ID (Unix)
+    perm: Option<PermFilter>, // Only entries whose permission bits match (Unix)
+    attributes: u32,         // Required Win32 attribute bits (Windows, 0 = any)
+    mime: Option<MimeFilter>, // Only files whose content has one of these types
+    time_filters: Vec<TimeFilter>, // Only entries modified/created/accessed recently
+    min_depth: usize,        // Ignore matches shallower than this
+    max_depth: usize,        // Do not descend deeper than this
+    stop_after_match: bool,  // Stop walking once a match is found
+    max_matches: Option<usize>, // Stop walking once this many matches are reported
+    first: bool,             // Report the first match in sorted traversal order (--first)
+    all: bool,               // Report every match instead of only the first
+    dedup_hardlinks: bool,   // Report each hard-linked file once (--all)
+    low_priority: bool,      // Throttle the walk (--low-priority)
+    one_file_system: bool,   // Do not cross into other mounted file systems
+    network: bool,           // Root is on a network share: retry transient errors
+    suggest: bool,           // Collect near-miss names for "did you mean"
+    skip: SkipRules,         // Folders pruned from the walk
+    follow_links: bool,      // Walk into symbolic links to directories
+    backend: Option<Backend>, // --backend; None picks one per volume
+    strategy: Strategy,      // Depth- or breadth-first walk order (--strategy)
+    already_searched: Vec<PathBuf>, // Subtrees completed before a --resume
+    checkpoint: Option<Arc<Checkpoint>>, // Where progress is saved (--checkpoint)
+    extra_roots: Vec<PathBuf>, // Further --path roots, none inside another, walked after the root
+    io_limit: Option<Arc<tuning::Limiter>>, // Adaptive I/O concurrency with --threads auto
+}
+
+impl SearchOptions {
+    // Whether likely locations are walked before the rest of the tree. Prioritized
+    // sub-walks could start on another file system, so --one-file-system keeps the plain
+    // root walk; --first needs an order that doesn't depend on recent results.
+    fn prioritize(&self) -> bool {
+        self.stop_after_match && !self.one_file_system && !self.first
+    }
+}
+
+// Where the search thread reports what it finds
+struct SearchChannels {
+    found: crossbeam_channel::Sender<PathBuf>,           // Matches
+    progress: crossbeam_channel::Sender<ProgressUpdate>, // Entry counts for the spinner
+    warnings: crossbeam_channel::Sender<Warning>,        // Problems met during the walk
+}
+
+// Summary of a finished (or cancelled) traversal
+#[derive(Debug)]
+struct SearchOutcome {
+    found: bool,    // Whether a match was sent on the found channel
+    limit_reached: bool, // Whether the walk stopped at --max-matches
+    scanned: usize, // Number of entries visited
+    suggestions: Vec<(String, PathBuf)>, // Closest names seen, as (name, example path)
+    duplicates: usize, // Matches dropped as hard links to an already reported file
+    denied: Vec<(PathBuf, usize)>, // Unreadable entries per top-level folder, most first
+}
 
 fn parallel_search(
     root: &Path,
-    target: &str,
-    search_files: bool,
-    verbose: bool,
-    max_depth: usize,
+    options: &SearchOptions,
     cancelled: &Arc<AtomicBool>,
-    found_tx: &crossbeam_channel::Sender<PathBuf>,
-    progress_tx: &crossbeam_channel::Sender<usize>,
-    stop_after_match: bool,
-) -> Result<bool, SpeedyError> {
-    let target = target.to_lowercase();
+    channels: &SearchChannels,
+    pool: &rayon::ThreadPool,
+) -> Result<SearchOutcome, SpeedyError> {
+    let matcher = NameMatcher::new(&options.target, options.ignore_accents);
+    let suggestions = options
+        .suggest
+        .then(|| Suggestions::new(&options.target, options.ignore_accents));
+    let stop_after_match = options.stop_after_match;
     let scanned = Arc::new(std::sync::atomic::AtomicUsize::new(0));
     let found = Arc::new(AtomicBool::new(false));
+    let matches = std::sync::atomic::AtomicUsize::new(0);
+    let limit_reached = AtomicBool::new(false);
+    // --first: the match with the lowest position in the walk so far, and that position
+    let first_match: Mutex<Option<(usize, PathBuf)>> = Mutex::new(None);
+    let first_position = std::sync::atomic::AtomicUsize::new(usize::MAX);
+    let seen_files = options.dedup_hardlinks.then(SeenFiles::new);
+    let denied = DeniedSummary::new(root, &options.extra_roots);
+    // With several roots, a match reachable from two of them (through a link) is reported once
+    let reported = (!options.extra_roots.is_empty()).then(|| Mutex::new(HashSet::new()));
 
-    // Create a parallel iterator over the directory entries
-    let walker = WalkDir::new(root)
-        .max_depth(max_depth)
-        .follow_links(false)
-        .into_iter()
-        .filter_entry(|e| !should_skip_directory(e.path()))
+    // Create a parallel iterator over the directory entries. When only the first match is
+    // needed, likely locations are walked before the rest of the tree.
+    // Each entry is paired with its depth below the search root.
+    let mut walks = schedule::plan_walks(
+        root,
+        &options.extra_roots,
+        options.max_depth,
+        options.prioritize(),
+        &options.skip,
+    );
+    for walk in &mut walks {
+        walk.exclude.extend(options.already_searched.iter().cloned());
+    }
+
+    // Walks are pulled from a queue so directories that failed with a transient network
+    // error can be queued again for another attempt
+    let queue = Arc::new(Mutex::new(VecDeque::from(walks)));
+    let next_walk = {
+        let queue = queue.clone();
+        move || queue.lock().ok()?.pop_front()
+    };
+
+    let visited = Arc::new(VisitedDirs::new());
+    let mut listed = std::iter::from_fn(next_walk).flat_map(|walk| {
+        let backend = Backend::for_dir(&walk.dir, options.backend);
+        backend.walk(walk, options, visited.clone())
+    });
+    // Taking the next entry may read a folder, one of the requests the limiter counts
+    let io_limit = options.io_limit.as_deref();
+    let entries = std::iter::from_fn(move || {
+        let _permit = io_limit.map(tuning::Limiter::acquire);
+        listed.next()
+    });
+    let walker = entries
         .filter_map(|e| {
             // Check if we should stop early
-            if cancelled.load(Ordering::SeqCst) || (found.load(Ordering::SeqCst) && stop_after_match) {
+            if cancelled.load(Ordering::SeqCst)
+                || (found.load(Ordering::SeqCst) && stop_after_match)
+                || limit_reached.load(Ordering::SeqCst)
+                || first_position.load(Ordering::SeqCst) != usize::MAX
+            {
                 return None;
             }
 
             match e {
-                Ok(entry) => {
+                Ok((entry, depth)) => {
                     // Update progress counter
                     let count = scanned.fetch_add(1, Ordering::Relaxed) + 1;
-                    if count % 500 == 0 {
-                        let _ = progress_tx.send(count);
+                    if count.is_multiple_of(500) {
+                        let _ = channels.progress.try_send(ProgressUpdate {
+                            scanned: count,
+                            matches: matches.load(Ordering::Relaxed),
+                            at: Instant::now(),
+                        });
+                    }
+                    // Throttle the walk so a background search leaves the disk usable
+                    if options.low_priority && count.is_multiple_of(priority::THROTTLE_BATCH) {
+                        std::thread::sleep(priority::THROTTLE_PAUSE);
+                    }
+                    if let Some(checkpoint) = &options.checkpoint {
+                        checkpoint.entered(&entry);
+                        if let Err(e) = checkpoint.save_if_due() {
+                            log::warn!("Could not save checkpoint: {}", e);
+                        }
                     }
-                    Some(entry)
+                    Some((entry, depth))
                 },
-                Err(e) => {
-                    if verbose && should_log_error(&e) {
-                        eprintln!("⚠️ Could not access directory: {}", e);
+                Err(failure) => {
+                    let (e, retry) = *failure;
+                    // Network hiccups: back off, then walk the directory again later
+                    if options.network
+                        && network::is_transient(&e)
+                        && let Some(retry) = retry
+                        && retry.attempt <= network::MAX_RETRIES
+                    {
+                        let _ = channels.warnings.send(Warning {
+                            kind: WarningKind::NetworkRetry,
+                            path: Some(retry.dir.clone()),
+                            message: format!(
+                                "Network error, retrying ({}/{}): {}",
+                                retry.attempt,
+                                network::MAX_RETRIES,
+                                e
+                            ),
+                            level: log::Level::Info,
+                        });
+                        if let Some(checkpoint) = &options.checkpoint {
+                            checkpoint.failed(&retry.dir);
+                        }
+                        std::thread::sleep(network::retry_backoff(retry.attempt));
+                        if let Ok(mut queue) = queue.lock() {
+                            queue.push_back(*retry);
+                        }
+                        return None;
                     }
+                    // Counted for the closing summary rather than dropped silently
+                    let denied_entry = e.io_error().is_some_and(|io| io.kind() == io::ErrorKind::PermissionDenied);
+                    if denied_entry && let Some(path) = e.path() {
+                        denied.record(path);
+                    }
+                    // Expected errors (permission denied, vanished entries) are only
+                    // interesting when debugging
+                    let _ = channels.warnings.send(Warning {
+                        kind: if denied_entry { WarningKind::PermissionDenied } else { WarningKind::Traversal },
+                        path: e.path().map(Path::to_path_buf),
+                        message: format!("Could not access directory: {}", e),
+                        level: if should_log_error(&e) { log::Level::Info } else { log::Level::Debug },
+                    });
                     None
                 }
             }
         });
 
-    // Use find_any for parallel search with early termination
-    let result = walker.par_bridge().find_any(|entry| {
-        if cancelled.load(Ordering::SeqCst) || (found.load(Ordering::SeqCst) && stop_after_match) {
+    // Use find_any for parallel search with early termination, on the caller's pool.
+    // Entries are numbered in walk order so --first can tell which match came first.
+    let result = pool.install(|| walker.enumerate().par_bridge().find_any(|(position, (entry, depth))| {
+        if cancelled.load(Ordering::SeqCst)
+            || (found.load(Ordering::SeqCst) && stop_after_match)
+            || limit_reached.load(Ordering::SeqCst)
+        {
+            return false;
+        }
+
+        // Shallower entries are still walked, just never reported
+        if *depth < options.min_depth {
             return false;
         }
 
         let path = entry.path();
-        let is_match = path.file_name()
-            .and_then(|n| n.to_str())
-            .map(|name| name.to_lowercase() == target)
-            .unwrap_or(false);
+        let name = path.file_name().and_then(|n| n.to_str());
+        let is_match = name.map(|name| matcher.matches(name)).unwrap_or(false);
 
         if is_match {
-            if (search_files && path.is_file()) || (!search_files && path.is_dir()) {
-                let _ = found_tx.send(path.to_path_buf());
+            // The filters read metadata and content, so they count against the I/O limit
+            let permit = io_limit.map(tuning::Limiter::acquire);
+            let wanted = filters::matches_any_type(&options.types, entry)
+                && filters::matches_owner_and_perm(entry, options.owner, options.perm)
+                && filters::matches_attributes(entry, options.attributes)
+                && filters::matches_times(entry, &options.time_filters)
+                && filters::matches_mime(entry, options.mime.as_ref())
+                && seen_files.as_ref().is_none_or(|seen| seen.first_sighting(entry))
+                && reported.as_ref().is_none_or(|reported| first_report(reported, path));
+            drop(permit);
+            if wanted {
+                // --first: keep the earliest match and let the entries handed out before
+                // it finish, since one of them may match too. Returning true here could
+                // drop such an entry, so the walk instead stops producing new ones.
+                if options.first {
+                    if let Ok(mut best) = first_match.lock()
+                        && best.as_ref().is_none_or(|(best, _)| position < best)
+                    {
+                        *best = Some((*position, path.to_path_buf()));
+                    }
+                    first_position.fetch_min(*position, Ordering::SeqCst);
+                    return false;
+                }
+                // Under --max-matches a worker first claims one of the N slots, so racing
+                // workers never send more than N results between them
+                let slot = match options.max_matches {
+                    Some(max) => matches.fetch_update(Ordering::SeqCst, Ordering::SeqCst, |n| (n < max).then_some(n + 1)),
+                    None => Ok(matches.fetch_add(1, Ordering::SeqCst)),
+                };
+                let Ok(claimed) = slot else {
+                    limit_reached.store(true, Ordering::SeqCst);
+                    return true;
+                };
+                let _ = channels.found.send(path.to_path_buf());
                 found.store(true, Ordering::SeqCst);
-                true
+                if options.max_matches.is_some_and(|max| claimed + 1 >= max) {
+                    limit_reached.store(true, Ordering::SeqCst);
+                    return true;
+                }
+                !options.all // Keep walking when every match is wanted
             } else {
                 false
             }
         } else {
+            // Remember near misses of a plausible type for "did you mean" suggestions
+            if let (Some(suggestions), Some(name)) = (&suggestions, name)
+                && filters::plausible_type(&options.types, entry)
+            {
+                suggestions.consider(name, path);
+            }
             false
         }
-    });
+    }));
+
+    if let Some((_, path)) = first_match.into_inner().ok().flatten() {
+        let _ = channels.found.send(path);
+        found.store(true, Ordering::SeqCst);
+    }
 
-    Ok(result.is_some())
+    let found = result.is_some() || found.load(Ordering::SeqCst);
+    Ok(SearchOutcome {
+        found,
+        limit_reached: limit_reached.load(Ordering::SeqCst),
+        scanned: scanned.load(Ordering::Relaxed),
+        suggestions: match suggestions {
+            Some(suggestions) if !found => suggestions.into_sorted(),
+            _ => Vec::new(),
+        },
+        duplicates: seen_files.map_or(0, |seen| seen.duplicates()),
+        denied: denied.into_sorted(),
+    })
+}
+
+// Report the warnings the search thread has sent so far, keeping them clear of the spinner
+fn report_warnings(
+    warning_rx: &crossbeam_channel::Receiver<Warning>,
+    progress: Option<&ProgressBar>,
+    json: bool,
+) {
+    for warning in warning_rx.try_iter() {
+        match progress {
+            Some(pb) => pb.suspend(|| warning.report(json)),
+            None => warning.report(json),
+        }
+    }
+}
+
+// True the first time a match is reported under its canonical path
+fn first_report(reported: &Mutex<HashSet<PathBuf>>, path: &Path) -> bool {
+    let canonical = path.canonicalize().unwrap_or_else(|_| path.to_path_buf());
+    reported.lock().map(|mut reported| reported.insert(canonical)).unwrap_or(true)
 }
 
 fn should_log_error(e: &walkdir::Error) -> bool {
@@ -423,53 +1619,113 @@ fn should_log_error(e: &walkdir::Error) -> bool {
 }
 
 
-fn should_skip_directory(path: &Path) -> bool {
-    // Check for folders with names that should be skipped
-    if let Some(name) = path.file_name().and_then(|n| n.to_str()) {
-        let lower = name.to_lowercase();
 
-        // Common "noisy" or system folders we don't want to scan
-        let skip_names = [
-            "$recycle.bin", "system volume information", "windows", "program files", 
-            "program files (x86)", "appdata", "temp", "tmp", "node_modules", ".git",
-        ];
+// `--path` value: a directory, or `@name` for a bookmark
+fn resolve_search_path(value: &str) -> Result<PathBuf, SpeedyError> {
+    let Some(name) = value.strip_prefix('@') else {
+        return Ok(PathBuf::from(value));
+    };
+    storage::load_bookmarks()
+        .into_iter()
+        .find(|(n, _)| n == name)
+        .map(|(_, path)| path)
+        .ok_or_else(|| {
+            SpeedyError::Argument(format!(
+                "Unknown bookmark '@{}' (see 'speedy bookmark list')",
+                name
+            ))
+        })
+}
 
-        if skip_names.contains(&lower.as_str()) {
-            return true;
+// Files and folders that mark the top of a project
+const PROJECT_MARKERS: &[&str] = &[".git", "Cargo.toml", "package.json"];
+
+// Nearest folder at or above `start` that contains a project marker
+fn find_project_root(start: &Path) -> Option<PathBuf> {
+    start
+        .ancestors()
+        .find(|dir| PROJECT_MARKERS.iter().any(|marker| dir.join(marker).exists()))
+        .map(Path::to_path_buf)
+}
+
+// `speedy bookmark add <name> <path> | remove <name> | list`
+fn bookmark_command(args: &[String]) -> Result<(), SpeedyError> {
+    match args.iter().map(String::as_str).collect::<Vec<_>>().as_slice() {
+        ["add", name, path] => {
+            let name = name.strip_prefix('@').unwrap_or(name);
+            if name.is_empty() || name.contains(['\t', '\n']) {
+                return Err(SpeedyError::Argument(format!("Invalid bookmark name: {:?}", name)));
+            }
+            let path = Path::new(path)
+                .canonicalize()
+                .map_err(|e| SpeedyError::Argument(format!("Cannot bookmark {}: {}", path, e)))?;
+            let path = longpath::for_display(&path);
+            storage::add_bookmark(name, &path)?;
+            println!("{}", i18n::format("bookmarked", &[&name, &path.display()]));
+        }
+        ["remove", name] => {
+            let name = name.strip_prefix('@').unwrap_or(name);
+            if storage::remove_bookmark(name)? {
+                println!("{}", i18n::format("bookmark_removed", &[&name]));
+            } else {
+                return Err(SpeedyError::Argument(format!("Unknown bookmark '@{}'", name)));
+            }
+        }
+        [] | ["list"] => {
+            let bookmarks = storage::load_bookmarks();
+            if bookmarks.is_empty() {
+                println!("{}", i18n::text("no_bookmarks"));
+            }
+            for (name, path) in bookmarks {
+                println!("  @{:<12} {}", name, path.display());
+            }
+        }
+        _ => {
+            return Err(SpeedyError::Argument(
+                "Usage: speedy bookmark add <name> <path> | remove <name> | list".to_string(),
+            ));
         }
     }
-
-    false
+    Ok(())
 }
 
-fn print_help() {
-    println!("Speedy - A fast file and folder search tool");
-    println!();
-    println!("USAGE:");
-    println!("  speedy search:file <name> [options]");
-    println!("  speedy search:folder <name> [options]");
-    println!();
-    println!("OPTIONS:");
-    println!("  --global           Search the entire system (default: current directory)");
-    println!("  --path <path>      Search in a specific directory");
-    println!("  --verbose          Show detailed search information and warnings");
-    println!("  --quiet            Suppress non-essential output");
-    println!("  --depth <num>      Limit search depth (default: unlimited)");
-    println!("  --notify           Show desktop notification when found");
-    println!("  --threads <num>    Set number of threads (default: CPU cores)");
-    println!("  --stop-after-match Stop searching after first match is found");
-    println!("  --help             Show this help message");
-    println!();
-    println!("EXAMPLES:");
-    println!("  speedy search:file document.txt --global");
-    println!("  speedy search:folder Projects --path ~/work");
-    println!("  speedy search:file config.ini --depth 3 --notify");
+// List past searches for `speedy history`, most recent last
+fn print_history() -> Result<(), SpeedyError> {
+    let history = storage::load_history();
+    if history.is_empty() {
+        println!("{}", i18n::text("no_history"));
+        return Ok(());
+    }
+
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0);
+    for entry in &history {
+        println!(
+            "{:>4}  {:>12}  {:>8.2?}  speedy {}",
+            entry.id,
+            i18n::format("ago", &[&format_age(now.saturating_sub(entry.timestamp))]),
+            std::time::Duration::from_millis(entry.duration_ms),
+            entry.args.join(" ")
+        );
+        println!("            → {}", entry.result);
+    }
     println!();
-    println!("PERFORMANCE TIPS:");
-    println!("  - Use --global only when necessary");
-    println!("  - Limit search depth with --depth for faster results");
-    println!("  - For large searches, use --threads to control CPU usage");
-    println!("  - Use --stop-after-match when you only need the first result");
+    println!("{}", i18n::text("rerun_hint"));
+    Ok(())
 }
 
+// Compact age such as "45s", "12m", "3h" or "2d"
+fn format_age(secs: u64) -> String {
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m", secs / 60),
+        3600..86400 => format!("{}h", secs / 3600),
+        _ => format!("{}d", secs / 86400),
+    }
+}
 
+fn print_help() {
+    print!("{}", i18n::text("help"));
+}
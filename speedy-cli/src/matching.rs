@@ -0,0 +1,70 @@
+// ========================= Name Matching =========================
+
+use unicode_normalization::UnicodeNormalization; // For NFC/NFD normalization
+use unicode_normalization::char::is_combining_mark; // For stripping accents
+
+// Compares entry names against the search target.
+// Both sides are Unicode-normalized and case-folded, so a composed "é" (NFC, typical on
+// Windows/Linux) matches a decomposed "e + ◌́" (NFD, typical on macOS).
+#[derive(Debug, Clone)]
+pub struct NameMatcher {
+    target: String,       // Target, already normalized
+    ignore_accents: bool, // Also fold diacritics ("résumé" == "resume", "straße" == "strasse")
+}
+
+impl NameMatcher {
+    pub fn new(target: &str, ignore_accents: bool) -> Self {
+        NameMatcher {
+            target: normalize(target, ignore_accents),
+            ignore_accents,
+        }
+    }
+
+    pub fn matches(&self, name: &str) -> bool {
+        // Fast path: plain ASCII names need no Unicode processing
+        if name.is_ascii() && self.target.is_ascii() {
+            return name.eq_ignore_ascii_case(&self.target);
+        }
+        normalize(name, self.ignore_accents) == self.target
+    }
+}
+
+// Canonical comparison form of a name
+pub fn normalize(name: &str, ignore_accents: bool) -> String {
+    if ignore_accents {
+        // Compatibility-decompose (also splits ligatures like "ﬁ"), drop the combining
+        // marks that carry the accents, then transliterate letters that have no
+        // decomposition (ß, ø, ł, ...)
+        let stripped: String = name.nfkd().filter(|c| !is_combining_mark(*c)).collect();
+        let mut folded = String::with_capacity(stripped.len());
+        for c in stripped.to_lowercase().chars() {
+            match transliterate(c) {
+                Some(replacement) => folded.push_str(replacement),
+                None => folded.push(c),
+            }
+        }
+        folded
+    } else {
+        name.nfc().collect::<String>().to_lowercase()
+    }
+}
+
+// ASCII spelling of lowercase letters that Unicode decomposition leaves untouched
+fn transliterate(c: char) -> Option<&'static str> {
+    let replacement = match c {
+        'ß' => "ss",
+        'æ' => "ae",
+        'œ' => "oe",
+        'ø' => "o",
+        'ł' => "l",
+        'đ' | 'ð' => "d",
+        'þ' => "th",
+        'ı' => "i",
+        'ħ' => "h",
+        'ŧ' => "t",
+        'ĸ' => "k",
+        'ŋ' => "ng",
+        _ => return None,
+    };
+    Some(replacement)
+}
@@ -0,0 +1,82 @@
+// ========================= Network Share Support =========================
+
+use std::io::ErrorKind; // For classifying transient errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::time::Duration; // For timeouts and retry backoff
+
+// How long to wait for a network root to answer before giving up
+pub const ROOT_TIMEOUT: Duration = Duration::from_secs(30);
+
+// How many times a directory is re-read after a transient network error
+pub const MAX_RETRIES: u32 = 3;
+
+// Delay before retry number `attempt` (1-based): 250ms, 500ms, 1s, ...
+pub fn retry_backoff(attempt: u32) -> Duration {
+    Duration::from_millis(250 << (attempt.saturating_sub(1)).min(4))
+}
+
+// Check that a root exists without hanging forever on an unreachable share.
+// Returns None if the share didn't answer within `timeout`.
+pub fn root_exists_within(root: &Path, timeout: Duration) -> Option<bool> {
+    let (tx, rx) = crossbeam_channel::bounded(1);
+    let root: PathBuf = root.to_path_buf();
+    // The probe thread is detached: if the share never answers it simply dies with the process
+    std::thread::spawn(move || {
+        let _ = tx.send(root.exists());
+    });
+    rx.recv_timeout(timeout).ok()
+}
+
+// Is this walk error a network hiccup worth retrying?
+pub fn is_transient(e: &walkdir::Error) -> bool {
+    let Some(io) = e.io_error() else {
+        return false; // Loop detection and similar errors are permanent
+    };
+
+    if matches!(
+        io.kind(),
+        ErrorKind::TimedOut
+            | ErrorKind::Interrupted
+            | ErrorKind::WouldBlock
+            | ErrorKind::ConnectionReset
+            | ErrorKind::ConnectionAborted
+            | ErrorKind::NotConnected
+            | ErrorKind::BrokenPipe
+            | ErrorKind::HostUnreachable
+            | ErrorKind::NetworkUnreachable
+            | ErrorKind::NetworkDown
+            | ErrorKind::StaleNetworkFileHandle
+    ) {
+        return true;
+    }
+
+    // SMB errors that std doesn't map to a specific kind
+    #[cfg(windows)]
+    {
+        const ERROR_BAD_NETPATH: i32 = 53;
+        const ERROR_UNEXP_NET_ERR: i32 = 59;
+        const ERROR_NETNAME_DELETED: i32 = 64;
+        const ERROR_SEM_TIMEOUT: i32 = 121;
+        const ERROR_NETWORK_UNREACHABLE: i32 = 1231;
+
+        if let Some(code) = io.raw_os_error() {
+            return matches!(
+                code,
+                ERROR_BAD_NETPATH
+                    | ERROR_UNEXP_NET_ERR
+                    | ERROR_NETNAME_DELETED
+                    | ERROR_SEM_TIMEOUT
+                    | ERROR_NETWORK_UNREACHABLE
+            );
+        }
+    }
+
+    #[cfg(unix)]
+    {
+        if let Some(code) = io.raw_os_error() {
+            return code == libc::EIO || code == libc::EAGAIN;
+        }
+    }
+
+    false
+}
@@ -0,0 +1,87 @@
+// ========================= Desktop Notifications =========================
+
+use std::path::Path; // For the found result
+use std::time::Duration; // For elapsed times and timeouts
+
+use notify_rust::Notification; // For desktop notifications
+
+use crate::SpeedyError;
+use crate::longpath;
+
+// How long we keep waiting for the user to click a notification action before exiting
+#[cfg(all(unix, not(target_os = "macos")))]
+const ACTION_TIMEOUT: Duration = Duration::from_secs(30);
+
+// Notify that `target` was found at `path`.
+// Where the notification server supports actions (Linux/BSD desktops), the notification
+// offers Open, Open folder and Copy path. Open is the default action, so clicking the body
+// does the same; servers that list the default action as a button show it only once.
+pub fn notify_found(target: &str, path: &Path) -> Result<(), SpeedyError> {
+    let shown = longpath::for_display(path);
+    let mut notification = Notification::new();
+    notification
+        .summary("Speedy Search")
+        .body(&format!("Found {}: {}", target, shown.display()));
+
+    #[cfg(all(unix, not(target_os = "macos")))]
+    {
+        use crate::{clipboard, launch};
+
+        let handle = notification
+            .action("default", "Open")
+            .action("folder", "Open folder")
+            .action("copy", "Copy path")
+            .show()?;
+
+        // wait_for_action blocks until the notification is acted on or closed; some servers
+        // keep notifications around indefinitely, so wait on a channel with a timeout instead
+        let (tx, rx) = crossbeam_channel::bounded(1);
+        std::thread::spawn(move || {
+            handle.wait_for_action(|action| {
+                let _ = tx.send(action.to_string());
+            });
+        });
+
+        if let Ok(action) = rx.recv_timeout(ACTION_TIMEOUT) {
+            match action.as_str() {
+                "default" => launch::open_path(path)?,
+                "folder" => launch::open_containing_folder(path)?,
+                "copy" => clipboard::copy_text(&shown.display().to_string())?,
+                _ => {} // Dismissed or closed
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(not(all(unix, not(target_os = "macos"))))]
+    {
+        notification.show()?;
+        Ok(())
+    }
+}
+
+// Searches shorter than this don't send a "not found" / "cancelled" notification,
+// since the user is most likely still watching the terminal
+pub const LONG_SEARCH: Duration = Duration::from_secs(10);
+
+// Notify that a long search finished without a result or was cancelled
+pub fn notify_unsuccessful(
+    target: &str,
+    elapsed: Duration,
+    scanned: usize,
+    cancelled: bool,
+) -> Result<(), SpeedyError> {
+    let headline = if cancelled {
+        format!("Search for {} was cancelled", target)
+    } else {
+        format!("Could not find {}", target)
+    };
+    Notification::new()
+        .summary("Speedy Search")
+        .body(&format!(
+            "{} after {:.0?} ({} locations scanned)",
+            headline, elapsed, scanned
+        ))
+        .show()?;
+    Ok(())
+}
@@ -0,0 +1,151 @@
+// ========================= Permission Problems =========================
+
+use std::collections::HashMap; // Counts per top-level folder
+use std::io; // For relaunch errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::sync::Mutex; // The counts are shared between worker threads
+
+// Entries that could not be read because access was denied, grouped by the top-level
+// folder (directly below a search root) they were found in
+#[derive(Debug)]
+pub struct DeniedSummary {
+    roots: Vec<PathBuf>,
+    counts: Mutex<HashMap<PathBuf, usize>>,
+}
+
+impl DeniedSummary {
+    pub fn new(root: &Path, extra_roots: &[PathBuf]) -> Self {
+        DeniedSummary {
+            roots: std::iter::once(root.to_path_buf()).chain(extra_roots.iter().cloned()).collect(),
+            counts: Mutex::new(HashMap::new()),
+        }
+    }
+
+    // Record an entry below one of the roots that could not be read
+    pub fn record(&self, path: &Path) {
+        let root = self.roots.iter().find(|root| path.starts_with(root)).unwrap_or(&self.roots[0]);
+        let top = match path.strip_prefix(root).ok().and_then(|p| p.components().next()) {
+            Some(first) => root.join(first),
+            None => root.clone(),
+        };
+        if let Ok(mut counts) = self.counts.lock() {
+            *counts.entry(top).or_insert(0) += 1;
+        }
+    }
+
+    // (top-level folder, denied entries), most affected first
+    pub fn into_sorted(self) -> Vec<(PathBuf, usize)> {
+        let mut counts: Vec<_> = self.counts.into_inner().unwrap_or_default().into_iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        counts
+    }
+}
+
+// Format a count with thousands separators, e.g. 1,204
+pub fn format_count(n: usize) -> String {
+    let digits = n.to_string();
+    let mut out = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            out.push(',');
+        }
+        out.push(c);
+    }
+    out
+}
+
+// ========================= Elevation =========================
+
+// Is the process running with administrator / root rights?
+#[cfg(windows)]
+pub fn is_elevated() -> bool {
+    unsafe { windows_sys::Win32::UI::Shell::IsUserAnAdmin() != 0 }
+}
+
+#[cfg(unix)]
+pub fn is_elevated() -> bool {
+    unsafe { libc::geteuid() == 0 }
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn is_elevated() -> bool {
+    false
+}
+
+// Start this program again with the same arguments through the UAC prompt. The elevated
+// copy runs in its own console, kept open (cmd /k) so its results can be read.
+#[cfg(windows)]
+pub fn relaunch_elevated(args: &[String]) -> io::Result<()> {
+    use windows_sys::Win32::UI::Shell::ShellExecuteW;
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    let exe = std::env::current_exe()?;
+    let cwd = std::env::current_dir()?;
+
+    // cmd strips the outer quotes of a /k command line that starts with a quote
+    let mut command = quote_windows_arg(&exe.to_string_lossy());
+    for arg in args {
+        command.push(' ');
+        command.push_str(&quote_windows_arg(arg));
+    }
+    let parameters = format!("/k \"{}\"", command);
+
+    let wide = |s: &str| s.encode_utf16().chain(std::iter::once(0)).collect::<Vec<u16>>();
+    let (verb, file, parameters, directory) = (
+        wide("runas"),
+        wide("cmd.exe"),
+        wide(&parameters),
+        wide(&cwd.to_string_lossy()),
+    );
+
+    // Values of 32 or below are errors, e.g. when the user declines the prompt
+    let result = unsafe {
+        ShellExecuteW(
+            std::ptr::null_mut(),
+            verb.as_ptr(),
+            file.as_ptr(),
+            parameters.as_ptr(),
+            directory.as_ptr(),
+            SW_SHOWNORMAL,
+        )
+    };
+    if result as isize <= 32 {
+        return Err(io::Error::other("The elevated search could not be started"));
+    }
+    Ok(())
+}
+
+#[cfg(not(windows))]
+pub fn relaunch_elevated(_args: &[String]) -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "--elevate is only supported on Windows; run with sudo instead",
+    ))
+}
+
+// Quote an argument for a Windows command line (CommandLineToArgvW rules)
+#[cfg(windows)]
+fn quote_windows_arg(arg: &str) -> String {
+    let mut quoted = String::from("\"");
+    let mut backslashes = 0;
+    for c in arg.chars() {
+        match c {
+            '\\' => backslashes += 1,
+            '"' => {
+                // Backslashes before a quote are escaped, then the quote itself
+                quoted.push_str(&"\\".repeat(backslashes * 2 + 1));
+                quoted.push('"');
+                backslashes = 0;
+            }
+            _ => {
+                quoted.push_str(&"\\".repeat(backslashes));
+                quoted.push(c);
+                backslashes = 0;
+            }
+        }
+    }
+    // Backslashes before the closing quote are escaped too
+    quoted.push_str(&"\\".repeat(backslashes * 2));
+    quoted.push('"');
+    quoted
+}
@@ -0,0 +1,210 @@
+// ========================= Picker =========================
+
+use std::fs::File; // The keyboard is read from the terminal, since stdin is the path list
+use std::io::{self, BufRead, BufReader, IsTerminal, Read, Write}; // For prompts and input
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // Exit status when nothing is picked
+
+use crate::SpeedyError;
+use crate::clipboard;
+use crate::i18n;
+use crate::launch;
+use crate::matching;
+
+// Entries listed at once; typing text narrows the list further
+const PAGE_SIZE: usize = 20;
+
+// What to do with the picked path
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Action {
+    Print,  // Write it to stdout, e.g. for `cd "$(... | speedy pick)"`
+    Open,   // Open with the default application
+    Folder, // Open the containing folder
+    Copy,   // Copy to the clipboard
+}
+
+impl Action {
+    fn parse(value: &str) -> Option<Action> {
+        match value.trim().to_lowercase().as_str() {
+            "" | "p" | "print" => Some(Action::Print),
+            "o" | "open" => Some(Action::Open),
+            "f" | "folder" => Some(Action::Folder),
+            "c" | "copy" => Some(Action::Copy),
+            _ => None,
+        }
+    }
+}
+
+// `speedy pick [--action print|open|folder|copy] [--select-1]`: choose one of the paths
+// piped in on stdin
+pub fn run(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let mut action = None;
+    let mut select_one = false; // Take a lone path without asking
+    let mut i = 0;
+    while i < args.len() {
+        match args[i].as_str() {
+            "--action" => {
+                let value = args.get(i + 1).ok_or_else(|| {
+                    SpeedyError::Argument("Missing action after --action".to_string())
+                })?;
+                action = Some(Action::parse(value).ok_or_else(|| {
+                    SpeedyError::Argument(format!(
+                        "Unknown action '{}' (use print, open, folder or copy)",
+                        value
+                    ))
+                })?);
+                i += 2;
+            }
+            "--select-1" => {
+                select_one = true;
+                i += 1;
+            }
+            other => {
+                return Err(SpeedyError::Argument(format!("Unknown option for pick: {}", other)));
+            }
+        }
+    }
+
+    if io::stdin().is_terminal() {
+        return Err(SpeedyError::Argument(
+            "speedy pick reads paths from stdin, e.g. speedy search .log --all --quiet --format \"{path}\" | speedy pick"
+                .to_string(),
+        ));
+    }
+    let mut paths: Vec<String> = Vec::new();
+    for line in io::stdin().lock().lines() {
+        let line = line?;
+        let line = line.trim();
+        if !line.is_empty() && !paths.iter().any(|p| p == line) {
+            paths.push(line.to_string());
+        }
+    }
+    if paths.is_empty() {
+        eprintln!("{}", i18n::text("pick_nothing"));
+        return Ok(ExitCode::FAILURE);
+    }
+
+    // A lone path with a known action needs no terminal at all
+    let lone = select_one && paths.len() == 1;
+    if let (true, Some(action)) = (lone, action) {
+        apply(action, Path::new(&paths[0]))?;
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut keyboard = BufReader::new(open_terminal()?);
+    let path = if lone {
+        PathBuf::from(&paths[0])
+    } else {
+        match choose(&paths, &mut keyboard)? {
+            Some(path) => path,
+            None => return Ok(ExitCode::FAILURE),
+        }
+    };
+    let action = match action {
+        Some(action) => action,
+        None => loop {
+            let Some(answer) = prompt(&mut keyboard, i18n::text("pick_action"))? else {
+                return Ok(ExitCode::FAILURE);
+            };
+            match Action::parse(&answer) {
+                Some(action) => break action,
+                None => eprintln!("{}", i18n::format("pick_unknown_action", &[&answer.trim()])),
+            }
+        },
+    };
+
+    apply(action, &path)?;
+    Ok(ExitCode::SUCCESS)
+}
+
+// Let the user narrow down and pick a path; None if they quit
+fn choose(paths: &[String], keyboard: &mut impl BufRead) -> Result<Option<PathBuf>, SpeedyError> {
+    let mut filter = String::new();
+    loop {
+        let needle = matching::normalize(&filter, false);
+        let shown: Vec<&String> = paths
+            .iter()
+            .filter(|p| matching::normalize(p, false).contains(&needle))
+            .collect();
+
+        eprintln!();
+        if shown.is_empty() {
+            eprintln!("{}", i18n::format("pick_no_match", &[&filter]));
+        }
+        for (n, path) in shown.iter().take(PAGE_SIZE).enumerate() {
+            eprintln!("  {:>2}. {}", n + 1, path);
+        }
+        if shown.len() > PAGE_SIZE {
+            eprintln!("{}", i18n::format("pick_more", &[&(shown.len() - PAGE_SIZE)]));
+        }
+
+        let hint = i18n::text(if shown.len() == 1 { "pick_hint_one" } else { "pick_hint_number" });
+        let text = if filter.is_empty() {
+            i18n::format("pick_prompt", &[&hint])
+        } else {
+            i18n::format("pick_prompt_filtered", &[&filter, &hint])
+        };
+        let Some(answer) = prompt(keyboard, &text)? else {
+            return Ok(None);
+        };
+
+        let answer = answer.trim();
+        match answer {
+            "q" => return Ok(None),
+            "-" => filter.clear(),
+            "" if shown.len() == 1 => return Ok(Some(PathBuf::from(shown[0]))),
+            "" => {}
+            _ => match answer.parse::<usize>() {
+                Ok(n) if (1..=shown.len().min(PAGE_SIZE)).contains(&n) => {
+                    return Ok(Some(PathBuf::from(shown[n - 1])));
+                }
+                Ok(n) => eprintln!("{}", i18n::format("pick_no_entry", &[&n])),
+                Err(_) => filter = answer.to_string(),
+            },
+        }
+    }
+}
+
+// Carry out the chosen action
+fn apply(action: Action, path: &Path) -> Result<(), SpeedyError> {
+    match action {
+        Action::Print => println!("{}", path.display()),
+        Action::Open => {
+            launch::open_path(path)?;
+            eprintln!("{}", i18n::format("pick_opened", &[&path.display()]));
+        }
+        Action::Folder => {
+            launch::open_containing_folder(path)?;
+            eprintln!("{}", i18n::format("pick_opened_folder", &[&path.display()]));
+        }
+        Action::Copy => {
+            clipboard::copy_text(&path.display().to_string())?;
+            eprintln!("{}", i18n::format("pick_copied", &[&path.display()]));
+        }
+    }
+    Ok(())
+}
+
+// Show a prompt on stderr and read one line of keyboard input; None at end of input
+fn prompt(keyboard: &mut impl BufRead, text: &str) -> io::Result<Option<String>> {
+    eprint!("{}", text);
+    io::stderr().flush()?;
+    let mut line = String::new();
+    if keyboard.read_line(&mut line)? == 0 {
+        eprintln!();
+        return Ok(None);
+    }
+    Ok(Some(line))
+}
+
+// The interactive terminal, independent of where stdin was redirected from
+fn open_terminal() -> Result<impl Read, SpeedyError> {
+    #[cfg(windows)]
+    let name = "CONIN$";
+    #[cfg(not(windows))]
+    let name = "/dev/tty";
+
+    File::open(name).map_err(|e| {
+        SpeedyError::Argument(format!("speedy pick needs an interactive terminal ({})", e))
+    })
+}
@@ -0,0 +1,65 @@
+// ========================= Low Priority Mode =========================
+
+use std::io; // For reporting OS errors
+use std::time::Duration; // For throttle pauses
+
+// How many entries the walker visits between throttle pauses in --low-priority mode
+pub const THROTTLE_BATCH: usize = 256;
+
+// How long the walker sleeps after each batch in --low-priority mode
+pub const THROTTLE_PAUSE: Duration = Duration::from_millis(2);
+
+// Lower the CPU and I/O priority of the current process.
+// Must run before the thread pool is built so worker threads inherit the priority.
+#[cfg(windows)]
+pub fn lower_process_priority() -> io::Result<()> {
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcess, PROCESS_MODE_BACKGROUND_BEGIN, SetPriorityClass,
+    };
+
+    // Background mode lowers CPU, I/O and memory priority in one call
+    let ok = unsafe { SetPriorityClass(GetCurrentProcess(), PROCESS_MODE_BACKGROUND_BEGIN) };
+    if ok == 0 {
+        return Err(io::Error::last_os_error());
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+pub fn lower_process_priority() -> io::Result<()> {
+    // Equivalent of `nice -n 19`
+    let rc = unsafe { libc::setpriority(libc::PRIO_PROCESS, 0, 19) };
+    if rc != 0 {
+        return Err(io::Error::last_os_error());
+    }
+
+    // Equivalent of `ionice -c 3` (idle I/O class), Linux only
+    #[cfg(target_os = "linux")]
+    {
+        const IOPRIO_WHO_PROCESS: libc::c_int = 1;
+        const IOPRIO_CLASS_IDLE: libc::c_int = 3;
+        const IOPRIO_CLASS_SHIFT: libc::c_int = 13;
+
+        let rc = unsafe {
+            libc::syscall(
+                libc::SYS_ioprio_set,
+                IOPRIO_WHO_PROCESS,
+                0,
+                IOPRIO_CLASS_IDLE << IOPRIO_CLASS_SHIFT,
+            )
+        };
+        if rc != 0 {
+            return Err(io::Error::last_os_error());
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(not(any(windows, unix)))]
+pub fn lower_process_priority() -> io::Result<()> {
+    Err(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "lowering priority is not supported on this platform",
+    ))
+}
@@ -0,0 +1,120 @@
+// ========================= Search Profiles =========================
+
+use std::fmt; // For displaying profiles
+use std::path::Path; // For working with filesystem paths
+
+use crate::SpeedyError;
+use crate::environment;
+
+// Common "noisy" or system folders we don't want to scan (compared case-insensitively)
+pub const SKIP_NAMES: [&str; 10] = [
+    "$recycle.bin", "system volume information", "windows", "program files",
+    "program files (x86)", "appdata", "temp", "tmp", "node_modules", ".git",
+];
+
+// Build output, caches and environments that the fast profile prunes as well
+pub const FAST_SKIP_NAMES: [&str; 12] = [
+    "target", "build", "dist", "out", "bin", "obj", "vendor", "__pycache__", "venv",
+    ".venv", ".cache", "library",
+];
+
+// A curated set of defaults selected with --profile. Explicit flags still apply on top.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Profile {
+    Fast,     // Aggressive pruning, stop at the first match
+    #[default]
+    Balanced, // The standard skip list
+    Thorough, // Everything, following symbolic links
+}
+
+impl Profile {
+    pub fn parse(value: &str) -> Result<Self, SpeedyError> {
+        match value.to_lowercase().as_str() {
+            "fast" => Ok(Profile::Fast),
+            "balanced" => Ok(Profile::Balanced),
+            "thorough" => Ok(Profile::Thorough),
+            _ => Err(SpeedyError::Parse(format!(
+                "Unknown profile '{}' (expected fast, balanced or thorough)",
+                value
+            ))),
+        }
+    }
+
+    // Which folders are pruned from the walk, including any named in SPEEDY_SKIP
+    pub fn skip_rules(self) -> SkipRules {
+        let (defaults, build_output, hidden) = match self {
+            Profile::Fast => (true, true, true),
+            Profile::Balanced => (true, false, false),
+            Profile::Thorough => (false, false, false),
+        };
+        SkipRules { defaults, build_output, hidden, extra: environment::skip_names() }
+    }
+
+    // Whether symbolic links to directories are walked into
+    pub fn follow_links(self) -> bool {
+        self == Profile::Thorough
+    }
+
+    // Whether the search ends at the first match
+    pub fn stop_after_match(self) -> bool {
+        self == Profile::Fast
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Profile::Fast => write!(f, "fast"),
+            Profile::Balanced => write!(f, "balanced"),
+            Profile::Thorough => write!(f, "thorough"),
+        }
+    }
+}
+
+// Folder pruning rules for a search
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SkipRules {
+    defaults: bool,     // Skip SKIP_NAMES
+    build_output: bool, // Skip FAST_SKIP_NAMES
+    hidden: bool,       // Skip dot-folders
+    extra: Vec<String>, // Lowercase names from SPEEDY_SKIP
+}
+
+impl SkipRules {
+    // Should the walk stay out of this directory?
+    pub fn should_skip(&self, path: &Path) -> bool {
+        let Some(name) = path.file_name().and_then(|n| n.to_str()) else {
+            return false;
+        };
+        if self.hidden && name.starts_with('.') {
+            return true;
+        }
+
+        let lower = name.to_lowercase();
+        (self.defaults && SKIP_NAMES.contains(&lower.as_str()))
+            || (self.build_output && FAST_SKIP_NAMES.contains(&lower.as_str()))
+            || self.extra.contains(&lower)
+    }
+
+    // Human-readable summary for --dry-run
+    pub fn describe(&self) -> String {
+        let mut parts = Vec::new();
+        if self.defaults {
+            parts.push(format!("folders named {}", SKIP_NAMES.join(", ")));
+        }
+        if self.build_output {
+            parts.push(format!("build and cache folders ({})", FAST_SKIP_NAMES.join(", ")));
+        }
+        if self.hidden {
+            parts.push("hidden dot-folders".to_string());
+        }
+        if !self.extra.is_empty() {
+            parts.push(format!("from {}: {}", environment::SKIP, self.extra.join(", ")));
+        }
+        if parts.is_empty() {
+            "none".to_string()
+        } else {
+            format!("{} (any letter case)", parts.join("; "))
+        }
+    }
+}
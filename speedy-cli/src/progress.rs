@@ -0,0 +1,95 @@
+// ========================= Progress Reporting =========================
+
+use std::time::{Duration, Instant}; // For rates and ETAs
+
+use crate::i18n;
+
+// Weight of the newest sample in the smoothed rate (exponential moving average)
+const SMOOTHING: f64 = 0.3;
+
+// Samples closer together than this are merged, so bursts don't make the rate jump
+const MIN_SAMPLE_GAP: Duration = Duration::from_millis(250);
+
+// A progress report sent from the search thread to the display loop
+#[derive(Debug, Clone, Copy)]
+pub struct ProgressUpdate {
+    pub scanned: usize, // Entries visited so far
+    pub matches: usize, // Matches found so far
+    pub at: Instant,    // When the counts were taken
+}
+
+// Smoothed throughput in entries per second
+#[derive(Debug, Default)]
+pub struct RateMeter {
+    last: Option<(usize, Instant)>, // Previous sample
+    rate: Option<f64>,              // Smoothed entries per second
+}
+
+impl RateMeter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    // Feed a progress update and return the smoothed rate, once there is one
+    pub fn update(&mut self, update: &ProgressUpdate) -> Option<f64> {
+        let Some((scanned, at)) = self.last else {
+            self.last = Some((update.scanned, update.at));
+            return None;
+        };
+        let elapsed = update.at.saturating_duration_since(at);
+        if elapsed < MIN_SAMPLE_GAP {
+            return self.rate;
+        }
+
+        let sample = update.scanned.saturating_sub(scanned) as f64 / elapsed.as_secs_f64();
+        self.rate = Some(match self.rate {
+            Some(rate) => rate + SMOOTHING * (sample - rate),
+            None => sample,
+        });
+        self.last = Some((update.scanned, update.at));
+        self.rate
+    }
+
+    // Estimated time until `total` entries have been scanned
+    pub fn eta(&self, scanned: usize, total: u64) -> Option<Duration> {
+        let rate = self.rate.filter(|r| *r > 0.0)?;
+        let remaining = total.saturating_sub(scanned as u64) as f64;
+        Some(Duration::from_secs_f64(remaining / rate))
+    }
+}
+
+// Spinner message, e.g. "Scanned 120,000 locations · 15.2k/s · ETA 1m 05s · 3 matches"
+pub fn message(update: &ProgressUpdate, rate: Option<f64>, eta: Option<Duration>) -> String {
+    let mut message = i18n::format("progress_scanned", &[&update.scanned]);
+    if let Some(rate) = rate {
+        message.push_str(&i18n::format("progress_rate", &[&format_rate(rate)]));
+    }
+    if let Some(eta) = eta {
+        message.push_str(&i18n::format("progress_eta", &[&format_duration(eta)]));
+    }
+    if update.matches > 0 {
+        message.push_str(&i18n::format("progress_matches", &[&update.matches]));
+    }
+    message
+}
+
+// Compact rate such as "850", "15.2k" or "1.3M"
+fn format_rate(rate: f64) -> String {
+    if rate >= 1_000_000.0 {
+        format!("{:.1}M", rate / 1_000_000.0)
+    } else if rate >= 1_000.0 {
+        format!("{:.1}k", rate / 1_000.0)
+    } else {
+        format!("{:.0}", rate)
+    }
+}
+
+// Compact duration such as "42s", "1m 05s" or "2h 10m"
+fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    match secs {
+        0..60 => format!("{}s", secs),
+        60..3600 => format!("{}m {:02}s", secs / 60, secs % 60),
+        _ => format!("{}h {:02}m", secs / 3600, (secs % 3600) / 60),
+    }
+}
@@ -0,0 +1,257 @@
+// ========================= Recycle Bin Search =========================
+
+use std::io; // For trash and shadow copy errors
+use std::path::{self, Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // For the command's exit status
+use std::time::{Duration, SystemTime, UNIX_EPOCH}; // For deletion times
+
+use walkdir::WalkDir; // For looking inside deleted folders and shadow copies
+
+use crate::SpeedyError;
+use crate::filters;
+use crate::longpath;
+use crate::matching::NameMatcher;
+
+// Where a deleted entry can still be found
+#[derive(Debug)]
+enum Source {
+    Trash,      // The Recycle Bin / trash
+    #[cfg_attr(not(windows), allow(dead_code))]
+    ShadowCopy, // A Volume Shadow Copy snapshot (--shadow)
+}
+
+// A deleted entry with the given name
+#[derive(Debug)]
+struct Deleted {
+    original: PathBuf,        // Where it was before it was deleted
+    stored: PathBuf,          // Where its content is now
+    when: Option<SystemTime>, // Deletion time, or the snapshot time for shadow copies
+    source: Source,
+}
+
+// `speedy search:recycled <name> [--path <dir>] [--deleted-within <age>] [--shadow]`:
+// find deleted files and folders in the Recycle Bin / trash, including inside deleted
+// folders, and optionally in shadow copies
+pub fn search_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let Some(target) = args.first() else {
+        return Err(SpeedyError::Argument(
+            "Usage: speedy search:recycled <name> [--path <dir>] [--shadow]".to_string(),
+        ));
+    };
+
+    let mut scope = None;
+    let mut within = None;
+    let mut shadow = false;
+    let mut i = 1;
+    while i < args.len() {
+        let value = || {
+            args.get(i + 1)
+                .ok_or_else(|| SpeedyError::Argument(format!("Missing value after {}", args[i])))
+        };
+        match args[i].as_str() {
+            "--path" => {
+                scope = Some(path::absolute(crate::resolve_search_path(value()?)?)?);
+                i += 2;
+            }
+            "--deleted-within" => {
+                within = Some(filters::parse_age(value()?)?);
+                i += 2;
+            }
+            "--shadow" => {
+                shadow = true;
+                i += 1;
+            }
+            other => {
+                return Err(SpeedyError::Argument(format!("Unknown argument: {}", other)));
+            }
+        }
+    }
+
+    let matcher = NameMatcher::new(target, false);
+    let mut found = in_trash(&matcher)?;
+    if shadow {
+        let shadow_scope = match &scope {
+            Some(scope) => scope.clone(),
+            None => dirs::home_dir().ok_or_else(|| {
+                SpeedyError::Argument("No home folder; pass --path to search shadow copies".to_string())
+            })?,
+        };
+        match in_shadow_copies(&matcher, &shadow_scope) {
+            Ok(copies) => found.extend(copies),
+            Err(e) => eprintln!("⚠️ Could not search shadow copies: {}", e),
+        }
+    }
+
+    let now = SystemTime::now();
+    found.retain(|d| {
+        scope.as_ref().is_none_or(|scope| d.original.starts_with(scope))
+            && within.is_none_or(|within| {
+                d.when.and_then(|when| now.duration_since(when).ok()).is_some_and(|age| age <= within)
+            })
+    });
+    // Most recently deleted first
+    found.sort_by(|a, b| b.when.cmp(&a.when).then_with(|| a.original.cmp(&b.original)));
+
+    if found.is_empty() {
+        println!("❌ No deleted entries named \"{}\"", target);
+        return Ok(ExitCode::SUCCESS);
+    }
+    println!("🗑️ Found {} deleted entr(ies) named \"{}\":", found.len(), target);
+    for deleted in &found {
+        let age = deleted
+            .when
+            .and_then(|when| now.duration_since(when).ok())
+            .map(|age| format!("{} ago", crate::format_age(age.as_secs())))
+            .unwrap_or_else(|| "at an unknown time".to_string());
+        let how = match deleted.source {
+            Source::Trash => format!("deleted {}", age),
+            Source::ShadowCopy => format!("in a shadow copy from {}", age),
+        };
+        println!("   {}", deleted.original.display());
+        println!("      {} · now at {}", how, longpath::for_display(&deleted.stored).display());
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// ========================= Trash =========================
+
+// Trashed entries named like the target, and entries with that name inside trashed folders
+#[cfg(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android"))))]
+fn in_trash(matcher: &NameMatcher) -> Result<Vec<Deleted>, SpeedyError> {
+    let items = trash::os_limited::list()
+        .map_err(|e| io::Error::other(format!("Could not read the trash: {}", e)))?;
+
+    let mut found = Vec::new();
+    for item in items {
+        let when = u64::try_from(item.time_deleted).ok().map(|secs| UNIX_EPOCH + Duration::from_secs(secs));
+        let Some(stored) = stored_path(&item) else {
+            continue;
+        };
+        found.extend(
+            matches_below(matcher, &stored, &item.original_path())
+                .map(|(original, stored)| Deleted { original, stored, when, source: Source::Trash }),
+        );
+    }
+    Ok(found)
+}
+
+#[cfg(not(any(windows, all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))))]
+fn in_trash(_matcher: &NameMatcher) -> Result<Vec<Deleted>, SpeedyError> {
+    Err(SpeedyError::Io(io::Error::new(
+        io::ErrorKind::Unsupported,
+        "Listing the trash is not supported on this platform",
+    )))
+}
+
+// Where a trashed item's content is kept: the $R file the item ID names on Windows
+#[cfg(windows)]
+fn stored_path(item: &trash::TrashItem) -> Option<PathBuf> {
+    Some(PathBuf::from(&item.id))
+}
+
+// Where a trashed item's content is kept: the ID is the item's .trashinfo file, and the
+// content sits in the trash's files/ folder under the same name
+#[cfg(all(unix, not(target_os = "macos"), not(target_os = "ios"), not(target_os = "android")))]
+fn stored_path(item: &trash::TrashItem) -> Option<PathBuf> {
+    let info = Path::new(&item.id);
+    let trash_dir = info.parent()?.parent()?;
+    Some(trash_dir.join("files").join(info.file_stem()?))
+}
+
+// Matches at or below `stored`, paired with the path they had below `original`
+fn matches_below<'a>(
+    matcher: &'a NameMatcher,
+    stored: &Path,
+    original: &'a Path,
+) -> impl Iterator<Item = (PathBuf, PathBuf)> + 'a {
+    let root = longpath::to_extended(stored);
+    WalkDir::new(&root)
+        .into_iter()
+        .filter_map(Result::ok)
+        .filter_map(move |entry| {
+            let relative = entry.path().strip_prefix(&root).ok()?;
+            // The stored top-level name may differ ($R... on Windows, "name.2" in a trash)
+            let original = if relative.as_os_str().is_empty() {
+                original.to_path_buf()
+            } else {
+                original.join(relative)
+            };
+            let name = original.file_name()?.to_str()?;
+            matcher
+                .matches(name)
+                .then(|| (original.clone(), entry.path().to_path_buf()))
+        })
+}
+
+// ========================= Shadow Copies =========================
+
+// Entries named like the target below `scope` in each Volume Shadow Copy of its volume
+// that no longer exist at their original path. When several snapshots hold one, the
+// newest is reported.
+#[cfg(windows)]
+fn in_shadow_copies(matcher: &NameMatcher, scope: &Path) -> io::Result<Vec<Deleted>> {
+    use std::collections::HashSet;
+    use std::path::{Component, Prefix};
+
+    let scope = longpath::for_display(scope);
+    let mut components = scope.components();
+    let drive = match components.next() {
+        Some(Component::Prefix(prefix)) => match prefix.kind() {
+            Prefix::Disk(letter) | Prefix::VerbatimDisk(letter) => letter.to_ascii_uppercase() as char,
+            _ => return Err(io::Error::other("shadow copies can only be searched on local drives")),
+        },
+        _ => return Err(io::Error::other("--path must include a drive letter")),
+    };
+    let relative: PathBuf = components.filter(|c| !matches!(c, Component::RootDir)).collect();
+
+    let mut snapshots = list_shadow_copies()?;
+    snapshots.retain(|(_, letter, _)| *letter == drive);
+    snapshots.sort_by(|a, b| b.2.cmp(&a.2)); // Newest first
+    if snapshots.is_empty() {
+        log::info!("No shadow copies of drive {}:", drive);
+    }
+
+    let mut seen = HashSet::new();
+    let mut found = Vec::new();
+    for (device, _, created) in snapshots {
+        let snapshot_scope = PathBuf::from(format!("{}\\", device)).join(&relative);
+        for (original, stored) in matches_below(matcher, &snapshot_scope, &scope) {
+            if original.symlink_metadata().is_err() && seen.insert(original.clone()) {
+                found.push(Deleted { original, stored, when: Some(created), source: Source::ShadowCopy });
+            }
+        }
+    }
+    Ok(found)
+}
+
+// (device path, drive letter, creation time) of every shadow copy, asked from WMI
+#[cfg(windows)]
+fn list_shadow_copies() -> io::Result<Vec<(String, char, SystemTime)>> {
+    let script = "$v = @{}; Get-CimInstance Win32_Volume | ForEach-Object { $v[$_.DeviceID] = $_.DriveLetter }; \
+                  Get-CimInstance Win32_ShadowCopy | ForEach-Object { \
+                  '{0}|{1}|{2}' -f $_.DeviceObject, $v[$_.VolumeName], ([DateTimeOffset]$_.InstallDate).ToUnixTimeSeconds() }";
+    let output = std::process::Command::new("powershell")
+        .args(["-NoProfile", "-NonInteractive", "-Command", script])
+        .output()?;
+    if !output.status.success() {
+        return Err(io::Error::other(format!(
+            "listing shadow copies failed (this needs an elevated prompt): {}",
+            String::from_utf8_lossy(&output.stderr).trim()
+        )));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout)
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.trim().split('|');
+            let device = fields.next()?.to_string();
+            let letter = fields.next()?.chars().next()?.to_ascii_uppercase();
+            let created = UNIX_EPOCH + Duration::from_secs(fields.next()?.parse().ok()?);
+            Some((device, letter, created))
+        })
+        .collect())
+}
+
+#[cfg(not(windows))]
+fn in_shadow_copies(_matcher: &NameMatcher, _scope: &Path) -> io::Result<Vec<Deleted>> {
+    Err(io::Error::new(io::ErrorKind::Unsupported, "shadow copies only exist on Windows"))
+}
@@ -0,0 +1,205 @@
+// ========================= Batch Rename =========================
+
+use std::collections::HashSet; // For spotting two entries renamed to the same name
+use std::fs; // For renaming entries
+use std::io::{self, BufRead, IsTerminal, Write}; // For the confirmation prompt
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // Failed renames are reported through the exit status
+
+use regex::Regex; // Name patterns with capture groups
+use walkdir::WalkDir; // For finding the entries to rename
+
+use crate::SpeedyError;
+use crate::filters::{self, EntryType};
+use crate::longpath;
+use crate::profile::Profile;
+
+// One planned rename
+#[derive(Debug)]
+struct Rename {
+    from: PathBuf,           // Current path
+    to: PathBuf,             // Path after renaming
+    depth: usize,            // Depth below the root; deeper entries are renamed first
+    problem: Option<String>, // Why the rename will be skipped, if it will
+}
+
+// `speedy rename <pattern> <replacement> [--path <dir>] [--type f|d|l|x] [--depth <num>]
+// [--dry-run] [--yes]`: rename every entry whose name matches a regular expression,
+// substituting $1, ${name} etc. from its capture groups
+pub fn run(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let (Some(pattern), Some(replacement)) = (args.first(), args.get(1)) else {
+        return Err(SpeedyError::Argument(
+            "Usage: speedy rename <pattern> <replacement> [--path <dir>]".to_string(),
+        ));
+    };
+    let pattern = Regex::new(pattern)
+        .map_err(|e| SpeedyError::Parse(format!("Invalid pattern: {}", e)))?;
+
+    let mut root = PathBuf::from(".");
+    let mut types = Vec::new();
+    let mut max_depth = usize::MAX;
+    let mut dry_run = false;
+    let mut assume_yes = false;
+    let mut i = 2;
+    while i < args.len() {
+        let value = || {
+            args.get(i + 1)
+                .ok_or_else(|| SpeedyError::Argument(format!("Missing value after {}", args[i])))
+        };
+        match args[i].as_str() {
+            "--path" => {
+                root = crate::resolve_search_path(value()?)?;
+                i += 2;
+            }
+            "--type" => {
+                types = EntryType::parse_list(value()?)?;
+                i += 2;
+            }
+            "--depth" => {
+                max_depth = value()?
+                    .parse()
+                    .map_err(|_| SpeedyError::Parse("Depth must be a number".to_string()))?;
+                i += 2;
+            }
+            "--dry-run" => {
+                dry_run = true;
+                i += 1;
+            }
+            "--yes" => {
+                assume_yes = true;
+                i += 1;
+            }
+            other => {
+                return Err(SpeedyError::Argument(format!("Unknown argument: {}", other)));
+            }
+        }
+    }
+
+    let renames = plan(&root, &pattern, replacement, &types, max_depth);
+    if renames.is_empty() {
+        println!("❌ No names below {} match the pattern", root.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    print_table(&renames);
+    let ready = renames.iter().filter(|r| r.problem.is_none()).count();
+    if dry_run {
+        println!("🧪 Dry run: {} of {} entr(ies) would be renamed", ready, renames.len());
+        return Ok(ExitCode::SUCCESS);
+    }
+    if ready == 0 {
+        return Ok(ExitCode::FAILURE);
+    }
+    if !assume_yes && !confirm(ready)? {
+        println!("Nothing was renamed.");
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let mut failed = renames.len() - ready;
+    for rename in renames.iter().filter(|r| r.problem.is_none()) {
+        if let Err(e) = fs::rename(&rename.from, &rename.to) {
+            println!("⚠️ Could not rename {}: {}", longpath::for_display(&rename.from).display(), e);
+            failed += 1;
+        }
+    }
+    println!("✏️ Renamed {} entr(ies)", renames.len() - failed);
+    if failed > 0 {
+        return Ok(ExitCode::FAILURE);
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// Work out every rename below `root`, deepest first so renaming a folder never
+// invalidates the paths of entries inside it
+fn plan(
+    root: &Path,
+    pattern: &Regex,
+    replacement: &str,
+    types: &[EntryType],
+    max_depth: usize,
+) -> Vec<Rename> {
+    let skip = Profile::default().skip_rules();
+    let mut renames = Vec::new();
+    let walker = WalkDir::new(longpath::to_extended(root))
+        .min_depth(1)
+        .max_depth(max_depth)
+        .into_iter()
+        .filter_entry(|e| !skip.should_skip(e.path()));
+
+    for entry in walker.filter_map(Result::ok) {
+        let name = entry.file_name().to_string_lossy();
+        if !pattern.is_match(&name) || !filters::matches_any_type(types, &entry) {
+            continue;
+        }
+        let new_name = pattern.replace(&name, replacement);
+        if new_name == name {
+            continue;
+        }
+        let problem = if new_name.is_empty() || new_name.contains(['/', '\\']) {
+            Some(format!("invalid name \"{}\"", new_name))
+        } else {
+            None
+        };
+        renames.push(Rename {
+            from: entry.path().to_path_buf(),
+            to: entry.path().with_file_name(new_name.as_ref()),
+            depth: entry.depth(),
+            problem,
+        });
+    }
+
+    // Refuse to overwrite existing entries or to give two entries the same name
+    let mut targets = HashSet::new();
+    for rename in renames.iter_mut() {
+        if rename.problem.is_some() {
+            continue;
+        }
+        if !targets.insert(rename.to.clone()) {
+            rename.problem = Some("another entry gets the same name".to_string());
+        } else if rename.to.symlink_metadata().is_ok() {
+            rename.problem = Some("target already exists".to_string());
+        }
+    }
+
+    renames.sort_by(|a, b| b.depth.cmp(&a.depth).then_with(|| a.from.cmp(&b.from)));
+    renames
+}
+
+// Preview table: old name, new name and folder, with skipped renames marked
+fn print_table(renames: &[Rename]) {
+    let name = |path: &PathBuf| path.file_name().unwrap_or_default().to_string_lossy().to_string();
+    let header = "Current name";
+    let longest = renames.iter().map(|r| name(&r.from).chars().count()).max().unwrap_or(0);
+    let width = longest.clamp(header.len(), 40);
+
+    println!("   {:<width$}    New name", header, width = width);
+    for rename in renames {
+        let folder = rename.from.parent().map(longpath::for_display).unwrap_or_default();
+        let status = match &rename.problem {
+            Some(problem) => format!("   ✗ skipped: {}", problem),
+            None => String::new(),
+        };
+        println!(
+            "   {:<width$} →  {}   ({}){}",
+            name(&rename.from),
+            name(&rename.to),
+            folder.display(),
+            status,
+            width = width
+        );
+    }
+    println!();
+}
+
+// Ask before renaming; without a terminal only --yes allows it
+fn confirm(count: usize) -> io::Result<bool> {
+    if !io::stdin().is_terminal() {
+        println!("⚠️ Rename {} entr(ies)? Not confirmed: pass --yes to run without a prompt", count);
+        return Ok(false);
+    }
+    print!("❓ Rename {} entr(ies)? [y/N] ", count);
+    io::stdout().flush()?;
+    let mut answer = String::new();
+    io::stdin().lock().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
+}
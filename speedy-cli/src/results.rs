@@ -0,0 +1,293 @@
+// ========================= Result Storage =========================
+
+use std::cmp::Reverse; // For a min-heap when merging sorted runs
+use std::collections::BinaryHeap; // Next path from each sorted run
+use std::env; // For the temporary directory
+use std::fs::{self, File}; // For spill files
+use std::io::{self, BufReader, BufWriter, Read, Write}; // For reading and writing spill files
+use std::mem; // For estimating the memory held by a path
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process; // The spill folder is named after the process
+
+use crate::SpeedyError;
+
+// Smallest accepted --max-memory; below this nearly every result would be its own run
+pub const MIN_BUDGET: usize = 1 << 20;
+
+// Spill files merged at once; beyond this the runs are combined into one first, so a
+// small budget on a huge result set doesn't run out of file handles
+const MAX_RUNS: usize = 64;
+
+// `--max-memory <size>`: bytes, or a number with a K, M or G suffix (powers of 1024)
+pub fn parse_budget(value: &str) -> Result<usize, SpeedyError> {
+    let value = value.trim();
+    let (digits, unit) = match value.char_indices().find(|(_, c)| !c.is_ascii_digit()) {
+        Some((i, _)) => value.split_at(i),
+        None => (value, ""),
+    };
+    let shift = match unit.to_uppercase().trim_end_matches(['B', 'I']) {
+        "" => 0,
+        "K" => 10,
+        "M" => 20,
+        "G" => 30,
+        _ => {
+            return Err(SpeedyError::Parse(format!(
+                "Invalid size '{}' (use e.g. 512M or 2G)",
+                value
+            )));
+        }
+    };
+    let bytes = digits
+        .parse::<usize>()
+        .ok()
+        .and_then(|n| n.checked_mul(1 << shift))
+        .ok_or_else(|| SpeedyError::Parse(format!("Invalid size '{}' (use e.g. 512M or 2G)", value)))?;
+    if bytes < MIN_BUDGET {
+        return Err(SpeedyError::Argument("--max-memory must be at least 1M".to_string()));
+    }
+    Ok(bytes)
+}
+
+// Paths collected by a search. Everything stays in memory unless a budget is set; past
+// the budget, results are sorted and written to temporary files ("runs") that are merged
+// back in order when the results are read.
+#[derive(Debug)]
+pub struct ResultStore {
+    memory: Vec<PathBuf>,    // Results not spilled yet
+    memory_bytes: usize,     // Estimated size of `memory`
+    budget: Option<usize>,   // --max-memory; None keeps everything in memory
+    runs: Vec<PathBuf>,      // Sorted spill files
+    spill_dir: Option<PathBuf>, // Created on the first spill, removed on drop
+    files_created: usize,    // For naming spill files
+    first: Option<PathBuf>,  // The first result received, kept for --notify
+    len: usize,              // Results stored in total
+}
+
+impl ResultStore {
+    pub fn new(budget: Option<usize>) -> Self {
+        ResultStore {
+            memory: Vec::new(),
+            memory_bytes: 0,
+            budget,
+            runs: Vec::new(),
+            spill_dir: None,
+            files_created: 0,
+            first: None,
+            len: 0,
+        }
+    }
+
+    pub fn push(&mut self, path: PathBuf) -> io::Result<()> {
+        if self.first.is_none() {
+            self.first = Some(path.clone());
+        }
+        self.memory_bytes += mem::size_of::<PathBuf>() + path.as_os_str().len();
+        self.memory.push(path);
+        self.len += 1;
+        if self.budget.is_some_and(|budget| self.memory_bytes > budget) {
+            self.spill()?;
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    // The first result received (not the first in sorted order)
+    pub fn first(&self) -> Option<&Path> {
+        self.first.as_deref()
+    }
+
+    // Whether some results live on disk
+    pub fn spilled(&self) -> bool {
+        !self.runs.is_empty()
+    }
+
+    // Every result in sorted order. Results held in memory are sorted in place; spilled
+    // runs are streamed from disk.
+    pub fn sorted(&mut self) -> io::Result<SortedResults<'_>> {
+        self.memory.sort();
+        let mut sources = Vec::new();
+        for run in &self.runs {
+            sources.push(Source::Run(BufReader::new(File::open(run)?)));
+        }
+        sources.push(Source::Memory(self.memory.iter()));
+        SortedResults::new(sources)
+    }
+
+    // Write the in-memory results out as one sorted run
+    fn spill(&mut self) -> io::Result<()> {
+        if self.runs.len() >= MAX_RUNS {
+            self.compact()?;
+        }
+        self.memory.sort();
+        let run = self.new_run()?;
+        let mut out = BufWriter::new(File::create(&run)?);
+        for path in &self.memory {
+            write_path(&mut out, path)?;
+        }
+        out.flush()?;
+        log::info!("Spilled {} result(s) to {}", self.memory.len(), run.display());
+        self.runs.push(run);
+        self.memory.clear();
+        self.memory_bytes = 0;
+        Ok(())
+    }
+
+    // Merge every run into a single one
+    fn compact(&mut self) -> io::Result<()> {
+        let sources = self
+            .runs
+            .iter()
+            .map(|run| Ok(Source::Run(BufReader::new(File::open(run)?))))
+            .collect::<io::Result<Vec<_>>>()?;
+        let merged = self.new_run()?;
+        let mut out = BufWriter::new(File::create(&merged)?);
+        for path in SortedResults::new(sources)? {
+            write_path(&mut out, &path?)?;
+        }
+        out.flush()?;
+        for run in self.runs.drain(..) {
+            let _ = fs::remove_file(run);
+        }
+        self.runs.push(merged);
+        Ok(())
+    }
+
+    // Path for the next spill file, creating the spill folder if needed
+    fn new_run(&mut self) -> io::Result<PathBuf> {
+        let dir = match &self.spill_dir {
+            Some(dir) => dir.clone(),
+            None => {
+                let dir = env::temp_dir().join(format!("speedy-results-{}", process::id()));
+                fs::create_dir_all(&dir)?;
+                self.spill_dir = Some(dir.clone());
+                dir
+            }
+        };
+        self.files_created += 1;
+        Ok(dir.join(format!("run-{}", self.files_created)))
+    }
+}
+
+impl Drop for ResultStore {
+    fn drop(&mut self) {
+        if let Some(dir) = &self.spill_dir {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+}
+
+// One sorted input of a merge
+enum Source<'a> {
+    Memory(std::slice::Iter<'a, PathBuf>),
+    Run(BufReader<File>),
+}
+
+impl Source<'_> {
+    fn next(&mut self) -> io::Result<Option<PathBuf>> {
+        match self {
+            Source::Memory(paths) => Ok(paths.next().cloned()),
+            Source::Run(reader) => read_path(reader),
+        }
+    }
+}
+
+// Results from several sorted sources, merged into one sorted stream
+pub struct SortedResults<'a> {
+    sources: Vec<Source<'a>>,
+    heads: BinaryHeap<Reverse<(PathBuf, usize)>>, // Next path of each source, by source index
+}
+
+impl<'a> SortedResults<'a> {
+    fn new(mut sources: Vec<Source<'a>>) -> io::Result<Self> {
+        let mut heads = BinaryHeap::new();
+        for (i, source) in sources.iter_mut().enumerate() {
+            if let Some(path) = source.next()? {
+                heads.push(Reverse((path, i)));
+            }
+        }
+        Ok(SortedResults { sources, heads })
+    }
+}
+
+impl Iterator for SortedResults<'_> {
+    type Item = io::Result<PathBuf>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let Reverse((path, i)) = self.heads.pop()?;
+        match self.sources[i].next() {
+            Ok(Some(next)) => self.heads.push(Reverse((next, i))),
+            Ok(None) => {}
+            Err(e) => return Some(Err(e)),
+        }
+        Some(Ok(path))
+    }
+}
+
+// ========================= Spill File Format =========================
+
+// Each path is stored as its length (u32, little-endian) followed by its raw bytes:
+// the OS bytes on Unix, UTF-16 code units on Windows, so no path is altered on the way
+fn write_path(out: &mut impl Write, path: &Path) -> io::Result<()> {
+    let bytes = path_bytes(path);
+    let len = u32::try_from(bytes.len()).map_err(|_| io::Error::other("path too long to spill"))?;
+    out.write_all(&len.to_le_bytes())?;
+    out.write_all(&bytes)
+}
+
+fn read_path(input: &mut impl Read) -> io::Result<Option<PathBuf>> {
+    let mut len = [0u8; 4];
+    match input.read_exact(&mut len) {
+        Ok(()) => {}
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => return Ok(None),
+        Err(e) => return Err(e),
+    }
+    let mut bytes = vec![0u8; u32::from_le_bytes(len) as usize];
+    input.read_exact(&mut bytes)?;
+    Ok(Some(path_from_bytes(bytes)))
+}
+
+#[cfg(unix)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::unix::ffi::OsStrExt;
+
+    path.as_os_str().as_bytes().to_vec()
+}
+
+#[cfg(unix)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::unix::ffi::OsStringExt;
+
+    PathBuf::from(std::ffi::OsString::from_vec(bytes))
+}
+
+#[cfg(windows)]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    use std::os::windows::ffi::OsStrExt;
+
+    path.as_os_str().encode_wide().flat_map(u16::to_le_bytes).collect()
+}
+
+#[cfg(windows)]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    use std::os::windows::ffi::OsStringExt;
+
+    let wide: Vec<u16> = bytes.chunks_exact(2).map(|c| u16::from_le_bytes([c[0], c[1]])).collect();
+    PathBuf::from(std::ffi::OsString::from_wide(&wide))
+}
+
+#[cfg(not(any(unix, windows)))]
+fn path_bytes(path: &Path) -> Vec<u8> {
+    path.to_string_lossy().into_owned().into_bytes()
+}
+
+#[cfg(not(any(unix, windows)))]
+fn path_from_bytes(bytes: Vec<u8>) -> PathBuf {
+    PathBuf::from(String::from_utf8_lossy(&bytes).into_owned())
+}
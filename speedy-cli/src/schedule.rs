@@ -0,0 +1,123 @@
+// ========================= Prioritized Walk Order =========================
+
+use std::cmp::Reverse; // For a min-rank priority queue
+use std::collections::BinaryHeap; // Priority queue of candidate directories
+use std::env; // For the current directory
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+
+use crate::profile::SkipRules;
+use crate::storage;
+
+// One directory walk in the overall search plan
+#[derive(Debug)]
+pub struct ScheduledWalk {
+    pub dir: PathBuf,          // Where this walk starts
+    pub base_depth: usize,     // Depth of `dir` below the search root
+    pub max_depth: usize,      // Depth limit relative to `dir`
+    pub exclude: Vec<PathBuf>, // Subtrees already covered by earlier walks
+    pub attempt: u32,          // 0 for planned walks, >0 when retrying after a network error
+}
+
+// Build the list of walks for a search. Without prioritization this is just the root.
+// With prioritization, likely locations (current dir, recent result folders, Desktop,
+// Documents, Downloads) are walked first and then skipped when the root is walked.
+// Further roots from repeated --path options are walked last, each in full.
+pub fn plan_walks(
+    root: &Path,
+    extra_roots: &[PathBuf],
+    max_depth: usize,
+    prioritize: bool,
+    skip: &SkipRules,
+) -> Vec<ScheduledWalk> {
+    let mut walks = Vec::new();
+    let mut covered: Vec<PathBuf> = Vec::new();
+
+    if prioritize {
+        let mut queue = BinaryHeap::new();
+        let mut push = |rank: usize, dir: Option<PathBuf>| {
+            if let Some(dir) = dir {
+                queue.push((Reverse(rank), dir));
+            }
+        };
+
+        push(0, env::current_dir().ok());
+        for (i, dir) in storage::load_recent_dirs().into_iter().enumerate() {
+            push(1 + i, Some(dir));
+        }
+        let after_recent = 1 + storage::MAX_RECENT_DIRS;
+        push(after_recent, dirs::desktop_dir());
+        push(after_recent + 1, dirs::document_dir());
+        push(after_recent + 2, dirs::download_dir());
+
+        // Compare canonical paths, but walk and report in the form the root was given
+        let canonical_root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+        while let Some((_, dir)) = queue.pop() {
+            let Ok(dir) = dir.canonicalize() else {
+                continue; // Missing or inaccessible
+            };
+            // Only directories strictly inside the root, not already covered, and not in a
+            // skipped folder can be searched early
+            let Ok(relative) = dir.strip_prefix(&canonical_root) else {
+                continue;
+            };
+            let depth = relative.components().count();
+            let dir = root.join(relative);
+            if depth == 0 || depth > max_depth || covered.iter().any(|c| dir.starts_with(c)) {
+                continue;
+            }
+            if dir.ancestors().take(depth).any(|d| skip.should_skip(d)) {
+                continue;
+            }
+
+            walks.push(ScheduledWalk {
+                dir: dir.clone(),
+                base_depth: depth,
+                max_depth: max_depth - depth,
+                exclude: covered.clone(),
+                attempt: 0,
+            });
+            covered.push(dir);
+        }
+    }
+
+    walks.push(ScheduledWalk {
+        dir: root.to_path_buf(),
+        base_depth: 0,
+        max_depth,
+        exclude: covered,
+        attempt: 0,
+    });
+    walks.extend(extra_roots.iter().map(|dir| ScheduledWalk {
+        dir: dir.clone(),
+        base_depth: 0,
+        max_depth,
+        exclude: Vec::new(),
+        attempt: 0,
+    }));
+    walks
+}
+
+// Drop roots that another root already covers: the same folder given twice (perhaps
+// spelled differently or through a link) or a folder inside another root. Returns the
+// roots to walk, in the order given, and each dropped root with the root covering it.
+pub fn distinct_roots(roots: Vec<PathBuf>) -> (Vec<PathBuf>, Vec<(PathBuf, PathBuf)>) {
+    let canonical: Vec<PathBuf> = roots
+        .iter()
+        .map(|root| root.canonicalize().unwrap_or_else(|_| root.to_path_buf()))
+        .collect();
+    let mut kept = Vec::new();
+    let mut covered = Vec::new();
+    for (i, root) in roots.iter().enumerate() {
+        // Covered by a root that contains it, or by an earlier spelling of the same folder
+        let by = (0..roots.len()).find(|&j| {
+            j != i
+                && canonical[i].starts_with(&canonical[j])
+                && (canonical[i] != canonical[j] || j < i)
+        });
+        match by {
+            Some(j) => covered.push((root.clone(), roots[j].clone())),
+            None => kept.push(root.clone()),
+        }
+    }
+    (kept, covered)
+}
@@ -0,0 +1,68 @@
+// ========================= Shell Integration =========================
+
+use std::process::ExitCode; // For the command's exit status
+
+use crate::SpeedyError;
+
+// `sdcd <name> [options]`: search for folders named <name>, pick one and cd into it.
+// Results are printed as plain absolute paths so the picker can hand one back unchanged.
+const BASH: &str = r#"# speedy shell integration: eval "$(speedy shell-init bash)"
+sdcd() {
+    if [ $# -eq 0 ]; then
+        echo "usage: sdcd <folder name> [speedy options]" >&2
+        return 2
+    fi
+    local dir
+    dir="$(speedy search:folder "$@" --all --quiet --absolute --format '{path}' | speedy pick --select-1 --action print)" || return
+    [ -n "$dir" ] && cd -- "$dir"
+}
+"#;
+
+const FISH: &str = r#"# speedy shell integration: speedy shell-init fish | source
+function sdcd --description 'cd into a folder found by speedy'
+    if test (count $argv) -eq 0
+        echo "usage: sdcd <folder name> [speedy options]" >&2
+        return 2
+    end
+    set -l dir (speedy search:folder $argv --all --quiet --absolute --format '{path}' | speedy pick --select-1 --action print)
+    or return
+    test -n "$dir"; and cd -- $dir
+end
+"#;
+
+const POWERSHELL: &str = r#"# speedy shell integration: speedy shell-init powershell | Out-String | Invoke-Expression
+function sdcd {
+    if ($args.Count -eq 0) {
+        Write-Error "usage: sdcd <folder name> [speedy options]"
+        return
+    }
+    $dir = speedy search:folder @args --all --quiet --absolute --format '{path}' | speedy pick --select-1 --action print
+    if ($LASTEXITCODE -eq 0 -and $dir) {
+        Set-Location -LiteralPath $dir
+    }
+}
+"#;
+
+// `speedy shell-init bash|zsh|fish|powershell`: print the shell functions to load
+pub fn init_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let script = match args.first().map(String::as_str) {
+        // The bash function is plain POSIX-style shell that zsh runs unchanged
+        Some("bash") => BASH,
+        Some("zsh") => &BASH.replace("speedy shell-init bash", "speedy shell-init zsh"),
+        Some("fish") => FISH,
+        Some("powershell" | "pwsh") => POWERSHELL,
+        Some(other) => {
+            return Err(SpeedyError::Argument(format!(
+                "Unknown shell '{}' (use bash, zsh, fish or powershell)",
+                other
+            )));
+        }
+        None => {
+            return Err(SpeedyError::Argument(
+                "Missing shell: speedy shell-init bash|zsh|fish|powershell".to_string(),
+            ));
+        }
+    };
+    print!("{}", script);
+    Ok(ExitCode::SUCCESS)
+}
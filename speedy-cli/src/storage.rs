@@ -0,0 +1,290 @@
+// ========================= Persistent Storage =========================
+
+use std::fs; // For reading and writing state files
+use std::io; // For I/O errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+
+// Directory where Speedy keeps state between runs
+// (e.g. %LOCALAPPDATA%\speedy on Windows, ~/.local/share/speedy on Linux)
+pub fn data_dir() -> Option<PathBuf> {
+    dirs::data_local_dir().map(|dir| dir.join("speedy"))
+}
+
+// Directory for user configuration such as bookmarks
+// (e.g. %APPDATA%\speedy on Windows, ~/.config/speedy on Linux)
+pub fn config_dir() -> Option<PathBuf> {
+    dirs::config_dir().map(|dir| dir.join("speedy"))
+}
+
+// Canonical key used to identify a search root across runs
+pub fn root_key(root: &Path) -> String {
+    root.canonicalize()
+        .unwrap_or_else(|_| root.to_path_buf())
+        .display()
+        .to_string()
+}
+
+// ========================= Entry Counts =========================
+
+const ENTRY_COUNTS_FILE: &str = "entry_counts.tsv";
+
+// Key for a root + depth combination (a depth limit changes the total)
+fn count_key(root: &Path, max_depth: usize) -> String {
+    if max_depth == usize::MAX {
+        root_key(root)
+    } else {
+        format!("{}|{}", root_key(root), max_depth)
+    }
+}
+
+// Read all stored counts as (key, count) pairs, one "count<TAB>key" per line
+fn read_entry_counts() -> Vec<(String, u64)> {
+    let Some(path) = data_dir().map(|d| d.join(ENTRY_COUNTS_FILE)) else {
+        return Vec::new();
+    };
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (count, key) = line.split_once('\t')?;
+            Some((key.to_string(), count.parse().ok()?))
+        })
+        .collect()
+}
+
+// Entry count recorded by a previous run for this root, if any
+pub fn load_entry_count(root: &Path, max_depth: usize) -> Option<u64> {
+    let key = count_key(root, max_depth);
+    read_entry_counts()
+        .into_iter()
+        .find(|(k, _)| *k == key)
+        .map(|(_, count)| count)
+}
+
+// Remember how many entries a run scanned under this root
+pub fn save_entry_count(root: &Path, max_depth: usize, count: u64) -> io::Result<()> {
+    let Some(dir) = data_dir() else {
+        return Ok(());
+    };
+    fs::create_dir_all(&dir)?;
+
+    let key = count_key(root, max_depth);
+    let mut counts = read_entry_counts();
+    match counts.iter_mut().find(|(k, _)| *k == key) {
+        Some(entry) => entry.1 = count,
+        None => counts.push((key, count)),
+    }
+
+    let contents: String = counts
+        .iter()
+        .map(|(key, count)| format!("{}\t{}\n", count, key))
+        .collect();
+    fs::write(dir.join(ENTRY_COUNTS_FILE), contents)
+}
+
+// ========================= Recent Result Folders =========================
+
+const RECENT_DIRS_FILE: &str = "recent_dirs.txt";
+
+// How many recent result folders are remembered
+pub const MAX_RECENT_DIRS: usize = 20;
+
+// Folders that recently contained a match, most recent first
+pub fn load_recent_dirs() -> Vec<PathBuf> {
+    let Some(path) = data_dir().map(|d| d.join(RECENT_DIRS_FILE)) else {
+        return Vec::new();
+    };
+    fs::read_to_string(path)
+        .map(|contents| contents.lines().map(PathBuf::from).collect())
+        .unwrap_or_default()
+}
+
+// Move `dir` to the front of the recent folders list
+pub fn record_recent_dir(dir: &Path) -> io::Result<()> {
+    let Some(data) = data_dir() else {
+        return Ok(());
+    };
+    fs::create_dir_all(&data)?;
+
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let mut recent = load_recent_dirs();
+    recent.retain(|d| *d != dir);
+    recent.insert(0, dir);
+    recent.truncate(MAX_RECENT_DIRS);
+
+    let contents: String = recent
+        .iter()
+        .map(|d| format!("{}\n", d.display()))
+        .collect();
+    fs::write(data.join(RECENT_DIRS_FILE), contents)
+}
+
+// ========================= Search History =========================
+
+const HISTORY_FILE: &str = "history.tsv";
+
+// How many past searches are kept
+pub const MAX_HISTORY: usize = 200;
+
+// One past search, re-runnable with `speedy rerun <id>`
+#[derive(Debug, Clone)]
+pub struct HistoryEntry {
+    pub id: u64,             // Increasing number shown by `speedy history`
+    pub timestamp: u64,      // When the search ran, seconds since the Unix epoch
+    pub duration_ms: u64,    // How long it took
+    pub result: String,      // Found path, match count, "not found" or "cancelled"
+    pub cwd: PathBuf,        // Working directory, so relative --path values still resolve
+    pub args: Vec<String>,   // Command and flags, without the program name
+}
+
+// Fields are tab-separated; escape the characters that would break a line apart
+fn escape_field(field: &str) -> String {
+    field
+        .replace('\\', "\\\\")
+        .replace('\t', "\\t")
+        .replace('\n', "\\n")
+        .replace('\r', "\\r")
+}
+
+fn unescape_field(field: &str) -> String {
+    let mut out = String::with_capacity(field.len());
+    let mut chars = field.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('t') => out.push('\t'),
+            Some('n') => out.push('\n'),
+            Some('r') => out.push('\r'),
+            Some(other) => out.push(other),
+            None => out.push('\\'),
+        }
+    }
+    out
+}
+
+// Past searches, oldest first. Each line is "id, timestamp, duration, result, cwd, args..."
+pub fn load_history() -> Vec<HistoryEntry> {
+    let Some(path) = data_dir().map(|d| d.join(HISTORY_FILE)) else {
+        return Vec::new();
+    };
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split('\t').map(unescape_field);
+            Some(HistoryEntry {
+                id: fields.next()?.parse().ok()?,
+                timestamp: fields.next()?.parse().ok()?,
+                duration_ms: fields.next()?.parse().ok()?,
+                result: fields.next()?,
+                cwd: PathBuf::from(fields.next()?),
+                args: fields.collect(),
+            })
+        })
+        .collect()
+}
+
+// Append a search to the history, dropping the oldest entries beyond MAX_HISTORY
+pub fn record_search(args: &[String], duration_ms: u64, result: &str) -> io::Result<()> {
+    let Some(data) = data_dir() else {
+        return Ok(());
+    };
+    fs::create_dir_all(&data)?;
+
+    let mut history = load_history();
+    let id = history.last().map_or(1, |entry| entry.id + 1);
+    history.push(HistoryEntry {
+        id,
+        timestamp: std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or(0),
+        duration_ms,
+        result: result.to_string(),
+        cwd: std::env::current_dir().unwrap_or_default(),
+        args: args.to_vec(),
+    });
+    let excess = history.len().saturating_sub(MAX_HISTORY);
+    history.drain(..excess);
+
+    let contents: String = history
+        .iter()
+        .map(|entry| {
+            let mut fields = vec![
+                entry.id.to_string(),
+                entry.timestamp.to_string(),
+                entry.duration_ms.to_string(),
+                escape_field(&entry.result),
+                escape_field(&entry.cwd.display().to_string()),
+            ];
+            fields.extend(entry.args.iter().map(|arg| escape_field(arg)));
+            format!("{}\n", fields.join("\t"))
+        })
+        .collect();
+    fs::write(data.join(HISTORY_FILE), contents)
+}
+
+// ========================= Bookmarks =========================
+
+const BOOKMARKS_FILE: &str = "bookmarks.tsv";
+
+// Named search roots, used as `--path @name`, as (name, path) sorted by name
+pub fn load_bookmarks() -> Vec<(String, PathBuf)> {
+    let Some(path) = config_dir().map(|d| d.join(BOOKMARKS_FILE)) else {
+        return Vec::new();
+    };
+    let Ok(contents) = fs::read_to_string(path) else {
+        return Vec::new();
+    };
+
+    contents
+        .lines()
+        .filter_map(|line| {
+            let (name, path) = line.split_once('\t')?;
+            Some((name.to_string(), PathBuf::from(path)))
+        })
+        .collect()
+}
+
+fn save_bookmarks(bookmarks: &[(String, PathBuf)]) -> io::Result<()> {
+    let Some(dir) = config_dir() else {
+        return Err(io::Error::new(io::ErrorKind::NotFound, "No configuration directory"));
+    };
+    fs::create_dir_all(&dir)?;
+
+    let contents: String = bookmarks
+        .iter()
+        .map(|(name, path)| format!("{}\t{}\n", name, path.display()))
+        .collect();
+    fs::write(dir.join(BOOKMARKS_FILE), contents)
+}
+
+// Add or replace a bookmark
+pub fn add_bookmark(name: &str, path: &Path) -> io::Result<()> {
+    let mut bookmarks = load_bookmarks();
+    bookmarks.retain(|(n, _)| n != name);
+    bookmarks.push((name.to_string(), path.to_path_buf()));
+    bookmarks.sort();
+    save_bookmarks(&bookmarks)
+}
+
+// Remove a bookmark; returns whether it existed
+pub fn remove_bookmark(name: &str) -> io::Result<bool> {
+    let mut bookmarks = load_bookmarks();
+    let before = bookmarks.len();
+    bookmarks.retain(|(n, _)| n != name);
+    if bookmarks.len() == before {
+        return Ok(false);
+    }
+    save_bookmarks(&bookmarks)?;
+    Ok(true)
+}
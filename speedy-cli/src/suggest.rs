@@ -0,0 +1,114 @@
+// ========================= "Did You Mean" Suggestions =========================
+
+use std::collections::BinaryHeap; // Bounded max-heap of the best candidates
+use std::path::{Path, PathBuf}; // For remembering where a candidate was seen
+use std::sync::Mutex; // The heap is shared between worker threads
+use std::sync::atomic::{AtomicUsize, Ordering}; // Cheap pre-check before locking
+
+use crate::matching;
+
+// How many suggestions are printed
+pub const MAX_SUGGESTIONS: usize = 5;
+
+// A near-miss name; ordered by distance so the heap's top is the worst kept candidate
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord)]
+struct Candidate {
+    distance: usize,
+    name: String,
+    path: PathBuf,
+}
+
+// Collects the names closest to the target while the walk runs
+#[derive(Debug)]
+pub struct Suggestions {
+    target: Vec<char>,       // Normalized target
+    ignore_accents: bool,    // Normalize candidates the same way as the matcher
+    max_distance: usize,     // Names further away than this are never suggested
+    worst_kept: AtomicUsize, // Distance a candidate must beat once the heap is full
+    best: Mutex<BinaryHeap<Candidate>>,
+}
+
+impl Suggestions {
+    pub fn new(target: &str, ignore_accents: bool) -> Self {
+        let target: Vec<char> = matching::normalize(target, ignore_accents).chars().collect();
+        // Allow roughly one typo per three characters, but at least two
+        let max_distance = (target.len() / 3).max(2);
+        Suggestions {
+            target,
+            ignore_accents,
+            max_distance,
+            worst_kept: AtomicUsize::new(max_distance),
+            best: Mutex::new(BinaryHeap::new()),
+        }
+    }
+
+    // Consider an entry name that did not match exactly
+    pub fn consider(&self, name: &str, path: &Path) {
+        let limit = self.worst_kept.load(Ordering::Relaxed);
+        // Length difference is a lower bound on edit distance: skip hopeless names cheaply
+        if name.chars().count().abs_diff(self.target.len()) > limit {
+            return;
+        }
+
+        let normalized: Vec<char> = matching::normalize(name, self.ignore_accents).chars().collect();
+        let Some(distance) = bounded_levenshtein(&self.target, &normalized, limit) else {
+            return;
+        };
+        if distance == 0 {
+            return; // Same name, just filtered out by type or other filters
+        }
+
+        let Ok(mut best) = self.best.lock() else {
+            return;
+        };
+        if best.iter().any(|c| c.name == name) {
+            return; // Same name seen elsewhere; one location is enough
+        }
+        best.push(Candidate {
+            distance,
+            name: name.to_string(),
+            path: path.to_path_buf(),
+        });
+        if best.len() > MAX_SUGGESTIONS {
+            best.pop();
+        }
+        if best.len() == MAX_SUGGESTIONS
+            && let Some(worst) = best.peek()
+        {
+            self.worst_kept.store(worst.distance, Ordering::Relaxed);
+        }
+    }
+
+    // Best suggestions, closest first, as (name, example path)
+    pub fn into_sorted(self) -> Vec<(String, PathBuf)> {
+        let best = self.best.into_inner().unwrap_or_default();
+        best.into_sorted_vec()
+            .into_iter()
+            .filter(|c| c.distance <= self.max_distance)
+            .map(|c| (c.name, c.path))
+            .collect()
+    }
+}
+
+// Levenshtein distance, or None as soon as it is certain to exceed `limit`
+fn bounded_levenshtein(a: &[char], b: &[char], limit: usize) -> Option<usize> {
+    let mut previous: Vec<usize> = (0..=b.len()).collect();
+    let mut current = vec![0; b.len() + 1];
+
+    for (i, ca) in a.iter().enumerate() {
+        current[0] = i + 1;
+        let mut row_min = current[0];
+        for (j, cb) in b.iter().enumerate() {
+            let substitution = previous[j] + usize::from(ca != cb);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+            row_min = row_min.min(current[j + 1]);
+        }
+        if row_min > limit {
+            return None;
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    let distance = previous[b.len()];
+    (distance <= limit).then_some(distance)
+}
@@ -0,0 +1,204 @@
+// ========================= Performance Telemetry =========================
+
+use std::collections::BTreeMap; // Runs grouped by volume and thread count
+use std::env; // For the platform
+use std::fs::{self, OpenOptions}; // For the telemetry file
+use std::io::Write; // For appending samples
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // For the command's exit status
+
+use serde_json::{Value, json}; // Samples are stored as JSON lines
+
+use crate::SpeedyError;
+use crate::i18n;
+use crate::tuning::{self, VolumeKind};
+
+// Traversals shorter than this say more about start-up than about the disk
+const MIN_ENTRIES: u64 = 10_000;
+
+// Thread counts are compared as multiples of the CPU count, in steps of this size, so
+// runs from machines with different CPUs can be pooled
+const RATIO_STEP: f64 = 0.5;
+
+// A fixed thread count is only suggested when it beats the automatic one by this factor
+const MIN_GAIN: f64 = 1.1;
+
+// How one traversal performed. Nothing identifying is kept: no paths, names or patterns,
+// only the kind of storage and how fast it was walked.
+#[derive(Debug)]
+pub struct Sample {
+    pub volume: VolumeKind,         // Storage behind the root
+    pub filesystem: Option<String>, // e.g. "ntfs", "ext4"
+    pub backend: String,            // Traversal backend used for the root
+    pub strategy: String,           // Depth- or breadth-first
+    pub threads: usize,             // Worker threads
+    pub auto_threads: bool,         // Whether the count was picked per volume (--threads auto)
+    pub entries: u64,               // Entries visited
+    pub seconds: f64,               // Traversal time
+    pub complete: bool,             // Whether the whole tree was walked
+}
+
+// Append a sample to the telemetry file as one JSON line
+pub fn record(file: &Path, sample: &Sample) -> Result<(), SpeedyError> {
+    let line = json!({
+        "speedy": env!("CARGO_PKG_VERSION"),
+        "os": env::consts::OS,
+        "cpus": num_cpus::get(),
+        "volume": volume_name(sample.volume),
+        "filesystem": sample.filesystem,
+        "backend": sample.backend,
+        "strategy": sample.strategy,
+        "threads": sample.threads,
+        "auto_threads": sample.auto_threads,
+        "entries": sample.entries,
+        "seconds": (sample.seconds * 1000.0).round() / 1000.0,
+        "entries_per_sec": (sample.entries as f64 / sample.seconds.max(f64::EPSILON)).round(),
+        "complete": sample.complete,
+    });
+    let mut out = OpenOptions::new().create(true).append(true).open(file)?;
+    writeln!(out, "{}", line)?;
+    Ok(())
+}
+
+// Stable name of a volume kind in the telemetry file
+fn volume_name(kind: VolumeKind) -> &'static str {
+    match kind {
+        VolumeKind::Ssd => "ssd",
+        VolumeKind::Hdd => "hdd",
+        VolumeKind::Network => "network",
+        VolumeKind::Unknown => "unknown",
+    }
+}
+
+fn volume_kind(name: &str) -> Option<VolumeKind> {
+    match name {
+        "ssd" => Some(VolumeKind::Ssd),
+        "hdd" => Some(VolumeKind::Hdd),
+        "network" => Some(VolumeKind::Network),
+        "unknown" => Some(VolumeKind::Unknown),
+        _ => None,
+    }
+}
+
+// ========================= speedy tune =========================
+
+// One usable run read back from a telemetry file
+struct Run {
+    volume: VolumeKind,
+    ratio: f64, // Threads per CPU, rounded to RATIO_STEP
+    auto_threads: bool,
+    rate: f64, // Entries per second
+}
+
+// `speedy tune <telemetry-file>...`: compare the recorded runs per kind of storage and
+// suggest the thread count that walked it fastest. Several files (e.g. shared by other
+// users) can be pooled.
+pub fn tune_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    if args.is_empty() {
+        return Err(SpeedyError::Argument(
+            "Usage: speedy tune <telemetry-file>... (record one with --telemetry-file)".to_string(),
+        ));
+    }
+    let files: Vec<PathBuf> = args.iter().map(PathBuf::from).collect();
+
+    let mut runs = Vec::new();
+    let mut skipped = 0;
+    for file in &files {
+        for (n, line) in fs::read_to_string(file)?.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            let value: Value = serde_json::from_str(line).map_err(|e| {
+                SpeedyError::Parse(format!("{} line {} is not a telemetry sample: {}", file.display(), n + 1, e))
+            })?;
+            match parse_run(&value) {
+                Some(run) => runs.push(run),
+                None => skipped += 1,
+            }
+        }
+    }
+    if skipped > 0 {
+        println!("{}", i18n::format("tune_skipped", &[&skipped, &MIN_ENTRIES]));
+    }
+    if runs.is_empty() {
+        println!("{}", i18n::text("tune_no_runs"));
+        return Ok(ExitCode::FAILURE);
+    }
+
+    let cpus = num_cpus::get();
+    let mut by_volume: BTreeMap<&str, Vec<&Run>> = BTreeMap::new();
+    for run in &runs {
+        by_volume.entry(volume_name(run.volume)).or_default().push(run);
+    }
+    for volume_runs in by_volume.values() {
+        let volume = volume_runs[0].volume;
+        println!();
+        println!("{}", i18n::format("tune_volume", &[&volume, &volume_runs.len()]));
+
+        // Median rate per thread ratio; automatic runs are compared separately
+        let mut by_ratio: BTreeMap<u32, Vec<f64>> = BTreeMap::new();
+        let mut auto_rates = Vec::new();
+        for run in volume_runs {
+            if run.auto_threads {
+                auto_rates.push(run.rate);
+            } else {
+                by_ratio.entry((run.ratio / RATIO_STEP).round() as u32).or_default().push(run.rate);
+            }
+        }
+        let auto = median(&mut auto_rates);
+        if let Some(auto) = auto {
+            let rate = format!("{:>10.0}", auto);
+            println!("{}", i18n::format("tune_auto", &[&rate, &auto_rates.len()]));
+        }
+        let mut best: Option<(f64, f64)> = None; // (ratio, rate)
+        for (step, rates) in &mut by_ratio {
+            let ratio = *step as f64 * RATIO_STEP;
+            let Some(rate) = median(rates) else {
+                continue;
+            };
+            let columns = (format!("{:>4}", ratio), format!("{:>10.0}", rate));
+            println!("{}", i18n::format("tune_ratio", &[&columns.0, &columns.1, &rates.len()]));
+            if best.is_none_or(|(_, best)| rate > best) {
+                best = Some((ratio, rate));
+            }
+        }
+
+        let automatic = tuning::thread_count(volume);
+        match (best, auto) {
+            (Some((ratio, rate)), auto) if auto.is_none_or(|auto| rate > auto * MIN_GAIN) => {
+                let threads = ((ratio * cpus as f64).round() as usize).max(1);
+                println!("{}", i18n::format("tune_suggested", &[&threads, &ratio, &cpus, &automatic]));
+                println!("{}", i18n::format("tune_default", &[&threads]));
+            }
+            (Some(_), _) => println!("{}", i18n::text("tune_keep_auto")),
+            _ => println!("{}", i18n::text("tune_only_auto")),
+        }
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+// A complete, long enough run from a telemetry line
+fn parse_run(value: &Value) -> Option<Run> {
+    let entries = value["entries"].as_u64()?;
+    let seconds = value["seconds"].as_f64()?;
+    let cpus = value["cpus"].as_u64().filter(|n| *n > 0)? as f64;
+    if !value["complete"].as_bool()? || entries < MIN_ENTRIES || seconds <= 0.0 {
+        return None;
+    }
+    let threads = value["threads"].as_u64()? as f64;
+    Some(Run {
+        volume: volume_kind(value["volume"].as_str()?)?,
+        ratio: (threads / cpus / RATIO_STEP).round().max(1.0) * RATIO_STEP,
+        auto_threads: value["auto_threads"].as_bool()?,
+        rate: entries as f64 / seconds,
+    })
+}
+
+fn median(values: &mut [f64]) -> Option<f64> {
+    if values.is_empty() {
+        return None;
+    }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
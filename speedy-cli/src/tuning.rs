@@ -0,0 +1,268 @@
+// ========================= Thread Auto-Tuning =========================
+
+use std::path::Path; // For inspecting the search root
+use std::sync::{Condvar, Mutex}; // Workers wait for a free permit
+use std::time::{Duration, Instant}; // For measuring I/O latency
+
+// Rough category of the storage behind a search root
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VolumeKind {
+    Ssd,     // Solid-state or otherwise low-latency local storage
+    Hdd,     // Spinning disk: benefits from more outstanding requests
+    Network, // SMB/NFS share: too many requests thrash the server
+    Unknown, // Could not tell; treated like an SSD
+}
+
+impl std::fmt::Display for VolumeKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            VolumeKind::Ssd => "SSD",
+            VolumeKind::Hdd => "HDD",
+            VolumeKind::Network => "network share",
+            VolumeKind::Unknown => "unknown volume",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+// Worker threads for a volume kind. The directory walk itself is serial, so workers
+// mostly match names and read metadata: spinning disks get more, so that their slow
+// metadata reads overlap, and network shares fewer, so the server isn't overloaded.
+pub fn thread_count(kind: VolumeKind) -> usize {
+    let cpus = num_cpus::get();
+    match kind {
+        VolumeKind::Ssd | VolumeKind::Unknown => cpus * 2,
+        VolumeKind::Hdd => cpus * 4,
+        VolumeKind::Network => cpus.min(4),
+    }
+}
+
+// Most I/O requests a search with --threads auto lets run at once on a volume kind: the
+// Limiter starts at thread_count and may go up to this much when latency stays low
+pub fn max_thread_count(kind: VolumeKind) -> usize {
+    match kind {
+        VolumeKind::Network => 8,
+        _ => thread_count(kind) * 2,
+    }
+}
+
+// Best-effort detection of what kind of storage `root` lives on
+#[cfg(windows)]
+pub fn detect_volume_kind(root: &Path) -> VolumeKind {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetDriveTypeW;
+
+    const DRIVE_REMOTE: u32 = 4;
+
+    let text = root.to_string_lossy();
+    let is_unc = text.starts_with(r"\\?\UNC\")
+        || (text.starts_with(r"\\") && !text.starts_with(r"\\?\") && !text.starts_with(r"\\.\"));
+    if is_unc {
+        return VolumeKind::Network;
+    }
+
+    // GetDriveTypeW wants the volume root, e.g. "C:\"
+    let Some(std::path::Component::Prefix(prefix)) = root.components().next() else {
+        return VolumeKind::Unknown;
+    };
+    let mut volume: Vec<u16> = prefix.as_os_str().encode_wide().collect();
+    volume.extend_from_slice(&[b'\\' as u16, 0]);
+
+    match unsafe { GetDriveTypeW(volume.as_ptr()) } {
+        DRIVE_REMOTE => VolumeKind::Network,
+        _ => VolumeKind::Unknown, // Seek-penalty queries need admin rights; assume SSD
+    }
+}
+
+// (device, file system type) of the mount that holds `root`
+#[cfg(target_os = "linux")]
+fn mount_for(root: &Path) -> Option<(String, String)> {
+    let root = root.canonicalize().unwrap_or_else(|_| root.to_path_buf());
+    let mounts = std::fs::read_to_string("/proc/mounts").ok()?;
+
+    // The mount with the longest matching mount point owns the root
+    mounts
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let device = fields.next()?;
+            let mount_point = fields.next()?;
+            let fstype = fields.next()?;
+            root.starts_with(mount_point)
+                .then_some((mount_point.len(), device, fstype))
+        })
+        .max_by_key(|(len, _, _)| *len)
+        .map(|(_, device, fstype)| (device.to_string(), fstype.to_string()))
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_volume_kind(root: &Path) -> VolumeKind {
+    let Some((device, fstype)) = mount_for(root) else {
+        return VolumeKind::Unknown;
+    };
+
+    let network_fs = ["nfs", "nfs4", "cifs", "smb3", "smbfs", "fuse.sshfs", "9p", "afs"];
+    if network_fs.contains(&fstype.as_str()) {
+        return VolumeKind::Network;
+    }
+
+    // /sys/class/block/<dev>/queue/rotational; partitions keep it on the parent disk
+    let Some(name) = device.strip_prefix("/dev/") else {
+        return VolumeKind::Unknown;
+    };
+    let Ok(sys_dev) = Path::new("/sys/class/block").join(name).canonicalize() else {
+        return VolumeKind::Unknown;
+    };
+    let rotational = [sys_dev.join("queue/rotational"), sys_dev.join("../queue/rotational")]
+        .iter()
+        .find_map(|p| std::fs::read_to_string(p).ok());
+
+    match rotational.as_deref().map(str::trim) {
+        Some("1") => VolumeKind::Hdd,
+        Some("0") => VolumeKind::Ssd,
+        _ => VolumeKind::Unknown,
+    }
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn detect_volume_kind(_root: &Path) -> VolumeKind {
+    VolumeKind::Unknown
+}
+
+// Lowercase file system name of the volume holding `root` (e.g. "ntfs", "ext4"), if known
+#[cfg(windows)]
+pub fn detect_filesystem(root: &Path) -> Option<String> {
+    use std::os::windows::ffi::OsStrExt;
+    use windows_sys::Win32::Storage::FileSystem::GetVolumeInformationW;
+
+    let Some(std::path::Component::Prefix(prefix)) = root.components().next() else {
+        return None;
+    };
+    let mut volume: Vec<u16> = prefix.as_os_str().encode_wide().collect();
+    volume.extend_from_slice(&[b'\\' as u16, 0]);
+
+    let mut name = [0u16; 32];
+    let ok = unsafe {
+        GetVolumeInformationW(
+            volume.as_ptr(),
+            std::ptr::null_mut(),
+            0,
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            std::ptr::null_mut(),
+            name.as_mut_ptr(),
+            name.len() as u32,
+        )
+    };
+    if ok == 0 {
+        return None;
+    }
+    let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+    Some(String::from_utf16_lossy(&name[..len]).to_lowercase())
+}
+
+#[cfg(target_os = "linux")]
+pub fn detect_filesystem(root: &Path) -> Option<String> {
+    mount_for(root).map(|(_, fstype)| fstype)
+}
+
+#[cfg(not(any(windows, target_os = "linux")))]
+pub fn detect_filesystem(_root: &Path) -> Option<String> {
+    None
+}
+
+// ========================= Adaptive Concurrency =========================
+
+// Latency samples collected before the limit is re-evaluated
+const WINDOW_SIZE: u32 = 256;
+
+// Limits how many I/O requests (reading the next entries of a folder, reading metadata or
+// content of a match) run at once, and adjusts that limit from their observed latency:
+// one more while latency stays near its usual level, a quarter fewer once it has doubled.
+// The usual level follows the averages slowly, so a few fast windows of cached entries
+// don't make every later one look slow.
+#[derive(Debug)]
+pub struct Limiter {
+    state: Mutex<LimiterState>,
+    freed: Condvar, // Signalled when a permit is returned or the limit grows
+    max: usize,
+}
+
+#[derive(Debug)]
+struct LimiterState {
+    limit: usize,               // Permits available
+    active: usize,              // Permits in use
+    total: Duration,            // Sum of latencies in the current window
+    samples: u32,               // Number of samples in the current window
+    baseline: Option<Duration>, // Usual window average, moving 1/16 towards each new one
+}
+
+// Held around one I/O request; records its latency and frees the permit when dropped
+pub struct Permit<'a> {
+    limiter: &'a Limiter,
+    started: Instant,
+}
+
+impl Limiter {
+    pub fn new(initial: usize, max: usize) -> Self {
+        let max = max.max(1);
+        Limiter {
+            state: Mutex::new(LimiterState {
+                limit: initial.clamp(1, max),
+                active: 0,
+                total: Duration::ZERO,
+                samples: 0,
+                baseline: None,
+            }),
+            freed: Condvar::new(),
+            max,
+        }
+    }
+
+    // Current concurrency limit
+    pub fn limit(&self) -> usize {
+        self.state.lock().map_or(self.max, |state| state.limit)
+    }
+
+    // Wait until a permit is free
+    pub fn acquire(&self) -> Permit<'_> {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        while state.active >= state.limit {
+            state = self.freed.wait(state).unwrap_or_else(|e| e.into_inner());
+        }
+        state.active += 1;
+        Permit { limiter: self, started: Instant::now() }
+    }
+
+    fn release(&self, latency: Duration) {
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        state.active -= 1;
+        state.total += latency;
+        state.samples += 1;
+        if state.samples < WINDOW_SIZE {
+            self.freed.notify_one();
+            return;
+        }
+
+        let average = state.total / state.samples;
+        state.total = Duration::ZERO;
+        state.samples = 0;
+        let baseline = *state.baseline.get_or_insert(average);
+        if average > baseline * 2 {
+            // Latency doubled: the device is saturated, back off
+            state.limit = (state.limit * 3 / 4).max(1);
+        } else if average < baseline + baseline / 4 {
+            // Latency is healthy: probe for more throughput
+            state.limit = (state.limit + 1).min(self.max);
+        }
+        state.baseline = Some(baseline * 15 / 16 + average / 16);
+        log::trace!("I/O latency {:?} (usually {:?}), {} at once", average, baseline, state.limit);
+        self.freed.notify_all();
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(self.started.elapsed());
+    }
+}
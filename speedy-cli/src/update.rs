@@ -0,0 +1,195 @@
+// ========================= Self-Update =========================
+
+use std::env; // For the running executable and the platform
+use std::fs::{self, File}; // For the downloaded binary
+use std::io; // For download errors
+use std::path::{Path, PathBuf}; // For working with filesystem paths
+use std::process::ExitCode; // For the command's exit status
+
+use serde_json::Value; // The release feed is JSON
+use sha2::Sha256; // Release binaries are checked against SHA256SUMS
+
+use crate::SpeedyError;
+use crate::environment;
+use crate::hash;
+
+// Latest release of Speedy, as published on GitHub
+const RELEASES_URL: &str = "https://api.github.com/repos/SinofPride-999/Speedy/releases/latest";
+
+// Release asset listing "<sha256>  <asset name>" for every binary, as written by sha256sum
+const CHECKSUMS_ASSET: &str = "SHA256SUMS";
+
+// `speedy self-update [--check]`: replace this executable with the latest release built
+// for this platform, after checking the download against the release's SHA256SUMS. That
+// catches a damaged download only: the digests come from the same release (or feed), so
+// they don't prove who published it.
+pub fn self_update_command(args: &[String]) -> Result<ExitCode, SpeedyError> {
+    let mut check_only = false;
+    for arg in args {
+        match arg.as_str() {
+            "--check" => check_only = true,
+            other => return Err(SpeedyError::Argument(format!("Unknown argument: {}", other))),
+        }
+    }
+
+    let exe = env::current_exe()?;
+    remove_previous(&exe);
+
+    let feed = environment::update_feed().unwrap_or_else(|| RELEASES_URL.to_string());
+    let release: Value = serde_json::from_str(&fetch_text(&feed)?)
+        .map_err(|e| SpeedyError::Update(format!("Unexpected answer from {}: {}", feed, e)))?;
+    let tag = release["tag_name"]
+        .as_str()
+        .ok_or_else(|| SpeedyError::Update(format!("No release version in {}", feed)))?;
+
+    let current = env!("CARGO_PKG_VERSION");
+    if parse_version(tag) <= parse_version(current) {
+        println!("✅ Speedy {} is up to date", current);
+        return Ok(ExitCode::SUCCESS);
+    }
+    println!("⬆️ Speedy {} is available (this is {})", tag, current);
+    if check_only {
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    let name = asset_name();
+    let binary_url = asset_url(&release, &name).ok_or_else(|| {
+        SpeedyError::Update(format!("Release {} has no build for this platform ({})", tag, name))
+    })?;
+    let sums_url = asset_url(&release, CHECKSUMS_ASSET).ok_or_else(|| {
+        SpeedyError::Update(format!("Release {} has no {}, so its download can't be verified", tag, CHECKSUMS_ASSET))
+    })?;
+    let expected = expected_digest(&fetch_text(&sums_url)?, &name).ok_or_else(|| {
+        SpeedyError::Update(format!("{} of release {} doesn't list {}", CHECKSUMS_ASSET, tag, name))
+    })?;
+
+    // Downloaded next to the executable so the final rename stays on one file system
+    let download = exe.with_file_name(format!(".{}.download", name));
+    println!("⬇️ Downloading {}...", name);
+    let result = fetch_to_file(&binary_url, &download)
+        .and_then(|()| verify(&download, &expected))
+        .and_then(|()| replace(&download, &exe));
+    if result.is_err() {
+        let _ = fs::remove_file(&download);
+    }
+    result?;
+
+    println!("✅ Updated Speedy {} → {} ({})", current, tag, exe.display());
+    Ok(ExitCode::SUCCESS)
+}
+
+// Release asset for this platform, e.g. speedy-linux-x86_64 or speedy-windows-x86_64.exe
+fn asset_name() -> String {
+    format!("speedy-{}-{}{}", env::consts::OS, env::consts::ARCH, env::consts::EXE_SUFFIX)
+}
+
+// Download URL of the release asset with the given name
+fn asset_url(release: &Value, name: &str) -> Option<String> {
+    release["assets"]
+        .as_array()?
+        .iter()
+        .find(|asset| asset["name"].as_str() == Some(name))?["browser_download_url"]
+        .as_str()
+        .map(str::to_string)
+}
+
+// Digest listed for `name` in a sha256sum-style file
+fn expected_digest(sums: &str, name: &str) -> Option<String> {
+    sums.lines().find_map(|line| {
+        let (digest, file) = line.trim().split_once(' ')?;
+        let file = file.trim_start_matches([' ', '*']);
+        (file == name).then(|| digest.to_lowercase())
+    })
+}
+
+// "v1.2.3" or "1.2.3-beta" -> [1, 2, 3]; parts that aren't numbers count as 0
+fn parse_version(version: &str) -> Vec<u64> {
+    version
+        .trim_start_matches(['v', 'V'])
+        .split(['-', '+'])
+        .next()
+        .unwrap_or_default()
+        .split('.')
+        .map(|part| part.parse().unwrap_or(0))
+        .collect()
+}
+
+// ========================= Download =========================
+
+fn agent() -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .user_agent(&format!("speedy/{}", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+fn fetch(url: &str) -> Result<ureq::Response, SpeedyError> {
+    agent()
+        .get(url)
+        .call()
+        .map_err(|e| SpeedyError::Update(format!("Could not download {}: {}", url, e)))
+}
+
+fn fetch_text(url: &str) -> Result<String, SpeedyError> {
+    Ok(fetch(url)?.into_string()?)
+}
+
+fn fetch_to_file(url: &str, path: &Path) -> Result<(), SpeedyError> {
+    let mut reader = fetch(url)?.into_reader();
+    let mut file = File::create(path)?;
+    io::copy(&mut reader, &mut file)?;
+    file.sync_all()?;
+    Ok(())
+}
+
+// Check the download arrived intact and make it executable
+fn verify(path: &Path, expected: &str) -> Result<(), SpeedyError> {
+    let actual = hash::digest_file::<Sha256>(path)?;
+    if actual != expected {
+        return Err(SpeedyError::Update(format!(
+            "The download is damaged (SHA-256 {} instead of the published {}); nothing was changed",
+            actual, expected
+        )));
+    }
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(path, fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(())
+}
+
+// ========================= Replacing the Executable =========================
+
+// Rename the new binary over the old one: atomic, so an interrupted update leaves either
+// version in place and never a partial file
+#[cfg(not(windows))]
+fn replace(new: &Path, exe: &Path) -> Result<(), SpeedyError> {
+    fs::rename(new, exe)?;
+    Ok(())
+}
+
+// A running executable can't be overwritten on Windows, but it can be renamed: move it
+// aside (removed by the next self-update) and put the new one in its place
+#[cfg(windows)]
+fn replace(new: &Path, exe: &Path) -> Result<(), SpeedyError> {
+    let previous = previous_path(exe);
+    let _ = fs::remove_file(&previous);
+    fs::rename(exe, &previous)?;
+    if let Err(e) = fs::rename(new, exe) {
+        let _ = fs::rename(&previous, exe);
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+// Where the replaced executable is kept until the next update
+fn previous_path(exe: &Path) -> PathBuf {
+    let mut name = exe.file_name().unwrap_or_default().to_os_string();
+    name.push(".old");
+    exe.with_file_name(name)
+}
+
+// Remove the executable an earlier update moved aside (Windows); best effort
+fn remove_previous(exe: &Path) {
+    let _ = fs::remove_file(previous_path(exe));
+}
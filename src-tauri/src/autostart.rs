@@ -0,0 +1,183 @@
+// Starting the launcher when the user logs in, the way each system does it for a user:
+// a value under the Run key (Windows), a LaunchAgent (macOS), an XDG autostart entry
+// (Linux). It's started with ARG, so it stays in the tray until called up.
+
+use std::path::PathBuf;
+
+// Argument the login item passes
+pub const ARG: &str = "--autostart";
+
+// Whether the launcher starts at login
+pub fn enabled(app: &tauri::AppHandle) -> Result<bool, String> {
+    system::enabled(app)
+}
+
+// Start the launcher at login, as the program running now, or stop doing so
+pub fn set(app: &tauri::AppHandle, enabled: bool) -> Result<(), String> {
+    let changed = if enabled { system::enable(app, &program()?) } else { system::disable(app) };
+    changed.map_err(|e| format!("Could not change starting at login: {}", e))
+}
+
+fn program() -> Result<PathBuf, String> {
+    std::env::current_exe().map_err(|e| e.to_string())
+}
+
+#[cfg(windows)]
+mod system {
+    use std::path::Path;
+
+    use windows_sys::Win32::Foundation::ERROR_FILE_NOT_FOUND;
+    use windows_sys::Win32::System::Registry::{
+        HKEY_CURRENT_USER, REG_SZ, RRF_RT_REG_SZ, RegDeleteKeyValueW, RegGetValueW, RegSetKeyValueW,
+    };
+
+    use super::ARG;
+
+    const RUN: &str = r"Software\Microsoft\Windows\CurrentVersion\Run";
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    pub fn enabled(app: &tauri::AppHandle) -> Result<bool, String> {
+        let (key, name) = (wide(RUN), wide(&app.package_info().name));
+        let mut size = 0u32;
+        let found = unsafe {
+            RegGetValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr(), RRF_RT_REG_SZ, std::ptr::null_mut(), std::ptr::null_mut(), &mut size)
+        };
+        Ok(found == 0)
+    }
+
+    pub fn enable(app: &tauri::AppHandle, program: &Path) -> Result<(), String> {
+        let (key, name) = (wide(RUN), wide(&app.package_info().name));
+        let command = wide(&format!("\"{}\" {}", program.display(), ARG));
+        let error = unsafe {
+            RegSetKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr(), REG_SZ, command.as_ptr().cast(), (command.len() * 2) as u32)
+        };
+        if error != 0 {
+            return Err(std::io::Error::from_raw_os_error(error as i32).to_string());
+        }
+        Ok(())
+    }
+
+    pub fn disable(app: &tauri::AppHandle) -> Result<(), String> {
+        let (key, name) = (wide(RUN), wide(&app.package_info().name));
+        let error = unsafe { RegDeleteKeyValueW(HKEY_CURRENT_USER, key.as_ptr(), name.as_ptr()) };
+        if error != 0 && error != ERROR_FILE_NOT_FOUND {
+            return Err(std::io::Error::from_raw_os_error(error as i32).to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::{Path, PathBuf};
+
+    use plist::{Dictionary, Value};
+
+    use super::ARG;
+
+    // ~/Library/LaunchAgents/<identifier>.plist, which launchd runs at login
+    fn agent(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let home = std::env::var_os("HOME").ok_or_else(|| "HOME is not set".to_string())?;
+        Ok(PathBuf::from(home).join("Library/LaunchAgents").join(format!("{}.plist", app.config().identifier)))
+    }
+
+    pub fn enabled(app: &tauri::AppHandle) -> Result<bool, String> {
+        Ok(agent(app)?.is_file())
+    }
+
+    pub fn enable(app: &tauri::AppHandle, program: &Path) -> Result<(), String> {
+        let agent = agent(app)?;
+        if let Some(dir) = agent.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        let mut plist = Dictionary::new();
+        plist.insert("Label".to_string(), Value::String(app.config().identifier.clone()));
+        plist.insert(
+            "ProgramArguments".to_string(),
+            Value::Array(vec![Value::String(program.to_string_lossy().into_owned()), Value::String(ARG.to_string())]),
+        );
+        plist.insert("RunAtLoad".to_string(), Value::Boolean(true));
+        Value::Dictionary(plist).to_file_xml(&agent).map_err(|e| e.to_string())
+    }
+
+    pub fn disable(app: &tauri::AppHandle) -> Result<(), String> {
+        let agent = agent(app)?;
+        if agent.is_file() {
+            std::fs::remove_file(&agent).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::env;
+    use std::path::{Path, PathBuf};
+
+    use super::ARG;
+
+    // $XDG_CONFIG_HOME/autostart/<identifier>.desktop, which desktops start at login
+    fn entry(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .ok_or_else(|| "HOME is not set".to_string())?;
+        Ok(config_home.join("autostart").join(format!("{}.desktop", app.config().identifier)))
+    }
+
+    pub fn enabled(app: &tauri::AppHandle) -> Result<bool, String> {
+        Ok(entry(app)?.is_file())
+    }
+
+    pub fn enable(app: &tauri::AppHandle, program: &Path) -> Result<(), String> {
+        let entry = entry(app)?;
+        if let Some(dir) = entry.parent() {
+            std::fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        // Quoted as the Exec key wants: ", `, $ and \ escaped, then \ once more for the file
+        let mut quoted = String::new();
+        for c in program.to_string_lossy().chars() {
+            if matches!(c, '"' | '`' | '$' | '\\') {
+                quoted.push('\\');
+            }
+            quoted.push(c);
+        }
+        let contents = format!(
+            "[Desktop Entry]\nType=Application\nName={}\nExec=\"{}\" {}\nTerminal=false\nX-GNOME-Autostart-enabled=true\n",
+            app.package_info().name,
+            quoted.replace('\\', "\\\\"),
+            ARG
+        );
+        std::fs::write(&entry, contents).map_err(|e| e.to_string())
+    }
+
+    pub fn disable(app: &tauri::AppHandle) -> Result<(), String> {
+        let entry = entry(app)?;
+        if entry.is_file() {
+            std::fs::remove_file(&entry).map_err(|e| e.to_string())?;
+        }
+        Ok(())
+    }
+}
+
+// Other systems: no login items
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use std::path::Path;
+
+    pub fn enabled(_app: &tauri::AppHandle) -> Result<bool, String> {
+        Ok(false)
+    }
+
+    pub fn enable(_app: &tauri::AppHandle, _program: &Path) -> Result<(), String> {
+        Err("starting at login isn't supported on this system".to_string())
+    }
+
+    pub fn disable(_app: &tauri::AppHandle) -> Result<(), String> {
+        Ok(())
+    }
+}
@@ -0,0 +1,278 @@
+// Browser bookmarks, and with preferences.index_browser_history the pages visited too,
+// copied from Chrome, Edge, Chromium and Firefox profiles into the `bookmarks` table so
+// they can be searched by title and URL like anything else. Only web addresses are
+// kept; they open in the default browser.
+//
+// History databases are locked while their browser runs, so each is read from a copy.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OpenFlags, params};
+
+use crate::web;
+
+// Pages taken from each profile's history, the latest visited first
+const HISTORY_LIMIT: i64 = 5_000;
+
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS bookmarks (
+            url TEXT PRIMARY KEY,
+            title TEXT NOT NULL,
+            browser TEXT NOT NULL,
+            is_history BOOLEAN NOT NULL,
+            visits INTEGER NOT NULL DEFAULT 0
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+struct Page {
+    url: String,
+    title: String,
+    visits: i64,
+}
+
+// What the browsers' profiles hold, each page with the browser it's from
+pub struct Pages {
+    bookmarks: Vec<(&'static str, Page)>,
+    history: Vec<(&'static str, Page)>,
+}
+
+// Read every profile; done before taking the writer, which storing needs
+pub fn read(include_history: bool) -> Pages {
+    let mut bookmarks = Vec::new();
+    let mut history = Vec::new();
+    for (browser, profile) in system::chromium_profiles() {
+        match chromium_bookmarks(&profile.join("Bookmarks")) {
+            Ok(pages) => bookmarks.extend(pages.into_iter().map(|page| (browser, page))),
+            Err(e) => log::debug!("No {} bookmarks in {}: {}", browser, profile.display(), e),
+        }
+        if include_history {
+            let query = "SELECT url, title, visit_count FROM urls WHERE hidden = 0 ORDER BY last_visit_time DESC LIMIT ?1";
+            match read_copy(&profile.join("History"), query) {
+                Ok(pages) => history.extend(pages.into_iter().map(|page| (browser, page))),
+                Err(e) => log::debug!("No {} history in {}: {}", browser, profile.display(), e),
+            }
+        }
+    }
+    for profile in system::firefox_profiles() {
+        let places = profile.join("places.sqlite");
+        let query = "SELECT p.url, COALESCE(b.title, p.title, ''), p.visit_count
+                     FROM moz_bookmarks b JOIN moz_places p ON p.id = b.fk
+                     WHERE b.type = 1 LIMIT ?1";
+        match read_copy(&places, query) {
+            Ok(pages) => bookmarks.extend(pages.into_iter().map(|page| ("Firefox", page))),
+            Err(e) => log::debug!("No Firefox bookmarks in {}: {}", profile.display(), e),
+        }
+        if include_history {
+            let query = "SELECT url, COALESCE(title, ''), visit_count FROM moz_places
+                         WHERE visit_count > 0 AND hidden = 0
+                         ORDER BY last_visit_date DESC LIMIT ?1";
+            match read_copy(&places, query) {
+                Ok(pages) => history.extend(pages.into_iter().map(|page| ("Firefox", page))),
+                Err(e) => log::debug!("No Firefox history in {}: {}", profile.display(), e),
+            }
+        }
+    }
+    Pages { bookmarks, history }
+}
+
+// Replace the table's contents with `pages`; the number of pages kept. A page both
+// bookmarked and visited is kept as the bookmark, with its visits.
+pub fn store(conn: &Connection, pages: &Pages) -> Result<usize, String> {
+    let tx = conn.unchecked_transaction().map_err(|e| e.to_string())?;
+    tx.execute("DELETE FROM bookmarks", []).map_err(|e| e.to_string())?;
+    {
+        let mut insert = tx.prepare(
+            "INSERT INTO bookmarks (url, title, browser, is_history, visits) VALUES (?1, ?2, ?3, ?4, ?5)
+             ON CONFLICT (url) DO UPDATE SET
+                 visits = MAX(visits, excluded.visits),
+                 title = CASE WHEN title = '' THEN excluded.title ELSE title END"
+        ).map_err(|e| e.to_string())?;
+        let all = pages.bookmarks.iter().map(|page| (page, false)).chain(pages.history.iter().map(|page| (page, true)));
+        for ((browser, page), is_history) in all {
+            if web::is_web_url(&page.url) {
+                insert
+                    .execute(params![page.url, page.title.trim(), browser, is_history, page.visits])
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    let count: i64 = tx.query_row("SELECT COUNT(*) FROM bookmarks", [], |row| row.get(0)).map_err(|e| e.to_string())?;
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(count as usize)
+}
+
+// Pages whose title or URL contains the characters of a LIKE `pattern` in order, as
+// (url, title, visits), the most visited first
+pub fn search(conn: &Connection, pattern: &str, limit: i64) -> Result<Vec<(String, String, i64)>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT url, title, visits FROM bookmarks
+         WHERE title LIKE ?1 ESCAPE '\\' OR url LIKE ?1 ESCAPE '\\'
+         ORDER BY is_history, visits DESC
+         LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![pattern, limit], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?;
+    rows.collect::<Result<_, _>>().map_err(|e| e.to_string())
+}
+
+// The bookmarks in a Chromium `Bookmarks` file: a JSON tree of folders under "roots"
+fn chromium_bookmarks(file: &Path) -> Result<Vec<Page>, String> {
+    let text = std::fs::read_to_string(file).map_err(|e| e.to_string())?;
+    let tree: serde_json::Value = serde_json::from_str(&text).map_err(|e| e.to_string())?;
+    let mut pages = Vec::new();
+    let mut folders: Vec<&serde_json::Value> = tree["roots"].as_object().map(|roots| roots.values().collect()).unwrap_or_default();
+    while let Some(node) = folders.pop() {
+        if let Some(url) = node["url"].as_str() {
+            let title = node["name"].as_str().unwrap_or_default().to_string();
+            pages.push(Page { url: url.to_string(), title, visits: 0 });
+        }
+        if let Some(children) = node["children"].as_array() {
+            folders.extend(children);
+        }
+    }
+    Ok(pages)
+}
+
+// Rows of (url, title, visits) that `query` (with ?1 bound to HISTORY_LIMIT) returns from
+// the SQLite database `file`, read from a copy in case its browser holds it locked
+fn read_copy(file: &Path, query: &str) -> Result<Vec<Page>, String> {
+    if !file.is_file() {
+        return Err("not found".to_string());
+    }
+    let copy = std::env::temp_dir().join(format!("speedy-{}-{:x}", std::process::id(), crate::icons::fnv1a(&file.to_string_lossy())));
+    let beside = |path: &Path, suffix: &str| PathBuf::from(format!("{}{}", path.display(), suffix));
+    std::fs::copy(file, &copy).map_err(|e| e.to_string())?;
+    // Recent changes may still be in the write-ahead log next to it
+    if beside(file, "-wal").is_file() {
+        let _ = std::fs::copy(beside(file, "-wal"), beside(&copy, "-wal"));
+    }
+    let pages = (|| {
+        let conn = Connection::open_with_flags(&copy, OpenFlags::SQLITE_OPEN_READ_WRITE | OpenFlags::SQLITE_OPEN_NO_MUTEX)?;
+        let mut stmt = conn.prepare(query)?;
+        let rows = stmt.query_map(params![HISTORY_LIMIT], |row| {
+            Ok(Page { url: row.get(0)?, title: row.get::<_, Option<String>>(1)?.unwrap_or_default(), visits: row.get(2)? })
+        })?;
+        rows.collect::<Result<Vec<_>, _>>()
+    })();
+    for suffix in ["-wal", "-shm", ""] {
+        let _ = std::fs::remove_file(beside(&copy, suffix));
+    }
+    pages.map_err(|e| e.to_string())
+}
+
+// Profile folders: "Default" and "Profile 1", "Profile 2"... in a Chromium user data folder
+fn chromium_profiles_in(user_data: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(user_data) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| {
+            let name = path.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+            path.is_dir() && (name == "Default" || name.starts_with("Profile "))
+        })
+        .collect()
+}
+
+// Profile folders in a Firefox `Profiles` folder: those with a places database
+fn firefox_profiles_in(profiles: &Path) -> Vec<PathBuf> {
+    let Ok(entries) = std::fs::read_dir(profiles) else {
+        return Vec::new();
+    };
+    entries.flatten().map(|entry| entry.path()).filter(|path| path.join("places.sqlite").is_file()).collect()
+}
+
+#[cfg(windows)]
+mod system {
+    use std::path::PathBuf;
+
+    use super::{chromium_profiles_in, firefox_profiles_in};
+
+    fn dir(variable: &str) -> Option<PathBuf> {
+        std::env::var_os(variable).map(PathBuf::from)
+    }
+
+    pub fn chromium_profiles() -> Vec<(&'static str, PathBuf)> {
+        let Some(local) = dir("LOCALAPPDATA") else {
+            return Vec::new();
+        };
+        [("Chrome", r"Google\Chrome\User Data"), ("Edge", r"Microsoft\Edge\User Data"), ("Chromium", r"Chromium\User Data")]
+            .into_iter()
+            .flat_map(|(browser, user_data)| chromium_profiles_in(&local.join(user_data)).into_iter().map(move |profile| (browser, profile)))
+            .collect()
+    }
+
+    pub fn firefox_profiles() -> Vec<PathBuf> {
+        dir("APPDATA").map(|roaming| firefox_profiles_in(&roaming.join(r"Mozilla\Firefox\Profiles"))).unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::PathBuf;
+
+    use super::{chromium_profiles_in, firefox_profiles_in};
+
+    fn support() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(|home| PathBuf::from(home).join("Library/Application Support"))
+    }
+
+    pub fn chromium_profiles() -> Vec<(&'static str, PathBuf)> {
+        let Some(support) = support() else {
+            return Vec::new();
+        };
+        [("Chrome", "Google/Chrome"), ("Edge", "Microsoft Edge"), ("Chromium", "Chromium")]
+            .into_iter()
+            .flat_map(|(browser, user_data)| chromium_profiles_in(&support.join(user_data)).into_iter().map(move |profile| (browser, profile)))
+            .collect()
+    }
+
+    pub fn firefox_profiles() -> Vec<PathBuf> {
+        support().map(|support| firefox_profiles_in(&support.join("Firefox/Profiles"))).unwrap_or_default()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::env;
+    use std::path::PathBuf;
+
+    use super::{chromium_profiles_in, firefox_profiles_in};
+
+    pub fn chromium_profiles() -> Vec<(&'static str, PathBuf)> {
+        let Some(config_home) = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+        else {
+            return Vec::new();
+        };
+        [("Chrome", "google-chrome"), ("Edge", "microsoft-edge"), ("Chromium", "chromium")]
+            .into_iter()
+            .flat_map(|(browser, user_data)| chromium_profiles_in(&config_home.join(user_data)).into_iter().map(move |profile| (browser, profile)))
+            .collect()
+    }
+
+    // Profiles sit directly in ~/.mozilla/firefox
+    pub fn firefox_profiles() -> Vec<PathBuf> {
+        env::var_os("HOME").map(|home| firefox_profiles_in(&PathBuf::from(home).join(".mozilla/firefox"))).unwrap_or_default()
+    }
+}
+
+// Other systems: no browsers looked for
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use std::path::PathBuf;
+
+    pub fn chromium_profiles() -> Vec<(&'static str, PathBuf)> {
+        Vec::new()
+    }
+
+    pub fn firefox_profiles() -> Vec<PathBuf> {
+        Vec::new()
+    }
+}
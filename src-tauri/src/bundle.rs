@@ -0,0 +1,121 @@
+// macOS applications: the .app bundles in the Applications folders, with the name,
+// bundle identifier and icon their Info.plist gives.
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use plist::Value;
+
+pub struct Bundle {
+    pub path: PathBuf,
+    pub name: String,
+    pub bundle_id: Option<String>,
+    pub icon: Option<String>,
+}
+
+// The Applications folders, the user's first
+fn application_dirs() -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    if let Some(home) = std::env::var_os("HOME") {
+        dirs.push(PathBuf::from(home).join("Applications"));
+    }
+    dirs.push(PathBuf::from("/Applications"));
+    dirs.push(PathBuf::from("/System/Applications"));
+    dirs
+}
+
+// Every bundle in the Applications folders and the folders directly inside them (Utilities,
+// say). A bundle whose identifier was seen already, in a folder listed earlier, is left out.
+pub fn find() -> Vec<Bundle> {
+    let mut seen = HashSet::new();
+    let mut bundles = Vec::new();
+    for dir in application_dirs() {
+        let mut paths = Vec::new();
+        for entry in read_dir(&dir) {
+            if is_bundle(&entry) {
+                paths.push(entry);
+            } else if entry.is_dir() {
+                paths.extend(read_dir(&entry).into_iter().filter(|path| is_bundle(path)));
+            }
+        }
+        for path in paths {
+            let bundle = read(&path);
+            if bundle.bundle_id.as_ref().map_or(true, |id| seen.insert(id.clone())) {
+                bundles.push(bundle);
+            }
+        }
+    }
+    bundles
+}
+
+// The bundle at `path`; without a readable Info.plist, it is named after its folder
+pub fn read(path: &Path) -> Bundle {
+    let folder_name = path.file_stem().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+    let info = Value::from_file(path.join("Contents/Info.plist")).ok();
+    let info = info.as_ref().and_then(Value::as_dictionary);
+    let key = |key: &str| info.and_then(|info| info.get(key)).and_then(Value::as_string).map(String::from);
+
+    // CFBundleIconFile names a file in Resources, often without its extension
+    let icon = key("CFBundleIconFile").map(|file| {
+        let file = if Path::new(&file).extension().is_none() { format!("{}.icns", file) } else { file };
+        path.join("Contents/Resources").join(file).to_string_lossy().into_owned()
+    });
+    Bundle {
+        path: path.to_path_buf(),
+        name: key("CFBundleDisplayName").or_else(|| key("CFBundleName")).unwrap_or(folder_name),
+        bundle_id: key("CFBundleIdentifier"),
+        icon,
+    }
+}
+
+// Start the app of the bundle at `path` by its identifier, which finds it wherever it is
+// now; bundles without one are opened by path
+pub fn launch(path: &Path) -> Result<(), String> {
+    let mut command = Command::new("open");
+    match read(path).bundle_id {
+        Some(id) => command.args(["-b", &id]),
+        None => command.arg(path),
+    };
+    let output = command.output().map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+    }
+    Ok(())
+}
+
+// Whether the bundle at `path` says it opens documents with `extension` or of one of
+// `content_types` (UTIs, such as public.plain-text), going by its CFBundleDocumentTypes
+pub fn handles(path: &Path, extension: &str, content_types: &[String]) -> bool {
+    let Ok(info) = Value::from_file(path.join("Contents/Info.plist")) else {
+        return false;
+    };
+    let document_types = info
+        .as_dictionary()
+        .and_then(|info| info.get("CFBundleDocumentTypes"))
+        .and_then(Value::as_array);
+    let strings = |document_type: &Value, key: &str| -> Vec<String> {
+        document_type
+            .as_dictionary()
+            .and_then(|document_type| document_type.get(key))
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_string).map(String::from).collect())
+            .unwrap_or_default()
+    };
+    document_types.into_iter().flatten().any(|document_type| {
+        strings(document_type, "CFBundleTypeExtensions")
+            .iter()
+            .any(|ext| ext == "*" || ext.eq_ignore_ascii_case(extension))
+            || strings(document_type, "LSItemContentTypes").iter().any(|uti| content_types.contains(uti))
+    })
+}
+
+fn is_bundle(path: &Path) -> bool {
+    path.extension().is_some_and(|ext| ext == "app") && path.is_dir()
+}
+
+fn read_dir(dir: &Path) -> Vec<PathBuf> {
+    std::fs::read_dir(dir)
+        .map(|entries| entries.filter_map(|e| e.ok()).map(|e| e.path()).collect())
+        .unwrap_or_default()
+}
@@ -0,0 +1,360 @@
+// Answers typed into the search box: arithmetic ("12*14", "2^10 / 3", "sqrt(2)") and unit
+// conversions ("5km in mi", "100 f to c", "2.5 gib as mb"). A bare number isn't a
+// question, so it's left to the file search.
+
+// The answer to `query` as (what was asked, the value), or None if it isn't a question
+pub fn answer(query: &str) -> Option<(String, String)> {
+    let query = query.trim();
+    if let Some(conversion) = convert(query) {
+        return Some(conversion);
+    }
+    // Digit groups joined by dashes, like 2024-01-05 or 555-123-4567, are dates and numbers
+    // to look for rather than subtractions
+    let dashed = query.split('-').all(|group| !group.is_empty() && group.bytes().all(|b| b.is_ascii_digit()));
+    if dashed && query.matches('-').count() >= 2 {
+        return None;
+    }
+    let mut parser = Parser { tokens: tokenize(query)?, at: 0, depth: 0 };
+    let is_question = parser.tokens.iter().any(|token| matches!(token, Token::Op(_) | Token::Name(_)));
+    let value = parser.expression()?;
+    if parser.at != parser.tokens.len() || !is_question || !value.is_finite() {
+        return None;
+    }
+    let value = format_number(value);
+    Some((format!("{} = {}", query, value), value))
+}
+
+// "<amount> <unit> in|to|as <unit>", the amount being any expression
+fn convert(query: &str) -> Option<(String, String)> {
+    let lower = query.to_lowercase();
+    for separator in [" to ", " in ", " as "] {
+        let Some((from, to)) = lower.rsplit_once(separator) else {
+            continue;
+        };
+        let (to_name, from) = (to.trim(), from.trim_end());
+        // The unit is the end of the left side: letters, and / ° ² ³ for km/h, °c, m²...
+        let amount = from.trim_end_matches(|c: char| c.is_alphabetic() || "/°²³".contains(c));
+        let from_name = from[amount.len()..].trim();
+        let (from_unit, to_unit) = (unit(from_name)?, unit(to_name)?);
+        if from_unit.dimension != to_unit.dimension || amount.trim().is_empty() {
+            return None;
+        }
+        let mut parser = Parser { tokens: tokenize(amount)?, at: 0, depth: 0 };
+        let amount = parser.expression()?;
+        if parser.at != parser.tokens.len() {
+            return None;
+        }
+        let value = if from_unit.dimension == Dimension::Temperature {
+            to_kelvin_inverse(to_name, to_kelvin(from_name, amount)?)?
+        } else {
+            amount * from_unit.factor / to_unit.factor
+        };
+        if !value.is_finite() {
+            return None;
+        }
+        let value = format_number(value);
+        let asked = format!("{} {}", format_number(amount), from_name);
+        return Some((format!("{} = {} {}", asked, value, to_name), value));
+    }
+    None
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Dimension {
+    Length,
+    Mass,
+    Volume,
+    Time,
+    Data,
+    Speed,
+    Temperature,
+}
+
+struct Unit {
+    dimension: Dimension,
+    // Size in the dimension's base unit: metres, kilograms, litres, seconds, bytes, m/s.
+    // Temperatures have offsets too and are converted through kelvin instead.
+    factor: f64,
+}
+
+// The unit called `name`, in any of its usual spellings
+fn unit(name: &str) -> Option<Unit> {
+    use Dimension::*;
+    let (dimension, factor) = match name.trim_end_matches('.') {
+        "mm" | "millimeter" | "millimeters" | "millimetre" | "millimetres" => (Length, 0.001),
+        "cm" | "centimeter" | "centimeters" | "centimetre" | "centimetres" => (Length, 0.01),
+        "m" | "meter" | "meters" | "metre" | "metres" => (Length, 1.0),
+        "km" | "kilometer" | "kilometers" | "kilometre" | "kilometres" => (Length, 1000.0),
+        "in" | "inch" | "inches" => (Length, 0.0254),
+        "ft" | "foot" | "feet" => (Length, 0.3048),
+        "yd" | "yard" | "yards" => (Length, 0.9144),
+        "mi" | "mile" | "miles" => (Length, 1609.344),
+        "nmi" | "nautical mile" | "nautical miles" => (Length, 1852.0),
+
+        "mg" | "milligram" | "milligrams" => (Mass, 1e-6),
+        "g" | "gram" | "grams" => (Mass, 0.001),
+        "kg" | "kilogram" | "kilograms" | "kilo" | "kilos" => (Mass, 1.0),
+        "t" | "tonne" | "tonnes" => (Mass, 1000.0),
+        "oz" | "ounce" | "ounces" => (Mass, 0.028349523125),
+        "lb" | "lbs" | "pound" | "pounds" => (Mass, 0.45359237),
+        "st" | "stone" | "stones" => (Mass, 6.35029318),
+
+        "ml" | "milliliter" | "milliliters" | "millilitre" | "millilitres" => (Volume, 0.001),
+        "cl" | "centiliter" | "centiliters" | "centilitre" | "centilitres" => (Volume, 0.01),
+        "l" | "liter" | "liters" | "litre" | "litres" => (Volume, 1.0),
+        "tsp" | "teaspoon" | "teaspoons" => (Volume, 0.00492892159375),
+        "tbsp" | "tablespoon" | "tablespoons" => (Volume, 0.01478676478125),
+        "floz" | "fl oz" | "fluid ounce" | "fluid ounces" => (Volume, 0.0295735295625),
+        "cup" | "cups" => (Volume, 0.2365882365),
+        "pt" | "pint" | "pints" => (Volume, 0.473176473),
+        "qt" | "quart" | "quarts" => (Volume, 0.946352946),
+        "gal" | "gallon" | "gallons" => (Volume, 3.785411784),
+
+        "ms" | "millisecond" | "milliseconds" => (Time, 0.001),
+        "s" | "sec" | "second" | "seconds" => (Time, 1.0),
+        "min" | "minute" | "minutes" => (Time, 60.0),
+        "h" | "hr" | "hour" | "hours" => (Time, 3600.0),
+        "d" | "day" | "days" => (Time, 86_400.0),
+        "wk" | "week" | "weeks" => (Time, 604_800.0),
+        "yr" | "year" | "years" => (Time, 31_557_600.0),
+
+        "bit" | "bits" => (Data, 0.125),
+        "b" | "byte" | "bytes" => (Data, 1.0),
+        "kb" | "kilobyte" | "kilobytes" => (Data, 1e3),
+        "mb" | "megabyte" | "megabytes" => (Data, 1e6),
+        "gb" | "gigabyte" | "gigabytes" => (Data, 1e9),
+        "tb" | "terabyte" | "terabytes" => (Data, 1e12),
+        "kib" | "kibibyte" | "kibibytes" => (Data, 1024.0),
+        "mib" | "mebibyte" | "mebibytes" => (Data, 1_048_576.0),
+        "gib" | "gibibyte" | "gibibytes" => (Data, 1_073_741_824.0),
+        "tib" | "tebibyte" | "tebibytes" => (Data, 1_099_511_627_776.0),
+
+        "m/s" => (Speed, 1.0),
+        "km/h" | "kph" | "kmh" => (Speed, 1.0 / 3.6),
+        "mph" => (Speed, 0.44704),
+        "kn" | "kt" | "knot" | "knots" => (Speed, 0.514444),
+
+        "c" | "°c" | "celsius" | "f" | "°f" | "fahrenheit" | "k" | "kelvin" => (Temperature, 1.0),
+        _ => return None,
+    };
+    Some(Unit { dimension, factor })
+}
+
+fn to_kelvin(name: &str, degrees: f64) -> Option<f64> {
+    match name {
+        "c" | "°c" | "celsius" => Some(degrees + 273.15),
+        "f" | "°f" | "fahrenheit" => Some((degrees - 32.0) * 5.0 / 9.0 + 273.15),
+        "k" | "kelvin" => Some(degrees),
+        _ => None,
+    }
+}
+
+fn to_kelvin_inverse(name: &str, kelvin: f64) -> Option<f64> {
+    match name {
+        "c" | "°c" | "celsius" => Some(kelvin - 273.15),
+        "f" | "°f" | "fahrenheit" => Some((kelvin - 273.15) * 9.0 / 5.0 + 32.0),
+        "k" | "kelvin" => Some(kelvin),
+        _ => None,
+    }
+}
+
+// `value` as typed back: up to 10 decimals without trailing zeros, in scientific notation
+// when very large or very small
+fn format_number(value: f64) -> String {
+    let magnitude = value.abs();
+    if magnitude != 0.0 && !(1e-6..1e15).contains(&magnitude) {
+        return format!("{:e}", value);
+    }
+    let text = format!("{:.10}", value);
+    let text = text.trim_end_matches('0').trim_end_matches('.');
+    if text == "-0" { "0".to_string() } else { text.to_string() }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Number(f64),
+    Name(String),
+    Op(char),
+    Open,
+    Close,
+}
+
+// None if `text` holds something an expression can't
+fn tokenize(text: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut chars = text.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        match c {
+            ' ' | '\t' => {
+                chars.next();
+            }
+            '0'..='9' | '.' => {
+                let mut number = String::new();
+                while let Some(c) = chars.next_if(|c| c.is_ascii_digit() || *c == '.' || *c == '_') {
+                    if c != '_' {
+                        number.push(c);
+                    }
+                }
+                // An exponent, as in 1.5e3
+                if chars.next_if(|c| *c == 'e' || *c == 'E').is_some() {
+                    number.push('e');
+                    if let Some(sign) = chars.next_if(|c| *c == '-' || *c == '+') {
+                        number.push(sign);
+                    }
+                    while let Some(c) = chars.next_if(char::is_ascii_digit) {
+                        number.push(c);
+                    }
+                }
+                tokens.push(Token::Number(number.parse().ok()?));
+            }
+            'a'..='z' | 'A'..='Z' => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(char::is_ascii_alphanumeric) {
+                    name.push(c.to_ascii_lowercase());
+                }
+                tokens.push(Token::Name(name));
+            }
+            '+' | '-' | '*' | '/' | '%' | '^' => {
+                chars.next();
+                tokens.push(Token::Op(c));
+            }
+            '×' => {
+                chars.next();
+                tokens.push(Token::Op('*'));
+            }
+            '÷' => {
+                chars.next();
+                tokens.push(Token::Op('/'));
+            }
+            '(' => {
+                chars.next();
+                tokens.push(Token::Open);
+            }
+            ')' => {
+                chars.next();
+                tokens.push(Token::Close);
+            }
+            _ => return None,
+        }
+    }
+    Some(tokens)
+}
+
+// Recursive descent, loosest binding first: + and -, then * / %, then unary minus, then
+// ^ (right to left, so 2^3^2 is 2^9 and -2^2 is -4)
+struct Parser {
+    tokens: Vec<Token>,
+    at: usize,
+    depth: usize, // Nesting of brackets, signs and powers so far
+}
+
+// Deeper nesting isn't a question anyone types, and would overflow the stack
+const MAX_DEPTH: usize = 64;
+
+impl Parser {
+    fn next_if(&mut self, wanted: &Token) -> bool {
+        if self.tokens.get(self.at) == Some(wanted) {
+            self.at += 1;
+            return true;
+        }
+        false
+    }
+
+    fn expression(&mut self) -> Option<f64> {
+        let mut value = self.term()?;
+        loop {
+            if self.next_if(&Token::Op('+')) {
+                value += self.term()?;
+            } else if self.next_if(&Token::Op('-')) {
+                value -= self.term()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    fn term(&mut self) -> Option<f64> {
+        let mut value = self.unary()?;
+        loop {
+            if self.next_if(&Token::Op('*')) {
+                value *= self.unary()?;
+            } else if self.next_if(&Token::Op('/')) {
+                value /= self.unary()?;
+            } else if self.next_if(&Token::Op('%')) {
+                value %= self.unary()?;
+            } else {
+                return Some(value);
+            }
+        }
+    }
+
+    // Every nested bracket, sign or power passes through here, so this is where depth is kept
+    fn unary(&mut self) -> Option<f64> {
+        if self.depth == MAX_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        let value = if self.next_if(&Token::Op('-')) {
+            self.unary().map(|value| -value)
+        } else if self.next_if(&Token::Op('+')) {
+            self.unary()
+        } else {
+            self.power()
+        };
+        self.depth -= 1;
+        value
+    }
+
+    fn power(&mut self) -> Option<f64> {
+        let base = self.primary()?;
+        if self.next_if(&Token::Op('^')) {
+            return Some(base.powf(self.unary()?));
+        }
+        Some(base)
+    }
+
+    fn primary(&mut self) -> Option<f64> {
+        let token = self.tokens.get(self.at)?.clone();
+        self.at += 1;
+        match token {
+            Token::Number(value) => Some(value),
+            Token::Open => {
+                let value = self.expression()?;
+                self.next_if(&Token::Close).then_some(value)
+            }
+            Token::Name(name) => match name.as_str() {
+                "pi" => Some(std::f64::consts::PI),
+                "e" => Some(std::f64::consts::E),
+                _ => {
+                    if !self.next_if(&Token::Open) {
+                        return None;
+                    }
+                    let argument = self.expression()?;
+                    if !self.next_if(&Token::Close) {
+                        return None;
+                    }
+                    function(&name, argument)
+                }
+            },
+            Token::Op(_) | Token::Close => None,
+        }
+    }
+}
+
+// Angles are in radians
+fn function(name: &str, x: f64) -> Option<f64> {
+    Some(match name {
+        "sqrt" => x.sqrt(),
+        "abs" => x.abs(),
+        "round" => x.round(),
+        "floor" => x.floor(),
+        "ceil" => x.ceil(),
+        "sin" => x.sin(),
+        "cos" => x.cos(),
+        "tan" => x.tan(),
+        "ln" => x.ln(),
+        "log" => x.log10(),
+        "exp" => x.exp(),
+        _ => return None,
+    })
+}
@@ -0,0 +1,373 @@
+// Clipboard history, off unless preferences.clipboard turns it on: text copied while it's
+// on is kept in the `clips` table, newest first and up to its limits, and found with the
+// `clip:` prefix. Picking a clip pastes it into the window that was in front.
+//
+// Clips are encrypted (ChaCha20-Poly1305) with a key kept beside the database, so a copy
+// of the database alone doesn't reveal them; a keyed digest tells copies of the same text
+// apart without decrypting. The key is made when the history is first turned on. Text
+// copied from a password manager, or marked by its program as not for clipboard history,
+// is never kept.
+
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use ring::aead::{Aad, CHACHA20_POLY1305, LessSafeKey, NONCE_LEN, Nonce, UnboundKey};
+use ring::hmac;
+use ring::rand::{SecureRandom, SystemRandom};
+use rusqlite::{Connection, params};
+use tauri::Manager;
+use tauri_plugin_clipboard_manager::ClipboardExt;
+
+use crate::{AppState, settings};
+
+// Start of the path of a clip result, followed by its id
+pub const SCHEME: &str = "clip:";
+
+// How often the clipboard is looked at for new text
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+// Wait for the window that was in front to have the focus back before pasting into it
+const FOCUS_DELAY: Duration = Duration::from_millis(150);
+
+// The clips' key, managed by the app and read from `dir` when first needed
+pub struct Clips {
+    dir: PathBuf,
+    key: Mutex<Option<Arc<Key>>>,
+}
+
+// 32 bytes for the cipher, 32 for digests
+struct Key {
+    cipher: LessSafeKey,
+    digest: hmac::Key,
+}
+
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS clips (
+            id INTEGER PRIMARY KEY,
+            digest BLOB NOT NULL UNIQUE,
+            nonce BLOB NOT NULL,
+            text BLOB NOT NULL,
+            copied_at INTEGER NOT NULL
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+impl Clips {
+    // With the key kept in `dir`; nothing is read until it's needed
+    pub fn new(dir: &Path) -> Clips {
+        Clips { dir: dir.to_path_buf(), key: Mutex::new(None) }
+    }
+
+    // Make the key if there is none yet, as the history is turned on
+    pub fn prepare(&self) -> Result<(), String> {
+        self.key(true).map(|_| ())
+    }
+
+    // The key, read on first use; None if it was never made and `create` is false. A key
+    // file that can't be read is an error: a new key would lose every clip kept.
+    fn key(&self, create: bool) -> Result<Option<Arc<Key>>, String> {
+        let mut loaded = self.key.lock().map_err(|e| e.to_string())?;
+        if let Some(key) = loaded.as_ref() {
+            return Ok(Some(key.clone()));
+        }
+        let file = self.dir.join("clipboard.key");
+        let bytes = match std::fs::read(&file) {
+            Ok(bytes) if bytes.len() == 64 => bytes,
+            Ok(_) => return Err(format!("The clipboard key {} is damaged; remove it to start a new history", file.display())),
+            Err(e) if e.kind() == ErrorKind::NotFound && !create => return Ok(None),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                let mut bytes = vec![0u8; 64];
+                SystemRandom::new().fill(&mut bytes).map_err(|_| "Could not make a clipboard key".to_string())?;
+                write_private(&file, &bytes).map_err(|e| format!("Could not save the clipboard key: {}", e))?;
+                bytes
+            }
+            Err(e) => return Err(format!("Could not read the clipboard key: {}", e)),
+        };
+        let cipher = UnboundKey::new(&CHACHA20_POLY1305, &bytes[..32]).map_err(|_| "Invalid clipboard key".to_string())?;
+        let key = Arc::new(Key { cipher: LessSafeKey::new(cipher), digest: hmac::Key::new(hmac::HMAC_SHA256, &bytes[32..]) });
+        *loaded = Some(key.clone());
+        Ok(Some(key))
+    }
+
+    // Keep `text`, copied at `now`, as the newest clip (moving it up if kept already), then
+    // drop the oldest beyond `max_items`
+    pub fn record(&self, conn: &Connection, text: &str, now: i64, max_items: usize) -> Result<(), String> {
+        let key = self.key(true)?.ok_or_else(|| "There is no clipboard key".to_string())?;
+        let mut nonce = [0u8; NONCE_LEN];
+        SystemRandom::new().fill(&mut nonce).map_err(|_| "Could not encrypt the clip".to_string())?;
+        let mut sealed = text.as_bytes().to_vec();
+        key.cipher
+            .seal_in_place_append_tag(Nonce::assume_unique_for_key(nonce), Aad::empty(), &mut sealed)
+            .map_err(|_| "Could not encrypt the clip".to_string())?;
+        let digest = hmac::sign(&key.digest, text.as_bytes());
+        conn.execute("DELETE FROM clips WHERE digest = ?1", params![digest.as_ref()]).map_err(|e| e.to_string())?;
+        conn.execute(
+            "INSERT INTO clips (digest, nonce, text, copied_at) VALUES (?1, ?2, ?3, ?4)",
+            params![digest.as_ref(), &nonce[..], sealed, now],
+        ).map_err(|e| e.to_string())?;
+        conn.execute(
+            "DELETE FROM clips WHERE id NOT IN (SELECT id FROM clips ORDER BY id DESC LIMIT ?1)",
+            params![max_items as i64],
+        ).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // Every clip as (id, text), the newest first
+    pub fn list(&self, conn: &Connection) -> Result<Vec<(i64, String)>, String> {
+        // Never turned on: nothing kept
+        let Some(key) = self.key(false)? else {
+            return Ok(Vec::new());
+        };
+        let mut stmt = conn.prepare("SELECT id, nonce, text FROM clips ORDER BY id DESC").map_err(|e| e.to_string())?;
+        let rows = stmt
+            .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, Vec<u8>>(1)?, row.get::<_, Vec<u8>>(2)?)))
+            .map_err(|e| e.to_string())?;
+        let mut clips = Vec::new();
+        for row in rows {
+            let (id, nonce, sealed) = row.map_err(|e| e.to_string())?;
+            // One the key can't open (the key file was replaced) is skipped
+            if let Some(text) = key.open(&nonce, sealed) {
+                clips.push((id, text));
+            }
+        }
+        Ok(clips)
+    }
+
+    // The text of the clip `id`
+    pub fn text(&self, conn: &Connection, id: i64) -> Result<String, String> {
+        let key = self.key(false)?.ok_or_else(|| "There is no clipboard key".to_string())?;
+        let (nonce, sealed): (Vec<u8>, Vec<u8>) = conn
+            .query_row("SELECT nonce, text FROM clips WHERE id = ?1", params![id], |row| Ok((row.get(0)?, row.get(1)?)))
+            .map_err(|_| "That clip is no longer in the clipboard history".to_string())?;
+        key.open(&nonce, sealed).ok_or_else(|| "That clip can't be decrypted".to_string())
+    }
+}
+
+impl Key {
+    fn open(&self, nonce: &[u8], mut sealed: Vec<u8>) -> Option<String> {
+        let nonce = Nonce::try_assume_unique_for_key(nonce).ok()?;
+        let text = self.cipher.open_in_place(nonce, Aad::empty(), &mut sealed).ok()?;
+        String::from_utf8(text.to_vec()).ok()
+    }
+}
+
+pub fn clear(conn: &Connection) -> Result<(), String> {
+    conn.execute("DELETE FROM clips", []).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Watch the clipboard for as long as the app runs, keeping what's copied while the
+// history is on
+pub fn start(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        // The text last seen; None while off, so that turning it on doesn't keep what was
+        // copied before
+        let mut last: Option<String> = None;
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+
+            let state = app.state::<AppState>();
+            let options = match state.readers.get() {
+                Ok(conn) => settings::load::<settings::Preferences>(&conn, settings::PREFERENCES).clipboard,
+                Err(_) => continue,
+            };
+            if !options.enabled {
+                last = None;
+                continue;
+            }
+            // Just turned on: make the key now rather than at the first copy
+            if last.is_none() {
+                if let Err(e) = app.state::<Clips>().prepare() {
+                    log::warn!("Could not prepare the clipboard history: {}", e);
+                }
+            }
+            let Ok(text) = app.clipboard().read_text() else {
+                continue;
+            };
+            let is_new = last.as_deref().is_some_and(|last| last != text);
+            let first = last.is_none();
+            last = Some(text.clone());
+            if first || !is_new || text.trim().is_empty() || text.len() > options.max_bytes {
+                continue;
+            }
+            if let Some(program) = system::secret_source(&options.excluded_programs) {
+                log::debug!("Not keeping a clip copied from {}", program);
+                continue;
+            }
+            let clips = app.state::<Clips>();
+            if let Ok(conn) = state.db.lock() {
+                if let Err(e) = clips.record(&conn, &text, crate::frecency::now(), options.max_items) {
+                    log::warn!("Could not keep a clip: {}", e);
+                }
+            }
+        }
+    });
+}
+
+// Put `text` on the clipboard
+pub fn copy(app: &tauri::AppHandle, text: &str) -> Result<(), String> {
+    app.clipboard().write_text(text.to_string()).map_err(|e| format!("Could not copy: {}", e))
+}
+
+// Put `text` on the clipboard, hide the launcher and paste it into the window now in front
+pub fn paste(app: &tauri::AppHandle, text: &str) -> Result<(), String> {
+    copy(app, text)?;
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.hide();
+    }
+    std::thread::sleep(FOCUS_DELAY);
+    system::send_paste().map_err(|e| format!("Copied, but could not paste: {}", e))
+}
+
+// Whether `program` matches an entry of the exclusion list, ignoring case
+fn is_excluded(program: &str, excluded: &[String]) -> bool {
+    let program = program.to_lowercase();
+    excluded.iter().any(|name| !name.trim().is_empty() && program.contains(&name.trim().to_lowercase()))
+}
+
+// Written readable by the user alone
+fn write_private(file: &Path, contents: &[u8]) -> std::io::Result<()> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    options.open(file)?.write_all(contents)
+}
+
+#[cfg(windows)]
+mod system {
+    use windows_sys::Win32::System::DataExchange::{GetClipboardOwner, IsClipboardFormatAvailable, RegisterClipboardFormatW};
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{
+        INPUT, INPUT_0, INPUT_KEYBOARD, KEYBDINPUT, KEYEVENTF_KEYUP, SendInput, VK_CONTROL, VK_V,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::GetWindowThreadProcessId;
+
+    use super::is_excluded;
+
+    // Formats password managers and other programs add to keep what they copy out of
+    // clipboard history
+    const PRIVATE_FORMATS: &[&str] = &["ExcludeClipboardContentFromMonitorProcessing", "Clipboard Viewer Ignore"];
+
+    // What the current clipboard text came from, if that's a reason not to keep it
+    pub fn secret_source(excluded: &[String]) -> Option<String> {
+        for format in PRIVATE_FORMATS {
+            let name: Vec<u16> = format.encode_utf16().chain(Some(0)).collect();
+            let id = unsafe { RegisterClipboardFormatW(name.as_ptr()) };
+            if id != 0 && unsafe { IsClipboardFormatAvailable(id) } != 0 {
+                return Some(format!("a program marking it private ({})", format));
+            }
+        }
+        let owner = unsafe { GetClipboardOwner() };
+        if owner.is_null() {
+            return None;
+        }
+        let mut pid = 0u32;
+        unsafe { GetWindowThreadProcessId(owner, &mut pid) };
+        crate::switcher::program(pid).filter(|program| is_excluded(program, excluded))
+    }
+
+    // Ctrl+V
+    pub fn send_paste() -> Result<(), String> {
+        let key = |vk: u16, flags: u32| INPUT {
+            r#type: INPUT_KEYBOARD,
+            Anonymous: INPUT_0 { ki: KEYBDINPUT { wVk: vk, wScan: 0, dwFlags: flags, time: 0, dwExtraInfo: 0 } },
+        };
+        let inputs = [key(VK_CONTROL, 0), key(VK_V, 0), key(VK_V, KEYEVENTF_KEYUP), key(VK_CONTROL, KEYEVENTF_KEYUP)];
+        let sent = unsafe { SendInput(inputs.len() as u32, inputs.as_ptr(), std::mem::size_of::<INPUT>() as i32) };
+        if sent as usize != inputs.len() {
+            return Err(std::io::Error::last_os_error().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::process::Command;
+
+    use super::is_excluded;
+
+    // The program in front, which is where a copy comes from
+    pub fn secret_source(excluded: &[String]) -> Option<String> {
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg("tell application \"System Events\" to get name of first process whose frontmost is true")
+            .output()
+            .ok()?;
+        let program = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        is_excluded(&program, excluded).then_some(program)
+    }
+
+    // Cmd+V, sent through System Events (which needs the Accessibility permission)
+    pub fn send_paste() -> Result<(), String> {
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg("tell application \"System Events\" to keystroke \"v\" using command down")
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::process::Command;
+
+    use super::is_excluded;
+
+    // Type KDE's password managers (and KeePassXC) add to what they copy
+    const PASSWORD_HINT: &str = "x-kde-passwordManagerHint";
+
+    // What the current clipboard text came from, if that's a reason not to keep it: a
+    // password manager's hint among its types, or an excluded program in front
+    pub fn secret_source(excluded: &[String]) -> Option<String> {
+        let types = Command::new("wl-paste")
+            .arg("--list-types")
+            .output()
+            .ok()
+            .filter(|output| output.status.success())
+            .or_else(|| Command::new("xclip").args(["-selection", "clipboard", "-t", "TARGETS", "-o"]).output().ok());
+        if types.is_some_and(|output| String::from_utf8_lossy(&output.stdout).contains(PASSWORD_HINT)) {
+            return Some("a password manager".to_string());
+        }
+        let output = Command::new("xdotool").args(["getactivewindow", "getwindowpid"]).output().ok()?;
+        let pid = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        let program = std::fs::read_to_string(format!("/proc/{}/comm", pid)).ok()?.trim().to_string();
+        is_excluded(&program, excluded).then_some(program)
+    }
+
+    // Ctrl+V, with xdotool on X11 or wtype on Wayland
+    pub fn send_paste() -> Result<(), String> {
+        let sent = Command::new("xdotool")
+            .args(["key", "--clearmodifiers", "ctrl+v"])
+            .status()
+            .or_else(|_| Command::new("wtype").args(["-M", "ctrl", "v", "-m", "ctrl"]).status())
+            .map_err(|e| format!("neither xdotool nor wtype could be run: {}", e))?;
+        if !sent.success() {
+            return Err("the keystroke could not be sent".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Other systems: nothing known to exclude, and no keystrokes
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    pub fn secret_source(_excluded: &[String]) -> Option<String> {
+        None
+    }
+
+    pub fn send_paste() -> Result<(), String> {
+        Err("pasting isn't supported on this system".to_string())
+    }
+}
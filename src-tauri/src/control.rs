@@ -0,0 +1,103 @@
+// Pausing, resuming and cancelling indexing. Indexers commit in batches and call `proceed`
+// between them, so a paused run holds no database lock (searches keep working) and a
+// cancelled one stops there, keeping what it indexed so far. Scheduled runs also stop
+// there once the condition they run under no longer holds.
+
+use std::sync::{Condvar, Mutex};
+
+use crate::progress;
+
+// Error returned by an indexing run that was cancelled
+pub const CANCELLED: &str = "Indexing was cancelled";
+
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+enum State {
+    #[default]
+    Running,
+    Paused,
+    Cancelled,
+}
+
+// Checked between batches; the runs stop when it returns false
+type Condition = Box<dyn Fn() -> bool + Send>;
+
+// Shared by all indexing runs, managed by the app
+#[derive(Default)]
+pub struct Control {
+    state: Mutex<State>,
+    changed: Condvar,
+    condition: Mutex<Option<Condition>>,
+}
+
+impl Control {
+    // Hold runs at their next batch; a pause outlasts the run, so runs started later wait too
+    pub fn pause(&self) {
+        self.update(|state| if state == State::Running { State::Paused } else { state });
+    }
+
+    pub fn resume(&self) {
+        self.update(|state| if state == State::Paused { State::Running } else { state });
+    }
+
+    // Stop the runs going on (paused ones included) at their next batch
+    pub fn cancel(&self) {
+        self.update(|_| State::Cancelled);
+    }
+
+    // Called when indexing is started anew, so an earlier cancel doesn't stop it, nor the
+    // condition of a scheduled run still going on. A pause stays in force.
+    pub fn start(&self) {
+        self.stop_when(None);
+        self.update(|state| if state == State::Cancelled { State::Running } else { state });
+    }
+
+    // Stop the runs going on at their next batch once `condition` returns false, like a
+    // cancel that doesn't hold back later runs; None lets them go on again
+    pub fn stop_when(&self, condition: Option<Condition>) {
+        if let Ok(mut current) = self.condition.lock() {
+            *current = condition;
+        }
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.get() == State::Paused
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.get() == State::Cancelled
+    }
+
+    // Between two batches: wait while paused, reporting it through `run`, and tell whether
+    // the run may go on
+    pub fn proceed(&self, run: &mut progress::Run) -> bool {
+        let Ok(mut state) = self.state.lock() else {
+            return true;
+        };
+        if *state == State::Paused {
+            run.set_paused(true);
+            while *state == State::Paused {
+                state = match self.changed.wait(state) {
+                    Ok(state) => state,
+                    Err(_) => return true,
+                };
+            }
+            run.set_paused(false);
+        }
+        if *state == State::Cancelled {
+            return false;
+        }
+        drop(state);
+        self.condition.lock().map_or(true, |condition| condition.as_ref().map_or(true, |holds| holds()))
+    }
+
+    fn get(&self) -> State {
+        self.state.lock().map(|state| *state).unwrap_or_default()
+    }
+
+    fn update(&self, change: impl FnOnce(State) -> State) {
+        if let Ok(mut state) = self.state.lock() {
+            *state = change(*state);
+            self.changed.notify_all();
+        }
+    }
+}
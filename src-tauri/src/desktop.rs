@@ -0,0 +1,237 @@
+// Linux applications: the desktop entries (.desktop files) in the XDG data directories,
+// where menus and launchers find them too, read and started as the Desktop Entry
+// Specification describes.
+
+use std::collections::{HashMap, HashSet};
+use std::env;
+use std::path::{Path, PathBuf};
+
+use walkdir::WalkDir;
+
+pub struct Entry {
+    pub path: PathBuf,
+    pub name: String,
+    exec: String,
+    pub icon: Option<String>,
+    pub working_dir: Option<String>,
+    terminal: bool,
+    pub mime_types: Vec<String>,
+}
+
+// The applications folders, most important first: the user's, then the system's
+fn application_dirs() -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let data_home = env::var_os("XDG_DATA_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| home.map(|home| home.join(".local/share")));
+    let data_dirs = env::var("XDG_DATA_DIRS")
+        .ok()
+        .filter(|dirs| !dirs.is_empty())
+        .unwrap_or_else(|| "/usr/local/share:/usr/share".to_string());
+
+    data_home
+        .into_iter()
+        .chain(env::split_paths(&data_dirs))
+        .map(|dir| dir.join("applications"))
+        .collect()
+}
+
+// Every desktop entry that shows in menus. An entry in a more important folder hides the
+// one with the same desktop file ID (its path below the applications folder) further down.
+pub fn find() -> Vec<Entry> {
+    let mut seen = HashSet::new();
+    let mut entries = Vec::new();
+    for dir in application_dirs() {
+        for file in WalkDir::new(&dir).into_iter().filter_map(|e| e.ok()) {
+            let path = file.path();
+            if !file.file_type().is_file() || path.extension().map_or(true, |ext| ext != "desktop") {
+                continue;
+            }
+            let id = path.strip_prefix(&dir).unwrap_or(path).to_string_lossy().replace('/', "-");
+            if !seen.insert(id) {
+                continue;
+            }
+            if let Some(entry) = read(path) {
+                entries.push(entry);
+            }
+        }
+    }
+    entries
+}
+
+// The application entry in the desktop file at `path`; None if it is something else (a
+// link, a folder) or hidden from menus, or its program isn't installed
+pub fn read(path: &Path) -> Option<Entry> {
+    let text = std::fs::read_to_string(path).ok()?;
+    let keys = main_group(&text);
+
+    let flag = |key: &str| keys.get(key).is_some_and(|value| value == "true");
+    if keys.get("Type").map(String::as_str) != Some("Application") || flag("NoDisplay") || flag("Hidden") {
+        return None;
+    }
+    if let Some(program) = keys.get("TryExec") {
+        if !installed(program) {
+            return None;
+        }
+    }
+
+    Some(Entry {
+        path: path.to_path_buf(),
+        name: keys.get("Name")?.clone(),
+        exec: keys.get("Exec")?.clone(),
+        icon: keys.get("Icon").filter(|icon| !icon.is_empty()).cloned(),
+        working_dir: keys.get("Path").filter(|dir| !dir.is_empty()).cloned(),
+        terminal: flag("Terminal"),
+        mime_types: keys.get("MimeType").map(|types| list(types)).unwrap_or_default(),
+    })
+}
+
+// What starting the application of the desktop file at `path` on `files` (none: just
+// starting it) runs: the program and its arguments, in a terminal if the entry asks for
+// one, and the folder to run it in
+pub fn command(path: &Path, files: &[&str]) -> Result<(Vec<String>, Option<String>), String> {
+    let entry = read(path).ok_or_else(|| "not an application".to_string())?;
+    let mut args = command_line(&entry, files)?;
+    if args.is_empty() {
+        return Err("empty Exec line".to_string());
+    }
+    if entry.terminal {
+        // No standard names the terminal; this is the Debian-style alternative most have
+        args.splice(0..0, ["x-terminal-emulator".to_string(), "-e".to_string()]);
+    }
+    Ok((args, entry.working_dir))
+}
+
+// The keys of the [Desktop Entry] group, unlocalized, with escapes in values resolved
+fn main_group(text: &str) -> HashMap<String, String> {
+    let mut keys = HashMap::new();
+    let mut in_main = false;
+    for line in text.lines().map(str::trim) {
+        if line.starts_with('[') {
+            in_main = line == "[Desktop Entry]";
+        } else if in_main && !line.starts_with('#') {
+            if let Some((key, value)) = line.split_once('=') {
+                keys.entry(key.trim().to_string()).or_insert_with(|| unescape(value.trim()));
+            }
+        }
+    }
+    keys
+}
+
+// The items of a list value, which ends each with a semicolon
+fn list(value: &str) -> Vec<String> {
+    value.split(';').map(str::trim).filter(|item| !item.is_empty()).map(String::from).collect()
+}
+
+// \s, \n, \t, \r and \\ in a value
+fn unescape(value: &str) -> String {
+    let mut text = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('s') => text.push(' '),
+            Some('n') => text.push('\n'),
+            Some('t') => text.push('\t'),
+            Some('r') => text.push('\r'),
+            Some(other) => text.push(other),
+            None => text.push('\\'),
+        }
+    }
+    text
+}
+
+// The Exec line as program and arguments: split where unquoted spaces are, with the
+// field codes filled in. Local paths are valid URLs here, so %u and %U take `files` as
+// they are; an entry for a single file gets the first.
+fn command_line(entry: &Entry, files: &[&str]) -> Result<Vec<String>, String> {
+    let mut args = Vec::new();
+    for (word, quoted) in split_exec(&entry.exec)? {
+        if quoted {
+            args.push(word);
+            continue;
+        }
+        match word.as_str() {
+            "%f" | "%u" => args.extend(files.first().map(|file| file.to_string())),
+            "%F" | "%U" => args.extend(files.iter().map(|file| file.to_string())),
+            "%i" => {
+                if let Some(icon) = &entry.icon {
+                    args.push("--icon".to_string());
+                    args.push(icon.clone());
+                }
+            }
+            _ => args.push(expand_codes(&word, entry)),
+        }
+    }
+    Ok(args)
+}
+
+// Field codes within one argument; deprecated and unknown ones are dropped
+fn expand_codes(word: &str, entry: &Entry) -> String {
+    let mut text = String::with_capacity(word.len());
+    let mut chars = word.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            text.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('%') => text.push('%'),
+            Some('c') => text.push_str(&entry.name),
+            Some('k') => text.push_str(&entry.path.to_string_lossy()),
+            _ => {}
+        }
+    }
+    text
+}
+
+// Split an Exec line into words, each with whether it was quoted. Inside double quotes
+// a backslash escapes ", `, $ and \.
+fn split_exec(exec: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut words = Vec::new();
+    let mut chars = exec.chars().peekable();
+    loop {
+        while chars.next_if(|c| *c == ' ' || *c == '\t').is_some() {}
+        let Some(first) = chars.next() else {
+            break;
+        };
+        let mut word = String::new();
+        if first == '"' {
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some('\\') => match chars.next() {
+                        Some(c @ ('"' | '`' | '$' | '\\')) => word.push(c),
+                        Some(c) => {
+                            word.push('\\');
+                            word.push(c);
+                        }
+                        None => return Err(format!("Unfinished quote in Exec line: {}", exec)),
+                    },
+                    Some(c) => word.push(c),
+                    None => return Err(format!("Unfinished quote in Exec line: {}", exec)),
+                }
+            }
+            words.push((word, true));
+        } else {
+            word.push(first);
+            while let Some(c) = chars.next_if(|c| *c != ' ' && *c != '\t') {
+                word.push(c);
+            }
+            words.push((word, false));
+        }
+    }
+    Ok(words)
+}
+
+// Whether `program` (a path, or a name looked up on PATH) exists
+pub fn installed(program: &str) -> bool {
+    if program.contains('/') {
+        return Path::new(program).is_file();
+    }
+    env::var_os("PATH").is_some_and(|path| env::split_paths(&path).any(|dir| dir.join(program).is_file()))
+}
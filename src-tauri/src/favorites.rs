@@ -0,0 +1,99 @@
+// Favorites: results the user pinned, which rank above others matching as well, and
+// aliases, keywords of their own that bring one result up first ("m" for Mail). They're
+// kept by path in `favorites`, apart from the index, so re-indexing doesn't lose them.
+
+use std::collections::HashSet;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+// Create the favorites table. A row goes once it's neither pinned nor aliased.
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS favorites (
+            path TEXT PRIMARY KEY,
+            pinned BOOLEAN NOT NULL DEFAULT 0,
+            alias TEXT UNIQUE COLLATE NOCASE
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+// Paths of the pinned results
+pub fn pinned(conn: &Connection) -> Result<HashSet<String>, String> {
+    let mut stmt = conn.prepare("SELECT path FROM favorites WHERE pinned").map_err(|e| e.to_string())?;
+    let paths = stmt
+        .query_map([], |row| row.get(0))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<HashSet<String>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(paths)
+}
+
+// The indexed entry whose alias is `text`, ignoring case, as (path, name, type)
+pub fn aliased(conn: &Connection, text: &str) -> Result<Option<(String, String, String)>, String> {
+    conn.query_row(
+        "SELECT f.path, COALESCE(a.name, t.name),
+                CASE WHEN a.id IS NOT NULL OR t.is_app THEN 'app' WHEN t.is_file THEN 'file' ELSE 'folder' END
+         FROM favorites f
+         LEFT JOIN applications a ON a.path = f.path
+         LEFT JOIN files t ON t.path = f.path
+         WHERE f.alias = ?1 AND (a.id IS NOT NULL OR t.id IS NOT NULL)",
+        params![text.trim()],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+    ).optional().map_err(|e| e.to_string())
+}
+
+pub fn set_pinned(conn: &Connection, path: &str, pinned: bool) -> Result<(), String> {
+    if pinned {
+        indexed(conn, path)?;
+    }
+    conn.execute(
+        "INSERT INTO favorites (path, pinned) VALUES (?1, ?2)
+         ON CONFLICT(path) DO UPDATE SET pinned = excluded.pinned",
+        params![path, pinned],
+    ).map_err(|e| e.to_string())?;
+    changed(conn)
+}
+
+// Give the result at `path` the alias `alias`; None or blank takes its alias away. An
+// alias names one result, so one another result has already is refused.
+pub fn set_alias(conn: &Connection, path: &str, alias: Option<&str>) -> Result<(), String> {
+    let alias = alias.map(str::trim).filter(|alias| !alias.is_empty());
+    if let Some(alias) = alias {
+        indexed(conn, path)?;
+        let owner: Option<String> = conn.query_row(
+            "SELECT path FROM favorites WHERE alias = ?1 AND path != ?2",
+            params![alias, path],
+            |row| row.get(0),
+        ).optional().map_err(|e| e.to_string())?;
+        if let Some(owner) = owner {
+            return Err(format!("\"{}\" is already the alias of {}", alias, owner));
+        }
+    }
+    conn.execute(
+        "INSERT INTO favorites (path, alias) VALUES (?1, ?2)
+         ON CONFLICT(path) DO UPDATE SET alias = excluded.alias",
+        params![path, alias],
+    ).map_err(|e| e.to_string())?;
+    changed(conn)
+}
+
+fn indexed(conn: &Connection, path: &str) -> Result<(), String> {
+    let found: bool = conn.query_row(
+        "SELECT EXISTS (SELECT 1 FROM files WHERE path = ?1)
+             OR EXISTS (SELECT 1 FROM applications WHERE path = ?1)",
+        params![path],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    if !found {
+        return Err(format!("{} is not in the index", path));
+    }
+    Ok(())
+}
+
+// Drop rows left with nothing to keep, and cached rankings made before the change
+fn changed(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "DELETE FROM favorites WHERE NOT pinned AND alias IS NULL;
+         DELETE FROM search_cache;"
+    ).map_err(|e| e.to_string())
+}
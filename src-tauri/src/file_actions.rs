@@ -0,0 +1,281 @@
+// Acting on results rather than opening them: copying, moving and renaming files and
+// folders, and sending them to the recycle bin or trash (never deleting them outright).
+// Copies report their progress as `copy-progress` events, at most every EMIT_INTERVAL.
+
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::Emitter;
+use walkdir::WalkDir;
+
+use crate::prune;
+
+// Name of the event carrying a CopyProgress
+pub const COPY_EVENT: &str = "copy-progress";
+
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+// What renaming across volumes fails with: EXDEV, ERROR_NOT_SAME_DEVICE
+#[cfg(unix)]
+const CROSS_DEVICE: i32 = 18;
+#[cfg(windows)]
+const CROSS_DEVICE: i32 = 17;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CopyProgress {
+    pub source: String,
+    pub destination: String,
+    pub copied_bytes: u64,
+    pub total_bytes: u64,
+    pub done: bool,
+}
+
+// Copy `path` into the folder `destination`, under the same name; the new path
+pub fn copy(app: &tauri::AppHandle, path: &str, destination: &str) -> Result<String, String> {
+    let (source, target) = paths(path, destination)?;
+    copy_tree(app, &source, &target).map_err(|e| format!("Could not copy {}: {}", path, e))?;
+    Ok(target.to_string_lossy().into_owned())
+}
+
+// Move `path` into the folder `destination`, under the same name; the new path. Moving to
+// another volume copies, then removes the original.
+pub fn move_to(app: &tauri::AppHandle, path: &str, destination: &str) -> Result<String, String> {
+    let (source, target) = paths(path, destination)?;
+    match fs::rename(&source, &target) {
+        Ok(()) => {}
+        Err(e) if e.raw_os_error() == Some(CROSS_DEVICE) => {
+            copy_tree(app, &source, &target).map_err(|e| format!("Could not move {}: {}", path, e))?;
+            let removed = if source.is_dir() { fs::remove_dir_all(&source) } else { fs::remove_file(&source) };
+            removed.map_err(|e| format!("Copied {}, but could not remove it: {}", path, e))?;
+        }
+        Err(e) => return Err(format!("Could not move {}: {}", path, e)),
+    }
+    Ok(target.to_string_lossy().into_owned())
+}
+
+// Give `path` the name `new_name`, in the same folder; the new path
+pub fn rename(path: &str, new_name: &str) -> Result<String, String> {
+    let source = existing(path)?;
+    let new_name = new_name.trim();
+    if new_name.is_empty() || new_name == "." || new_name == ".." || new_name.contains(['/', '\\']) {
+        return Err(format!("{} is not a valid name", new_name));
+    }
+    let target = source.with_file_name(new_name);
+    // On a volume that ignores case, "Foo" → "foo" finds the item itself there
+    if target.exists() && !same_item(&source, &target) {
+        return Err(format!("{} already exists", target.display()));
+    }
+    fs::rename(&source, &target).map_err(|e| format!("Could not rename {}: {}", path, e))?;
+    Ok(target.to_string_lossy().into_owned())
+}
+
+// Send `path` to the recycle bin or trash, from where it can be put back
+pub fn trash(path: &str) -> Result<(), String> {
+    existing(path)?;
+    system::trash(path).map_err(|e| format!("Could not move {} to the trash: {}", path, e))
+}
+
+// Whether `a` and `b` lead to the same item
+fn same_item(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => false,
+    }
+}
+
+fn existing(path: &str) -> Result<PathBuf, String> {
+    let path = Path::new(path);
+    if !path.is_absolute() || prune::is_gone(path) {
+        return Err(format!("{} does not exist", path.display()));
+    }
+    Ok(path.to_path_buf())
+}
+
+// The item to copy or move and where it goes; an item already there isn't replaced
+fn paths(path: &str, destination: &str) -> Result<(PathBuf, PathBuf), String> {
+    let source = existing(path)?;
+    let destination = Path::new(destination);
+    if !destination.is_dir() {
+        return Err(format!("{} is not a folder", destination.display()));
+    }
+    let name = source.file_name().ok_or_else(|| format!("{} has no name", path))?;
+    let target = destination.join(name);
+    if target.exists() {
+        return Err(format!("{} already exists", target.display()));
+    }
+    if source.is_dir() && target.starts_with(&source) {
+        return Err(format!("{} cannot go inside itself", path));
+    }
+    Ok((source, target))
+}
+
+// Copy the file or folder `source` to `target`, links as links where the system allows
+fn copy_tree(app: &tauri::AppHandle, source: &Path, target: &Path) -> Result<(), String> {
+    let entries: Vec<walkdir::DirEntry> = WalkDir::new(source).into_iter().collect::<Result<_, _>>().map_err(|e| e.to_string())?;
+    let total_bytes = entries
+        .iter()
+        .filter(|entry| entry.file_type().is_file())
+        .filter_map(|entry| entry.metadata().ok())
+        .map(|meta| meta.len())
+        .sum();
+    let mut progress = Progress {
+        app,
+        status: CopyProgress {
+            source: source.to_string_lossy().into_owned(),
+            destination: target.to_string_lossy().into_owned(),
+            copied_bytes: 0,
+            total_bytes,
+            done: false,
+        },
+        last_emit: Instant::now(),
+    };
+
+    for entry in &entries {
+        // The root itself is `target`; joining its empty relative path would add a slash
+        let relative = entry.path().strip_prefix(source).map_err(|e| e.to_string())?;
+        let to = if relative.as_os_str().is_empty() { target.to_path_buf() } else { target.join(relative) };
+        let file_type = entry.file_type();
+        if file_type.is_dir() {
+            fs::create_dir_all(&to).map_err(|e| e.to_string())?;
+        } else if file_type.is_symlink() {
+            copy_link(entry.path(), &to)?;
+        } else {
+            copy_file(entry.path(), &to, &mut progress).map_err(|e| format!("{}: {}", entry.path().display(), e))?;
+        }
+    }
+    progress.status.done = true;
+    progress.emit();
+    Ok(())
+}
+
+// Copy one file in chunks, counting them toward the progress
+fn copy_file(from: &Path, to: &Path, progress: &mut Progress) -> Result<(), String> {
+    let mut reader = fs::File::open(from).map_err(|e| e.to_string())?;
+    let mut writer = fs::File::create(to).map_err(|e| e.to_string())?;
+    let mut buffer = vec![0u8; 1 << 20];
+    loop {
+        let read = reader.read(&mut buffer).map_err(|e| e.to_string())?;
+        if read == 0 {
+            break;
+        }
+        writer.write_all(&buffer[..read]).map_err(|e| e.to_string())?;
+        progress.add(read as u64);
+    }
+    // Keep what the file says about itself, as copying with the system would
+    let meta = reader.metadata().map_err(|e| e.to_string())?;
+    writer.set_permissions(meta.permissions()).map_err(|e| e.to_string())?;
+    if let Ok(modified) = meta.modified() {
+        let _ = writer.set_modified(modified);
+    }
+    Ok(())
+}
+
+#[cfg(unix)]
+fn copy_link(from: &Path, to: &Path) -> Result<(), String> {
+    let link = fs::read_link(from).map_err(|e| e.to_string())?;
+    std::os::unix::fs::symlink(link, to).map_err(|e| e.to_string())
+}
+
+// Making links needs a privilege on Windows; what they point at is copied instead
+#[cfg(windows)]
+fn copy_link(from: &Path, to: &Path) -> Result<(), String> {
+    fs::copy(from, to).map(|_| ()).map_err(|e| e.to_string())
+}
+
+struct Progress<'a> {
+    app: &'a tauri::AppHandle,
+    status: CopyProgress,
+    last_emit: Instant,
+}
+
+impl Progress<'_> {
+    fn add(&mut self, bytes: u64) {
+        self.status.copied_bytes += bytes;
+        if self.last_emit.elapsed() >= EMIT_INTERVAL {
+            self.emit();
+        }
+    }
+
+    fn emit(&mut self) {
+        self.last_emit = Instant::now();
+        let _ = self.app.emit(COPY_EVENT, &self.status);
+    }
+}
+
+#[cfg(windows)]
+mod system {
+    use windows_sys::Win32::UI::Shell::{
+        FO_DELETE, FOF_ALLOWUNDO, FOF_NOCONFIRMATION, FOF_NOERRORUI, FOF_SILENT, FOF_WANTNUKEWARNING,
+        SHFILEOPSTRUCTW, SHFileOperationW,
+    };
+
+    // FOF_ALLOWUNDO sends it to the recycle bin. Where there is none (a network share, an
+    // item too big for it) the shell would delete it for good; FOF_WANTNUKEWARNING has it
+    // ask first.
+    pub fn trash(path: &str) -> Result<(), String> {
+        // A list of paths, each ending with a null, and the list with another
+        let from: Vec<u16> = path.encode_utf16().chain([0, 0]).collect();
+        let mut operation = SHFILEOPSTRUCTW {
+            wFunc: FO_DELETE,
+            pFrom: from.as_ptr(),
+            fFlags: (FOF_ALLOWUNDO | FOF_NOCONFIRMATION | FOF_WANTNUKEWARNING | FOF_NOERRORUI | FOF_SILENT) as u16,
+            ..Default::default()
+        };
+        let result = unsafe { SHFileOperationW(&mut operation) };
+        if operation.fAnyOperationsAborted != 0 {
+            return Err("cancelled".to_string());
+        }
+        if result != 0 {
+            return Err(format!("error {:#x}", result));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::process::Command;
+
+    // Through Finder, so the item can be put back where it was
+    pub fn trash(path: &str) -> Result<(), String> {
+        let path = path.replace('\\', "\\\\").replace('"', "\\\"");
+        let script = format!("tell application \"Finder\" to delete POSIX file \"{}\"", path);
+        let output = Command::new("osascript").args(["-e", &script]).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::io::ErrorKind;
+    use std::process::Command;
+
+    // gio follows the trash specification, including the trash folders on other volumes;
+    // KDE's kioclient does where there is no gio
+    pub fn trash(path: &str) -> Result<(), String> {
+        let commands: [&[&str]; 2] = [&["gio", "trash", path], &["kioclient5", "move", path, "trash:/"]];
+        for command in commands {
+            match Command::new(command[0]).args(&command[1..]).output() {
+                Ok(output) if output.status.success() => return Ok(()),
+                Ok(output) => return Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Err("neither gio nor kioclient5 is installed".to_string())
+    }
+}
+
+// Other systems: no trash to use
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    pub fn trash(_path: &str) -> Result<(), String> {
+        Err("the trash isn't supported on this system".to_string())
+    }
+}
@@ -0,0 +1,50 @@
+// Frecency: how often and how recently something was opened, as one number. Every use adds
+// 1 to a score that halves every HALF_LIFE, so a file opened daily outranks one opened once
+// this morning, and both fade once they stop being used.
+//
+// The score is stored as ln(score) + now * DECAY, which doesn't change as time passes:
+// ordering rows by the stored value orders them by their score today, without decaying
+// every row on every search.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+// Time for an unused score to halve
+const HALF_LIFE_SECS: f64 = 30.0 * 24.0 * 60.0 * 60.0;
+// Decay rate per second
+const DECAY: f64 = std::f64::consts::LN_2 / HALF_LIFE_SECS;
+
+// Stored value of something never used; below any score of something used since 1970
+pub const NEVER_USED: f64 = 0.0;
+
+pub fn now() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or_default()
+}
+
+// Stored value after one more use at `now`
+pub fn bump(stored: f64, now: i64) -> f64 {
+    encode(score(stored, now) + 1.0, now)
+}
+
+// Stored value for a usage count and last use recorded before frecency existed, as if all
+// the uses had happened at the last one
+pub fn from_history(count: i64, last_used: i64) -> f64 {
+    if count <= 0 {
+        return NEVER_USED;
+    }
+    encode(count as f64, last_used)
+}
+
+// Decayed score at `now`: roughly the number of recent uses
+pub fn score(stored: f64, now: i64) -> f64 {
+    if stored == NEVER_USED {
+        return 0.0;
+    }
+    (stored - now as f64 * DECAY).exp()
+}
+
+fn encode(score: f64, at: i64) -> f64 {
+    score.ln() + at as f64 * DECAY
+}
@@ -0,0 +1,93 @@
+// Fuzzy matching of what the user typed against names. The query's characters have to
+// appear in the name in order (a subsequence); matches at the start of the name, at the
+// start of a word and in unbroken runs score higher, so "vsc" finds "Visual Studio Code".
+
+// Points for each matched character
+const MATCH: f64 = 1.0;
+// Extra points when a character starts a word ("S" in "Visual Studio", "C" in "myCode")
+const WORD_START_BONUS: f64 = 2.0;
+// Extra points when a character directly follows the previous match
+const CONSECUTIVE_BONUS: f64 = 1.5;
+// Extra points when the first character matches the very start of the name
+const PREFIX_BONUS: f64 = 3.0;
+// Points lost for every character skipped between (or before) matches
+const GAP_PENALTY: f64 = 0.05;
+
+// Score of `name` for `query` between 0 and 1, or None if the name doesn't contain the
+// query's characters in order. Case is ignored.
+pub fn score(query: &str, name: &str) -> Option<f64> {
+    let query: Vec<char> = query.chars().filter(|c| !c.is_whitespace()).flat_map(char::to_lowercase).collect();
+    if query.is_empty() {
+        return Some(0.0);
+    }
+    let original: Vec<char> = name.chars().collect();
+    let lower: Vec<char> = original.iter().map(|c| c.to_lowercase().next().unwrap_or(*c)).collect();
+    if lower.len() < query.len() {
+        return None;
+    }
+
+    // best[j]: best score of the query so far with its latest character matched at j
+    let mut best: Vec<Option<f64>> = vec![None; lower.len()];
+    for (i, &q) in query.iter().enumerate() {
+        let mut next = vec![None; lower.len()];
+        // Best earlier match to continue from, with the gap up to here already deducted
+        let mut running: Option<f64> = None;
+        for j in 0..lower.len() {
+            if lower[j] == q {
+                let own = MATCH
+                    + if is_word_start(&original, j) { WORD_START_BONUS } else { 0.0 }
+                    + if i == 0 && j == 0 { PREFIX_BONUS } else { 0.0 };
+                let previous = if i == 0 {
+                    Some(-(j as f64) * GAP_PENALTY) // Characters skipped before the first match
+                } else {
+                    let consecutive = j.checked_sub(1).and_then(|k| best[k]).map(|s| s + CONSECUTIVE_BONUS);
+                    max(consecutive, running)
+                };
+                next[j] = previous.map(|s| s + own);
+            }
+            // From j + 1 on, a match at j - 1 is one skipped character away
+            if let Some(k) = j.checked_sub(1) {
+                running = max(running, best[k]).map(|s| s - GAP_PENALTY);
+            }
+        }
+        best = next;
+    }
+
+    let raw = best.into_iter().flatten().fold(None, |acc: Option<f64>, s| max(acc, Some(s)))?;
+    let n = query.len() as f64;
+    let perfect = PREFIX_BONUS + n * (MATCH + WORD_START_BONUS) + (n - 1.0) * CONSECUTIVE_BONUS;
+    Some((raw / perfect).clamp(0.0, 1.0))
+}
+
+// Whether the character at `i` starts a word: the first character, one after a separator
+// or a lowercase-to-uppercase step ("myCode"), or the first digit of a number
+fn is_word_start(name: &[char], i: usize) -> bool {
+    let Some(&previous) = i.checked_sub(1).and_then(|p| name.get(p)) else {
+        return true;
+    };
+    let current = name[i];
+    !previous.is_alphanumeric()
+        || (previous.is_lowercase() && current.is_uppercase())
+        || (!previous.is_ascii_digit() && current.is_ascii_digit())
+}
+
+fn max(a: Option<f64>, b: Option<f64>) -> Option<f64> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a.max(b)),
+        (a, b) => a.or(b),
+    }
+}
+
+// SQL LIKE pattern that keeps only names containing the query's characters in order
+// ("vsc" -> "%v%s%c%"), so the database narrows the candidates before they are scored
+pub fn like_pattern(query: &str) -> String {
+    let mut pattern = String::from("%");
+    for c in query.chars().filter(|c| !c.is_whitespace()) {
+        if matches!(c, '%' | '_' | '\\') {
+            pattern.push('\\');
+        }
+        pattern.push(c);
+        pattern.push('%');
+    }
+    pattern
+}
@@ -0,0 +1,82 @@
+// History: every result opened, newest last, for showing recent items while the query is
+// empty. Only the latest HISTORY_LIMIT opens are kept; the user can clear them all.
+
+use std::path::Path;
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{prune, web};
+
+const HISTORY_LIMIT: i64 = 1_000;
+
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS history (
+            id INTEGER PRIMARY KEY,
+            path TEXT NOT NULL,
+            name TEXT NOT NULL,
+            kind TEXT NOT NULL,
+            opened_at INTEGER NOT NULL
+        );
+        CREATE INDEX IF NOT EXISTS history_path ON history (path);"
+    ).map_err(|e| e.to_string())
+}
+
+// Note that the result at `path` was opened at `now`, named and typed as the index has it
+pub fn record(conn: &Connection, path: &str, now: i64) -> Result<(), String> {
+    let indexed: Option<(String, String)> = conn.query_row(
+        "SELECT name, 'app' FROM applications WHERE path = ?1
+         UNION ALL
+         SELECT name, CASE WHEN is_app THEN 'app' WHEN is_file THEN 'file' ELSE 'folder' END
+         FROM files WHERE path = ?1
+         LIMIT 1",
+        params![path],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().map_err(|e| e.to_string())?;
+    let (name, kind) = indexed.unwrap_or_else(|| {
+        if web::is_web_url(path) {
+            return (path.to_string(), "web".to_string());
+        }
+        let name = Path::new(path).file_name().map_or_else(|| path.to_string(), |name| name.to_string_lossy().into_owned());
+        let kind = if Path::new(path).is_dir() { "folder" } else { "file" };
+        (name, kind.to_string())
+    });
+    conn.execute(
+        "INSERT INTO history (path, name, kind, opened_at) VALUES (?1, ?2, ?3, ?4)",
+        params![path, name, kind, now],
+    ).map_err(|e| e.to_string())?;
+    conn.execute(
+        "DELETE FROM history WHERE id <= (SELECT MAX(id) FROM history) - ?1",
+        params![HISTORY_LIMIT],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Up to `limit` results opened lately, as (path, name, type), the latest first and each
+// once. Ones no longer there are left out.
+pub fn recent(conn: &Connection, limit: usize) -> Result<Vec<(String, String, String)>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT path, name, kind FROM history
+         WHERE id IN (SELECT MAX(id) FROM history GROUP BY path)
+         ORDER BY id DESC"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+        .map_err(|e| e.to_string())?;
+    let mut recent = Vec::new();
+    for row in rows {
+        if recent.len() >= limit {
+            break;
+        }
+        let (path, name, kind): (String, String, String) = row.map_err(|e| e.to_string())?;
+        if !prune::is_gone(Path::new(&path)) {
+            recent.push((path, name, kind));
+        }
+    }
+    Ok(recent)
+}
+
+pub fn clear(conn: &Connection) -> Result<(), String> {
+    conn.execute("DELETE FROM history", []).map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+// The global shortcut that brings the launcher up from anywhere (Alt+Space unless the user
+// picked another) and puts it away again. It's saved under settings::HOTKEY; one another
+// application already holds can't be registered, and the previous one stays in place.
+
+use rusqlite::Connection;
+use tauri::{Emitter, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::settings;
+
+pub const DEFAULT: &str = "Alt+Space";
+
+// Sent to the frontend when the shortcut shows the window, to clear and focus the search
+pub const SHOWN_EVENT: &str = "launcher-shown";
+
+// The plugin, toggling the window whenever a registered shortcut is pressed
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event| {
+            if event.state() == ShortcutState::Pressed {
+                toggle(app);
+            }
+        })
+        .build()
+}
+
+// The saved shortcut, as an accelerator such as "Ctrl+Shift+K"
+pub fn saved(conn: &Connection) -> String {
+    settings::load::<Option<String>>(conn, settings::HOTKEY).unwrap_or_else(|| DEFAULT.to_string())
+}
+
+// Register the saved shortcut at startup
+pub fn register_saved(app: &tauri::AppHandle, conn: &Connection) -> Result<(), String> {
+    let accelerator = saved(conn);
+    let shortcut = parse(&accelerator)?;
+    app.global_shortcut()
+        .register(shortcut)
+        .map_err(|e| format!("Could not register {}: {}", accelerator, e))
+}
+
+// Replace the shortcut with `accelerator` and save it. If the system won't have it
+// (another application uses it), the current one is registered again and kept.
+pub fn set(app: &tauri::AppHandle, conn: &Connection, accelerator: &str) -> Result<String, String> {
+    let shortcut = parse(accelerator)?;
+    let current = saved(conn);
+    let current_shortcut = parse(&current).ok();
+    if current_shortcut == Some(shortcut) {
+        return Ok(current);
+    }
+
+    let shortcuts = app.global_shortcut();
+    if let Some(current) = current_shortcut {
+        let _ = shortcuts.unregister(current);
+    }
+    if let Err(e) = shortcuts.register(shortcut) {
+        if let Some(current) = current_shortcut {
+            let _ = shortcuts.register(current);
+        }
+        return Err(format!("{} is already in use by another application ({})", accelerator, e));
+    }
+    let accelerator = accelerator.trim().to_string();
+    settings::save(conn, settings::HOTKEY, &accelerator)?;
+    Ok(accelerator)
+}
+
+fn parse(accelerator: &str) -> Result<Shortcut, String> {
+    accelerator
+        .trim()
+        .parse::<Shortcut>()
+        .map_err(|e| format!("{} is not a valid shortcut: {}", accelerator, e))
+}
+
+// Hide the window if it's in front; otherwise bring it up, focused
+fn toggle(app: &tauri::AppHandle) {
+    let Some(window) = app.get_webview_window("main") else {
+        return;
+    };
+    if window.is_visible().unwrap_or(false) && window.is_focused().unwrap_or(false) {
+        let _ = window.hide();
+    } else {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = app.emit(SHOWN_EVENT, ());
+    }
+}
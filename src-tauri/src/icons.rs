@@ -0,0 +1,268 @@
+// Icons for the results list, as PNG files cached in the app data folder. They come from
+// the program or shortcut (Windows), the bundle's .icns file (macOS) or the icon theme
+// (Linux), are made the first time a result asks for one, and are reused after that;
+// `applications.icon_path` points at the cached file, `icon_source` at what it was made from.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{OptionalExtension, params};
+use tauri::Manager;
+
+use crate::AppState;
+
+// Edge length, in pixels, icons are made at
+const SIZE: u32 = 48;
+
+// The PNG icon for the result at `path`, made now if it isn't cached yet; None where there
+// is no icon to be had (a Store app, a URI, a desktop entry whose icon is an SVG)
+pub fn get(app: &tauri::AppHandle, path: &str) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
+    let known: Option<(Option<String>, Option<String>, Option<String>)> = {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        conn.query_row(
+            "SELECT icon_path, icon_source, target FROM applications WHERE path = ?1",
+            params![path],
+            |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+        ).optional().map_err(|e| e.to_string())?
+    };
+    let (cached, source, target) = known.unwrap_or_default();
+    if let Some(cached) = cached.filter(|cached| Path::new(cached).is_file()) {
+        return Ok(Some(cached));
+    }
+
+    let file = cache_dir(app)?.join(format!("{:016x}.png", fnv1a(path)));
+    // Extracting can take a moment (a program's resources, sips), so without the lock
+    if !file.is_file() && !system::extract(path, source.as_deref(), target.as_deref(), &file)? {
+        return Ok(None);
+    }
+    let icon = file.to_string_lossy().into_owned();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    conn.execute(
+        "UPDATE applications SET icon_path = ?2 WHERE path = ?1",
+        params![path, icon],
+    ).map_err(|e| e.to_string())?;
+    Ok(Some(icon))
+}
+
+fn cache_dir(app: &tauri::AppHandle) -> Result<PathBuf, String> {
+    let dir = app.path().app_data_dir().map_err(|e| e.to_string())?.join("icons");
+    std::fs::create_dir_all(&dir).map_err(|e| e.to_string())?;
+    Ok(dir)
+}
+
+// A hash of `text` that stays the same across runs and Rust versions, to name cache files by
+pub fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ byte as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(windows)]
+mod system {
+    use std::path::Path;
+
+    use windows_sys::Win32::Graphics::Gdi::{
+        BI_RGB, BITMAP, BITMAPINFO, BITMAPINFOHEADER, DIB_RGB_COLORS, DeleteObject, GetDC, GetDIBits,
+        GetObjectW, ReleaseDC,
+    };
+    use windows_sys::Win32::UI::Shell::{SHDefExtractIconW, SHFILEINFOW, SHGFI_ICON, SHGFI_LARGEICON, SHGetFileInfoW};
+    use windows_sys::Win32::UI::WindowsAndMessaging::{DestroyIcon, GetIconInfo, HICON, ICONINFO};
+
+    use super::SIZE;
+
+    // Write the icon of `path` to `out`: the shortcut's own icon location, else the icon of
+    // the program it starts or of the file itself, else the one Explorer shows for its type
+    pub fn extract(path: &str, source: Option<&str>, target: Option<&str>, out: &Path) -> Result<bool, String> {
+        let wide = |text: &str| text.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        let mut candidates: Vec<(&str, i32)> = Vec::new();
+        if let Some(source) = source {
+            // "file,index", or a file whose first icon is meant
+            match source.rsplit_once(',').and_then(|(file, index)| Some((file, index.trim().parse().ok()?))) {
+                Some((file, index)) => candidates.push((file, index)),
+                None => candidates.push((source, 0)),
+            }
+        }
+        candidates.extend(target.map(|target| (target, 0)));
+        candidates.push((path, 0));
+
+        let mut icon: HICON = std::ptr::null_mut();
+        for (file, index) in &candidates {
+            if !Path::new(file).is_file() {
+                continue;
+            }
+            // Large icon at SIZE in the low word; no small one
+            let result = unsafe { SHDefExtractIconW(wide(file).as_ptr(), *index, 0, &mut icon, std::ptr::null_mut(), SIZE) };
+            if result == 0 && !icon.is_null() {
+                break;
+            }
+            icon = std::ptr::null_mut();
+        }
+        if icon.is_null() {
+            let file = wide(target.unwrap_or(path));
+            let mut info = SHFILEINFOW::default();
+            let found = unsafe {
+                SHGetFileInfoW(file.as_ptr(), 0, &mut info, std::mem::size_of::<SHFILEINFOW>() as u32, SHGFI_ICON | SHGFI_LARGEICON)
+            };
+            if found == 0 || info.hIcon.is_null() {
+                return Ok(false);
+            }
+            icon = info.hIcon;
+        }
+
+        let pixels = unsafe { rgba(icon) };
+        unsafe { DestroyIcon(icon) };
+        let Some((width, height, pixels)) = pixels else {
+            return Ok(false);
+        };
+        write_png(out, width, height, &pixels)?;
+        Ok(true)
+    }
+
+    // The icon's colour bitmap as RGBA rows, top to bottom
+    unsafe fn rgba(icon: HICON) -> Option<(u32, u32, Vec<u8>)> {
+        let mut info = ICONINFO::default();
+        if GetIconInfo(icon, &mut info) == 0 {
+            return None;
+        }
+        let pixels = color_bits(&info);
+        DeleteObject(info.hbmColor);
+        DeleteObject(info.hbmMask);
+        pixels
+    }
+
+    unsafe fn color_bits(info: &ICONINFO) -> Option<(u32, u32, Vec<u8>)> {
+        // Monochrome icons have no colour bitmap; they're rare enough to go without
+        if info.hbmColor.is_null() {
+            return None;
+        }
+        let mut bitmap = BITMAP::default();
+        let size = std::mem::size_of::<BITMAP>() as i32;
+        if GetObjectW(info.hbmColor, size, (&mut bitmap as *mut BITMAP).cast()) == 0 {
+            return None;
+        }
+        let (width, height) = (bitmap.bmWidth, bitmap.bmHeight);
+        let mut header = BITMAPINFO {
+            bmiHeader: BITMAPINFOHEADER {
+                biSize: std::mem::size_of::<BITMAPINFOHEADER>() as u32,
+                biWidth: width,
+                // Negative: rows top to bottom
+                biHeight: -height,
+                biPlanes: 1,
+                biBitCount: 32,
+                biCompression: BI_RGB,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let mut pixels = vec![0u8; width as usize * height as usize * 4];
+        let dc = GetDC(std::ptr::null_mut());
+        let lines = GetDIBits(dc, info.hbmColor, 0, height as u32, pixels.as_mut_ptr().cast(), &mut header, DIB_RGB_COLORS);
+        ReleaseDC(std::ptr::null_mut(), dc);
+        if lines == 0 {
+            return None;
+        }
+
+        // BGRA to RGBA. Old icons leave alpha at 0 everywhere and keep their shape in the
+        // mask instead; those are shown opaque.
+        let has_alpha = pixels.chunks_exact(4).any(|pixel| pixel[3] != 0);
+        for pixel in pixels.chunks_exact_mut(4) {
+            pixel.swap(0, 2);
+            if !has_alpha {
+                pixel[3] = 255;
+            }
+        }
+        Some((width as u32, height as u32, pixels))
+    }
+
+    fn write_png(out: &Path, width: u32, height: u32, pixels: &[u8]) -> Result<(), String> {
+        let file = std::fs::File::create(out).map_err(|e| e.to_string())?;
+        let mut encoder = png::Encoder::new(std::io::BufWriter::new(file), width, height);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(|e| e.to_string())?;
+        writer.write_image_data(pixels).map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::SIZE;
+    use crate::bundle;
+
+    // Convert the bundle's .icns file with sips, which comes with macOS
+    pub fn extract(path: &str, source: Option<&str>, _target: Option<&str>, out: &Path) -> Result<bool, String> {
+        let icns = match source {
+            Some(source) => Some(source.to_string()),
+            None if path.ends_with(".app") => bundle::read(Path::new(path)).icon,
+            None => None,
+        };
+        let Some(icns) = icns.filter(|icns| Path::new(icns).is_file()) else {
+            return Ok(false);
+        };
+        let status = Command::new("sips")
+            .args(["-s", "format", "png", "-Z", &SIZE.to_string(), &icns, "--out"])
+            .arg(out)
+            .output()
+            .map_err(|e| e.to_string())?
+            .status;
+        Ok(status.success() && out.is_file())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::env;
+    use std::path::{Path, PathBuf};
+
+    use super::SIZE;
+
+    // Sizes looked for in the icon theme after SIZE, the closest first
+    const THEME_SIZES: [u32; 5] = [64, 32, 128, 256, 24];
+
+    // Copy the PNG the desktop entry's Icon key names: a file, or an icon in the hicolor
+    // theme (which every theme falls back on) or among the pixmaps
+    pub fn extract(_path: &str, source: Option<&str>, _target: Option<&str>, out: &Path) -> Result<bool, String> {
+        let Some(png) = source.and_then(find) else {
+            return Ok(false);
+        };
+        std::fs::copy(png, out).map_err(|e| e.to_string())?;
+        Ok(true)
+    }
+
+    fn find(icon: &str) -> Option<PathBuf> {
+        if Path::new(icon).is_absolute() {
+            return Some(PathBuf::from(icon)).filter(|file| is_png(file));
+        }
+        let data_home = env::var_os("XDG_DATA_HOME")
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".local/share")));
+        let data_dirs = env::var("XDG_DATA_DIRS").unwrap_or_else(|_| "/usr/local/share:/usr/share".to_string());
+        let data_dirs: Vec<PathBuf> = data_home.into_iter().chain(env::split_paths(&data_dirs)).collect();
+
+        let file_name = format!("{}.png", icon);
+        let themed = std::iter::once(SIZE).chain(THEME_SIZES).flat_map(|size| {
+            let size = format!("{0}x{0}", size);
+            data_dirs.iter().map(move |dir| dir.join("icons/hicolor").join(&size).join("apps"))
+        });
+        let pixmaps = data_dirs.iter().map(|dir| dir.join("pixmaps"));
+        themed.chain(pixmaps).map(|dir| dir.join(&file_name)).find(|file| is_png(file))
+    }
+
+    fn is_png(file: &Path) -> bool {
+        file.extension().is_some_and(|ext| ext == "png") && file.is_file()
+    }
+}
+
+// Other systems: no icons
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use std::path::Path;
+
+    pub fn extract(_path: &str, _source: Option<&str>, _target: Option<&str>, _out: &Path) -> Result<bool, String> {
+        Ok(false)
+    }
+}
@@ -0,0 +1,338 @@
+// Starting what a result points at (a program, a document, a folder, or a URI such as
+// ms-settings: or shell:AppsFolder\...) the way the system's own shell opens it, and
+// reporting when that fails rather than returning as if it had worked.
+
+use std::path::Path;
+
+use crate::prune;
+
+// Open `path` with whatever the system associates with it
+pub fn open(path: &str) -> Result<(), String> {
+    if !is_uri(path) && prune::is_gone(Path::new(path)) {
+        return Err(format!("{} does not exist", path));
+    }
+    system::open(path).map_err(|e| format!("Could not open {}: {}", path, e))
+}
+
+// Start `target` with `arguments`, a command line as typed, in `working_dir`, and as
+// administrator if `elevated` (the system asks for consent or a password)
+pub fn program(target: &str, arguments: Option<&str>, working_dir: Option<&str>, elevated: bool) -> Result<(), String> {
+    if !is_uri(target) && prune::is_gone(Path::new(target)) {
+        return Err(format!("{} does not exist", target));
+    }
+    let working_dir = working_dir.filter(|dir| Path::new(dir).is_dir());
+    system::program(target, arguments, working_dir, elevated)
+        .map_err(|e| format!("Could not start {}: {}", target, e))
+}
+
+// Show `path` in the file manager, in its folder and selected
+pub fn reveal(path: &str) -> Result<(), String> {
+    if is_uri(path) {
+        return Err(format!("{} is not in a folder", path));
+    }
+    if prune::is_gone(Path::new(path)) {
+        return Err(format!("{} does not exist", path));
+    }
+    system::reveal(path).map_err(|e| format!("Could not show {}: {}", path, e))
+}
+
+// Whether `path` starts with a URI scheme. Schemes are two characters or more, so a drive
+// letter ("C:") isn't taken for one.
+fn is_uri(path: &str) -> bool {
+    let Some((scheme, _)) = path.split_once(':') else {
+        return false;
+    };
+    scheme.len() > 1
+        && scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+        && scheme.chars().all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'))
+}
+
+// Whether `path` is a file that may be run
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    path.metadata().is_ok_and(|meta| meta.is_file() && meta.permissions().mode() & 0o111 != 0)
+}
+
+// Split a command line into arguments the way a POSIX shell would, without expanding
+// anything: whitespace separates, quotes group, and a backslash escapes what follows
+// (inside double quotes only ", \, $ and `)
+#[cfg(unix)]
+fn split_args(line: &str) -> Vec<String> {
+    let mut args = Vec::new();
+    let mut arg: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            ' ' | '\t' | '\n' => args.extend(arg.take()),
+            '\'' => {
+                let word = arg.get_or_insert_with(String::new);
+                word.extend(chars.by_ref().take_while(|&c| c != '\''));
+            }
+            '"' => {
+                let word = arg.get_or_insert_with(String::new);
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some(c @ ('"' | '\\' | '$' | '`')) => word.push(c),
+                            Some(c) => word.extend(['\\', c]),
+                            None => word.push('\\'),
+                        },
+                        c => word.push(c),
+                    }
+                }
+            }
+            '\\' => arg.get_or_insert_with(String::new).extend(chars.next()),
+            c => arg.get_or_insert_with(String::new).push(c),
+        }
+    }
+    args.extend(arg);
+    args
+}
+
+#[cfg(windows)]
+mod system {
+    use std::os::windows::process::CommandExt;
+    use std::process::Command;
+
+    use windows_sys::Win32::System::Com::{
+        COINIT_APARTMENTTHREADED, COINIT_DISABLE_OLE1DDE, CoInitializeEx, CoUninitialize,
+    };
+    use windows_sys::Win32::UI::Shell::{
+        SEE_MASK_FLAG_NO_UI, SEE_MASK_NOASYNC, SHELLEXECUTEINFOW, ShellExecuteExW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::SW_SHOWNORMAL;
+
+    use crate::uwp;
+
+    pub fn open(path: &str) -> Result<(), String> {
+        // Store apps start through the AppsFolder, by their AUMID
+        if path.starts_with(uwp::APPS_FOLDER) {
+            Command::new("explorer.exe").arg(path).spawn().map_err(|e| e.to_string())?;
+            return Ok(());
+        }
+        shell_execute(None, path, None, None)
+    }
+
+    // Explorer wants the path quoted after the comma, not the whole argument, so it's
+    // passed as written. It exits with 1 whether or not that worked; it isn't waited for.
+    pub fn reveal(path: &str) -> Result<(), String> {
+        Command::new("explorer.exe")
+            .raw_arg(format!("/select,\"{}\"", path))
+            .spawn()
+            .map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // The "runas" verb shows the UAC prompt; declining it is an error like any other
+    pub fn program(target: &str, arguments: Option<&str>, working_dir: Option<&str>, elevated: bool) -> Result<(), String> {
+        shell_execute(elevated.then_some("runas"), target, arguments, working_dir)
+    }
+
+    // ShellExecuteEx with the default action (or `verb`): programs run, documents open in
+    // their app, URIs go to their handler. Errors come back instead of as a message box.
+    fn shell_execute(verb: Option<&str>, file: &str, parameters: Option<&str>, directory: Option<&str>) -> Result<(), String> {
+        let wide = |text: &str| text.encode_utf16().chain(Some(0)).collect::<Vec<u16>>();
+        let (verb, file) = (verb.map(wide), wide(file));
+        let (parameters, directory) = (parameters.map(wide), directory.map(wide));
+        let pointer = |text: &Option<Vec<u16>>| text.as_ref().map_or(std::ptr::null(), |text| text.as_ptr());
+
+        let mut info = SHELLEXECUTEINFOW {
+            cbSize: std::mem::size_of::<SHELLEXECUTEINFOW>() as u32,
+            fMask: SEE_MASK_NOASYNC | SEE_MASK_FLAG_NO_UI,
+            lpVerb: pointer(&verb),
+            lpFile: file.as_ptr(),
+            lpParameters: pointer(&parameters),
+            lpDirectory: pointer(&directory),
+            nShow: SW_SHOWNORMAL,
+            ..Default::default()
+        };
+        // Shell extensions handling the file may need COM on this thread
+        let initialized = unsafe { CoInitializeEx(std::ptr::null(), (COINIT_APARTMENTTHREADED | COINIT_DISABLE_OLE1DDE) as u32) } >= 0;
+        let succeeded = unsafe { ShellExecuteExW(&mut info) } != 0;
+        let error = std::io::Error::last_os_error();
+        if initialized {
+            unsafe { CoUninitialize() };
+        }
+        if !succeeded {
+            return Err(error.to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::Path;
+    use std::process::Command;
+
+    use crate::bundle;
+
+    pub fn open(path: &str) -> Result<(), String> {
+        if path.ends_with(".app") {
+            return bundle::launch(Path::new(path));
+        }
+        let output = Command::new("open").arg(path).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    pub fn reveal(path: &str) -> Result<(), String> {
+        let output = Command::new("open").args(["-R", path]).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    pub fn program(target: &str, arguments: Option<&str>, working_dir: Option<&str>, elevated: bool) -> Result<(), String> {
+        if elevated {
+            return Err("running as administrator isn't supported on macOS".to_string());
+        }
+        let arguments = arguments.map(super::split_args).unwrap_or_default();
+        let mut command = if target.ends_with(".app") {
+            // Arguments reach the app only when this starts it
+            let mut command = Command::new("open");
+            match bundle::read(Path::new(target)).bundle_id {
+                Some(id) => command.args(["-b", &id]),
+                None => command.arg(target),
+            };
+            command.arg("--args").args(arguments);
+            command
+        } else if super::is_executable(Path::new(target)) {
+            let mut command = Command::new(target);
+            command.args(arguments);
+            command
+        } else if arguments.is_empty() {
+            return open(target);
+        } else {
+            return Err("not a program".to_string());
+        };
+        if let Some(dir) = working_dir {
+            command.current_dir(dir);
+        }
+        command.spawn().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::io::ErrorKind;
+    use std::path::Path;
+    use std::process::Command;
+
+    use crate::desktop;
+
+    pub fn open(path: &str) -> Result<(), String> {
+        // Desktop entries run their Exec line
+        if path.ends_with(".desktop") {
+            return program(path, None, None, false);
+        }
+        // gio hands the file to the desktop's launcher and says when nothing can open it;
+        // xdg-open, where there is no gio, may wait for the app it starts, so isn't waited for
+        match Command::new("gio").args(["open", path]).output() {
+            Ok(output) if output.status.success() => Ok(()),
+            Ok(output) => Err(String::from_utf8_lossy(&output.stderr).trim().to_string()),
+            Err(e) if e.kind() == ErrorKind::NotFound => {
+                Command::new("xdg-open").arg(path).spawn().map_err(|e| e.to_string())?;
+                Ok(())
+            }
+            Err(e) => Err(e.to_string()),
+        }
+    }
+
+    // The file manager's FileManager1 D-Bus interface selects the item (Nautilus, Dolphin,
+    // Nemo, Thunar...); without it, the folder is opened with nothing selected
+    pub fn reveal(path: &str) -> Result<(), String> {
+        let uris = format!("['file://{}']", encode_path(path));
+        let shown = Command::new("gdbus")
+            .args(["call", "--session", "--dest", "org.freedesktop.FileManager1"])
+            .args(["--object-path", "/org/freedesktop/FileManager1"])
+            .args(["--method", "org.freedesktop.FileManager1.ShowItems", &uris, ""])
+            .output()
+            .is_ok_and(|output| output.status.success());
+        if shown {
+            return Ok(());
+        }
+        let folder = Path::new(path).parent().ok_or_else(|| "no parent folder".to_string())?;
+        open(&folder.to_string_lossy())
+    }
+
+    // `path` percent-encoded for a file: URI; the slashes stay
+    fn encode_path(path: &str) -> String {
+        let mut encoded = String::with_capacity(path.len());
+        for byte in path.bytes() {
+            match byte {
+                b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => encoded.push(byte as char),
+                _ => encoded.push_str(&format!("%{:02X}", byte)),
+            }
+        }
+        encoded
+    }
+
+    pub fn program(target: &str, arguments: Option<&str>, working_dir: Option<&str>, elevated: bool) -> Result<(), String> {
+        let (mut args, mut working_dir) = (Vec::new(), working_dir.map(String::from));
+        if target.ends_with(".desktop") {
+            let (exec, dir) = desktop::command(Path::new(target), &[])?;
+            args = exec;
+            working_dir = working_dir.or(dir);
+        } else if super::is_executable(Path::new(target)) {
+            args.push(target.to_string());
+        } else if arguments.is_none() && !elevated {
+            return open(target);
+        } else {
+            return Err("not a program".to_string());
+        }
+        args.extend(arguments.map(super::split_args).unwrap_or_default());
+        if elevated {
+            args.splice(0..0, elevation()?);
+        }
+
+        let mut command = Command::new(&args[0]);
+        command.args(&args[1..]);
+        if let Some(dir) = working_dir.filter(|dir| Path::new(dir).is_dir()) {
+            command.current_dir(dir);
+        }
+        command.spawn().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // What to run a command as root through: pkexec, which asks in a dialog, keeping what a
+    // graphical program needs to reach the display; else sudo, asking in a terminal
+    fn elevation() -> Result<Vec<String>, String> {
+        if desktop::installed("pkexec") {
+            let mut prefix = vec!["pkexec".to_string(), "env".to_string()];
+            for name in ["DISPLAY", "XAUTHORITY", "WAYLAND_DISPLAY", "XDG_RUNTIME_DIR"] {
+                if let Ok(value) = std::env::var(name) {
+                    prefix.push(format!("{}={}", name, value));
+                }
+            }
+            Ok(prefix)
+        } else if desktop::installed("sudo") {
+            Ok(["x-terminal-emulator", "-e", "sudo"].map(String::from).to_vec())
+        } else {
+            Err("neither pkexec nor sudo is installed".to_string())
+        }
+    }
+}
+
+// Other systems: nothing to launch with
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    pub fn open(_path: &str) -> Result<(), String> {
+        Err("launching isn't supported on this system".to_string())
+    }
+
+    pub fn reveal(_path: &str) -> Result<(), String> {
+        Err("showing files isn't supported on this system".to_string())
+    }
+
+    pub fn program(_target: &str, _arguments: Option<&str>, _working_dir: Option<&str>, _elevated: bool) -> Result<(), String> {
+        Err("launching isn't supported on this system".to_string())
+    }
+}
@@ -1,18 +1,62 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
+mod autostart;
+mod bookmarks;
+#[cfg(target_os = "macos")]
+mod bundle;
+mod calc;
+mod clipboard;
+mod control;
+#[cfg(target_os = "linux")]
+mod desktop;
+mod favorites;
+mod file_actions;
+mod frecency;
+mod fuzzy;
+mod history;
+mod hotkey;
+mod icons;
+mod launch;
+mod name_index;
+mod open_with;
+mod pool;
+mod progress;
+mod providers;
+mod prune;
+mod query;
+mod runner;
+mod scheduler;
+mod searches;
+mod settings;
+#[cfg(target_os = "windows")]
+mod shortcut;
+mod switcher;
+mod symbols;
+mod tray;
+mod usn;
+#[cfg(target_os = "windows")]
+mod uwp;
+mod walk;
+mod watcher;
+mod web;
+
 use std::sync::Mutex;
 use tauri::Manager;
 use walkdir::WalkDir;
-use rusqlite::{Connection, params};
+use rusqlite::{Connection, OptionalExtension, params};
 use serde::{Serialize, Deserialize};
-use std::process::Command;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::ffi::OsStr;
-use std::env;
-// use windows_shortcuts::Shortcut;
+
+// Entries written per transaction while indexing; pausing and cancelling take effect
+// between batches
+const INDEX_BATCH: usize = 5_000;
 
 struct AppState {
+    // The one connection that writes
     db: Mutex<Connection>,
+    // Connections for searches, which don't wait for writes
+    readers: pool::ReadPool,
 }
 
 #[derive(Serialize, Deserialize)]
@@ -29,7 +73,11 @@ async fn initialize_database(app: tauri::AppHandle) -> Result<(), String> {
     std::fs::create_dir_all(&app_dir).map_err(|e| e.to_string())?;
     
     let db_path = app_dir.join("speedy_index.db");
-    let conn = Connection::open(db_path).map_err(|e| e.to_string())?;
+    let conn = Connection::open(&db_path).map_err(|e| e.to_string())?;
+    // WAL lets searches read while the indexer writes
+    conn.pragma_update_and_check(None, "journal_mode", "WAL", |row| row.get::<_, String>(0))
+        .map_err(|e| e.to_string())?;
+    conn.pragma_update(None, "synchronous", "NORMAL").map_err(|e| e.to_string())?;
     
     conn.execute_batch(
         "CREATE TABLE IF NOT EXISTS files (
@@ -38,8 +86,11 @@ async fn initialize_database(app: tauri::AppHandle) -> Result<(), String> {
             name TEXT,
             is_file BOOLEAN,
             is_app BOOLEAN,
+            size INTEGER,
+            modified INTEGER,
             last_accessed INTEGER,
-            access_count INTEGER DEFAULT 0
+            access_count INTEGER DEFAULT 0,
+            frecency REAL NOT NULL DEFAULT 0
         );
         CREATE TABLE IF NOT EXISTS search_cache (
             query TEXT PRIMARY KEY,
@@ -51,12 +102,115 @@ async fn initialize_database(app: tauri::AppHandle) -> Result<(), String> {
             path TEXT NOT NULL UNIQUE,
             name TEXT NOT NULL,
             icon_path TEXT,
+            icon_source TEXT,
+            target TEXT,
+            arguments TEXT,
+            working_dir TEXT,
+            bundle_id TEXT,
+            default_args TEXT,
             last_used TIMESTAMP,
-            times_used INTEGER DEFAULT 0
+            times_used INTEGER DEFAULT 0,
+            frecency REAL NOT NULL DEFAULT 0
         );"
     ).map_err(|e| e.to_string())?;
+    migrate_frecency(&conn)?;
+    name_index::create(&conn)?;
+    usn::create(&conn)?;
+    migrate_file_details(&conn)?;
+    migrate_application_columns(&conn)?;
+    settings::create(&conn)?;
+    scheduler::create(&conn)?;
+    favorites::create(&conn)?;
+    history::create(&conn)?;
+    bookmarks::create(&conn)?;
+    clipboard::create(&conn)?;
     
-    app.manage(AppState { db: Mutex::new(conn) });
+    app.manage(clipboard::Clips::new(&app_dir));
+    app.manage(AppState { db: Mutex::new(conn), readers: pool::ReadPool::new(db_path) });
+    Ok(())
+}
+
+// Add the size and modified columns to databases created before them. Catching up from
+// the change journal wouldn't fill them in for unchanged files, so the journal positions
+// are dropped and the next start walks every root again.
+fn migrate_file_details(conn: &Connection) -> Result<(), String> {
+    let has_columns: bool = conn.query_row(
+        "SELECT COUNT(*) > 0 FROM pragma_table_info('files') WHERE name = 'size'",
+        [],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())?;
+    if !has_columns {
+        conn.execute_batch(
+            "ALTER TABLE files ADD COLUMN size INTEGER;
+             ALTER TABLE files ADD COLUMN modified INTEGER;
+             DELETE FROM usn_state;"
+        ).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+// Add the columns describing how to launch an application (what a shortcut points at, a
+// bundle's identifier, the arguments the user saved for it) and where its icon comes
+// from to databases created before them; the indexed ones are filled in when
+// applications are next indexed
+fn migrate_application_columns(conn: &Connection) -> Result<(), String> {
+    for column in ["target", "arguments", "working_dir", "bundle_id", "default_args", "icon_source"] {
+        let has_column: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info('applications') WHERE name = ?1",
+            params![column],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+        if !has_column {
+            conn.execute_batch(&format!("ALTER TABLE applications ADD COLUMN {} TEXT", column))
+                .map_err(|e| e.to_string())?;
+            // icon_path held the icon's source until icons were cached; now it's the PNG
+            if column == "icon_source" {
+                conn.execute_batch("UPDATE applications SET icon_source = icon_path, icon_path = NULL")
+                    .map_err(|e| e.to_string())?;
+            }
+        }
+    }
+    Ok(())
+}
+
+// Add the frecency column to databases created before it existed, filled in from the usage
+// counts they already hold
+fn migrate_frecency(conn: &Connection) -> Result<(), String> {
+    let tables = [
+        ("files", "access_count", "last_accessed"),
+        ("applications", "times_used", "last_used"),
+    ];
+    for (table, count, last) in tables {
+        let has_column: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM pragma_table_info(?1) WHERE name = 'frecency'",
+            params![table],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+        if !has_column {
+            conn.execute_batch(&format!(
+                "ALTER TABLE {} ADD COLUMN frecency REAL NOT NULL DEFAULT 0", table
+            )).map_err(|e| e.to_string())?;
+
+            let mut stmt = conn.prepare(&format!(
+                "SELECT id, {}, CAST(COALESCE({}, 0) AS INTEGER) FROM {} WHERE {} > 0",
+                count, last, table, count
+            )).map_err(|e| e.to_string())?;
+            let used = stmt
+                .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get(1)?, row.get(2)?)))
+                .map_err(|e| e.to_string())?
+                .collect::<Result<Vec<_>, _>>()
+                .map_err(|e| e.to_string())?;
+            for (id, count, last_used) in used {
+                conn.execute(
+                    &format!("UPDATE {} SET frecency = ?1 WHERE id = ?2", table),
+                    params![frecency::from_history(count, last_used), id],
+                ).map_err(|e| e.to_string())?;
+            }
+        }
+        conn.execute_batch(&format!(
+            "CREATE INDEX IF NOT EXISTS {}_frecency ON {} (frecency DESC)", table, table
+        )).map_err(|e| e.to_string())?;
+    }
     Ok(())
 }
 
@@ -76,41 +230,340 @@ async fn toggle_window(visible: bool, app: tauri::AppHandle) -> Result<(), Strin
 
 #[tauri::command]
 async fn index_files(path: String, app: tauri::AppHandle) -> Result<usize, String> {
+    app.state::<control::Control>().start();
+    walk_files(Path::new(&path), &app)
+}
+
+// Add or update the rows of everything below `root`, a batch at a time. Returns how many
+// entries were written, or control::CANCELLED if the walk was cancelled (the entries
+// written until then stay).
+fn walk_files(root: &Path, app: &tauri::AppHandle) -> Result<usize, String> {
     let state = app.state::<AppState>();
-    let mut conn = state.db.lock().map_err(|e| e.to_string())?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let control = app.state::<control::Control>();
+    let (config, previous) = {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let config: settings::IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+        (config, watcher::count_below(&conn, root)?)
+    };
+    let excludes = config.excludes();
+    let mut run = progress::begin(app, progress::Phase::Files, Some(root), (previous > 0).then_some(previous));
 
     let mut count = 0;
+    let cancelled = std::thread::scope(|scope| -> Result<bool, String> {
+        // Walkers wait while the writer is a batch behind, pausing included
+        let (sender, found) = crossbeam_channel::bounded(INDEX_BATCH);
+        let excludes = &excludes;
+        scope.spawn(move || walk::walk(root, config.max_depth, excludes, sender));
 
-    for entry in WalkDir::new(path).max_depth(5).into_iter().filter_map(|e| e.ok()) {
-        let path = entry.path().to_string_lossy().into_owned();
-        let name = entry.file_name().to_string_lossy().into_owned();
-        let is_file = entry.file_type().is_file();
-        let is_app = is_file && entry.path().extension().map_or(false, |ext| ext == "exe");
+        loop {
+            // Gathered before taking the database, which is only held for the writes
+            let batch: Vec<walk::Found> = found.iter().take(INDEX_BATCH).collect();
+            let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+            let tx = conn.transaction().map_err(|e| e.to_string())?;
+            walk::insert(&tx, &batch)?;
+            tx.commit().map_err(|e| e.to_string())?;
+            drop(conn);
+            for entry in &batch {
+                run.advance(Path::new(&entry.path));
+            }
+            count += batch.len();
 
-        tx.execute(
-            "INSERT OR REPLACE INTO files (path, name, is_file, is_app, last_accessed)
-             VALUES (?1, ?2, ?3, ?4, strftime('%s','now'))",
-            params![path, name, is_file, is_app],
-        ).map_err(|e| e.to_string())?;
+            if batch.len() < INDEX_BATCH {
+                return Ok(false); // Walked everything
+            }
+            if !control.proceed(&mut run) {
+                return Ok(true); // Dropping the receiver stops the walkers
+            }
+        }
+    })?;
 
-        count += 1;
+    // Also after a cancel, so the names written so far can be searched
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    name_index::rebuild(&conn, "files")?;
+    if cancelled {
+        return Err(control::CANCELLED.to_string());
     }
+    Ok(count)
+}
+
+// Bring one indexed root up to date: from the NTFS change journal when it can tell what
+// changed since the last launch, by walking the root otherwise
+fn update_root(root: &Path, app: &tauri::AppHandle) -> Result<usize, String> {
+    {
+        let state = app.state::<AppState>();
+        let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+        if let Some((changed, position)) = usn::changes_since_last_index(&conn, root) {
+            let _run = progress::begin(app, progress::Phase::Journal, Some(root), None);
+            let config: settings::IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+            let applied = watcher::apply(&mut conn, &config, changed.into_iter().collect())?;
+            usn::remember(&conn, root, position)?;
+            scheduler::mark_indexed(&conn, root)?;
+            log::info!("{}: {} change(s) applied from the change journal", root.display(), applied);
+            return Ok(applied);
+        }
+    }
+    walk_root(root, app)
+}
 
-    tx.commit().map_err(|e| e.to_string())?;
+// Index everything below `root` by walking it
+fn walk_root(root: &Path, app: &tauri::AppHandle) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    // Taken before walking, so changes made during the walk are read next time. The old
+    // position is dropped first: if the walk doesn't finish, the next launch walks again.
+    let position = usn::position(root);
+    usn::forget(&state.db.lock().map_err(|e| e.to_string())?, root)?;
+    let count = walk_files(root, app)?;
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    if let Some(position) = position {
+        usn::remember(&conn, root, position)?;
+    }
+    scheduler::mark_indexed(&conn, root)?;
     Ok(count)
 }
 
+// Walk every root again from scratch, then the applications, and drop entries that are
+// gone; in the background
+fn rebuild_index(app: &tauri::AppHandle) {
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let config: settings::IndexConfig = match app.state::<AppState>().db.lock() {
+            Ok(conn) => settings::load(&conn, settings::INDEX_CONFIG),
+            Err(_) => return,
+        };
+        let control = app.state::<control::Control>();
+        control.start();
+        for root in &config.roots {
+            if control.is_cancelled() {
+                break;
+            }
+            if let Err(e) = walk_root(root, &app) {
+                log::warn!("Could not index {}: {}", root.display(), e);
+            }
+        }
+        if !control.is_cancelled() {
+            if let Err(e) = find_applications(&app) {
+                log::warn!("Could not index applications: {}", e);
+            }
+        }
+        if !control.is_cancelled() {
+            if let Err(e) = index_bookmarks(&app) {
+                log::warn!("Could not index bookmarks: {}", e);
+            }
+        }
+        if !control.is_cancelled() {
+            if let Err(e) = prune::prune(&app) {
+                log::warn!("Could not prune the index: {}", e);
+            }
+        }
+    });
+}
+
+// Where indexing is at; the same status `index-progress` events carry
 #[tauri::command]
-async fn index_applications(app: tauri::AppHandle) -> Result<usize, String> {
+fn index_status(app: tauri::AppHandle) -> progress::Status {
+    let mut status = progress::status(&app);
+    status.paused = app.state::<control::Control>().is_paused();
+    status
+}
+
+#[tauri::command]
+fn pause_indexing(app: tauri::AppHandle) {
+    app.state::<control::Control>().pause();
+}
+
+#[tauri::command]
+fn resume_indexing(app: tauri::AppHandle) {
+    app.state::<control::Control>().resume();
+}
+
+#[tauri::command]
+fn cancel_indexing(app: tauri::AppHandle) {
+    app.state::<control::Control>().cancel();
+}
+
+#[tauri::command]
+async fn get_index_config(app: tauri::AppHandle) -> Result<settings::IndexConfig, String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(settings::load(&conn, settings::INDEX_CONFIG))
+}
+
+// Save a new index configuration and bring the index in line with it: entries of roots no
+// longer indexed are removed, and roots are walked again in the background where the
+// change means they hold something else. Returns the configuration as saved.
+#[tauri::command]
+async fn set_index_config(config: settings::IndexConfig, app: tauri::AppHandle) -> Result<settings::IndexConfig, String> {
+    apply_index_config(config, &app)
+}
+
+fn apply_index_config(config: settings::IndexConfig, app: &tauri::AppHandle) -> Result<settings::IndexConfig, String> {
+    let config = config.validate()?;
+    let (previous, removed) = {
+        let state = app.state::<AppState>();
+        let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+        let previous: settings::IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+        let removed: Vec<PathBuf> = previous.roots.iter().filter(|root| !config.covers(root)).cloned().collect();
+
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        settings::save(&tx, settings::INDEX_CONFIG, &config)?;
+        for root in &removed {
+            watcher::remove(&tx, root)?;
+            usn::forget(&tx, root)?;
+        }
+        tx.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+        tx.commit().map_err(|e| e.to_string())?;
+        (previous, removed)
+    };
+    watcher::watch(app, &config.roots);
+
+    // A new depth or exclude list changes what every root holds; otherwise only new roots
+    // need walking, and roots inside a removed one, whose entries went with it
+    let same_walk = config.max_depth == previous.max_depth && config.excludes == previous.excludes;
+    let roots: Vec<PathBuf> = config.roots
+        .iter()
+        .filter(|root| {
+            !same_walk
+                || !previous.roots.contains(root)
+                || removed.iter().any(|gone| root.starts_with(gone))
+        })
+        .cloned()
+        .collect();
+    let app_handle = app.clone();
+    tauri::async_runtime::spawn(async move {
+        let control = app_handle.state::<control::Control>();
+        control.start();
+        for root in roots {
+            if control.is_cancelled() {
+                break;
+            }
+            if let Err(e) = walk_root(&root, &app_handle) {
+                log::warn!("Could not index {}: {}", root.display(), e);
+            }
+        }
+        // Entries now excluded or too deep are still in the index
+        if !same_walk && !control.is_cancelled() {
+            if let Err(e) = prune::prune(&app_handle) {
+                log::warn!("Could not prune the index: {}", e);
+            }
+        }
+    });
+    Ok(config)
+}
+
+// Remove the entries of files and folders that are gone, or that the index configuration
+// no longer covers; returns how many
+#[tauri::command]
+async fn prune_index(app: tauri::AppHandle) -> Result<usize, String> {
+    app.state::<control::Control>().start();
+    prune::prune(&app)
+}
+
+// All the settings: preferences, global shortcut and index configuration
+#[tauri::command]
+async fn get_settings(app: tauri::AppHandle) -> Result<settings::Settings, String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(settings::Settings {
+        preferences: settings::load(&conn, settings::PREFERENCES),
+        hotkey: hotkey::saved(&conn),
+        index: settings::load(&conn, settings::INDEX_CONFIG),
+    })
+}
+
+// Save the settings and put them into effect: the theme, the shortcut, the index
+// configuration (re-indexing where it changed). Nothing is saved unless the preferences
+// and index configuration are valid and the shortcut is free; returns the settings as saved.
+#[tauri::command]
+async fn update_settings(settings: settings::Settings, app: tauri::AppHandle) -> Result<settings::Settings, String> {
+    let preferences = settings.preferences.validate()?;
+    let new_index = settings.index.validate()?;
+    let (hotkey, index, previous) = {
+        let state = app.state::<AppState>();
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let hotkey = hotkey::set(&app, &conn, &settings.hotkey)?;
+        let previous: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
+        // Turning the clipboard history on needs its key; off, it forgets what it kept
+        if !previous.clipboard.enabled && preferences.clipboard.enabled {
+            app.state::<clipboard::Clips>().prepare()?;
+        }
+        settings::save(&conn, settings::PREFERENCES, &preferences)?;
+        if previous.clipboard.enabled && !preferences.clipboard.enabled {
+            clipboard::clear(&conn)?;
+        }
+        // Rankings were cut to the old limits
+        conn.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+        let index: settings::IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+        (hotkey, index, previous)
+    };
+    apply_theme(&app, preferences.theme);
+    // History was added to or dropped from what is indexed of the browsers
+    if preferences.index_browser_history != previous.index_browser_history {
+        let app = app.clone();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = index_bookmarks(&app) {
+                log::warn!("Could not index bookmarks: {}", e);
+            }
+        });
+    }
+    let index = if new_index == index { index } else { apply_index_config(new_index, &app)? };
+    Ok(settings::Settings { preferences, hotkey, index })
+}
+
+// Show the window in the theme chosen; with System, in the system's
+fn apply_theme(app: &tauri::AppHandle, theme: settings::Theme) {
+    let theme = match theme {
+        settings::Theme::System => None,
+        settings::Theme::Light => Some(tauri::Theme::Light),
+        settings::Theme::Dark => Some(tauri::Theme::Dark),
+    };
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.set_theme(theme);
+    }
+}
+
+#[tauri::command]
+async fn get_schedule_config(app: tauri::AppHandle) -> Result<settings::ScheduleConfig, String> {
     let state = app.state::<AppState>();
-    let mut conn = state.db.lock().map_err(|e| e.to_string())?;
-    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(settings::load(&conn, settings::SCHEDULE))
+}
 
+// Save when roots are re-indexed in the background; the scheduler reads it on its next check
+#[tauri::command]
+async fn set_schedule_config(config: settings::ScheduleConfig, app: tauri::AppHandle) -> Result<settings::ScheduleConfig, String> {
+    let config = config.validate()?;
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    settings::save(&conn, settings::SCHEDULE, &config)?;
+    Ok(config)
+}
+
+// Whether a file is a program, listed as an app in results
+fn is_app_file(path: &std::path::Path) -> bool {
+    path.extension().map_or(false, |ext| ext == "exe")
+}
+
+#[tauri::command]
+async fn index_applications(app: tauri::AppHandle) -> Result<usize, String> {
+    app.state::<control::Control>().start();
+    find_applications(&app)
+}
+
+// Add or update the rows of known programs and of the programs in the standard install
+// locations, committing after each location. Returns how many were written, or
+// control::CANCELLED if indexing was cancelled in between.
+fn find_applications(app: &tauri::AppHandle) -> Result<usize, String> {
+    let state = app.state::<AppState>();
     let mut count = 0;
 
     #[cfg(target_os = "windows")]
     {
+        let control = app.state::<control::Control>();
+        let previous: usize = state.db.lock().map_err(|e| e.to_string())?
+            .query_row("SELECT COUNT(*) FROM applications", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        let mut run = progress::begin(app, progress::Phase::Applications, None, (previous > 0).then_some(previous));
+
         // Standard Windows application locations
         let app_paths = vec![
             PathBuf::from(r"C:\ProgramData\Microsoft\Windows\Start Menu\Programs"),
@@ -146,236 +599,636 @@ async fn index_applications(app: tauri::AppHandle) -> Result<usize, String> {
         ];
 
         // Add system apps to database
+        let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
         for (_, display_name, path) in system_apps {
             tx.execute(
-                "INSERT OR REPLACE INTO applications 
+                "INSERT INTO applications 
                 (path, name, last_used, times_used) 
-                VALUES (?1, ?2, strftime('%s','now'), 0)",
+                VALUES (?1, ?2, strftime('%s','now'), 0)
+                ON CONFLICT(path) DO UPDATE SET name = excluded.name",
                 params![path, display_name],
             ).map_err(|e| e.to_string())?;
             count += 1;
+            run.advance(Path::new(path));
         }
+        tx.commit().map_err(|e| e.to_string())?;
+        drop(conn);
 
-        // Index applications from standard locations
+        // Index applications from standard locations, one location per batch
         for base_path in app_paths {
+            if !control.proceed(&mut run) {
+                name_index::rebuild(&state.db.lock().map_err(|e| e.to_string())?, "applications")?;
+                return Err(control::CANCELLED.to_string());
+            }
+            let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+            let tx = conn.transaction().map_err(|e| e.to_string())?;
             for entry in WalkDir::new(base_path).max_depth(5).into_iter().filter_map(|e| e.ok()) {
                 let path = entry.path();
                 if path.is_file() {
                     if let Some(ext) = path.extension().and_then(OsStr::to_str) {
-                        if ext == "exe" { // Just look for .exe files for now
-                            let name = path.file_stem()
-                                .and_then(OsStr::to_str)
-                                .unwrap_or_default()
-                                .to_string();
+                        let name = path.file_stem()
+                            .and_then(OsStr::to_str)
+                            .unwrap_or_default()
+                            .to_string();
 
+                        if ext.eq_ignore_ascii_case("exe") {
                             tx.execute(
-                                "INSERT OR REPLACE INTO applications 
+                                "INSERT INTO applications 
                                 (path, name, last_used, times_used) 
-                                VALUES (?1, ?2, strftime('%s','now'), 0)",
+                                VALUES (?1, ?2, strftime('%s','now'), 0)
+                                ON CONFLICT(path) DO UPDATE SET name = excluded.name",
                                 params![path.to_string_lossy().into_owned(), name],
                             ).map_err(|e| e.to_string())?;
-                            
-                            count += 1;
+                        } else if ext.eq_ignore_ascii_case("lnk") {
+                            // Shortcuts are stored with what they point at, which is launched
+                            let link = match shortcut::read(path) {
+                                Ok(link) => link,
+                                Err(e) => {
+                                    log::debug!("Skipping shortcut: {}", e);
+                                    continue;
+                                }
+                            };
+                            tx.execute(
+                                "INSERT INTO applications
+                                (path, name, target, arguments, working_dir, icon_source, last_used, times_used)
+                                VALUES (?1, ?2, ?3, ?4, ?5, ?6, strftime('%s','now'), 0)
+                                ON CONFLICT(path) DO UPDATE SET
+                                    name = excluded.name, target = excluded.target,
+                                    arguments = excluded.arguments, working_dir = excluded.working_dir,
+                                    icon_source = excluded.icon_source",
+                                params![
+                                    path.to_string_lossy().into_owned(), name,
+                                    link.target, link.arguments, link.working_dir, link.icon
+                                ],
+                            ).map_err(|e| e.to_string())?;
+                        } else {
+                            continue;
                         }
+
+                        count += 1;
+                        run.advance(path);
                     }
                 }
             }
+            tx.commit().map_err(|e| e.to_string())?;
+        }
+
+        // Store apps, which replace the ones found before: an uninstalled one has no file
+        // that pruning could find gone
+        if !control.proceed(&mut run) {
+            name_index::rebuild(&state.db.lock().map_err(|e| e.to_string())?, "applications")?;
+            return Err(control::CANCELLED.to_string());
+        }
+        match uwp::installed() {
+            Ok(packaged) => {
+                let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+                let tx = conn.transaction().map_err(|e| e.to_string())?;
+                let installed: std::collections::HashSet<&str> = packaged.iter().map(|(_, path)| path.as_str()).collect();
+                let mut stmt = tx.prepare("SELECT path FROM applications WHERE substr(path, 1, ?1) = ?2")
+                    .map_err(|e| e.to_string())?;
+                let known = stmt
+                    .query_map(params![uwp::APPS_FOLDER.len(), uwp::APPS_FOLDER], |row| row.get::<_, String>(0))
+                    .map_err(|e| e.to_string())?
+                    .collect::<Result<Vec<_>, _>>()
+                    .map_err(|e| e.to_string())?;
+                drop(stmt);
+                for path in known.iter().filter(|path| !installed.contains(path.as_str())) {
+                    tx.execute("DELETE FROM applications WHERE path = ?1", params![path])
+                        .map_err(|e| e.to_string())?;
+                }
+                for (name, path) in &packaged {
+                    tx.execute(
+                        "INSERT INTO applications 
+                        (path, name, last_used, times_used) 
+                        VALUES (?1, ?2, strftime('%s','now'), 0)
+                        ON CONFLICT(path) DO UPDATE SET name = excluded.name",
+                        params![path, name],
+                    ).map_err(|e| e.to_string())?;
+                    count += 1;
+                    run.advance(Path::new(path));
+                }
+                tx.commit().map_err(|e| e.to_string())?;
+            }
+            Err(e) => log::warn!("Could not list Store apps: {}", e),
         }
     }
 
-    tx.commit().map_err(|e| e.to_string())?;
+    #[cfg(target_os = "macos")]
+    {
+        let mut run = progress::begin(app, progress::Phase::Applications, None, None);
+        let bundles = bundle::find();
+
+        let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        for bundle in &bundles {
+            tx.execute(
+                "INSERT INTO applications
+                (path, name, bundle_id, icon_source, last_used, times_used)
+                VALUES (?1, ?2, ?3, ?4, strftime('%s','now'), 0)
+                ON CONFLICT(path) DO UPDATE SET
+                    name = excluded.name, bundle_id = excluded.bundle_id, icon_source = excluded.icon_source",
+                params![bundle.path.to_string_lossy().into_owned(), bundle.name, bundle.bundle_id, bundle.icon],
+            ).map_err(|e| e.to_string())?;
+            count += 1;
+            run.advance(&bundle.path);
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+
+    #[cfg(target_os = "linux")]
+    {
+        let mut run = progress::begin(app, progress::Phase::Applications, None, None);
+        let entries = desktop::find();
+
+        // The entries found replace the ones from before, which may since be hidden
+        let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+        let tx = conn.transaction().map_err(|e| e.to_string())?;
+        let found: std::collections::HashSet<String> = entries.iter()
+            .map(|entry| entry.path.to_string_lossy().into_owned())
+            .collect();
+        let mut stmt = tx.prepare("SELECT path FROM applications WHERE path LIKE '%.desktop'")
+            .map_err(|e| e.to_string())?;
+        let known = stmt
+            .query_map([], |row| row.get::<_, String>(0))
+            .map_err(|e| e.to_string())?
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.to_string())?;
+        drop(stmt);
+        for path in known.iter().filter(|path| !found.contains(*path)) {
+            tx.execute("DELETE FROM applications WHERE path = ?1", params![path])
+                .map_err(|e| e.to_string())?;
+        }
+        for entry in &entries {
+            tx.execute(
+                "INSERT INTO applications
+                (path, name, icon_source, working_dir, last_used, times_used)
+                VALUES (?1, ?2, ?3, ?4, strftime('%s','now'), 0)
+                ON CONFLICT(path) DO UPDATE SET
+                    name = excluded.name, icon_source = excluded.icon_source, working_dir = excluded.working_dir",
+                params![entry.path.to_string_lossy().into_owned(), entry.name, entry.icon, entry.working_dir],
+            ).map_err(|e| e.to_string())?;
+            count += 1;
+            run.advance(&entry.path);
+        }
+        tx.commit().map_err(|e| e.to_string())?;
+    }
+
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    name_index::rebuild(&conn, "applications")?;
+    Ok(count)
+}
+
+// Copy the browsers' bookmarks (and history, if the preferences say so) into the index.
+// Returns how many pages it holds.
+fn index_bookmarks(app: &tauri::AppHandle) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    let include_history = {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        settings::load::<settings::Preferences>(&conn, settings::PREFERENCES).index_browser_history
+    };
+    let pages = bookmarks::read(include_history);
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let count = bookmarks::store(&conn, &pages)?;
+    conn.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
     Ok(count)
 }
 
+// First page of results for `query`
 #[tauri::command]
 async fn search(query: String, app: tauri::AppHandle) -> Result<Vec<SearchResult>, String> {
+    search_page(&query, 0, &app)
+}
+
+// The page of results for `query` starting at `offset`; empty once there are no more
+#[tauri::command]
+async fn search_more(query: String, offset: usize, app: tauri::AppHandle) -> Result<Vec<SearchResult>, String> {
+    search_page(&query, offset, &app)
+}
+
+// A page of results (preferences.result_limit of them) for `query` from `offset` on. The
+// whole ranking (up to preferences.ranked_limit) is cached, so further pages are a lookup,
+// not another search.
+fn search_page(query: &str, offset: usize, app: &tauri::AppHandle) -> Result<Vec<SearchResult>, String> {
+    let token = app.state::<searches::Searches>().begin();
 
     let state = app.state::<AppState>();
-    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let conn = state.readers.get()?;
+    let preferences: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
 
     // Try to retrieve from cache first
     if let Ok(cached) = conn.query_row(
         "SELECT results FROM search_cache 
          WHERE query = ?1 
-         AND timestamp > strftime('%s','now','-5 minutes')",
-        params![query],
+         AND timestamp > strftime('%s','now') - ?2",
+        params![query, preferences.cache_ttl_secs as i64],
         |row| {
             let results: String = row.get(0)?;
             Ok(serde_json::from_str::<Vec<SearchResult>>(&results).unwrap_or_default())
         },
     ) {
         if !cached.is_empty() {
-            return Ok(cached);
+            return Ok(cached.into_iter().skip(offset).take(preferences.result_limit).collect());
         }
     }
 
-    // Search files from database
-    let mut stmt = conn.prepare(
-        "SELECT path, name, is_file, is_app 
-         FROM files 
-         WHERE name LIKE ?1 
-         ORDER BY last_accessed DESC, access_count DESC
-         LIMIT 20"
-    ).map_err(|e| e.to_string())?;
+    let ranking = providers::search(query, app, &token, &preferences)?;
+    let results = ranking.results;
 
-    let mut results = stmt
-        .query_map(params![format!("%{}%", query)], |row| {
-            Ok(SearchResult {
-                path: row.get(0)?,
-                name: row.get(1)?,
-                r#type: if row.get(3)? { "app".into() } 
-                       else if row.get(2)? { "file".into() } 
-                       else { "folder".into() },
-                score: None,
-            })
-        })
-        .map_err(|e| e.to_string())?
-        .collect::<Result<Vec<_>, _>>()
-        .map_err(|e| e.to_string())?;
+    // Cache the results, unless the indexer is writing: searching doesn't wait for it.
+    // Rankings with results that mustn't be reused (open windows, clips) aren't kept.
+    if !results.is_empty() && ranking.cacheable {
+        if let Ok(writer) = state.db.try_lock() {
+            writer.execute(
+                "INSERT OR REPLACE INTO search_cache (query, results, timestamp)
+                 VALUES (?1, ?2, strftime('%s','now'))",
+                params![query, serde_json::to_string(&results).map_err(|e| e.to_string())?],
+            ).map_err(|e| e.to_string())?;
+        }
+    }
 
-    // Search applications
-    let app_results = search_apps(&query)?;
-    results.extend(app_results);
+    // Still correct for this query, so cached above, but the user has typed on
+    token.check()?;
+    Ok(results.into_iter().skip(offset).take(preferences.result_limit).collect())
+}
 
-    // Sort all results by score (if available) or by type
-    results.sort_by(|a, b| {
-        b.score.partial_cmp(&a.score)
-            .unwrap_or_else(|| a.r#type.cmp(&b.r#type))
-    });
+#[tauri::command]
+async fn open_path(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    open_result(&path, None, false, &app)
+}
 
-    // Cache the results
-    if !results.is_empty() {
-        conn.execute(
-            "INSERT OR REPLACE INTO search_cache (query, results, timestamp)
-             VALUES (?1, ?2, strftime('%s','now'))",
-            params![query, serde_json::to_string(&results).map_err(|e| e.to_string())?],
-        ).map_err(|e| e.to_string())?;
-    }
+// Start the application at `path` with `args` (None: its saved default arguments), as
+// administrator if `elevated`
+#[tauri::command]
+async fn launch_app_with_args(path: String, args: Option<String>, elevated: bool, app: tauri::AppHandle) -> Result<(), String> {
+    open_result(&path, args, elevated, &app)
+}
 
-    Ok(results)
+// A PNG file with the icon of the result at `path`, for the results list; None if it has none
+#[tauri::command]
+async fn get_icon(path: String, app: tauri::AppHandle) -> Result<Option<String>, String> {
+    icons::get(&app, &path)
 }
 
-fn search_apps(query: &str) -> Result<Vec<SearchResult>, String> {
-    let mut results = Vec::new();
-    
-    #[cfg(target_os = "windows")]
-    {
-        let start_menu_paths = vec![
-            PathBuf::from(r"C:\ProgramData\Microsoft\Windows\Start Menu\Programs"),
-            PathBuf::from(r"C:\Users\All Users\Microsoft\Windows\Start Menu\Programs"),
-        ];
-        
-        for path in start_menu_paths {
-            if let Ok(entries) = std::fs::read_dir(&path) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    if let Ok(file_type) = entry.file_type() {
-                        if file_type.is_file() {
-                            if let Some(ext) = entry.path().extension().and_then(OsStr::to_str) {
-                                if ext == "lnk" {
-                                    if let Some(name) = entry.file_name().to_str() {
-                                        if name.to_lowercase().contains(&query.to_lowercase()) {
-                                            results.push(SearchResult {
-                                                path: entry.path().to_string_lossy().into_owned(),
-                                                name: name.to_string(),
-                                                r#type: "app".to_string(),
-                                                score: Some(1.0),
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+// The arguments saved for the application at `path`
+#[tauri::command]
+async fn get_default_args(path: String, app: tauri::AppHandle) -> Result<Option<String>, String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let args: Option<Option<String>> = conn.query_row(
+        "SELECT default_args FROM applications WHERE path = ?1",
+        params![path],
+        |row| row.get(0),
+    ).optional().map_err(|e| e.to_string())?;
+    Ok(args.flatten())
+}
+
+// Save the arguments the application at `path` starts with; None or blank clears them
+#[tauri::command]
+async fn set_default_args(path: String, args: Option<String>, app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let args = args.map(|args| args.trim().to_string()).filter(|args| !args.is_empty());
+    let updated = conn.execute(
+        "UPDATE applications SET default_args = ?2 WHERE path = ?1",
+        params![path, args],
+    ).map_err(|e| e.to_string())?;
+    if updated == 0 {
+        return Err(format!("{} is not an indexed application", path));
+    }
+    Ok(())
+}
+
+// Open a result and count the use for ranking. Applications start with `args`, or else
+// the arguments saved for them; a shortcut's own arguments come first.
+fn open_result(path: &str, args: Option<String>, elevated: bool, app: &tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+
+    // An open window is brought to the front, not started again
+    if path.starts_with(switcher::SCHEME) {
+        return switcher::activate(path);
+    }
+
+    // A web address isn't indexed; it opens in the default browser
+    if web::is_web_url(path) {
+        let preferences: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
+        if preferences.launch.record_history {
+            history::record(&conn, path, frecency::now())?;
         }
+        drop(conn);
+        return launch::open(path);
     }
-    
-    #[cfg(target_os = "macos")]
-    {
-        let app_dirs = vec![
-            PathBuf::from("/Applications"),
-            PathBuf::from("/System/Applications"),
-            PathBuf::from(format!("{}/Applications", env::var("HOME").unwrap())),
-        ];
-        
-        for dir in app_dirs {
-            if let Ok(entries) = std::fs::read_dir(dir) {
-                for entry in entries.filter_map(|e| e.ok()) {
-                    if let Ok(file_type) = entry.file_type() {
-                        if file_type.is_dir() {
-                            if let Some(ext) = entry.path().extension().and_then(OsStr::to_str) {
-                                if ext == "app" {
-                                    if let Some(name) = entry.file_name().to_str() {
-                                        if name.to_lowercase().contains(&query.to_lowercase()) {
-                                            results.push(SearchResult {
-                                                path: entry.path().to_string_lossy().into_owned(),
-                                                name: name.to_string(),
-                                                r#type: "app".to_string(),
-                                                score: Some(1.0),
-                                            });
-                                        }
-                                    }
-                                }
-                            }
-                        }
-                    }
-                }
-            }
+
+    // A result whose target is gone is stale: drop it from the index so it stops showing up
+    if prune::is_gone(Path::new(path)) {
+        prune::forget(&conn, Path::new(path))?;
+        return Err(format!("{} no longer exists and was removed from the index", path));
+    }
+
+    // Count the use in whichever table knows the path, and forget cached rankings
+    let now = frecency::now();
+    for (table, count, last) in [("files", "access_count", "last_accessed"), ("applications", "times_used", "last_used")] {
+        let stored: Option<f64> = conn.query_row(
+            &format!("SELECT frecency FROM {} WHERE path = ?1", table),
+            params![path],
+            |row| row.get(0),
+        ).optional().map_err(|e| e.to_string())?;
+        if let Some(stored) = stored {
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET {} = {} + 1, {} = ?2, frecency = ?3 WHERE path = ?1",
+                    table, count, count, last
+                ),
+                params![path, now, frecency::bump(stored, now)],
+            ).map_err(|e| e.to_string())?;
         }
     }
-    
-    Ok(results)
+    conn.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+    let preferences: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
+    if preferences.launch.record_history {
+        history::record(&conn, path, now)?;
+    }
+
+    // A shortcut is launched as what it points at, with its arguments and working folder
+    let details: Option<(Option<String>, Option<String>, Option<String>, Option<String>)> = conn.query_row(
+        "SELECT target, arguments, working_dir, default_args FROM applications WHERE path = ?1",
+        params![path],
+        |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?)),
+    ).optional().map_err(|e| e.to_string())?;
+    // Not held while the system asks for consent to elevate
+    drop(conn);
+
+    let (target, link_args, working_dir, default_args) = details.unwrap_or_default();
+    let args = args.or(default_args);
+    let arguments = match (link_args, args) {
+        (Some(link_args), Some(args)) => Some(format!("{} {}", link_args, args)),
+        (link_args, args) => link_args.or(args),
+    };
+    match target {
+        None if arguments.is_none() && !elevated => launch::open(path),
+        target => launch::program(target.as_deref().unwrap_or(path), arguments.as_deref(), working_dir.as_deref(), elevated),
+    }
 }
 
 #[tauri::command]
-async fn open_path(path: String, app: tauri::AppHandle) -> Result<(), String> {
+fn launch_app(path: String) -> Result<(), String> {
+    launch::open(&path)
+}
+
+// The applications that can open the file at `path`, for an Open With menu
+#[tauri::command]
+async fn get_open_with_candidates(path: String) -> Result<Vec<open_with::Candidate>, String> {
+    Ok(open_with::candidates(&path))
+}
+
+// Open the file at `path` with the application at `app_path`, one of its candidates
+#[tauri::command]
+async fn open_with(path: String, app_path: String, app: tauri::AppHandle) -> Result<(), String> {
+    open_with::open(&path, &app_path)?;
     let state = app.state::<AppState>();
     let conn = state.db.lock().map_err(|e| e.to_string())?;
+    let preferences: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
+    if preferences.launch.record_history {
+        history::record(&conn, &path, frecency::now())?;
+    }
+    Ok(())
+}
 
-    conn.execute(
-        "UPDATE files 
-         SET access_count = access_count + 1, 
-             last_accessed = strftime('%s','now') 
-         WHERE path = ?1",
-        params![path],
-    ).map_err(|e| e.to_string())?;
+// Up to `limit` results opened lately, the latest first, for when the query is empty
+#[tauri::command]
+async fn get_recent(limit: usize, app: tauri::AppHandle) -> Result<Vec<SearchResult>, String> {
+    let state = app.state::<AppState>();
+    let conn = state.readers.get()?;
+    let recent = history::recent(&conn, limit)?;
+    Ok(recent
+        .into_iter()
+        .map(|(path, name, kind)| SearchResult { path, name, r#type: kind, score: None })
+        .collect())
+}
 
-    launch_app(path)?;
-    Ok(())
+// Forget which results were opened. Frecency, which ranks them, stays.
+#[tauri::command]
+async fn clear_history(app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    history::clear(&conn)
 }
 
+// Paste the clip `id` from the clipboard history into the window the launcher was called
+// up from
 #[tauri::command]
-fn launch_app(path: String) -> Result<(), String> {
-    #[cfg(target_os = "windows")]
-    {
-        // Simple launch command that works for both .exe and system commands
-        Command::new("cmd")
-            .args(&["/C", "start", "", &path])
-            .spawn()
-            .map_err(|e| e.to_string())?;
+async fn paste_clip(id: i64, app: tauri::AppHandle) -> Result<(), String> {
+    let text = {
+        let conn = app.state::<AppState>().readers.get()?;
+        app.state::<clipboard::Clips>().text(&conn, id)?
+    };
+    clipboard::paste(&app, &text)
+}
+
+#[tauri::command]
+async fn clear_clips(app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    clipboard::clear(&conn)
+}
+
+// Put `text` (a calculator answer, a character) on the clipboard
+#[tauri::command]
+async fn copy_to_clipboard(text: String, app: tauri::AppHandle) -> Result<(), String> {
+    clipboard::copy(&app, &text)
+}
+
+// Run a command typed after `>` with the configured shell: in a terminal window, or with
+// `capture` (or when the shell is set to run without one) hidden, returning its output
+#[tauri::command]
+async fn run_command(command: String, capture: Option<bool>, app: tauri::AppHandle) -> Result<Option<runner::Output>, String> {
+    if command.trim().is_empty() {
+        return Err("There is no command to run".to_string());
     }
-    
+    let preferences: settings::Preferences = {
+        let conn = app.state::<AppState>().readers.get()?;
+        settings::load(&conn, settings::PREFERENCES)
+    };
+    let shell = runner::shell(&preferences.shell.program);
+    if capture.unwrap_or(!preferences.shell.in_terminal) {
+        // Waits for the command, so off the threads serving other commands
+        let output = tauri::async_runtime::spawn_blocking(move || runner::capture(&shell, &command))
+            .await
+            .map_err(|e| e.to_string())??;
+        Ok(Some(output))
+    } else {
+        runner::in_terminal(&shell, &command)?;
+        Ok(None)
+    }
+}
+
+// Copy the file or folder at `path` into the folder `destination`; the copy's path.
+// Progress comes as `copy-progress` events.
+#[tauri::command]
+async fn copy_item(path: String, destination: String, app: tauri::AppHandle) -> Result<String, String> {
+    // Walks and copies the whole tree, so off the threads serving other commands
+    tauri::async_runtime::spawn_blocking(move || file_actions::copy(&app, &path, &destination))
+        .await
+        .map_err(|e| e.to_string())?
+}
+
+// Move the file or folder at `path` into the folder `destination`; its new path
+#[tauri::command]
+async fn move_item(path: String, destination: String, app: tauri::AppHandle) -> Result<String, String> {
+    // Copies the whole tree when moving to another volume
+    let moved = {
+        let (app, path) = (app.clone(), path.clone());
+        tauri::async_runtime::spawn_blocking(move || file_actions::move_to(&app, &path, &destination))
+            .await
+            .map_err(|e| e.to_string())??
+    };
+    forget_moved(&app, &path)?;
+    Ok(moved)
+}
+
+// Rename the file or folder at `path` to `new_name`; its new path
+#[tauri::command]
+async fn rename_item(path: String, new_name: String, app: tauri::AppHandle) -> Result<String, String> {
+    let renamed = file_actions::rename(&path, &new_name)?;
+    forget_moved(&app, &path)?;
+    Ok(renamed)
+}
+
+// Send the file or folder at `path` to the recycle bin or trash
+#[tauri::command]
+async fn trash_item(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    file_actions::trash(&path)?;
+    forget_moved(&app, &path)
+}
+
+// Drop an item that was moved or trashed from the index now rather than when the watcher
+// gets to it, so it doesn't show up in results; its new place is indexed by the watcher
+fn forget_moved(app: &tauri::AppHandle, path: &str) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    prune::forget(&conn, Path::new(path))?;
     Ok(())
 }
 
+// Pin the result at `path` above the others matching a query
+#[tauri::command]
+async fn pin_item(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    favorites::set_pinned(&conn, &path, true)
+}
+
+#[tauri::command]
+async fn unpin_item(path: String, app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    favorites::set_pinned(&conn, &path, false)
+}
+
+// Make typing `alias` bring up the result at `path` first; None or blank removes its alias
+#[tauri::command]
+async fn set_alias(path: String, alias: Option<String>, app: tauri::AppHandle) -> Result<(), String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    favorites::set_alias(&conn, &path, alias.as_deref())
+}
+
+// The global shortcut that shows the launcher
+#[tauri::command]
+async fn get_hotkey(app: tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    Ok(hotkey::saved(&conn))
+}
+
+// Use `accelerator` ("Alt+Space", "Ctrl+Shift+K") as the global shortcut from now on;
+// refused if it isn't valid or another application holds it
+#[tauri::command]
+async fn set_hotkey(accelerator: String, app: tauri::AppHandle) -> Result<String, String> {
+    let state = app.state::<AppState>();
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    hotkey::set(&app, &conn, &accelerator)
+}
+
+// Whether the launcher starts when the user logs in
+#[tauri::command]
+async fn get_autostart(app: tauri::AppHandle) -> Result<bool, String> {
+    autostart::enabled(&app)
+}
+
+#[tauri::command]
+async fn set_autostart(enabled: bool, app: tauri::AppHandle) -> Result<(), String> {
+    autostart::set(&app, enabled)
+}
+
+// Open the folder containing `path` in the file manager, with it selected
+#[tauri::command]
+fn reveal_in_folder(path: String) -> Result<(), String> {
+    launch::reveal(&path)
+}
+
 fn main() {
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
+        .plugin(hotkey::plugin())
+        .plugin(tauri_plugin_clipboard_manager::init())
+        .on_window_event(tray::on_window_event)
+        .manage(progress::Progress::default())
+        .manage(control::Control::default())
+        .manage(searches::Searches::default())
         .setup(|app| {
             let window = app.get_webview_window("main")
                 .ok_or("Failed to get window".to_string())?;
 
-            window.show().map_err(|e| e.to_string())?;
-            window.set_focus().map_err(|e| e.to_string())?;
+            // Started at login, it waits in the tray until called up
+            if !std::env::args().any(|arg| arg == autostart::ARG) {
+                window.show().map_err(|e| e.to_string())?;
+                window.set_focus().map_err(|e| e.to_string())?;
+            }
 
             tauri::async_runtime::block_on(initialize_database(app.handle().clone()))?;
 
+            tray::create(app.handle())?;
+
+            // Without its shortcut the launcher still starts, reachable from the tray
+            if let Ok(conn) = app.state::<AppState>().db.lock() {
+                if let Err(e) = hotkey::register_saved(app.handle(), &conn) {
+                    log::warn!("{}", e);
+                }
+                let preferences: settings::Preferences = settings::load(&conn, settings::PREFERENCES);
+                apply_theme(app.handle(), preferences.theme);
+            }
+
             let app_handle = app.handle().clone();
             tauri::async_runtime::spawn(async move {
-                let _ = index_files("C:\\Users".to_string(), app_handle.clone()).await;
-                let _ = index_files("C:\\Program Files".to_string(), app_handle.clone()).await;
-                let _ = index_applications(app_handle.clone()).await;
+                let config: settings::IndexConfig = match app_handle.state::<AppState>().db.lock() {
+                    Ok(conn) => settings::load(&conn, settings::INDEX_CONFIG),
+                    Err(_) => return,
+                };
+                let control = app_handle.state::<control::Control>();
+                for root in &config.roots {
+                    if control.is_cancelled() {
+                        break;
+                    }
+                    if let Err(e) = update_root(root, &app_handle) {
+                        log::warn!("Could not index {}: {}", root.display(), e);
+                    }
+                }
+                if !control.is_cancelled() {
+                    let _ = find_applications(&app_handle);
+                }
+                if !control.is_cancelled() {
+                    if let Err(e) = index_bookmarks(&app_handle) {
+                        log::warn!("Could not index bookmarks: {}", e);
+                    }
+                }
+
+                // From here on, changes below the roots are applied as they happen
+                watcher::start(app_handle.clone());
+                // and stale roots are re-indexed when the computer is idle
+                scheduler::start(app_handle.clone());
+                clipboard::start(app_handle.clone());
             });
 
             Ok(())
@@ -383,10 +1236,46 @@ fn main() {
         .invoke_handler(tauri::generate_handler![
             toggle_window,
             search,
+            search_more,
             index_files,
             index_applications,
+            index_status,
+            pause_indexing,
+            resume_indexing,
+            cancel_indexing,
+            get_index_config,
+            set_index_config,
+            prune_index,
+            get_settings,
+            update_settings,
+            get_schedule_config,
+            set_schedule_config,
             open_path,
-            launch_app
+            launch_app,
+            launch_app_with_args,
+            reveal_in_folder,
+            get_open_with_candidates,
+            open_with,
+            copy_item,
+            move_item,
+            rename_item,
+            trash_item,
+            pin_item,
+            unpin_item,
+            set_alias,
+            get_recent,
+            clear_history,
+            paste_clip,
+            clear_clips,
+            copy_to_clipboard,
+            run_command,
+            get_hotkey,
+            set_hotkey,
+            get_autostart,
+            set_autostart,
+            get_icon,
+            get_default_args,
+            set_default_args
         ])
         .run(tauri::generate_context!())
         .expect("error while running tauri application");
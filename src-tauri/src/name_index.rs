@@ -0,0 +1,73 @@
+// Full-text index over the names in `files` and `applications`: FTS5 tables with the
+// trigram tokenizer, which answer "name contains ..." from the index instead of reading
+// every row, however many million there are. The indexes mirror their tables' `name`
+// column (external content) and are rebuilt after every indexing run; the file watcher
+// keeps them current in between, row by row.
+
+use rusqlite::{Connection, params};
+
+// Tables whose names are indexed; each gets a `<table>_fts` companion
+pub const TABLES: [&str; 2] = ["files", "applications"];
+
+// Trigrams need at least this many characters; shorter queries can't use the index
+const MIN_QUERY_CHARS: usize = 3;
+
+// Create the index tables, filling them once if the database already had rows
+pub fn create(conn: &Connection) -> Result<(), String> {
+    for table in TABLES {
+        let exists: bool = conn.query_row(
+            "SELECT COUNT(*) > 0 FROM sqlite_master WHERE name = ?1",
+            params![format!("{}_fts", table)],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+        if exists {
+            continue;
+        }
+        conn.execute_batch(&format!(
+            "CREATE VIRTUAL TABLE {}_fts USING fts5(
+                name, content='{}', content_rowid='id', tokenize='trigram'
+            )",
+            table, table
+        )).map_err(|e| e.to_string())?;
+        rebuild(conn, table)?;
+    }
+    Ok(())
+}
+
+// Re-read every name of `table` into its index
+pub fn rebuild(conn: &Connection, table: &str) -> Result<(), String> {
+    conn.execute(
+        &format!("INSERT INTO {}_fts ({}_fts) VALUES ('rebuild')", table, table),
+        [],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Add one row's name to the index of `table`
+pub fn add(conn: &Connection, table: &str, id: i64, name: &str) -> Result<(), String> {
+    conn.execute(
+        &format!("INSERT INTO {}_fts (rowid, name) VALUES (?1, ?2)", table),
+        params![id, name],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Take one row's name out of the index of `table`; `name` must be the indexed one
+pub fn forget(conn: &Connection, table: &str, id: i64, name: &str) -> Result<(), String> {
+    conn.execute(
+        &format!("INSERT INTO {}_fts ({}_fts, rowid, name) VALUES ('delete', ?1, ?2)", table, table),
+        params![id, name],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// MATCH expression for names containing `query` (case-insensitive), or None when the
+// query is too short for the index
+pub fn substring_query(query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.chars().count() < MIN_QUERY_CHARS {
+        return None;
+    }
+    // A quoted phrase, with quotes doubled, is matched literally
+    Some(format!("\"{}\"", query.replace('"', "\"\"")))
+}
@@ -0,0 +1,282 @@
+// "Open with": the applications the system knows can open a file, besides the one it
+// opens with by default, and opening the file in one of them. Windows keeps them in the
+// registry, macOS in each bundle's Info.plist, Linux in the desktop entries' MimeType keys.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::prune;
+
+#[derive(Serialize)]
+pub struct Candidate {
+    pub name: String,
+    // What `open` takes as the application: a program, an .app bundle or a .desktop file
+    pub path: String,
+}
+
+// The applications that can open `path`, the default one first where it is known
+pub fn candidates(path: &str) -> Vec<Candidate> {
+    let mut candidates = system::candidates(path);
+    let mut seen = std::collections::HashSet::new();
+    candidates.retain(|candidate| seen.insert(candidate.path.to_lowercase()));
+    candidates
+}
+
+// Open `path` with the application at `app`
+pub fn open(path: &str, app: &str) -> Result<(), String> {
+    for file in [path, app] {
+        if prune::is_gone(Path::new(file)) {
+            return Err(format!("{} does not exist", file));
+        }
+    }
+    system::open(path, app).map_err(|e| format!("Could not open {} with {}: {}", path, app, e))
+}
+
+#[cfg(windows)]
+mod system {
+    use std::path::Path;
+
+    use windows_sys::Win32::System::Registry::{
+        HKEY, HKEY_CLASSES_ROOT, HKEY_CURRENT_USER, HKEY_LOCAL_MACHINE, KEY_READ, RRF_RT_REG_SZ,
+        RegCloseKey, RegEnumKeyExW, RegEnumValueW, RegGetValueW, RegOpenKeyExW,
+    };
+
+    use super::Candidate;
+    use crate::launch;
+
+    const FILE_EXTS: &str = r"Software\Microsoft\Windows\CurrentVersion\Explorer\FileExts";
+    const APP_PATHS: &str = r"SOFTWARE\Microsoft\Windows\CurrentVersion\App Paths";
+
+    // The programs of the extension's ProgIDs (its default one first), then the ones the
+    // user has picked in Explorer's Open With, then the ones registered to offer for it
+    pub fn candidates(path: &str) -> Vec<Candidate> {
+        let Some(ext) = Path::new(path).extension() else {
+            return Vec::new();
+        };
+        let ext = format!(".{}", ext.to_string_lossy().to_lowercase());
+
+        let mut progids: Vec<String> = string(HKEY_CLASSES_ROOT, &ext, None).into_iter().collect();
+        progids.extend(value_names(HKEY_CLASSES_ROOT, &format!(r"{}\OpenWithProgids", ext)));
+        let mut programs: Vec<String> = progids.iter().filter_map(|progid| progid_program(progid)).collect();
+
+        // Explorer's list is values "a", "b"... naming programs, and MRUList giving their order
+        let user_list = format!(r"{}\{}\OpenWithList", FILE_EXTS, ext);
+        let exe_names = value_names(HKEY_CURRENT_USER, &user_list)
+            .into_iter()
+            .filter(|name| name != "MRUList")
+            .filter_map(|name| string(HKEY_CURRENT_USER, &user_list, Some(&name)))
+            .chain(subkey_names(HKEY_CLASSES_ROOT, &format!(r"{}\OpenWithList", ext)));
+        programs.extend(exe_names.filter_map(|exe| exe_program(&exe)));
+
+        programs
+            .into_iter()
+            .map(|program| Candidate { name: program_name(&program), path: program })
+            .collect()
+    }
+
+    pub fn open(path: &str, app: &str) -> Result<(), String> {
+        launch::program(app, Some(&format!("\"{}\"", path)), None, false)
+    }
+
+    // The program of the ProgID's default verb
+    fn progid_program(progid: &str) -> Option<String> {
+        let verb = string(HKEY_CLASSES_ROOT, &format!(r"{}\shell", progid), None).unwrap_or_else(|| "open".to_string());
+        program(&string(HKEY_CLASSES_ROOT, &format!(r"{}\shell\{}\command", progid, verb), None)?)
+    }
+
+    // The program registered under a file name such as notepad.exe
+    fn exe_program(exe: &str) -> Option<String> {
+        string(HKEY_CLASSES_ROOT, &format!(r"Applications\{}\shell\open\command", exe), None)
+            .or_else(|| string(HKEY_CURRENT_USER, &format!(r"{}\{}", APP_PATHS, exe), None))
+            .or_else(|| string(HKEY_LOCAL_MACHINE, &format!(r"{}\{}", APP_PATHS, exe), None))
+            .and_then(|command| program(&command))
+    }
+
+    // The program a command line starts, if it exists and takes a file to open. rundll32
+    // commands call into a DLL, which started this way wouldn't get the file.
+    fn program(command: &str) -> Option<String> {
+        let command = command.trim();
+        let file = match command.strip_prefix('"') {
+            Some(rest) => rest.split('"').next()?,
+            None => match command.to_ascii_lowercase().find(".exe") {
+                Some(end) => &command[..end + 4],
+                None => command.split_whitespace().next()?,
+            },
+        };
+        let name = Path::new(file).file_name()?.to_string_lossy().to_lowercase();
+        Some(file.to_string()).filter(|file| Path::new(file).is_file() && name != "rundll32.exe")
+    }
+
+    // The name the program registered for Open With, else its file name
+    fn program_name(program: &str) -> String {
+        let exe = Path::new(program).file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default();
+        string(HKEY_CLASSES_ROOT, &format!(r"Applications\{}", exe), Some("FriendlyAppName"))
+            .unwrap_or_else(|| Path::new(program).file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or(exe))
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        text.encode_utf16().chain(Some(0)).collect()
+    }
+
+    // A string value under `key` (None: its default value), expanded if it names variables
+    fn string(root: HKEY, key: &str, value: Option<&str>) -> Option<String> {
+        let (key, value) = (wide(key), value.map(wide));
+        let value = value.as_ref().map_or(std::ptr::null(), |value| value.as_ptr());
+        let mut size = 0u32;
+        let found = unsafe {
+            RegGetValueW(root, key.as_ptr(), value, RRF_RT_REG_SZ, std::ptr::null_mut(), std::ptr::null_mut(), &mut size)
+        };
+        if found != 0 {
+            return None;
+        }
+        let mut text = vec![0u16; size as usize / 2 + 1];
+        let mut size = (text.len() * 2) as u32;
+        let read = unsafe {
+            RegGetValueW(root, key.as_ptr(), value, RRF_RT_REG_SZ, std::ptr::null_mut(), text.as_mut_ptr().cast(), &mut size)
+        };
+        if read != 0 {
+            return None;
+        }
+        let len = text.iter().position(|&c| c == 0).unwrap_or(text.len());
+        Some(String::from_utf16_lossy(&text[..len])).filter(|text| !text.is_empty())
+    }
+
+    fn value_names(root: HKEY, key: &str) -> Vec<String> {
+        names(root, key, |key, index, name, len| unsafe {
+            RegEnumValueW(key, index, name, len, std::ptr::null(), std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut())
+        })
+    }
+
+    fn subkey_names(root: HKEY, key: &str) -> Vec<String> {
+        names(root, key, |key, index, name, len| unsafe {
+            RegEnumKeyExW(key, index, name, len, std::ptr::null(), std::ptr::null_mut(), std::ptr::null_mut(), std::ptr::null_mut())
+        })
+    }
+
+    // The names `enumerate` lists under `key`, until it stops returning success
+    fn names(root: HKEY, key: &str, enumerate: impl Fn(HKEY, u32, *mut u16, *mut u32) -> u32) -> Vec<String> {
+        let mut handle: HKEY = std::ptr::null_mut();
+        if unsafe { RegOpenKeyExW(root, wide(key).as_ptr(), 0, KEY_READ, &mut handle) } != 0 {
+            return Vec::new();
+        }
+        let mut names = Vec::new();
+        // Names are at most 16383 characters
+        let mut name = vec![0u16; 16384];
+        for index in 0.. {
+            let mut len = name.len() as u32;
+            if enumerate(handle, index, name.as_mut_ptr(), &mut len) != 0 {
+                break;
+            }
+            names.push(String::from_utf16_lossy(&name[..len as usize]));
+        }
+        unsafe { RegCloseKey(handle) };
+        names
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::Candidate;
+    use crate::bundle;
+
+    // The bundles declaring the file's extension or one of its types
+    pub fn candidates(path: &str) -> Vec<Candidate> {
+        let extension = Path::new(path).extension().map(|ext| ext.to_string_lossy().into_owned()).unwrap_or_default();
+        let content_types = content_types(path);
+        let mut candidates: Vec<Candidate> = bundle::find()
+            .into_iter()
+            .filter(|found| bundle::handles(&found.path, &extension, &content_types))
+            .map(|found| Candidate { name: found.name, path: found.path.to_string_lossy().into_owned() })
+            .collect();
+        candidates.sort_by_key(|candidate| candidate.name.to_lowercase());
+        candidates
+    }
+
+    pub fn open(path: &str, app: &str) -> Result<(), String> {
+        let output = Command::new("open").args(["-a", app, path]).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    // The file's type and the ones it conforms to (public.plain-text, public.text...), as
+    // Spotlight has them; none for a file it hasn't indexed
+    fn content_types(path: &str) -> Vec<String> {
+        let Ok(output) = Command::new("mdls").args(["-raw", "-name", "kMDItemContentTypeTree", path]).output() else {
+            return Vec::new();
+        };
+        // A list such as ("public.plain-text", "public.text"), or (null)
+        String::from_utf8_lossy(&output.stdout)
+            .split('"')
+            .skip(1)
+            .step_by(2)
+            .map(String::from)
+            .collect()
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::path::Path;
+    use std::process::Command;
+
+    use super::Candidate;
+    use crate::desktop;
+
+    // The desktop entries listing the file's MIME type, the default one for it first
+    pub fn candidates(path: &str) -> Vec<Candidate> {
+        let Some(mime_type) = xdg_mime(&["query", "filetype", path]) else {
+            return Vec::new();
+        };
+        let default = xdg_mime(&["query", "default", &mime_type]);
+        let mut entries: Vec<desktop::Entry> = desktop::find()
+            .into_iter()
+            .filter(|entry| entry.mime_types.contains(&mime_type))
+            .collect();
+        entries.sort_by_key(|entry| {
+            let is_default = default.as_deref().is_some_and(|id| entry.path.ends_with(id));
+            (!is_default, entry.name.to_lowercase())
+        });
+        entries
+            .into_iter()
+            .map(|entry| Candidate { name: entry.name, path: entry.path.to_string_lossy().into_owned() })
+            .collect()
+    }
+
+    pub fn open(path: &str, app: &str) -> Result<(), String> {
+        let (args, working_dir) = desktop::command(Path::new(app), &[path])?;
+        let mut command = Command::new(&args[0]);
+        command.args(&args[1..]);
+        if let Some(dir) = working_dir.filter(|dir| Path::new(dir).is_dir()) {
+            command.current_dir(dir);
+        }
+        command.spawn().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    // What xdg-mime answers, if it's installed and has an answer
+    fn xdg_mime(args: &[&str]) -> Option<String> {
+        let output = Command::new("xdg-mime").args(args).output().ok()?;
+        let answer = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Some(answer).filter(|answer| output.status.success() && !answer.is_empty())
+    }
+}
+
+// Other systems: nothing to open with
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use super::Candidate;
+
+    pub fn candidates(_path: &str) -> Vec<Candidate> {
+        Vec::new()
+    }
+
+    pub fn open(_path: &str, _app: &str) -> Result<(), String> {
+        Err("opening with another app isn't supported on this system".to_string())
+    }
+}
@@ -0,0 +1,69 @@
+// Read-only connections for searches. The database runs in WAL mode, where readers see the
+// last committed state while a writer is busy, so searches take a connection from here
+// instead of waiting for `AppState::db`, which the indexer holds for a batch at a time.
+
+use std::ops::Deref;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rusqlite::{Connection, OpenFlags};
+
+// Connections kept open between searches; more are opened when searches overlap
+const MAX_IDLE: usize = 4;
+
+pub struct ReadPool {
+    path: PathBuf,
+    idle: Mutex<Vec<Connection>>,
+}
+
+impl ReadPool {
+    pub fn new(path: PathBuf) -> Self {
+        ReadPool { path, idle: Mutex::new(Vec::new()) }
+    }
+
+    // An idle connection, or a new one; it goes back to the pool when dropped
+    pub fn get(&self) -> Result<Reader<'_>, String> {
+        let idle = self.idle.lock().map_err(|e| e.to_string())?.pop();
+        let conn = match idle {
+            Some(conn) => conn,
+            None => self.open()?,
+        };
+        Ok(Reader { pool: self, conn: Some(conn) })
+    }
+
+    fn open(&self) -> Result<Connection, String> {
+        let conn = Connection::open_with_flags(
+            &self.path,
+            OpenFlags::SQLITE_OPEN_READ_ONLY | OpenFlags::SQLITE_OPEN_NO_MUTEX,
+        ).map_err(|e| e.to_string())?;
+        // Readers only wait while a checkpoint briefly locks the whole file
+        conn.busy_timeout(Duration::from_secs(1)).map_err(|e| e.to_string())?;
+        Ok(conn)
+    }
+}
+
+pub struct Reader<'a> {
+    pool: &'a ReadPool,
+    conn: Option<Connection>,
+}
+
+impl Deref for Reader<'_> {
+    type Target = Connection;
+
+    fn deref(&self) -> &Connection {
+        self.conn.as_ref().expect("connection taken before drop")
+    }
+}
+
+impl Drop for Reader<'_> {
+    fn drop(&mut self) {
+        if let (Some(conn), Ok(mut idle)) = (self.conn.take(), self.pool.idle.lock()) {
+            // Back without the progress handler a search may have set
+            conn.progress_handler(0, None::<fn() -> bool>);
+            if idle.len() < MAX_IDLE {
+                idle.push(conn);
+            }
+        }
+    }
+}
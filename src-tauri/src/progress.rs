@@ -0,0 +1,123 @@
+// Progress of indexing runs, for the frontend: the latest state is kept in app state for
+// the `index_status` command and sent as `index-progress` events while a run goes on, at
+// most every EMIT_INTERVAL so a fast walk doesn't flood the webview.
+
+use std::path::Path;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+use tauri::{Emitter, Manager};
+
+// Name of the event carrying a Status
+pub const EVENT: &str = "index-progress";
+
+const EMIT_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Phase {
+    #[default]
+    Idle,
+    // Applying changes read from the NTFS change journal
+    Journal,
+    Files,
+    Applications,
+    // Removing entries that are gone or no longer covered by the configuration
+    Pruning,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct Status {
+    pub phase: Phase,
+    // Root being indexed
+    pub root: Option<String>,
+    pub current_path: Option<String>,
+    pub indexed: usize,
+    // Expected entries: what the root held after its last index, if it was indexed before
+    pub total: Option<usize>,
+    pub eta_secs: Option<u64>,
+    // Waiting for indexing to be resumed
+    pub paused: bool,
+}
+
+// Latest status, managed by the app
+#[derive(Default)]
+pub struct Progress(Mutex<Status>);
+
+pub fn status(app: &tauri::AppHandle) -> Status {
+    app.state::<Progress>().0.lock().map(|status| status.clone()).unwrap_or_default()
+}
+
+// One indexing run; reports Idle once dropped, however the run ended
+pub struct Run {
+    app: tauri::AppHandle,
+    status: Status,
+    started: Instant,
+    last_emit: Instant,
+    paused_at: Option<Instant>,
+}
+
+pub fn begin(app: &tauri::AppHandle, phase: Phase, root: Option<&Path>, total: Option<usize>) -> Run {
+    let now = Instant::now();
+    let run = Run {
+        app: app.clone(),
+        status: Status {
+            phase,
+            root: root.map(|root| root.to_string_lossy().into_owned()),
+            total,
+            ..Status::default()
+        },
+        started: now,
+        last_emit: now,
+        paused_at: None,
+    };
+    publish(&run.app, &run.status);
+    run
+}
+
+impl Run {
+    // Count one more entry indexed
+    pub fn advance(&mut self, path: &Path) {
+        self.status.indexed += 1;
+        if self.last_emit.elapsed() < EMIT_INTERVAL {
+            return;
+        }
+        self.last_emit = Instant::now();
+        self.status.current_path = Some(path.to_string_lossy().into_owned());
+        self.status.eta_secs = self.eta();
+        publish(&self.app, &self.status);
+    }
+
+    pub fn set_paused(&mut self, paused: bool) {
+        if paused {
+            self.paused_at = Some(Instant::now());
+        } else if let Some(paused_at) = self.paused_at.take() {
+            // Time spent paused doesn't count towards the rate
+            self.started += paused_at.elapsed();
+        }
+        self.status.paused = paused;
+        publish(&self.app, &self.status);
+    }
+
+    // Time left at the rate so far; unknown without an expected total, or once the walk
+    // has outgrown it
+    fn eta(&self) -> Option<u64> {
+        let left = self.status.total?.checked_sub(self.status.indexed)?;
+        let rate = self.status.indexed as f64 / self.started.elapsed().as_secs_f64();
+        (rate > 0.0).then(|| (left as f64 / rate).ceil() as u64)
+    }
+}
+
+impl Drop for Run {
+    fn drop(&mut self) {
+        publish(&self.app, &Status { indexed: self.status.indexed, ..Status::default() });
+    }
+}
+
+fn publish(app: &tauri::AppHandle, status: &Status) {
+    if let Ok(mut current) = app.state::<Progress>().0.lock() {
+        *current = status.clone();
+    }
+    let _ = app.emit(EVENT, status);
+}
@@ -0,0 +1,466 @@
+// Where results come from. Each source is a ResultProvider: the index's files and its
+// applications, aliases, the calculator, web addresses. A search runs the providers side
+// by side, each on a connection of its own, then ranks what they found together: best
+// score first, each path once, up to the ranked limit.
+//
+// Typing a provider's prefix ("app:", "=") searches it alone, with the rest of the query.
+// Some providers (open windows, clipboard history, shell commands) are only searched
+// that way.
+
+use rayon::prelude::*;
+use rusqlite::types::Value;
+use rusqlite::{Connection, params_from_iter};
+use tauri::Manager;
+
+use crate::searches::Token;
+use crate::settings::Preferences;
+use crate::{AppState, SearchResult, bookmarks, calc, clipboard, favorites, frecency, fuzzy, name_index, query, switcher, symbols, web};
+
+// Rows fetched from each table before fuzzy ranking picks the best ones
+const CANDIDATE_LIMIT: i64 = 500;
+// Fuzzy (non-substring) matches are looked for among this many most used rows, since
+// they can't be found through the name index
+const FUZZY_SCAN_LIMIT: i64 = 50_000;
+// How much frecency (recent, frequent use) counts next to the fuzzy score when ranking
+const FRECENCY_WEIGHT: f64 = 0.1;
+// What pinning adds to a result's score: more than fuzzy matching and frecency make up
+const PIN_BOOST: f64 = 1.0;
+// Score of the result whose alias the query is, above any found by name
+const ALIAS_SCORE: f64 = 100.0;
+// Score of opening the web address the query is, above everything found locally
+const ADDRESS_SCORE: f64 = 200.0;
+// Score of the calculator's answer: a query that is a sum is asking for it
+const CALC_SCORE: f64 = 300.0;
+// Most bookmarks in a ranking, so that a long browser history doesn't crowd out files
+const BOOKMARK_BUDGET: usize = 50;
+// Score of a character whose name the query is, without the prefix: that of a perfect
+// match, so that files of the same name come with it
+const SYMBOL_SCORE: f64 = 1.0;
+// Characters of a clip shown as its name
+const CLIP_NAME_LENGTH: usize = 80;
+// Candidates scored between two checks for a newer search
+const SCORE_BATCH: usize = 256;
+// SQLite steps between two checks for a newer search while a query runs
+const SEARCH_CHECK_STEPS: i32 = 1_000;
+
+// What a provider searches with
+pub struct Context<'a> {
+    pub app: &'a tauri::AppHandle,
+    pub conn: &'a Connection,
+    pub token: &'a Token,
+    pub preferences: &'a Preferences,
+    // Whether the query came with the provider's prefix, asking for it alone
+    pub prefixed: bool,
+}
+
+pub trait ResultProvider: Sync {
+    // Typed before a query, it searches this provider alone
+    fn prefix(&self) -> Option<&'static str> {
+        None
+    }
+
+    // Searched only when its prefix is typed
+    fn prefix_only(&self) -> bool {
+        false
+    }
+
+    // Most results it adds to a ranking; None: as many as are ranked
+    fn budget(&self) -> Option<usize> {
+        None
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String>;
+
+    // Offered when no provider searched found anything
+    fn fallback(&self, _query: &str, _preferences: &Preferences) -> Vec<SearchResult> {
+        Vec::new()
+    }
+
+    // Whether a ranking with its results may be cached and reused; not when they go
+    // stale within moments or mustn't be stored in the clear
+    fn cacheable(&self) -> bool {
+        true
+    }
+}
+
+pub struct Ranking {
+    pub results: Vec<SearchResult>,
+    pub cacheable: bool,
+}
+
+// Every provider, in the order results of equal score are listed
+const PROVIDERS: &[&dyn ResultProvider] = &[&Calculator, &Web, &Aliases, &Applications, &Files, &Bookmarks, &Windows, &Clips, &Shell, &Symbols];
+
+// The ranking for `query`: the providers it names by prefix, or all the others
+pub fn search(query: &str, app: &tauri::AppHandle, token: &Token, preferences: &Preferences) -> Result<Ranking, String> {
+    let pool = &app.state::<AppState>().readers;
+    let trimmed = query.trim_start();
+    let prefixed = PROVIDERS.iter().find_map(|provider| {
+        let rest = trimmed.strip_prefix(provider.prefix()?)?;
+        Some((*provider, rest))
+    });
+    let selected: Vec<(&dyn ResultProvider, &str)> = match prefixed {
+        Some((provider, rest)) => vec![(provider, rest)],
+        None => PROVIDERS.iter().filter(|provider| !provider.prefix_only()).map(|provider| (*provider, query)).collect(),
+    };
+
+    let found: Vec<(bool, Vec<SearchResult>)> = selected
+        .par_iter()
+        .map(|(provider, query)| {
+            let conn = pool.get()?;
+            // Interrupts a query (a long LIKE scan, say) once a newer search has started
+            let watching = token.clone();
+            conn.progress_handler(SEARCH_CHECK_STEPS, Some(move || watching.superseded()));
+            let context = Context { app, conn: &conn, token, preferences, prefixed: prefixed.is_some() };
+            let mut results = provider.search(query, &context).map_err(|e| token.explain(e))?;
+            sort(&mut results);
+            results.truncate(provider.budget().unwrap_or(preferences.ranked_limit));
+            Ok((provider.cacheable(), results))
+        })
+        .collect::<Result<_, String>>()?;
+
+    // Best score first; the same path can come from more than one provider, keep its best entry
+    let cacheable = found.iter().all(|(cacheable, results)| *cacheable || results.is_empty());
+    let mut results: Vec<SearchResult> = found.into_iter().flat_map(|(_, results)| results).collect();
+    sort(&mut results);
+    let mut seen = std::collections::HashSet::new();
+    results.retain(|result| seen.insert(result.path.clone()));
+    results.truncate(preferences.ranked_limit);
+    if results.is_empty() {
+        for (provider, query) in &selected {
+            results.extend(provider.fallback(query, preferences));
+        }
+    }
+    Ok(Ranking { results, cacheable })
+}
+
+fn sort(results: &mut [SearchResult]) {
+    results.sort_by(|a, b| {
+        b.score.partial_cmp(&a.score)
+            .unwrap_or_else(|| a.r#type.cmp(&b.r#type))
+    });
+}
+
+// Indexed files and folders
+struct Files;
+
+impl ResultProvider for Files {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("file:")
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        ranked(context, "files", query)
+    }
+}
+
+// Indexed applications
+struct Applications;
+
+impl ResultProvider for Applications {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("app:")
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        ranked(context, "applications", query)
+    }
+}
+
+// The result the query is the alias of, whether or not its name matches
+struct Aliases;
+
+impl ResultProvider for Aliases {
+    fn budget(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        let parsed = query::parse(query, frecency::now());
+        Ok(favorites::aliased(context.conn, &parsed.text)?
+            .map(|(path, name, kind)| SearchResult { path, name, r#type: kind, score: Some(ALIAS_SCORE) })
+            .into_iter()
+            .collect())
+    }
+}
+
+// The answer to a sum or a unit conversion; its value stands in for a path
+struct Calculator;
+
+impl ResultProvider for Calculator {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("=")
+    }
+
+    fn budget(&self) -> Option<usize> {
+        Some(1)
+    }
+
+    fn search(&self, query: &str, _context: &Context) -> Result<Vec<SearchResult>, String> {
+        Ok(calc::answer(query)
+            .map(|(asked, value)| SearchResult { path: value, name: asked, r#type: "calc".to_string(), score: Some(CALC_SCORE) })
+            .into_iter()
+            .collect())
+    }
+}
+
+// Opening the web address the query is, and searching the web for it when nothing local
+// matches (or when asked with the prefix)
+struct Web;
+
+impl ResultProvider for Web {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("web:")
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        let mut results: Vec<SearchResult> = web::address(query)
+            .map(|address| {
+                let name = format!("Open {}", address);
+                SearchResult { path: address, name, r#type: "web".to_string(), score: Some(ADDRESS_SCORE) }
+            })
+            .into_iter()
+            .collect();
+        if context.prefixed && !query.trim().is_empty() {
+            results.extend(self.fallback(query, context.preferences));
+        }
+        Ok(results)
+    }
+
+    fn fallback(&self, query: &str, preferences: &Preferences) -> Vec<SearchResult> {
+        preferences
+            .search_engines
+            .iter()
+            .map(|engine| SearchResult {
+                path: engine.url_for(query),
+                name: format!("Search {} for \"{}\"", engine.name, query.trim()),
+                r#type: "web".to_string(),
+                score: None,
+            })
+            .collect()
+    }
+}
+
+// Browser bookmarks and history, by title or URL, the more visited a little higher
+struct Bookmarks;
+
+impl ResultProvider for Bookmarks {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("bm:")
+    }
+
+    fn budget(&self) -> Option<usize> {
+        Some(BOOKMARK_BUDGET)
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        let text = query.trim();
+        let mut results = Vec::new();
+        for (i, (url, title, visits)) in bookmarks::search(context.conn, &fuzzy::like_pattern(text), CANDIDATE_LIMIT)?.into_iter().enumerate() {
+            if i % SCORE_BATCH == 0 {
+                context.token.check()?;
+            }
+            let address = url.split_once("://").map_or(url.as_str(), |(_, rest)| rest);
+            let scores = [fuzzy::score(text, &title), fuzzy::score(text, address)];
+            let Some(fuzzy) = scores.into_iter().flatten().reduce(f64::max) else {
+                continue;
+            };
+            let score = fuzzy + FRECENCY_WEIGHT * (1.0 + visits as f64).ln();
+            let name = if title.is_empty() { url.clone() } else { title };
+            results.push(SearchResult { path: url, name, r#type: "bookmark".to_string(), score: Some(score) });
+        }
+        Ok(results)
+    }
+}
+
+// Windows open now, by title or by the name of their program. By its prefix only:
+// listing them starts a program (osascript, wmctrl) that every keystroke would wait for.
+struct Windows;
+
+impl ResultProvider for Windows {
+    fn prefix(&self) -> Option<&'static str> {
+        Some("win:")
+    }
+
+    fn prefix_only(&self) -> bool {
+        true
+    }
+
+    fn search(&self, query: &str, _context: &Context) -> Result<Vec<SearchResult>, String> {
+        // With the prefix alone, every window: an empty query matches anything
+        let text = query.trim();
+        let mut results = Vec::new();
+        for window in switcher::list() {
+            let scores = [fuzzy::score(text, &window.title), fuzzy::score(text, &window.program)];
+            let Some(fuzzy) = scores.into_iter().flatten().reduce(f64::max) else {
+                continue;
+            };
+            let path = switcher::path(&window);
+            results.push(SearchResult { path, name: window.title, r#type: "window".to_string(), score: Some(fuzzy) });
+        }
+        Ok(results)
+    }
+
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+// The clipboard history, newest first; by its prefix only, so clips never show up
+// among other results
+struct Clips;
+
+impl ResultProvider for Clips {
+    fn prefix(&self) -> Option<&'static str> {
+        Some(clipboard::SCHEME)
+    }
+
+    fn prefix_only(&self) -> bool {
+        true
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        let text = query.trim();
+        let mut results = Vec::new();
+        for (id, clip) in context.app.state::<clipboard::Clips>().list(context.conn)? {
+            let Some(score) = fuzzy::score(text, &clip) else {
+                continue;
+            };
+            // The first line, shortened, stands for the clip
+            let first_line = clip.trim().lines().next().unwrap_or_default();
+            let mut name: String = first_line.chars().take(CLIP_NAME_LENGTH).collect();
+            if name.len() < clip.trim().len() {
+                name.push('…');
+            }
+            results.push(SearchResult { path: format!("{}{}", clipboard::SCHEME, id), name, r#type: "clip".to_string(), score: Some(score) });
+        }
+        Ok(results)
+    }
+
+    fn cacheable(&self) -> bool {
+        false
+    }
+}
+
+// The query as a shell command to run; its text stands in for a path. By its prefix only,
+// so that nothing typed is ever offered as a command without asking for it.
+struct Shell;
+
+impl ResultProvider for Shell {
+    fn prefix(&self) -> Option<&'static str> {
+        Some(">")
+    }
+
+    fn prefix_only(&self) -> bool {
+        true
+    }
+
+    fn search(&self, query: &str, _context: &Context) -> Result<Vec<SearchResult>, String> {
+        let command = query.trim();
+        if command.is_empty() {
+            return Ok(Vec::new());
+        }
+        let name = format!("Run {}", command);
+        Ok(vec![SearchResult { path: command.to_string(), name, r#type: "command".to_string(), score: None }])
+    }
+}
+
+// Emoji and special characters by name; the character stands in for a path. Without the
+// prefix, only a character whose name the query is.
+struct Symbols;
+
+impl ResultProvider for Symbols {
+    fn prefix(&self) -> Option<&'static str> {
+        Some(":")
+    }
+
+    fn search(&self, query: &str, context: &Context) -> Result<Vec<SearchResult>, String> {
+        let result = |symbol: &symbols::Symbol, score| SearchResult {
+            path: symbol.text.to_string(),
+            name: symbol.name.to_string(),
+            r#type: "symbol".to_string(),
+            score: Some(score),
+        };
+        if !context.prefixed {
+            return Ok(symbols::named(query).into_iter().map(|symbol| result(symbol, SYMBOL_SCORE)).collect());
+        }
+        // A whole shortcode (":shrug:") ends in a colon too
+        let text = query.trim().trim_end_matches(':');
+        Ok(symbols::all()
+            .filter_map(|symbol| {
+                let names = std::iter::once(symbol.name).chain(symbol.aliases.split(' '));
+                let score = names.filter_map(|name| fuzzy::score(text, name)).reduce(f64::max)?;
+                Some(result(symbol, score))
+            })
+            .collect())
+    }
+}
+
+// Rows of `table` matching `query`, ranked by fuzzy score, nudged up by how often and how
+// recently an entry was opened, and raised above the rest if pinned
+fn ranked(context: &Context, table: &str, query: &str) -> Result<Vec<SearchResult>, String> {
+    let now = frecency::now();
+    let parsed = query::parse(query, now);
+    let candidates = candidates(context.conn, table, &parsed)?;
+    let pinned = favorites::pinned(context.conn)?;
+    let mut results = Vec::new();
+    for (i, (path, name, kind, stored)) in candidates.into_iter().enumerate() {
+        if i % SCORE_BATCH == 0 {
+            context.token.check()?;
+        }
+        if let Some(fuzzy) = fuzzy::score(&parsed.text, &name) {
+            let frecency = FRECENCY_WEIGHT * (1.0 + frecency::score(stored, now)).ln();
+            let pin = if pinned.contains(&path) { PIN_BOOST } else { 0.0 };
+            results.push(SearchResult { path, name, r#type: kind, score: Some(fuzzy + frecency + pin) });
+        }
+    }
+    Ok(results)
+}
+
+// Candidate rows of `table` for ranking, as (path, name, type, stored frecency): names
+// containing the query, found through the name index, and among the most used entries
+// also names containing the query's characters in order, for the fuzzy matches. Both
+// only take rows passing the query's filters.
+fn candidates(conn: &Connection, table: &str, query: &query::Query) -> Result<Vec<(String, String, String, f64)>, String> {
+    let kind = if table == "files" {
+        "CASE WHEN t.is_app THEN 'app' WHEN t.is_file THEN 'file' ELSE 'folder' END"
+    } else {
+        "'app'"
+    };
+    // Bound after the pattern (?1) and the limit (?2)
+    let (filter, filter_values) = query.filters.sql(table, 3);
+    let mut found = Vec::new();
+    let mut collect = |sql: String, pattern: String| -> Result<(), String> {
+        let mut stmt = conn.prepare(&sql).map_err(|e| e.to_string())?;
+        let mut values = vec![Value::Text(pattern), Value::Integer(CANDIDATE_LIMIT as i64)];
+        values.extend(filter_values.iter().cloned());
+        let rows = stmt
+            .query_map(params_from_iter(values), |row| {
+                Ok((row.get(0)?, row.get(1)?, row.get(2)?, row.get(3)?))
+            })
+            .map_err(|e| e.to_string())?;
+        for row in rows {
+            found.push(row.map_err(|e| e.to_string())?);
+        }
+        Ok(())
+    };
+
+    if let Some(phrase) = name_index::substring_query(&query.text) {
+        collect(format!(
+            "SELECT t.path, t.name, {}, t.frecency
+             FROM {}_fts JOIN {} t ON t.id = {}_fts.rowid
+             WHERE {}_fts MATCH ?1 AND {}
+             ORDER BY t.frecency DESC
+             LIMIT ?2",
+            kind, table, table, table, table, filter
+        ), phrase)?;
+    }
+    collect(format!(
+        "SELECT t.path, t.name, {}, t.frecency
+         FROM (SELECT * FROM {} t WHERE {} ORDER BY frecency DESC LIMIT {}) t
+         WHERE t.name LIKE ?1 ESCAPE '\\'
+         LIMIT ?2",
+        kind, table, filter, FUZZY_SCAN_LIMIT
+    ), fuzzy::like_pattern(&query.text))?;
+    Ok(found)
+}
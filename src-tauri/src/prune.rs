@@ -0,0 +1,139 @@
+// Taking entries out of the index that no longer belong in it: files and folders that are
+// gone, and entries the index configuration stopped covering (an added exclude pattern, a
+// smaller depth). Walks and the watcher only add what they find, so without this the index
+// keeps everything it ever saw.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OptionalExtension, params};
+use tauri::Manager;
+
+use crate::settings::{self, IndexConfig};
+use crate::{AppState, control, name_index, progress, watcher};
+
+// Rows checked per batch; the disk is checked without holding the database lock
+const BATCH: i64 = 5_000;
+
+// Check every indexed entry and remove the ones that don't belong. Returns how many rows
+// were removed, or control::CANCELLED if indexing was cancelled in between (what was
+// removed until then stays removed).
+pub fn prune(app: &tauri::AppHandle) -> Result<usize, String> {
+    let state = app.state::<AppState>();
+    let control = app.state::<control::Control>();
+    let (config, total) = {
+        let conn = state.db.lock().map_err(|e| e.to_string())?;
+        let config: IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+        let total: usize = conn.query_row("SELECT COUNT(*) FROM files", [], |row| row.get(0))
+            .map_err(|e| e.to_string())?;
+        (config, total)
+    };
+    let excludes = config.excludes();
+    let mut run = progress::begin(app, progress::Phase::Pruning, None, Some(total));
+
+    let mut removed = 0;
+    let mut cancelled = false;
+    let mut last_id = 0;
+    loop {
+        let rows = next_batch(&state.db.lock().map_err(|e| e.to_string())?, last_id)?;
+        let Some(&(id, _)) = rows.last() else {
+            break;
+        };
+        last_id = id;
+
+        let mut unwanted: Vec<PathBuf> = Vec::new();
+        for (_, path) in &rows {
+            let path = Path::new(path);
+            run.advance(path);
+            if !config.includes(&excludes, path) || is_gone(path) {
+                unwanted.push(path.to_path_buf());
+            }
+        }
+        if !unwanted.is_empty() {
+            let mut conn = state.db.lock().map_err(|e| e.to_string())?;
+            let tx = conn.transaction().map_err(|e| e.to_string())?;
+            for path in &unwanted {
+                removed += watcher::remove(&tx, path)?;
+            }
+            tx.commit().map_err(|e| e.to_string())?;
+        }
+
+        if !control.proceed(&mut run) {
+            cancelled = true;
+            break;
+        }
+    }
+
+    let conn = state.db.lock().map_err(|e| e.to_string())?;
+    if !cancelled {
+        removed += prune_applications(&conn)?;
+    }
+    if removed > 0 {
+        conn.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+    }
+    log::info!("Pruned {} index entries", removed);
+    if cancelled {
+        return Err(control::CANCELLED.to_string());
+    }
+    Ok(removed)
+}
+
+// Up to BATCH rows of `files` (id, path) following the row `last_id`
+fn next_batch(conn: &Connection, last_id: i64) -> Result<Vec<(i64, String)>, String> {
+    let mut stmt = conn.prepare(
+        "SELECT id, path FROM files WHERE id > ?1 ORDER BY id LIMIT ?2"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![last_id, BATCH], |row| Ok((row.get(0)?, row.get(1)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+    Ok(rows)
+}
+
+// Remove the applications whose program is gone. Entries that aren't file paths (commands
+// like explorer.exe, URIs like ms-settings:) are kept.
+fn prune_applications(conn: &Connection) -> Result<usize, String> {
+    let mut stmt = conn.prepare("SELECT id, path, name FROM applications").map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map([], |row| Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?)))
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    let mut removed = 0;
+    for (id, path, name) in rows {
+        if is_gone(Path::new(&path)) {
+            remove_application(conn, id, &name)?;
+            removed += 1;
+        }
+    }
+    Ok(removed)
+}
+
+// Take `path`, and everything below it, out of both tables right away: used when opening a
+// result finds its target gone
+pub fn forget(conn: &Connection, path: &Path) -> Result<usize, String> {
+    let mut removed = watcher::remove(conn, path)?;
+    let application: Option<(i64, String)> = conn.query_row(
+        "SELECT id, name FROM applications WHERE path = ?1",
+        params![path.to_string_lossy()],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().map_err(|e| e.to_string())?;
+    if let Some((id, name)) = application {
+        remove_application(conn, id, &name)?;
+        removed += 1;
+    }
+    conn.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+    Ok(removed)
+}
+
+// Whether `path` is a file system path with nothing behind it any more
+pub fn is_gone(path: &Path) -> bool {
+    path.is_absolute() && std::fs::symlink_metadata(path).is_err()
+}
+
+fn remove_application(conn: &Connection, id: i64, name: &str) -> Result<(), String> {
+    name_index::forget(conn, "applications", id, name)?;
+    conn.execute("DELETE FROM applications WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+    Ok(())
+}
@@ -0,0 +1,213 @@
+// Filter operators typed into the search box next to the words searched for:
+//
+//   type:app, type:file, type:folder   kind of entry; type:file,folder for either
+//   ext:pdf, ext:jpg,png               extension
+//   in:Downloads, in:C:\Projects       below a folder of that name, or below that path
+//   size>10mb, size<1gb                file size in b, kb, mb, gb or tb
+//   modified<7d, modified>1y           changed within (or not for) h, d, w, m (months), y
+//
+// Anything else, words that merely contain a colon included, is searched for as before.
+
+use rusqlite::types::Value;
+
+pub struct Query {
+    // The words to match names against, filters taken out
+    pub text: String,
+    pub filters: Filters,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Kind {
+    App,
+    File,
+    Folder,
+}
+
+#[derive(Debug, Default)]
+pub struct Filters {
+    kinds: Vec<Kind>,
+    extensions: Vec<String>,
+    folders: Vec<String>,
+    min_size: Option<i64>,
+    max_size: Option<i64>,
+    // Seconds since 1970
+    modified_after: Option<i64>,
+    modified_before: Option<i64>,
+}
+
+// Split `input` into filters and words; `now` is what ages like "7d" count back from
+pub fn parse(input: &str, now: i64) -> Query {
+    let mut filters = Filters::default();
+    let mut words = Vec::new();
+    for word in input.split_whitespace() {
+        if !filters.take(word, now) {
+            words.push(word);
+        }
+    }
+    Query { text: words.join(" "), filters }
+}
+
+impl Filters {
+    // Apply `word` if it is a filter; false if it is a word to search for
+    fn take(&mut self, word: &str, now: i64) -> bool {
+        if let Some((key, value)) = word.split_once(':') {
+            let values = || value.split(',').filter(|v| !v.is_empty());
+            match key.to_lowercase().as_str() {
+                "type" => {
+                    let kinds: Option<Vec<Kind>> = values().map(kind).collect();
+                    match kinds {
+                        Some(kinds) if !kinds.is_empty() => self.kinds.extend(kinds),
+                        _ => return false,
+                    }
+                }
+                "ext" => self.extensions.extend(values().map(|v| v.trim_start_matches('.').to_lowercase())),
+                "in" if !value.is_empty() => self.folders.push(value.to_string()),
+                _ => return false,
+            }
+            return true;
+        }
+
+        let Some((key, greater, value)) = comparison(word) else {
+            return false;
+        };
+        match key.as_str() {
+            "size" => match size(value) {
+                Some(bytes) if greater => self.min_size = Some(bytes),
+                Some(bytes) => self.max_size = Some(bytes),
+                None => return false,
+            },
+            // "modified<7d": the change is less than 7 days old, so after now - 7 days
+            "modified" => match age(value) {
+                Some(secs) if greater => self.modified_before = Some(now - secs),
+                Some(secs) => self.modified_after = Some(now - secs),
+                None => return false,
+            },
+            _ => return false,
+        }
+        true
+    }
+
+    // SQL condition on the row `t` of `table` ("files" or "applications") and the values
+    // it binds, numbered from `first`
+    pub fn sql(&self, table: &str, first: usize) -> (String, Vec<Value>) {
+        let mut conditions: Vec<String> = Vec::new();
+        let mut values: Vec<Value> = Vec::new();
+        let bind = |value: Value, values: &mut Vec<Value>| -> String {
+            values.push(value);
+            format!("?{}", first + values.len() - 1)
+        };
+        let is_files = table == "files";
+
+        if !self.kinds.is_empty() {
+            let kinds: Vec<&str> = self.kinds.iter().map(|kind| match (kind, is_files) {
+                (Kind::App, true) => "t.is_app",
+                (Kind::File, true) => "(t.is_file AND NOT t.is_app)",
+                (Kind::Folder, true) => "NOT t.is_file",
+                (Kind::App, false) => "1",
+                (_, false) => "0",
+            }).collect();
+            conditions.push(format!("({})", kinds.join(" OR ")));
+        }
+        if !self.extensions.is_empty() {
+            // Applications are named without their extension; their path has it
+            let column = if is_files { "t.name" } else { "t.path" };
+            let any: Vec<String> = self.extensions.iter().map(|ext| {
+                format!("{} LIKE {} ESCAPE '\\'", column, bind(Value::Text(format!("%.{}", escape_like(ext))), &mut values))
+            }).collect();
+            conditions.push(format!("({})", any.join(" OR ")));
+        }
+        for folder in &self.folders {
+            let separator = std::path::MAIN_SEPARATOR.to_string();
+            let folder = folder.trim_end_matches(['/', '\\']);
+            // A full path is a prefix; a single name may be any folder along the path
+            let pattern = if std::path::Path::new(folder).is_absolute() {
+                format!("{}{}%", escape_like(folder), escape_like(&separator))
+            } else {
+                let separator = escape_like(&separator);
+                format!("%{}{}{}%", separator, escape_like(folder), separator)
+            };
+            conditions.push(format!("t.path LIKE {} ESCAPE '\\'", bind(Value::Text(pattern), &mut values)));
+        }
+        let numeric = [
+            ("t.size >=", self.min_size),
+            ("t.size <=", self.max_size),
+            ("t.modified >=", self.modified_after),
+            ("t.modified <=", self.modified_before),
+        ];
+        for (comparison, limit) in numeric {
+            if let Some(limit) = limit {
+                // Applications have no size or modification time to compare
+                conditions.push(if is_files {
+                    format!("{} {}", comparison, bind(Value::Integer(limit), &mut values))
+                } else {
+                    "0".to_string()
+                });
+            }
+        }
+
+        if conditions.is_empty() {
+            return ("1".to_string(), values);
+        }
+        (conditions.join(" AND "), values)
+    }
+}
+
+fn kind(value: &str) -> Option<Kind> {
+    match value.to_lowercase().as_str() {
+        "app" | "apps" => Some(Kind::App),
+        "file" | "files" => Some(Kind::File),
+        "folder" | "folders" | "dir" => Some(Kind::Folder),
+        _ => None,
+    }
+}
+
+// "size>10mb" -> ("size", true, "10mb"); ">=" and "<=" read as ">" and "<"
+fn comparison(word: &str) -> Option<(String, bool, &str)> {
+    let at = word.find(['<', '>'])?;
+    let greater = word[at..].starts_with('>');
+    let value = word[at + 1..].trim_start_matches('=');
+    Some((word[..at].to_lowercase(), greater, value))
+}
+
+// "10mb" -> bytes
+fn size(value: &str) -> Option<i64> {
+    let split = value.find(|c: char| !c.is_ascii_digit() && c != '.').unwrap_or(value.len());
+    let number: f64 = value[..split].parse().ok()?;
+    let unit: i64 = match value[split..].to_lowercase().as_str() {
+        "" | "b" => 1,
+        "k" | "kb" => 1 << 10,
+        "m" | "mb" => 1 << 20,
+        "g" | "gb" => 1 << 30,
+        "t" | "tb" => 1 << 40,
+        _ => return None,
+    };
+    Some((number * unit as f64) as i64)
+}
+
+// "7d" -> seconds
+fn age(value: &str) -> Option<i64> {
+    let split = value.find(|c: char| !c.is_ascii_digit()).unwrap_or(value.len());
+    let number: i64 = value[..split].parse().ok()?;
+    let unit: i64 = match value[split..].to_lowercase().as_str() {
+        "h" => 60 * 60,
+        "" | "d" => 24 * 60 * 60,
+        "w" => 7 * 24 * 60 * 60,
+        "m" => 30 * 24 * 60 * 60,
+        "y" => 365 * 24 * 60 * 60,
+        _ => return None,
+    };
+    // An age too long to count in seconds isn't a filter either
+    number.checked_mul(unit)
+}
+
+// `text` with LIKE's wildcards (and the escape character) escaped by '\'
+fn escape_like(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        if matches!(c, '%' | '_' | '\\') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
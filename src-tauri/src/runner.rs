@@ -0,0 +1,286 @@
+// Shell commands typed after `>`: run by the shell in preferences.shell (or the system's:
+// cmd.exe, $SHELL, /bin/sh), either in a new terminal window that stays open, or hidden,
+// with the output captured for the launcher to show. A captured command is stopped after
+// CAPTURE_TIMEOUT, and only the first OUTPUT_LIMIT bytes of each stream are kept.
+
+use std::io::Read;
+use std::path::Path;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+
+use serde::Serialize;
+
+const CAPTURE_TIMEOUT: Duration = Duration::from_secs(30);
+const OUTPUT_LIMIT: usize = 64 * 1024;
+// How long output is still read once the command has ended
+const OUTPUT_GRACE: Duration = Duration::from_millis(500);
+
+#[derive(Debug, Serialize)]
+pub struct Output {
+    // None when the command was stopped, or killed by a signal
+    pub exit_code: Option<i32>,
+    pub stdout: String,
+    pub stderr: String,
+    pub timed_out: bool,
+}
+
+// The shell `configured`, or the system's when that's empty
+pub fn shell(configured: &str) -> String {
+    let configured = configured.trim();
+    if !configured.is_empty() {
+        return configured.to_string();
+    }
+    system::default_shell()
+}
+
+// Run `command` in a terminal window of its own, which stays open once it's done
+pub fn in_terminal(shell: &str, command: &str) -> Result<(), String> {
+    system::in_terminal(shell, command).map_err(|e| format!("Could not open a terminal: {}", e))
+}
+
+// Run `command` without a window and wait for its output
+pub fn capture(shell: &str, command: &str) -> Result<Output, String> {
+    let mut child = shell_command(shell, command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|e| format!("Could not run {}: {}", shell, e))?;
+    // Read as it comes, or a command writing more than a pipe holds would never finish
+    let stdout = drain(child.stdout.take());
+    let stderr = drain(child.stderr.take());
+
+    let started = Instant::now();
+    let (status, timed_out) = loop {
+        if let Some(status) = child.try_wait().map_err(|e| e.to_string())? {
+            break (Some(status), false);
+        }
+        if started.elapsed() > CAPTURE_TIMEOUT {
+            stop(&mut child);
+            break (None, true);
+        }
+        std::thread::sleep(Duration::from_millis(50));
+    };
+    // Something the command left running in the background can hold the streams open
+    let finished = Instant::now();
+    while !(stdout.0.is_finished() && stderr.0.is_finished()) && finished.elapsed() < OUTPUT_GRACE {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    let text = |(_, kept): &Drain| String::from_utf8_lossy(&kept.lock().unwrap_or_else(|e| e.into_inner())).into_owned();
+    Ok(Output { exit_code: status.and_then(|status| status.code()), stdout: text(&stdout), stderr: text(&stderr), timed_out })
+}
+
+fn stop(child: &mut Child) {
+    let _ = child.kill();
+    let _ = child.wait();
+}
+
+// A thread reading a stream, and what it has kept so far
+type Drain = (JoinHandle<()>, Arc<Mutex<Vec<u8>>>);
+
+// Read `stream` until it closes, keeping its first OUTPUT_LIMIT bytes
+fn drain(stream: Option<impl Read + Send + 'static>) -> Drain {
+    let kept = Arc::new(Mutex::new(Vec::new()));
+    let keeping = kept.clone();
+    let reader = std::thread::spawn(move || {
+        let Some(mut stream) = stream else {
+            return;
+        };
+        let mut buffer = [0u8; 8192];
+        while let Ok(read) = stream.read(&mut buffer) {
+            if read == 0 {
+                break;
+            }
+            let Ok(mut kept) = keeping.lock() else {
+                break;
+            };
+            let room = OUTPUT_LIMIT - kept.len();
+            kept.extend_from_slice(&buffer[..read.min(room)]);
+        }
+    });
+    (reader, kept)
+}
+
+// Lowercase file name of `shell` without its extension, to tell shells apart by
+fn shell_name(shell: &str) -> String {
+    Path::new(shell).file_stem().map(|name| name.to_string_lossy().to_lowercase()).unwrap_or_default()
+}
+
+// `shell` running `command` and exiting, in the home folder
+fn shell_command(shell: &str, command: &str) -> Command {
+    let mut process = Command::new(shell);
+    match shell_name(shell).as_str() {
+        // cmd.exe parses its command line itself; quoting it would change the command
+        #[cfg(windows)]
+        "cmd" => {
+            use std::os::windows::process::CommandExt;
+            process.arg("/C").raw_arg(command);
+        }
+        "powershell" | "pwsh" => {
+            process.args(["-NoProfile", "-Command", command]);
+        }
+        _ => {
+            process.args(["-c", command]);
+        }
+    }
+    if let Some(home) = system::home() {
+        process.current_dir(home);
+    }
+    system::hide_window(&mut process);
+    process
+}
+
+#[cfg(windows)]
+mod system {
+    use std::os::windows::process::CommandExt;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    use super::shell_name;
+
+    // Process creation flags
+    const CREATE_NEW_CONSOLE: u32 = 0x0000_0010;
+    const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+    pub fn default_shell() -> String {
+        std::env::var("ComSpec").unwrap_or_else(|_| "cmd.exe".to_string())
+    }
+
+    pub fn home() -> Option<PathBuf> {
+        std::env::var_os("USERPROFILE").map(PathBuf::from)
+    }
+
+    pub fn hide_window(process: &mut Command) {
+        process.creation_flags(CREATE_NO_WINDOW);
+    }
+
+    // A console window of its own, kept open by the shell once the command is done
+    pub fn in_terminal(shell: &str, command: &str) -> Result<(), String> {
+        let mut process = Command::new(shell);
+        match shell_name(shell).as_str() {
+            "cmd" => {
+                process.arg("/K").raw_arg(command);
+            }
+            "powershell" | "pwsh" => {
+                process.args(["-NoExit", "-Command", command]);
+            }
+            _ => {
+                process.args(["-c", command]);
+            }
+        }
+        if let Some(home) = home() {
+            process.current_dir(home);
+        }
+        process.creation_flags(CREATE_NEW_CONSOLE).spawn().map_err(|e| e.to_string())?;
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    // Type argv[1] into a new Terminal window, which runs it in the user's login shell
+    const DO_SCRIPT: &str = r#"
+        on run argv
+            tell application "Terminal"
+                do script (item 1 of argv)
+                activate
+            end tell
+        end run"#;
+
+    pub fn default_shell() -> String {
+        std::env::var("SHELL").ok().filter(|shell| !shell.is_empty()).unwrap_or_else(|| "/bin/zsh".to_string())
+    }
+
+    pub fn home() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(PathBuf::from)
+    }
+
+    pub fn hide_window(_process: &mut Command) {}
+
+    pub fn in_terminal(shell: &str, command: &str) -> Result<(), String> {
+        let script = format!("{} -c {}", quote(shell), quote(command));
+        let output = Command::new("osascript").arg("-e").arg(DO_SCRIPT).arg(script).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+
+    // `text` in single quotes for a POSIX shell
+    fn quote(text: &str) -> String {
+        format!("'{}'", text.replace('\'', r"'\''"))
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::env;
+    use std::io::ErrorKind;
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    // Tried in order when $TERMINAL isn't set
+    const TERMINALS: &[&str] = &["x-terminal-emulator", "gnome-terminal", "konsole", "xfce4-terminal", "alacritty", "kitty", "xterm"];
+
+    pub fn default_shell() -> String {
+        env::var("SHELL").ok().filter(|shell| !shell.is_empty()).unwrap_or_else(|| "/bin/sh".to_string())
+    }
+
+    pub fn home() -> Option<PathBuf> {
+        env::var_os("HOME").map(PathBuf::from)
+    }
+
+    pub fn hide_window(_process: &mut Command) {}
+
+    // The shell runs the command, then carries on interactively so the window stays open
+    pub fn in_terminal(shell: &str, command: &str) -> Result<(), String> {
+        let script = format!("{}; exec \"$0\"", command);
+        let preferred = env::var("TERMINAL").ok().filter(|terminal| !terminal.is_empty());
+        for terminal in preferred.iter().map(String::as_str).chain(TERMINALS.iter().copied()) {
+            let mut process = Command::new(terminal);
+            // Each wants the rest of its arguments marked as the command differently
+            match terminal.rsplit('/').next().unwrap_or(terminal) {
+                "gnome-terminal" => process.arg("--"),
+                "xfce4-terminal" => process.arg("-x"),
+                "kitty" => &mut process,
+                _ => process.arg("-e"),
+            };
+            process.args([shell, "-c", &script]);
+            if let Some(home) = home() {
+                process.current_dir(home);
+            }
+            match process.spawn() {
+                Ok(_) => return Ok(()),
+                Err(e) if e.kind() == ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.to_string()),
+            }
+        }
+        Err("no terminal was found; set $TERMINAL".to_string())
+    }
+}
+
+// Other systems: commands run captured only
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use std::path::PathBuf;
+    use std::process::Command;
+
+    pub fn default_shell() -> String {
+        "/bin/sh".to_string()
+    }
+
+    pub fn home() -> Option<PathBuf> {
+        std::env::var_os("HOME").map(PathBuf::from)
+    }
+
+    pub fn hide_window(_process: &mut Command) {}
+
+    pub fn in_terminal(_shell: &str, _command: &str) -> Result<(), String> {
+        Err("terminals aren't supported on this system".to_string())
+    }
+}
@@ -0,0 +1,286 @@
+// Background re-indexing. The watcher misses changes made while the app wasn't running or
+// while it was overwhelmed, so roots whose last index is older than the scheduled
+// interval are brought up to date again, and entries that are gone pruned, but only while
+// the computer is idle (and on AC power, if so configured), so the walk doesn't compete
+// with the user. A run stops at its next batch once the user is back.
+
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+
+use rusqlite::{Connection, params};
+use tauri::Manager;
+
+use crate::settings::{self, IndexConfig, ScheduleConfig};
+use crate::{AppState, control, frecency, progress, prune, update_root};
+
+// How often the scheduler looks whether a re-index is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+// How often a scheduled run looks again whether the computer is still idle
+const READY_RECHECK: Duration = Duration::from_secs(10);
+
+// Create the table holding when each root was last indexed
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS indexed_roots (
+            root TEXT PRIMARY KEY,
+            indexed_at INTEGER NOT NULL
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+// Record that `root` was indexed completely just now
+pub fn mark_indexed(conn: &Connection, root: &Path) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO indexed_roots (root, indexed_at) VALUES (?1, ?2)",
+        params![root.to_string_lossy(), frecency::now()],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Configured roots not indexed within `interval`, oldest first
+fn stale_roots(conn: &Connection, config: &IndexConfig, interval: Duration) -> Result<Vec<PathBuf>, String> {
+    let cutoff = frecency::now() - interval.as_secs() as i64;
+    let mut stale = Vec::new();
+    for root in &config.roots {
+        let indexed_at: i64 = conn.query_row(
+            "SELECT COALESCE(MAX(indexed_at), 0) FROM indexed_roots WHERE root = ?1",
+            params![root.to_string_lossy()],
+            |row| row.get(0),
+        ).map_err(|e| e.to_string())?;
+        if indexed_at < cutoff {
+            stale.push((indexed_at, root.clone()));
+        }
+    }
+    stale.sort();
+    Ok(stale.into_iter().map(|(_, root)| root).collect())
+}
+
+// Check for stale roots every CHECK_INTERVAL on a background thread, for as long as the
+// app runs
+pub fn start(app: tauri::AppHandle) {
+    std::thread::spawn(move || {
+        // After a cancel, nothing is scheduled again before this
+        let mut held_until: Option<Instant> = None;
+        loop {
+            std::thread::sleep(CHECK_INTERVAL);
+
+            let (schedule, stale) = {
+                let state = app.state::<AppState>();
+                let Ok(conn) = state.db.lock() else {
+                    return;
+                };
+                let schedule: ScheduleConfig = settings::load(&conn, settings::SCHEDULE);
+                let config: IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+                match stale_roots(&conn, &config, schedule.interval()) {
+                    Ok(stale) => (schedule, stale),
+                    Err(e) => {
+                        log::warn!("Could not check for stale index roots: {}", e);
+                        continue;
+                    }
+                }
+            };
+
+            let control = app.state::<control::Control>();
+            if control.is_cancelled() && held_until.is_none() {
+                // The user stopped indexing; don't start it again right away
+                held_until = Some(Instant::now() + schedule.interval());
+            }
+            if held_until.is_some_and(|until| Instant::now() < until) {
+                continue;
+            }
+            if !schedule.enabled
+                || stale.is_empty()
+                || control.is_paused()
+                || progress::status(&app).phase != progress::Phase::Idle
+                || !ready(&schedule)
+            {
+                continue;
+            }
+
+            held_until = None;
+            control.start();
+            // Stop when the user comes back, also in the middle of a root; the rest waits
+            // for the next idle time
+            control.stop_when(Some(Box::new(still_ready(schedule.clone()))));
+            for root in stale {
+                if control.is_cancelled() || !ready(&schedule) {
+                    break;
+                }
+                match update_root(&root, &app) {
+                    Ok(count) => log::info!("Scheduled re-index of {}: {} entries", root.display(), count),
+                    Err(e) if e == control::CANCELLED => {
+                        log::info!("Scheduled re-index of {} stopped before it was done", root.display());
+                    }
+                    Err(e) => log::warn!("Scheduled re-index of {} failed: {}", root.display(), e),
+                }
+            }
+            // Re-indexing only adds; take out what has gone since
+            if !control.is_cancelled() && ready(&schedule) {
+                if let Err(e) = prune::prune(&app) {
+                    log::warn!("Scheduled prune of the index failed: {}", e);
+                }
+            }
+            control.stop_when(None);
+        }
+    });
+}
+
+// Whether the computer is idle enough (and powered) for a scheduled run. Where the idle
+// time can't be read, nothing is scheduled: the user may well be working. The same goes
+// for an unknown power source when runs wait for AC power.
+fn ready(schedule: &ScheduleConfig) -> bool {
+    let Some(idle) = system::idle_time() else {
+        log::debug!("The idle time is unknown, no scheduled re-index");
+        return false;
+    };
+    let powered = !schedule.ac_power_only || system::on_battery() == Some(false);
+    idle >= schedule.idle_time() && powered
+}
+
+// ready(), looked at no more than every READY_RECHECK while a scheduled run walks, since
+// finding the idle time can mean starting a program
+fn still_ready(schedule: ScheduleConfig) -> impl Fn() -> bool + Send {
+    let last = Mutex::new((Instant::now(), true));
+    move || {
+        let Ok(mut last) = last.lock() else {
+            return true;
+        };
+        if last.0.elapsed() >= READY_RECHECK {
+            *last = (Instant::now(), ready(&schedule));
+        }
+        last.1
+    }
+}
+
+#[cfg(windows)]
+mod system {
+    use std::time::Duration;
+
+    use windows_sys::Win32::System::Power::{GetSystemPowerStatus, SYSTEM_POWER_STATUS};
+    use windows_sys::Win32::System::SystemInformation::GetTickCount;
+    use windows_sys::Win32::UI::Input::KeyboardAndMouse::{GetLastInputInfo, LASTINPUTINFO};
+
+    // Time since the last keyboard or mouse input
+    pub fn idle_time() -> Option<Duration> {
+        let mut info = LASTINPUTINFO { cbSize: std::mem::size_of::<LASTINPUTINFO>() as u32, dwTime: 0 };
+        if unsafe { GetLastInputInfo(&mut info) } == 0 {
+            return None;
+        }
+        // Both are milliseconds since boot, wrapping every 49.7 days
+        let now = unsafe { GetTickCount() };
+        Some(Duration::from_millis(now.wrapping_sub(info.dwTime) as u64))
+    }
+
+    pub fn on_battery() -> Option<bool> {
+        let mut status = SYSTEM_POWER_STATUS::default();
+        if unsafe { GetSystemPowerStatus(&mut status) } == 0 {
+            return None;
+        }
+        match status.ACLineStatus {
+            0 => Some(true),
+            1 => Some(false),
+            _ => None, // Unknown
+        }
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    // Time since the last input: IOKit's HIDIdleTime, in nanoseconds, as ioreg lists it
+    pub fn idle_time() -> Option<Duration> {
+        let listing = output("ioreg", &["-c", "IOHIDSystem", "-d", "4"])?;
+        // |   "HIDIdleTime" = 1234567890
+        let line = listing.lines().find(|line| line.contains("\"HIDIdleTime\""))?;
+        let nanos = line.rsplit('=').next()?.trim().parse().ok()?;
+        Some(Duration::from_nanos(nanos))
+    }
+
+    // pmset names the source first: "Now drawing from 'AC Power'" or "'Battery Power'"
+    pub fn on_battery() -> Option<bool> {
+        let status = output("pmset", &["-g", "batt"])?;
+        if status.contains("'Battery Power'") {
+            Some(true)
+        } else if status.contains("'AC Power'") {
+            Some(false)
+        } else {
+            None
+        }
+    }
+
+    fn output(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).stderr(Stdio::null()).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::fs;
+    use std::process::{Command, Stdio};
+    use std::time::Duration;
+
+    // Asks GNOME's idle monitor, which also works on Wayland
+    const MUTTER_IDLE: &[&str] = &[
+        "call", "--session",
+        "--dest", "org.gnome.Mutter.IdleMonitor",
+        "--object-path", "/org/gnome/Mutter/IdleMonitor/Core",
+        "--method", "org.gnome.Mutter.IdleMonitor.GetIdletime",
+    ];
+
+    // Time since the last input: from the X screensaver extension through xprintidle,
+    // otherwise from GNOME. Both answer in milliseconds.
+    pub fn idle_time() -> Option<Duration> {
+        let millis = output("xprintidle", &[])
+            .and_then(|out| out.trim().parse().ok())
+            .or_else(|| {
+                // "(uint64 12345,)"
+                let reply = output("gdbus", MUTTER_IDLE)?;
+                reply.trim().trim_start_matches("(uint64 ").trim_end_matches(",)").parse().ok()
+            })?;
+        Some(Duration::from_millis(millis))
+    }
+
+    // From /sys/class/power_supply: on AC while a mains supply is online. Without one that
+    // says so, on battery when a battery is discharging; a computer with neither is on AC.
+    pub fn on_battery() -> Option<bool> {
+        let supplies = fs::read_dir("/sys/class/power_supply").ok()?;
+        let mut mains_offline = false;
+        let mut discharging = false;
+        for supply in supplies.flatten() {
+            let read = |name: &str| {
+                fs::read_to_string(supply.path().join(name)).map(|value| value.trim().to_string()).unwrap_or_default()
+            };
+            match read("type").as_str() {
+                "Mains" if read("online") == "1" => return Some(false),
+                "Mains" => mains_offline = true,
+                "Battery" => discharging |= read("status") == "Discharging",
+                _ => {}
+            }
+        }
+        Some(mains_offline || discharging)
+    }
+
+    fn output(program: &str, args: &[&str]) -> Option<String> {
+        let output = Command::new(program).args(args).stderr(Stdio::null()).output().ok()?;
+        output.status.success().then(|| String::from_utf8_lossy(&output.stdout).into_owned())
+    }
+}
+
+// Other systems: idle time and power source are unknown, so nothing is scheduled
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use std::time::Duration;
+
+    pub fn idle_time() -> Option<Duration> {
+        None
+    }
+
+    pub fn on_battery() -> Option<bool> {
+        None
+    }
+}
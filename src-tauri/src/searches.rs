@@ -0,0 +1,46 @@
+// Search as you type: every keystroke starts a search, and only the newest one matters.
+// Each search takes the next generation number; once a newer one has started, the older
+// search stops at its next check (between pages of database work and between scoring
+// batches) and returns SUPERSEDED instead of results that would overwrite fresher ones.
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+// Error returned by a search a newer one has replaced
+pub const SUPERSEDED: &str = "Search superseded by a newer one";
+
+// Generation of the newest search, managed by the app
+#[derive(Default)]
+pub struct Searches(Arc<AtomicU64>);
+
+impl Searches {
+    pub fn begin(&self) -> Token {
+        let generation = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+        Token { latest: Arc::clone(&self.0), generation }
+    }
+}
+
+#[derive(Clone)]
+pub struct Token {
+    latest: Arc<AtomicU64>,
+    generation: u64,
+}
+
+impl Token {
+    pub fn superseded(&self) -> bool {
+        self.latest.load(Ordering::SeqCst) != self.generation
+    }
+
+    pub fn check(&self) -> Result<(), String> {
+        if self.superseded() {
+            return Err(SUPERSEDED.to_string());
+        }
+        Ok(())
+    }
+
+    // The error to report for `error`: a query interrupted because the search was
+    // superseded reports that instead
+    pub fn explain(&self, error: String) -> String {
+        if self.superseded() { SUPERSEDED.to_string() } else { error }
+    }
+}
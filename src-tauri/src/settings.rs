@@ -0,0 +1,299 @@
+// User settings, kept in the `settings` table as one JSON value per key. Anything never
+// saved (or saved by a version that stored it differently) reads as its default.
+
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+
+use glob::{MatchOptions, Pattern};
+use rusqlite::{Connection, OptionalExtension, params};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use crate::web::{self, SearchEngine};
+
+// Key of the index configuration
+pub const INDEX_CONFIG: &str = "index_config";
+// Key of the background re-index schedule
+pub const SCHEDULE: &str = "schedule";
+// Key of the global shortcut showing the launcher, an accelerator string
+pub const HOTKEY: &str = "hotkey";
+// Key of the preferences: look, result limits, caching, launch behavior, web search
+pub const PREFERENCES: &str = "preferences";
+
+// Create the settings table
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS settings (
+            key TEXT PRIMARY KEY,
+            value TEXT NOT NULL
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+// The value saved under `key`, or the default
+pub fn load<T: DeserializeOwned + Default>(conn: &Connection, key: &str) -> T {
+    let value: Option<String> = conn.query_row(
+        "SELECT value FROM settings WHERE key = ?1",
+        params![key],
+        |row| row.get(0),
+    ).optional().ok().flatten();
+    value.and_then(|value| serde_json::from_str(&value).ok()).unwrap_or_default()
+}
+
+pub fn save<T: Serialize>(conn: &Connection, key: &str, value: &T) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO settings (key, value) VALUES (?1, ?2)",
+        params![key, serde_json::to_string(value).map_err(|e| e.to_string())?],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// What gets indexed: the folders walked, how deep below them, and entries left out
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct IndexConfig {
+    pub roots: Vec<PathBuf>,
+    // Glob patterns ("node_modules", "*.tmp", "C:\Users\*\AppData"). A pattern without a
+    // path separator is matched against each name in a path, one with a separator against
+    // the whole path; case is ignored. Excluded folders aren't walked at all.
+    pub excludes: Vec<String>,
+    pub max_depth: usize,
+}
+
+impl Default for IndexConfig {
+    fn default() -> Self {
+        IndexConfig {
+            roots: vec![PathBuf::from("C:\\Users"), PathBuf::from("C:\\Program Files")],
+            excludes: Vec::new(),
+            max_depth: 5,
+        }
+    }
+}
+
+impl IndexConfig {
+    // Check a configuration sent by the frontend, tidying the roots: each has to be an
+    // existing folder, and the same folder listed twice is kept once
+    pub fn validate(mut self) -> Result<IndexConfig, String> {
+        if self.max_depth == 0 {
+            return Err("The maximum depth must be at least 1".to_string());
+        }
+        let mut roots: Vec<PathBuf> = Vec::new();
+        for root in self.roots {
+            if !root.is_dir() {
+                return Err(format!("{} is not a folder", root.display()));
+            }
+            if !roots.contains(&root) {
+                roots.push(root);
+            }
+        }
+        self.roots = roots;
+        self.excludes.retain(|pattern| !pattern.trim().is_empty());
+        for pattern in &self.excludes {
+            Pattern::new(pattern).map_err(|e| format!("Invalid exclude pattern {}: {}", pattern, e))?;
+        }
+        Ok(self)
+    }
+
+    // The exclude patterns, compiled for matching many paths
+    pub fn excludes(&self) -> Excludes {
+        Excludes(self.excludes.iter().filter_map(|pattern| Pattern::new(pattern).ok()).collect())
+    }
+
+    // Whether `path` lies below one of the roots
+    pub fn covers(&self, path: &Path) -> bool {
+        self.depth(path).is_some()
+    }
+
+    // Depth of `path` below the root that contains it
+    pub fn depth(&self, path: &Path) -> Option<usize> {
+        self.roots
+            .iter()
+            .filter_map(|root| path.strip_prefix(root).ok())
+            .map(|relative| relative.components().count())
+            .min()
+    }
+
+    // Whether the index is meant to hold `path`: below a root, not too deep, not excluded
+    pub fn includes(&self, excludes: &Excludes, path: &Path) -> bool {
+        self.depth(path).is_some_and(|depth| depth <= self.max_depth) && !excludes.matches(path)
+    }
+}
+
+pub struct Excludes(Vec<Pattern>);
+
+impl Excludes {
+    // Whether `path` is excluded, itself or as part of an excluded folder
+    pub fn matches(&self, path: &Path) -> bool {
+        let options = MatchOptions { case_sensitive: false, ..MatchOptions::new() };
+        self.0.iter().any(|pattern| {
+            if pattern.as_str().contains(['/', '\\']) {
+                path.ancestors().any(|folder| pattern.matches_path_with(folder, options))
+            } else {
+                path.iter().any(|name| pattern.matches_with(&name.to_string_lossy(), options))
+            }
+        })
+    }
+}
+
+// Everything the settings screen edits at once: the preferences, the global shortcut and
+// the index configuration, each saved under its own key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Settings {
+    #[serde(flatten)]
+    pub preferences: Preferences,
+    pub hotkey: String,
+    pub index: IndexConfig,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Theme {
+    // Whatever the system uses
+    #[default]
+    System,
+    Light,
+    Dark,
+}
+
+// Preferences saved before a field existed read that field as its default
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    pub theme: Theme,
+    // Results per page sent to the frontend
+    pub result_limit: usize,
+    // Results ranked and cached per query; pages are cut from these
+    pub ranked_limit: usize,
+    // How long the ranking of a query is reused before searching again
+    pub cache_ttl_secs: u64,
+    pub launch: LaunchBehavior,
+    // Offered, in this order, to search the web when nothing local matches
+    pub search_engines: Vec<SearchEngine>,
+    // Index the pages visited in browsers along with their bookmarks
+    pub index_browser_history: bool,
+    pub clipboard: ClipboardHistory,
+    pub shell: ShellConfig,
+}
+
+impl Default for Preferences {
+    fn default() -> Self {
+        Preferences {
+            theme: Theme::System,
+            result_limit: 20,
+            ranked_limit: 200,
+            cache_ttl_secs: 5 * 60,
+            launch: LaunchBehavior::default(),
+            search_engines: web::default_engines(),
+            index_browser_history: false,
+            clipboard: ClipboardHistory::default(),
+            shell: ShellConfig::default(),
+        }
+    }
+}
+
+impl Preferences {
+    pub fn validate(self) -> Result<Preferences, String> {
+        if self.result_limit == 0 {
+            return Err("At least 1 result has to be shown per page".to_string());
+        }
+        if self.ranked_limit < self.result_limit {
+            return Err("At least a page of results has to be ranked".to_string());
+        }
+        for engine in &self.search_engines {
+            engine.validate()?;
+        }
+        if self.clipboard.max_items == 0 || self.clipboard.max_bytes == 0 {
+            return Err("The clipboard history has to keep at least 1 clip of 1 byte".to_string());
+        }
+        Ok(self)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LaunchBehavior {
+    // Hide the launcher once a result is opened
+    pub hide_after_open: bool,
+    // Keep the history of opened results that recent items come from
+    pub record_history: bool,
+}
+
+impl Default for LaunchBehavior {
+    fn default() -> Self {
+        LaunchBehavior { hide_after_open: true, record_history: true }
+    }
+}
+
+// Clipboard history, which is off until turned on
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClipboardHistory {
+    pub enabled: bool,
+    // Clips kept; the oldest go first
+    pub max_items: usize,
+    // Text longer than this (in UTF-8 bytes) isn't kept
+    pub max_bytes: usize,
+    // Programs whose copies aren't kept, by (part of) their name, ignoring case
+    pub excluded_programs: Vec<String>,
+}
+
+impl Default for ClipboardHistory {
+    fn default() -> Self {
+        let password_managers = ["1Password", "Bitwarden", "KeePass", "LastPass", "Dashlane", "Enpass", "Keeper", "Proton Pass"];
+        ClipboardHistory {
+            enabled: false,
+            max_items: 200,
+            max_bytes: 16 * 1024,
+            excluded_programs: password_managers.iter().map(|name| name.to_string()).collect(),
+        }
+    }
+}
+
+// How commands typed after `>` are run
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ShellConfig {
+    // The shell program ("pwsh", "/bin/zsh"); empty for the system's
+    pub program: String,
+    // Run in a terminal window that stays open, rather than with the output captured
+    pub in_terminal: bool,
+}
+
+impl Default for ShellConfig {
+    fn default() -> Self {
+        ShellConfig { program: String::new(), in_terminal: true }
+    }
+}
+
+// When roots are re-indexed in the background: once their last index is older than
+// `interval_hours`, as soon as nobody has used the keyboard or mouse for `idle_minutes`
+// (and, with `ac_power_only`, the computer isn't running on battery)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ScheduleConfig {
+    pub enabled: bool,
+    pub interval_hours: u64,
+    pub idle_minutes: u64,
+    pub ac_power_only: bool,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        ScheduleConfig { enabled: true, interval_hours: 24, idle_minutes: 5, ac_power_only: true }
+    }
+}
+
+impl ScheduleConfig {
+    pub fn validate(self) -> Result<ScheduleConfig, String> {
+        if self.interval_hours == 0 {
+            return Err("The re-index interval must be at least 1 hour".to_string());
+        }
+        Ok(self)
+    }
+
+    pub fn interval(&self) -> Duration {
+        Duration::from_secs(self.interval_hours * 60 * 60)
+    }
+
+    pub fn idle_time(&self) -> Duration {
+        Duration::from_secs(self.idle_minutes * 60)
+    }
+}
@@ -0,0 +1,264 @@
+// Reading Windows shortcut (.lnk) files, in the Shell Link format (MS-SHLLINK), for what
+// they point at: the program, its arguments and working directory, and the icon. Only
+// the parts needed for that are read; the rest of the file is skipped.
+
+use std::path::Path;
+
+// LinkFlags
+const HAS_TARGET_ID_LIST: u32 = 1 << 0;
+const HAS_LINK_INFO: u32 = 1 << 1;
+const HAS_NAME: u32 = 1 << 2;
+const HAS_RELATIVE_PATH: u32 = 1 << 3;
+const HAS_WORKING_DIR: u32 = 1 << 4;
+const HAS_ARGUMENTS: u32 = 1 << 5;
+const HAS_ICON_LOCATION: u32 = 1 << 6;
+const IS_UNICODE: u32 = 1 << 7;
+const HAS_EXP_STRING: u32 = 1 << 9;
+const HAS_EXP_ICON: u32 = 1 << 14;
+
+// LinkInfoFlags
+const VOLUME_ID_AND_LOCAL_BASE_PATH: u32 = 1 << 0;
+const COMMON_NETWORK_RELATIVE_LINK: u32 = 1 << 1;
+
+// Signatures of the extra data blocks holding paths with environment variables
+const ENVIRONMENT_BLOCK: u32 = 0xA000_0001;
+const ICON_ENVIRONMENT_BLOCK: u32 = 0xA000_0007;
+
+const HEADER_SIZE: usize = 0x4C;
+
+#[derive(Debug, Default)]
+pub struct Shortcut {
+    // None for shortcuts that only name a shell item (installer-advertised programs,
+    // Store apps); those are launched through the .lnk itself
+    pub target: Option<String>,
+    pub arguments: Option<String>,
+    pub working_dir: Option<String>,
+    // "path" or "path,index", as in Windows icon locations
+    pub icon: Option<String>,
+}
+
+// Read the shortcut at `path`, with environment variables in its paths expanded
+pub fn read(path: &Path) -> Result<Shortcut, String> {
+    let data = std::fs::read(path).map_err(|e| e.to_string())?;
+    let mut shortcut = parse(&data).ok_or_else(|| format!("{} is not a valid shortcut", path.display()))?;
+    // A relative target is relative to the folder holding the shortcut
+    if let (Some(target), Some(folder)) = (&shortcut.target, path.parent()) {
+        if Path::new(target).is_relative() {
+            shortcut.target = Some(folder.join(target).to_string_lossy().into_owned());
+        }
+    }
+    Ok(shortcut)
+}
+
+fn parse(data: &[u8]) -> Option<Shortcut> {
+    let mut reader = Reader { data, at: 0 };
+    if reader.u32()? as usize != HEADER_SIZE {
+        return None;
+    }
+    reader.at = 0x14;
+    let flags = reader.u32()?;
+    reader.at = 0x38;
+    let icon_index = reader.u32()? as i32;
+    reader.at = HEADER_SIZE;
+
+    if flags & HAS_TARGET_ID_LIST != 0 {
+        let size = reader.u16()? as usize;
+        reader.skip(size)?;
+    }
+    let mut linked = None;
+    if flags & HAS_LINK_INFO != 0 {
+        let start = reader.at;
+        let size = reader.u32()? as usize;
+        linked = link_info(reader.slice(start, size)?);
+        reader.at = start + size;
+    }
+
+    let unicode = flags & IS_UNICODE != 0;
+    let mut string = |flag: u32| -> Option<Option<String>> {
+        if flags & flag == 0 {
+            return Some(None);
+        }
+        Some(Some(reader.counted_string(unicode)?))
+    };
+    let _name = string(HAS_NAME)?;
+    let relative = string(HAS_RELATIVE_PATH)?;
+    let working_dir = string(HAS_WORKING_DIR)?;
+    let arguments = string(HAS_ARGUMENTS)?;
+    let icon_location = string(HAS_ICON_LOCATION)?;
+
+    // Extra data blocks, until a terminal block smaller than a block header
+    let mut expanded_target = None;
+    let mut expanded_icon = None;
+    while let Some(size) = reader.u32().map(|size| size as usize) {
+        if size < 8 {
+            break;
+        }
+        let start = reader.at - 4;
+        let signature = reader.u32()?;
+        if signature == ENVIRONMENT_BLOCK || signature == ICON_ENVIRONMENT_BLOCK {
+            // 260 bytes of ANSI path, then 520 bytes of the same path in UTF-16
+            let unicode = reader.slice(start + 8 + 260, 520).map(utf16_until_nul);
+            let ansi = reader.slice(start + 8, 260).map(ansi_until_nul);
+            let value = unicode.filter(|s| !s.is_empty()).or(ansi).filter(|s| !s.is_empty());
+            if signature == ENVIRONMENT_BLOCK {
+                expanded_target = value;
+            } else {
+                expanded_icon = value;
+            }
+        }
+        reader.at = start + size;
+    }
+
+    // The environment block keeps the path as written (%ProgramFiles%\...), which holds on
+    // other machines too; the link info has it as resolved when the shortcut was made
+    let target = expanded_target
+        .filter(|_| flags & HAS_EXP_STRING != 0)
+        .or(linked)
+        .or(relative)
+        .map(|target| expand_env(&target));
+    let icon = expanded_icon
+        .filter(|_| flags & HAS_EXP_ICON != 0)
+        .or(icon_location)
+        .filter(|icon| !icon.is_empty())
+        .map(|icon| match icon_index {
+            0 => expand_env(&icon),
+            index => format!("{},{}", expand_env(&icon), index),
+        });
+
+    Some(Shortcut {
+        target,
+        arguments: arguments.filter(|a| !a.is_empty()),
+        working_dir: working_dir.filter(|w| !w.is_empty()).map(|w| expand_env(&w)),
+        icon,
+    })
+}
+
+// The target path held by a LinkInfo structure: a local path, or a network share path,
+// each followed by its common path suffix
+fn link_info(info: &[u8]) -> Option<String> {
+    let mut reader = Reader { data: info, at: 4 };
+    let header_size = reader.u32()? as usize;
+    let flags = reader.u32()?;
+    let _volume_id = reader.u32()?;
+    let local_base = reader.u32()? as usize;
+    let network_link = reader.u32()? as usize;
+    let suffix = reader.u32()? as usize;
+    // Newer shortcuts also have both paths in UTF-16
+    let (local_base_unicode, suffix_unicode) = if header_size >= 0x24 {
+        (Some(reader.u32()? as usize), Some(reader.u32()? as usize))
+    } else {
+        (None, None)
+    };
+    let text = |ansi: usize, unicode: Option<usize>| -> Option<String> {
+        match unicode {
+            Some(at) if at > 0 => info.get(at..).map(utf16_until_nul),
+            _ => info.get(ansi..).map(ansi_until_nul),
+        }
+    };
+    let suffix = text(suffix, suffix_unicode).unwrap_or_default();
+
+    let base = if flags & VOLUME_ID_AND_LOCAL_BASE_PATH != 0 {
+        text(local_base, local_base_unicode)?
+    } else if flags & COMMON_NETWORK_RELATIVE_LINK != 0 {
+        let link = info.get(network_link..)?;
+        let mut reader = Reader { data: link, at: 8 };
+        let net_name = reader.u32()? as usize;
+        let _device_name = reader.u32()?;
+        let _provider = reader.u32()?;
+        let net_name_unicode = if net_name > 0x14 { Some(reader.u32()? as usize) } else { None };
+        match net_name_unicode {
+            Some(at) => link.get(at..).map(utf16_until_nul)?,
+            None => link.get(net_name..).map(ansi_until_nul)?,
+        }
+    } else {
+        return None;
+    };
+
+    if suffix.is_empty() {
+        Some(base)
+    } else if base.ends_with('\\') {
+        Some(format!("{}{}", base, suffix))
+    } else {
+        Some(format!("{}\\{}", base, suffix))
+    }
+}
+
+// Replace %NAME% with the value of the environment variable NAME; unknown names are kept
+fn expand_env(text: &str) -> String {
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(start) = rest.find('%') {
+        let Some(length) = rest[start + 1..].find('%') else {
+            break;
+        };
+        let name = &rest[start + 1..start + 1 + length];
+        expanded.push_str(&rest[..start]);
+        match std::env::var(name) {
+            Ok(value) if !name.is_empty() => expanded.push_str(&value),
+            _ => expanded.push_str(&rest[start..start + length + 2]),
+        }
+        rest = &rest[start + length + 2..];
+    }
+    expanded.push_str(rest);
+    expanded
+}
+
+// Text up to the first NUL, as single bytes. The ANSI code page isn't known here; the
+// paths that matter are ASCII, and newer shortcuts store UTF-16 besides.
+fn ansi_until_nul(bytes: &[u8]) -> String {
+    let end = bytes.iter().position(|&b| b == 0).unwrap_or(bytes.len());
+    bytes[..end].iter().map(|&b| b as char).collect()
+}
+
+fn utf16_until_nul(bytes: &[u8]) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_le_bytes([pair[0], pair[1]]))
+        .take_while(|&unit| unit != 0)
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+// Little-endian reads that return None instead of running past the end
+struct Reader<'a> {
+    data: &'a [u8],
+    at: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn slice(&self, start: usize, length: usize) -> Option<&'a [u8]> {
+        self.data.get(start..start.checked_add(length)?)
+    }
+
+    fn skip(&mut self, length: usize) -> Option<()> {
+        self.slice(self.at, length)?;
+        self.at += length;
+        Some(())
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        let bytes = self.slice(self.at, 2)?;
+        self.at += 2;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        let bytes = self.slice(self.at, 4)?;
+        self.at += 4;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    // A StringData entry: a character count, then that many characters
+    fn counted_string(&mut self, unicode: bool) -> Option<String> {
+        let count = self.u16()? as usize;
+        if unicode {
+            let bytes = self.slice(self.at, count * 2)?;
+            self.at += count * 2;
+            Some(utf16_until_nul(bytes))
+        } else {
+            let bytes = self.slice(self.at, count)?;
+            self.at += count;
+            Some(ansi_until_nul(bytes))
+        }
+    }
+}
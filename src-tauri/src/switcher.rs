@@ -0,0 +1,261 @@
+// Switching to windows already open: the top-level windows on screen, listed as results
+// whose path is SCHEME followed by the system's id for the window, so that opening one
+// brings that window to the front instead of starting the program again.
+//
+// Windows lists them itself; macOS asks System Events (which needs the Accessibility
+// permission); Linux asks wmctrl, so only X11 windows (XWayland's included) are found.
+
+// Start of the path of a window result
+pub const SCHEME: &str = "window:";
+
+pub struct Window {
+    // The system's id for the window, valid while it's open
+    pub id: String,
+    pub title: String,
+    // Name of the program the window belongs to
+    pub program: String,
+}
+
+// The windows open now, the launcher's own left out
+pub fn list() -> Vec<Window> {
+    match system::list() {
+        Ok(windows) => windows,
+        Err(e) => {
+            log::debug!("Could not list windows: {}", e);
+            Vec::new()
+        }
+    }
+}
+
+// The path of the result for `window`
+pub fn path(window: &Window) -> String {
+    format!("{}{}", SCHEME, window.id)
+}
+
+// File name, without .exe, of the program running as `pid`
+#[cfg(windows)]
+pub fn program(pid: u32) -> Option<String> {
+    system::program(pid)
+}
+
+// Bring the window of the result at `path` to the front, restored if minimized
+pub fn activate(path: &str) -> Result<(), String> {
+    let id = path.strip_prefix(SCHEME).ok_or_else(|| format!("{} is not a window", path))?;
+    system::activate(id).map_err(|e| format!("Could not switch to the window: {}", e))
+}
+
+#[cfg(windows)]
+mod system {
+    use std::path::Path;
+
+    use windows_sys::Win32::Foundation::{CloseHandle, HWND, LPARAM};
+    use windows_sys::Win32::Graphics::Dwm::{DWMWA_CLOAKED, DwmGetWindowAttribute};
+    use windows_sys::Win32::System::Threading::{
+        GetCurrentProcessId, OpenProcess, PROCESS_NAME_WIN32, PROCESS_QUERY_LIMITED_INFORMATION, QueryFullProcessImageNameW,
+    };
+    use windows_sys::Win32::UI::WindowsAndMessaging::{
+        EnumWindows, GW_OWNER, GWL_EXSTYLE, GetWindow, GetWindowLongW, GetWindowTextLengthW, GetWindowTextW,
+        GetWindowThreadProcessId, IsIconic, IsWindow, IsWindowVisible, SW_RESTORE, SetForegroundWindow, ShowWindow,
+        WS_EX_TOOLWINDOW,
+    };
+
+    use super::Window;
+
+    pub fn list() -> Result<Vec<Window>, String> {
+        let mut handles: Vec<HWND> = Vec::new();
+        unsafe extern "system" fn collect(hwnd: HWND, handles: LPARAM) -> i32 {
+            (*(handles as *mut Vec<HWND>)).push(hwnd);
+            1
+        }
+        unsafe { EnumWindows(Some(collect), &mut handles as *mut Vec<HWND> as LPARAM) };
+
+        let own = unsafe { GetCurrentProcessId() };
+        let mut windows = Vec::new();
+        for hwnd in handles {
+            let mut pid = 0u32;
+            unsafe { GetWindowThreadProcessId(hwnd, &mut pid) };
+            if pid == own || !is_switchable(hwnd) {
+                continue;
+            }
+            let title = title(hwnd);
+            if title.is_empty() {
+                continue;
+            }
+            windows.push(Window { id: (hwnd as isize).to_string(), title, program: program(pid).unwrap_or_default() });
+        }
+        Ok(windows)
+    }
+
+    // What the taskbar would show: visible, unowned, not a tool window, not cloaked (a
+    // window on another virtual desktop or a suspended store app)
+    fn is_switchable(hwnd: HWND) -> bool {
+        let mut cloaked = 0u32;
+        unsafe {
+            IsWindowVisible(hwnd) != 0
+                && GetWindow(hwnd, GW_OWNER).is_null()
+                && GetWindowLongW(hwnd, GWL_EXSTYLE) as u32 & WS_EX_TOOLWINDOW == 0
+                && (DwmGetWindowAttribute(hwnd, DWMWA_CLOAKED as u32, (&mut cloaked as *mut u32).cast(), 4) != 0 || cloaked == 0)
+        }
+    }
+
+    fn title(hwnd: HWND) -> String {
+        let length = unsafe { GetWindowTextLengthW(hwnd) };
+        if length <= 0 {
+            return String::new();
+        }
+        let mut buffer = vec![0u16; length as usize + 1];
+        let copied = unsafe { GetWindowTextW(hwnd, buffer.as_mut_ptr(), buffer.len() as i32) };
+        String::from_utf16_lossy(&buffer[..copied.max(0) as usize])
+    }
+
+    pub fn program(pid: u32) -> Option<String> {
+        let process = unsafe { OpenProcess(PROCESS_QUERY_LIMITED_INFORMATION, 0, pid) };
+        if process.is_null() {
+            return None;
+        }
+        let mut buffer = vec![0u16; 1024];
+        let mut size = buffer.len() as u32;
+        let found = unsafe { QueryFullProcessImageNameW(process, PROCESS_NAME_WIN32, buffer.as_mut_ptr(), &mut size) };
+        unsafe { CloseHandle(process) };
+        if found == 0 {
+            return None;
+        }
+        let path = String::from_utf16_lossy(&buffer[..size as usize]);
+        Path::new(&path).file_stem().map(|stem| stem.to_string_lossy().into_owned())
+    }
+
+    pub fn activate(id: &str) -> Result<(), String> {
+        let hwnd = id.parse::<isize>().map_err(|e| e.to_string())? as HWND;
+        unsafe {
+            if IsWindow(hwnd) == 0 {
+                return Err("it has been closed".to_string());
+            }
+            if IsIconic(hwnd) != 0 {
+                ShowWindow(hwnd, SW_RESTORE);
+            }
+            // Allowed: the launcher, in front while the user picks, hands the foreground over
+            if SetForegroundWindow(hwnd) == 0 {
+                return Err("the system refused to bring it to the front".to_string());
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "macos")]
+mod system {
+    use std::process::Command;
+
+    use super::Window;
+
+    // One line per window: process id, program and title, separated by tabs
+    const LIST: &str = r#"
+        set found to ""
+        tell application "System Events"
+            repeat with p in (every process whose background only is false)
+                repeat with w in (every window of p)
+                    set found to found & (unix id of p) & tab & (name of p) & tab & (name of w) & linefeed
+                end repeat
+            end repeat
+        end tell
+        return found"#;
+
+    // Raise the window titled argv[2] of the process argv[1] and bring the process forward
+    const ACTIVATE: &str = r#"
+        on run argv
+            tell application "System Events"
+                tell (first process whose unix id is (item 1 of argv as integer))
+                    set frontmost to true
+                    perform action "AXRaise" of (first window whose name is (item 2 of argv))
+                end tell
+            end tell
+        end run"#;
+
+    pub fn list() -> Result<Vec<Window>, String> {
+        let output = Command::new("osascript").arg("-e").arg(LIST).output().map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        let own = std::process::id().to_string();
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.splitn(3, '\t');
+                let (pid, program, title) = (fields.next()?, fields.next()?, fields.next()?);
+                if pid == own || title.is_empty() {
+                    return None;
+                }
+                // The title is part of the id: System Events has no lasting id for a window
+                Some(Window { id: format!("{}\t{}", pid, title), title: title.to_string(), program: program.to_string() })
+            })
+            .collect())
+    }
+
+    pub fn activate(id: &str) -> Result<(), String> {
+        let (pid, title) = id.split_once('\t').ok_or_else(|| "unknown window".to_string())?;
+        let output = Command::new("osascript")
+            .arg("-e")
+            .arg(ACTIVATE)
+            .arg(pid)
+            .arg(title)
+            .output()
+            .map_err(|e| e.to_string())?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        Ok(())
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod system {
+    use std::process::Command;
+
+    use super::Window;
+
+    // `wmctrl -lp` prints a line per window: id, desktop, process id, host, title
+    pub fn list() -> Result<Vec<Window>, String> {
+        let output = Command::new("wmctrl").arg("-lp").output().map_err(|e| format!("wmctrl: {}", e))?;
+        if !output.status.success() {
+            return Err(String::from_utf8_lossy(&output.stderr).trim().to_string());
+        }
+        let own = std::process::id().to_string();
+        Ok(String::from_utf8_lossy(&output.stdout)
+            .lines()
+            .filter_map(|line| {
+                let mut fields = line.split_whitespace();
+                let (id, desktop, pid) = (fields.next()?, fields.next()?, fields.next()?);
+                fields.next()?;
+                let title = fields.collect::<Vec<_>>().join(" ");
+                // Desktop -1 holds panels and docks, on every desktop
+                if pid == own || desktop == "-1" || title.is_empty() {
+                    return None;
+                }
+                let program = std::fs::read_to_string(format!("/proc/{}/comm", pid)).unwrap_or_default().trim().to_string();
+                Some(Window { id: id.to_string(), title, program })
+            })
+            .collect())
+    }
+
+    pub fn activate(id: &str) -> Result<(), String> {
+        let status = Command::new("wmctrl").args(["-i", "-a", id]).status().map_err(|e| format!("wmctrl: {}", e))?;
+        if !status.success() {
+            return Err("it has been closed".to_string());
+        }
+        Ok(())
+    }
+}
+
+// Other systems: no windows to switch to
+#[cfg(not(any(windows, target_os = "macos", target_os = "linux")))]
+mod system {
+    use super::Window;
+
+    pub fn list() -> Result<Vec<Window>, String> {
+        Ok(Vec::new())
+    }
+
+    pub fn activate(_id: &str) -> Result<(), String> {
+        Err("switching windows isn't supported on this system".to_string())
+    }
+}
@@ -0,0 +1,362 @@
+// Emoji and special characters by name, for the ones a keyboard doesn't have: typing
+// ":shrug" (Slack and GitHub style) or "em dash" offers the character to copy. The tables
+// are built in; each entry has its Unicode name, shortened where that's clearer, and the
+// other names people know it by.
+
+pub struct Symbol {
+    pub text: &'static str,
+    pub name: &'static str,
+    // Other names, space separated; shortcodes without their colons
+    pub aliases: &'static str,
+}
+
+const fn symbol(text: &'static str, name: &'static str, aliases: &'static str) -> Symbol {
+    Symbol { text, name, aliases }
+}
+
+// Characters, which a query that is one's name finds without the prefix too
+const CHARACTERS: &[Symbol] = &[
+    // Punctuation and typography
+    symbol("—", "Em dash", "mdash dash"),
+    symbol("–", "En dash", "ndash range"),
+    symbol("−", "Minus sign", "minus"),
+    symbol("…", "Ellipsis", "hellip dots"),
+    symbol("•", "Bullet", "bull"),
+    symbol("·", "Middle dot", "middot interpunct"),
+    symbol("“", "Left double quotation mark", "ldquo quote"),
+    symbol("”", "Right double quotation mark", "rdquo quote"),
+    symbol("‘", "Left single quotation mark", "lsquo quote"),
+    symbol("’", "Right single quotation mark", "rsquo apostrophe"),
+    symbol("«", "Left guillemet", "laquo quote"),
+    symbol("»", "Right guillemet", "raquo quote"),
+    symbol("„", "Low double quotation mark", "bdquo quote"),
+    symbol("¡", "Inverted exclamation mark", "iexcl"),
+    symbol("¿", "Inverted question mark", "iquest"),
+    symbol("§", "Section sign", "section sect"),
+    symbol("¶", "Pilcrow", "paragraph para"),
+    symbol("†", "Dagger", "obelus"),
+    symbol("‡", "Double dagger", "diesis"),
+    symbol("‰", "Per mille sign", "permille"),
+    symbol("′", "Prime", "minutes feet"),
+    symbol("″", "Double prime", "seconds inches"),
+    symbol("©", "Copyright sign", "copyright copy"),
+    symbol("®", "Registered sign", "registered reg"),
+    symbol("™", "Trade mark sign", "trademark tm"),
+    symbol("°", "Degree sign", "degree deg"),
+    symbol("№", "Numero sign", "number"),
+    symbol("\u{a0}", "No-break space", "nbsp nonbreaking space"),
+    symbol("\u{200b}", "Zero width space", "zwsp"),
+    // Math
+    symbol("±", "Plus-minus sign", "plusminus pm"),
+    symbol("×", "Multiplication sign", "times multiply"),
+    symbol("÷", "Division sign", "divide"),
+    symbol("≠", "Not equal to", "ne notequal"),
+    symbol("≈", "Almost equal to", "approx approximately"),
+    symbol("≤", "Less-than or equal to", "le lessequal"),
+    symbol("≥", "Greater-than or equal to", "ge greaterequal"),
+    symbol("∞", "Infinity", "infin"),
+    symbol("√", "Square root", "sqrt radical"),
+    symbol("∑", "N-ary summation", "sum sigma"),
+    symbol("∏", "N-ary product", "product"),
+    symbol("∫", "Integral", "int"),
+    symbol("∂", "Partial differential", "partial"),
+    symbol("∆", "Increment", "delta change"),
+    symbol("∇", "Nabla", "del gradient"),
+    symbol("∈", "Element of", "in isin"),
+    symbol("∉", "Not an element of", "notin"),
+    symbol("∅", "Empty set", "empty"),
+    symbol("∩", "Intersection", "cap"),
+    symbol("∪", "Union", "cup"),
+    symbol("⊂", "Subset of", "subset"),
+    symbol("∀", "For all", "forall"),
+    symbol("∃", "There exists", "exists"),
+    symbol("¬", "Not sign", "not negation"),
+    symbol("∧", "Logical and", "and wedge"),
+    symbol("∨", "Logical or", "or vee"),
+    symbol("⊕", "Circled plus", "xor oplus"),
+    symbol("∝", "Proportional to", "prop"),
+    symbol("∴", "Therefore", "there4"),
+    symbol("½", "Vulgar fraction one half", "half"),
+    symbol("⅓", "Vulgar fraction one third", "third"),
+    symbol("¼", "Vulgar fraction one quarter", "quarter"),
+    symbol("¾", "Vulgar fraction three quarters", "threequarters"),
+    symbol("²", "Superscript two", "squared sup2"),
+    symbol("³", "Superscript three", "cubed sup3"),
+    // Greek letters
+    symbol("α", "Greek small letter alpha", "alpha"),
+    symbol("β", "Greek small letter beta", "beta"),
+    symbol("γ", "Greek small letter gamma", "gamma"),
+    symbol("δ", "Greek small letter delta", "delta"),
+    symbol("ε", "Greek small letter epsilon", "epsilon"),
+    symbol("θ", "Greek small letter theta", "theta"),
+    symbol("λ", "Greek small letter lambda", "lambda"),
+    symbol("μ", "Greek small letter mu", "mu micro"),
+    symbol("π", "Greek small letter pi", "pi"),
+    symbol("σ", "Greek small letter sigma", "sigma"),
+    symbol("τ", "Greek small letter tau", "tau"),
+    symbol("φ", "Greek small letter phi", "phi"),
+    symbol("ω", "Greek small letter omega", "omega"),
+    symbol("Δ", "Greek capital letter delta", "delta"),
+    symbol("Σ", "Greek capital letter sigma", "sigma"),
+    symbol("Ω", "Greek capital letter omega", "omega ohm"),
+    // Currency
+    symbol("€", "Euro sign", "euro eur"),
+    symbol("£", "Pound sign", "pound gbp sterling"),
+    symbol("¥", "Yen sign", "yen jpy yuan"),
+    symbol("¢", "Cent sign", "cent"),
+    symbol("₹", "Indian rupee sign", "rupee inr"),
+    symbol("₽", "Ruble sign", "ruble rub"),
+    symbol("₩", "Won sign", "won krw"),
+    symbol("₺", "Turkish lira sign", "lira try"),
+    symbol("₿", "Bitcoin sign", "bitcoin btc"),
+    // Arrows and keys
+    symbol("→", "Rightwards arrow", "rarr right arrow"),
+    symbol("←", "Leftwards arrow", "larr left arrow"),
+    symbol("↑", "Upwards arrow", "uarr up arrow"),
+    symbol("↓", "Downwards arrow", "darr down arrow"),
+    symbol("↔", "Left right arrow", "harr"),
+    symbol("⇒", "Rightwards double arrow", "implies rArr"),
+    symbol("⇔", "Left right double arrow", "iff hArr"),
+    symbol("↩", "Leftwards arrow with hook", "return"),
+    symbol("⌘", "Place of interest sign", "command cmd"),
+    symbol("⌥", "Option key", "option alt"),
+    symbol("⇧", "Upwards white arrow", "shift"),
+    symbol("⌃", "Up arrowhead", "control ctrl"),
+    symbol("⏎", "Return symbol", "enter"),
+    symbol("⌫", "Erase to the left", "backspace delete"),
+    symbol("⎋", "Broken circle with northwest arrow", "escape esc"),
+    symbol("⇥", "Rightwards arrow to bar", "tab"),
+    // Marks and shapes
+    symbol("✓", "Check mark", "check tick"),
+    symbol("✗", "Ballot X", "cross x"),
+    symbol("★", "Black star", "star filled"),
+    symbol("☆", "White star", "star outline"),
+    symbol("♥", "Black heart suit", "heart"),
+    symbol("♠", "Black spade suit", "spade"),
+    symbol("♦", "Black diamond suit", "diamond"),
+    symbol("♣", "Black club suit", "club"),
+    symbol("■", "Black square", "square"),
+    symbol("□", "White square", "square"),
+    symbol("●", "Black circle", "circle dot"),
+    symbol("○", "White circle", "circle"),
+    symbol("▲", "Black up-pointing triangle", "triangle up"),
+    symbol("▼", "Black down-pointing triangle", "triangle down"),
+    symbol("♪", "Eighth note", "note music"),
+    symbol("☐", "Ballot box", "checkbox"),
+    symbol("☑", "Ballot box with check", "checkbox checked"),
+    // Text faces
+    symbol("¯\\_(ツ)_/¯", "Shrug", "shrug kaomoji"),
+    symbol("(╯°□°)╯︵ ┻━┻", "Table flip", "tableflip flip"),
+    symbol("┬─┬ノ( º _ ºノ)", "Put the table back", "unflip"),
+    symbol("( ͡° ͜ʖ ͡°)", "Lenny face", "lenny"),
+    symbol("ಠ_ಠ", "Look of disapproval", "disapproval lod"),
+    symbol("(ノಠ益ಠ)ノ", "Rage face", "rage"),
+    symbol("ʕ•ᴥ•ʔ", "Bear face", "bear"),
+];
+
+// Emoji, found with the prefix only: their names are everyday words ("rocket", "key")
+const EMOJI: &[Symbol] = &[
+    // Faces
+    symbol("😀", "Grinning face", "grinning smile"),
+    symbol("😃", "Grinning face with big eyes", "smiley"),
+    symbol("😄", "Grinning face with smiling eyes", "smile"),
+    symbol("😁", "Beaming face", "grin"),
+    symbol("😆", "Grinning squinting face", "laughing satisfied"),
+    symbol("😅", "Grinning face with sweat", "sweat_smile"),
+    symbol("😂", "Face with tears of joy", "joy lol"),
+    symbol("🤣", "Rolling on the floor laughing", "rofl"),
+    symbol("🙂", "Slightly smiling face", "slightly_smiling_face"),
+    symbol("🙃", "Upside-down face", "upside_down_face"),
+    symbol("😉", "Winking face", "wink"),
+    symbol("😊", "Smiling face with smiling eyes", "blush"),
+    symbol("😇", "Smiling face with halo", "innocent angel"),
+    symbol("🥰", "Smiling face with hearts", "smiling_face_with_three_hearts"),
+    symbol("😍", "Smiling face with heart-eyes", "heart_eyes love"),
+    symbol("😘", "Face blowing a kiss", "kissing_heart kiss"),
+    symbol("😋", "Face savoring food", "yum"),
+    symbol("😛", "Face with tongue", "stuck_out_tongue"),
+    symbol("😜", "Winking face with tongue", "stuck_out_tongue_winking_eye"),
+    symbol("🤪", "Zany face", "zany_face crazy"),
+    symbol("🤔", "Thinking face", "thinking hmm"),
+    symbol("🤨", "Face with raised eyebrow", "raised_eyebrow"),
+    symbol("😐", "Neutral face", "neutral_face"),
+    symbol("😑", "Expressionless face", "expressionless"),
+    symbol("😶", "Face without mouth", "no_mouth"),
+    symbol("🙄", "Face with rolling eyes", "roll_eyes eyeroll"),
+    symbol("😏", "Smirking face", "smirk"),
+    symbol("😬", "Grimacing face", "grimacing"),
+    symbol("🤐", "Zipper-mouth face", "zipper_mouth_face"),
+    symbol("🤫", "Shushing face", "shushing_face shh"),
+    symbol("🤭", "Face with hand over mouth", "hand_over_mouth oops"),
+    symbol("😌", "Relieved face", "relieved"),
+    symbol("😔", "Pensive face", "pensive"),
+    symbol("😴", "Sleeping face", "sleeping"),
+    symbol("🥱", "Yawning face", "yawning_face tired"),
+    symbol("😷", "Face with medical mask", "mask"),
+    symbol("🤒", "Face with thermometer", "face_with_thermometer sick"),
+    symbol("🤢", "Nauseated face", "nauseated_face"),
+    symbol("🤮", "Face vomiting", "vomiting_face"),
+    symbol("🥵", "Hot face", "hot_face"),
+    symbol("🥶", "Cold face", "cold_face"),
+    symbol("🤯", "Exploding head", "exploding_head mindblown"),
+    symbol("🥳", "Partying face", "partying_face party"),
+    symbol("😎", "Smiling face with sunglasses", "sunglasses cool"),
+    symbol("🤓", "Nerd face", "nerd_face"),
+    symbol("🧐", "Face with monocle", "monocle_face"),
+    symbol("😕", "Confused face", "confused"),
+    symbol("😟", "Worried face", "worried"),
+    symbol("🙁", "Slightly frowning face", "slightly_frowning_face"),
+    symbol("😮", "Face with open mouth", "open_mouth wow"),
+    symbol("😲", "Astonished face", "astonished"),
+    symbol("😳", "Flushed face", "flushed"),
+    symbol("🥺", "Pleading face", "pleading_face puppy"),
+    symbol("😢", "Crying face", "cry"),
+    symbol("😭", "Loudly crying face", "sob"),
+    symbol("😱", "Face screaming in fear", "scream"),
+    symbol("😩", "Weary face", "weary"),
+    symbol("😤", "Face with steam from nose", "triumph huff"),
+    symbol("😡", "Pouting face", "rage angry"),
+    symbol("😠", "Angry face", "angry"),
+    symbol("🤬", "Face with symbols on mouth", "cursing_face"),
+    symbol("😈", "Smiling face with horns", "smiling_imp devil"),
+    symbol("💀", "Skull", "skull dead"),
+    symbol("💩", "Pile of poo", "poop hankey"),
+    symbol("🤡", "Clown face", "clown_face"),
+    symbol("👻", "Ghost", "ghost"),
+    symbol("👽", "Alien", "alien"),
+    symbol("🤖", "Robot", "robot bot"),
+    symbol("🙈", "See-no-evil monkey", "see_no_evil"),
+    symbol("🙉", "Hear-no-evil monkey", "hear_no_evil"),
+    symbol("🙊", "Speak-no-evil monkey", "speak_no_evil"),
+    // People and gestures
+    symbol("🤷", "Person shrugging", "shrug"),
+    symbol("🤦", "Person facepalming", "facepalm"),
+    symbol("🙇", "Person bowing", "bow"),
+    symbol("🙋", "Person raising hand", "raising_hand"),
+    symbol("🙏", "Folded hands", "pray please thanks"),
+    symbol("👍", "Thumbs up", "thumbsup +1 yes"),
+    symbol("👎", "Thumbs down", "thumbsdown -1 no"),
+    symbol("👌", "OK hand", "ok_hand"),
+    symbol("✌️", "Victory hand", "v peace"),
+    symbol("🤞", "Crossed fingers", "crossed_fingers luck"),
+    symbol("🤘", "Sign of the horns", "metal"),
+    symbol("👋", "Waving hand", "wave hello bye"),
+    symbol("👏", "Clapping hands", "clap"),
+    symbol("🙌", "Raising hands", "raised_hands hooray"),
+    symbol("👐", "Open hands", "open_hands"),
+    symbol("🤝", "Handshake", "handshake deal"),
+    symbol("💪", "Flexed biceps", "muscle strong"),
+    symbol("👉", "Backhand index pointing right", "point_right"),
+    symbol("👈", "Backhand index pointing left", "point_left"),
+    symbol("👆", "Backhand index pointing up", "point_up_2"),
+    symbol("👇", "Backhand index pointing down", "point_down"),
+    symbol("✋", "Raised hand", "hand raised_hand stop"),
+    symbol("🖕", "Middle finger", "middle_finger fu"),
+    symbol("👀", "Eyes", "eyes look"),
+    symbol("🧠", "Brain", "brain"),
+    // Hearts and symbols
+    symbol("❤️", "Red heart", "heart love"),
+    symbol("🧡", "Orange heart", "orange_heart"),
+    symbol("💛", "Yellow heart", "yellow_heart"),
+    symbol("💚", "Green heart", "green_heart"),
+    symbol("💙", "Blue heart", "blue_heart"),
+    symbol("💜", "Purple heart", "purple_heart"),
+    symbol("🖤", "Black heart", "black_heart"),
+    symbol("💔", "Broken heart", "broken_heart"),
+    symbol("💯", "Hundred points", "100 perfect"),
+    symbol("💥", "Collision", "boom"),
+    symbol("💤", "Zzz", "zzz sleep"),
+    symbol("💬", "Speech balloon", "speech_balloon comment"),
+    symbol("✅", "Check mark button", "white_check_mark done"),
+    symbol("❌", "Cross mark", "x no"),
+    symbol("❓", "Red question mark", "question"),
+    symbol("❗", "Red exclamation mark", "exclamation heavy_exclamation_mark"),
+    symbol("⚠️", "Warning", "warning"),
+    symbol("🚫", "Prohibited", "no_entry_sign forbidden"),
+    symbol("⛔", "No entry", "no_entry"),
+    symbol("🔴", "Red circle", "red_circle"),
+    symbol("🟢", "Green circle", "green_circle"),
+    symbol("🔵", "Blue circle", "large_blue_circle"),
+    // Nature, food, objects
+    symbol("🔥", "Fire", "fire lit"),
+    symbol("✨", "Sparkles", "sparkles"),
+    symbol("⭐", "Star", "star"),
+    symbol("🌟", "Glowing star", "star2"),
+    symbol("⚡", "High voltage", "zap lightning"),
+    symbol("☀️", "Sun", "sunny"),
+    symbol("🌙", "Crescent moon", "crescent_moon moon"),
+    symbol("🌈", "Rainbow", "rainbow"),
+    symbol("❄️", "Snowflake", "snowflake"),
+    symbol("☔", "Umbrella with rain drops", "umbrella rain"),
+    symbol("🌱", "Seedling", "seedling"),
+    symbol("🌲", "Evergreen tree", "evergreen_tree"),
+    symbol("🌸", "Cherry blossom", "cherry_blossom"),
+    symbol("🌹", "Rose", "rose"),
+    symbol("🐶", "Dog face", "dog"),
+    symbol("🐱", "Cat face", "cat"),
+    symbol("🐛", "Bug", "bug"),
+    symbol("🦀", "Crab", "crab rust ferris"),
+    symbol("🐍", "Snake", "snake python"),
+    symbol("🦄", "Unicorn", "unicorn"),
+    symbol("🍕", "Pizza", "pizza"),
+    symbol("🍔", "Hamburger", "hamburger burger"),
+    symbol("🍺", "Beer mug", "beer"),
+    symbol("🍻", "Clinking beer mugs", "beers cheers"),
+    symbol("☕", "Hot beverage", "coffee tea"),
+    symbol("🎂", "Birthday cake", "birthday cake"),
+    symbol("🍰", "Shortcake", "cake"),
+    symbol("🎉", "Party popper", "tada party"),
+    symbol("🎊", "Confetti ball", "confetti_ball"),
+    symbol("🎁", "Wrapped gift", "gift present"),
+    symbol("🏆", "Trophy", "trophy win"),
+    symbol("🥇", "First place medal", "1st_place_medal gold"),
+    symbol("🚀", "Rocket", "rocket ship launch"),
+    symbol("✈️", "Airplane", "airplane plane"),
+    symbol("🚗", "Automobile", "car red_car"),
+    symbol("🏠", "House", "house home"),
+    symbol("💡", "Light bulb", "bulb idea"),
+    symbol("💻", "Laptop", "computer laptop"),
+    symbol("⌨️", "Keyboard", "keyboard"),
+    symbol("📱", "Mobile phone", "iphone phone"),
+    symbol("📧", "E-mail", "email mail"),
+    symbol("📎", "Paperclip", "paperclip attachment"),
+    symbol("📌", "Pushpin", "pushpin pin"),
+    symbol("📝", "Memo", "memo pencil note"),
+    symbol("📅", "Calendar", "date calendar"),
+    symbol("📈", "Chart increasing", "chart_with_upwards_trend up"),
+    symbol("📉", "Chart decreasing", "chart_with_downwards_trend down"),
+    symbol("🔒", "Locked", "lock"),
+    symbol("🔑", "Key", "key"),
+    symbol("🔧", "Wrench", "wrench tool"),
+    symbol("🔨", "Hammer", "hammer"),
+    symbol("⚙️", "Gear", "gear settings"),
+    symbol("🧪", "Test tube", "test_tube test"),
+    symbol("🔍", "Magnifying glass tilted left", "mag search"),
+    symbol("⏰", "Alarm clock", "alarm_clock"),
+    symbol("⌛", "Hourglass done", "hourglass wait"),
+    symbol("💰", "Money bag", "moneybag money"),
+    symbol("🎵", "Musical note", "musical_note music"),
+    symbol("🚧", "Construction", "construction wip"),
+    symbol("🏁", "Chequered flag", "checkered_flag finish"),
+];
+
+// Every character and emoji
+pub fn all() -> impl Iterator<Item = &'static Symbol> {
+    CHARACTERS.iter().chain(EMOJI)
+}
+
+// The characters (not emoji) whose name `query` is, ignoring case and a closing "sign"
+// ("degree" for the degree sign). Other names are left to the prefix: many are words.
+pub fn named(query: &str) -> Vec<&'static Symbol> {
+    let query = query.trim().to_lowercase();
+    if query.is_empty() {
+        return Vec::new();
+    }
+    CHARACTERS
+        .iter()
+        .filter(|symbol| {
+            let name = symbol.name.to_lowercase();
+            name == query || name.strip_suffix(" sign") == Some(query.as_str())
+        })
+        .collect()
+}
@@ -0,0 +1,86 @@
+// The tray icon, which keeps the launcher reachable while its window is hidden: closing the
+// window only hides it, and Quit in the tray menu is what ends the app. A left click on
+// the icon shows or hides the window, as Show/Hide does.
+
+use tauri::menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem};
+use tauri::tray::{MouseButton, MouseButtonState, TrayIconBuilder, TrayIconEvent};
+use tauri::{Emitter, Manager};
+
+use crate::{control, hotkey};
+
+// Sent to the frontend to open its settings
+pub const SETTINGS_EVENT: &str = "open-settings";
+
+pub fn create(app: &tauri::AppHandle) -> tauri::Result<()> {
+    let paused = app.state::<control::Control>().is_paused();
+    let pause = CheckMenuItem::with_id(app, "pause", "Pause Indexing", true, paused, None::<&str>)?;
+    let menu = Menu::with_items(app, &[
+        &MenuItem::with_id(app, "toggle", "Show/Hide", true, None::<&str>)?,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, "rebuild", "Rebuild Index", true, None::<&str>)?,
+        &pause,
+        &PredefinedMenuItem::separator(app)?,
+        &MenuItem::with_id(app, "settings", "Settings", true, None::<&str>)?,
+        &MenuItem::with_id(app, "quit", "Quit", true, None::<&str>)?,
+    ])?;
+
+    let mut tray = TrayIconBuilder::with_id("main")
+        .tooltip("Speedy")
+        .menu(&menu)
+        .on_menu_event(move |app, event| match event.id.as_ref() {
+            "toggle" => toggle(app),
+            "rebuild" => crate::rebuild_index(app),
+            "pause" => {
+                let control = app.state::<control::Control>();
+                if control.is_paused() {
+                    control.resume();
+                } else {
+                    control.pause();
+                }
+                // The item checks itself when clicked; this keeps it true to the state
+                let _ = pause.set_checked(control.is_paused());
+            }
+            "settings" => {
+                show(app);
+                let _ = app.emit(SETTINGS_EVENT, ());
+            }
+            "quit" => app.exit(0),
+            _ => {}
+        })
+        .on_tray_icon_event(|tray, event| {
+            if let TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } = event {
+                toggle(tray.app_handle());
+            }
+        });
+    if let Some(icon) = app.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.build(app)?;
+    Ok(())
+}
+
+// Closing the window hides it to the tray instead of quitting
+pub fn on_window_event(window: &tauri::Window, event: &tauri::WindowEvent) {
+    if let tauri::WindowEvent::CloseRequested { api, .. } = event {
+        api.prevent_close();
+        let _ = window.hide();
+    }
+}
+
+fn toggle(app: &tauri::AppHandle) {
+    match app.get_webview_window("main") {
+        Some(window) if window.is_visible().unwrap_or(false) => {
+            let _ = window.hide();
+        }
+        Some(_) => show(app),
+        None => {}
+    }
+}
+
+fn show(app: &tauri::AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.show();
+        let _ = window.set_focus();
+        let _ = app.emit(hotkey::SHOWN_EVENT, ());
+    }
+}
@@ -0,0 +1,322 @@
+// On NTFS volumes the USN change journal lists every create, delete and rename. Remembering
+// where in the journal the last index of a root ended lets the next launch apply just the
+// changes made since, instead of walking the whole root again. Reading the journal needs
+// administrator rights; without them (or on other file systems, or when the journal was
+// reset or has wrapped past the remembered point) the root is walked as before.
+
+use std::path::{Path, PathBuf};
+
+use rusqlite::{Connection, OptionalExtension, params};
+
+// Create the table holding the remembered journal position of each indexed root
+pub fn create(conn: &Connection) -> Result<(), String> {
+    conn.execute_batch(
+        "CREATE TABLE IF NOT EXISTS usn_state (
+            root TEXT PRIMARY KEY,
+            journal_id INTEGER NOT NULL,
+            next_usn INTEGER NOT NULL
+        );"
+    ).map_err(|e| e.to_string())
+}
+
+// Where the journal of a root's volume currently ends
+#[derive(Debug, Clone, Copy)]
+#[cfg_attr(not(windows), allow(dead_code))]
+pub struct Position {
+    journal_id: u64,
+    next_usn: i64,
+}
+
+// The journal's current end for `root`, taken before a full walk so the changes made
+// during the walk are picked up next time. None where there is no readable journal.
+pub fn position(root: &Path) -> Option<Position> {
+    journal::Journal::open(root).map(|journal| journal.position())
+}
+
+// Remember `position` as the point `root` is indexed up to
+pub fn remember(conn: &Connection, root: &Path, position: Position) -> Result<(), String> {
+    conn.execute(
+        "INSERT OR REPLACE INTO usn_state (root, journal_id, next_usn) VALUES (?1, ?2, ?3)",
+        params![root.to_string_lossy(), position.journal_id as i64, position.next_usn],
+    ).map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Drop the remembered position of a root that is no longer indexed
+pub fn forget(conn: &Connection, root: &Path) -> Result<(), String> {
+    conn.execute("DELETE FROM usn_state WHERE root = ?1", params![root.to_string_lossy()])
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
+// Paths created, deleted or renamed below `root` since it was last indexed, and the
+// position to remember once they are applied. None when the changes can't be read and
+// the root has to be walked.
+pub fn changes_since_last_index(conn: &Connection, root: &Path) -> Option<(Vec<PathBuf>, Position)> {
+    let (journal_id, next_usn): (i64, i64) = conn.query_row(
+        "SELECT journal_id, next_usn FROM usn_state WHERE root = ?1",
+        params![root.to_string_lossy()],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().ok()??;
+
+    let journal = journal::Journal::open(root)?;
+    let position = journal.position();
+    if position.journal_id != journal_id as u64 || !journal.still_holds(next_usn) {
+        return None; // Recreated or wrapped: some changes are lost
+    }
+    let changed = journal.changed_paths(next_usn, position.next_usn)?;
+    Some((changed.into_iter().filter(|path| path.starts_with(root)).collect(), position))
+}
+
+#[cfg(windows)]
+mod journal {
+    use std::collections::HashMap;
+    use std::ffi::c_void;
+    use std::os::windows::ffi::{OsStrExt, OsStringExt};
+    use std::path::{Component, Path, PathBuf};
+
+    use windows_sys::Win32::Foundation::{CloseHandle, GENERIC_READ, HANDLE, INVALID_HANDLE_VALUE};
+    use windows_sys::Win32::Storage::FileSystem::{
+        CreateFileW, FileIdType, GetFinalPathNameByHandleW, GetVolumeInformationW, OpenFileById,
+        FILE_FLAG_BACKUP_SEMANTICS, FILE_ID_DESCRIPTOR, FILE_ID_DESCRIPTOR_0, FILE_NAME_NORMALIZED,
+        FILE_READ_ATTRIBUTES, FILE_SHARE_DELETE, FILE_SHARE_READ, FILE_SHARE_WRITE, OPEN_EXISTING,
+        VOLUME_NAME_DOS,
+    };
+    use windows_sys::Win32::System::IO::DeviceIoControl;
+    use windows_sys::Win32::System::Ioctl::{
+        FSCTL_QUERY_USN_JOURNAL, FSCTL_READ_USN_JOURNAL, READ_USN_JOURNAL_DATA_V0, USN_JOURNAL_DATA_V0,
+        USN_REASON_FILE_CREATE, USN_REASON_FILE_DELETE, USN_REASON_RENAME_NEW_NAME,
+        USN_REASON_RENAME_OLD_NAME, USN_RECORD_V2,
+    };
+
+    use super::Position;
+
+    // Journal records are read in chunks of this size
+    const BUFFER_SIZE: usize = 64 * 1024;
+
+    // Changes that add, remove or move an entry; content changes don't affect the index
+    const REASONS: u32 = USN_REASON_FILE_CREATE
+        | USN_REASON_FILE_DELETE
+        | USN_REASON_RENAME_OLD_NAME
+        | USN_REASON_RENAME_NEW_NAME;
+
+    pub struct Journal {
+        volume: HANDLE,
+        data: USN_JOURNAL_DATA_V0,
+    }
+
+    impl Journal {
+        // The journal of the NTFS volume holding `root`
+        pub fn open(root: &Path) -> Option<Journal> {
+            let Some(Component::Prefix(prefix)) = root.components().next() else {
+                return None;
+            };
+            let drive = prefix.as_os_str().to_string_lossy().trim_start_matches(r"\\?\").to_string();
+            if !is_ntfs(&format!("{}\\", drive)) {
+                return None;
+            }
+
+            let device = wide(&format!(r"\\.\{}", drive));
+            let volume = unsafe {
+                CreateFileW(
+                    device.as_ptr(),
+                    GENERIC_READ,
+                    FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                    std::ptr::null(),
+                    OPEN_EXISTING,
+                    0,
+                    std::ptr::null_mut(),
+                )
+            };
+            if volume == INVALID_HANDLE_VALUE {
+                return None; // Usually: not running as administrator
+            }
+            // Closed again by Drop if the journal can't be queried
+            let mut journal = Journal { volume, data: USN_JOURNAL_DATA_V0::default() };
+
+            let mut returned = 0u32;
+            let ok = unsafe {
+                DeviceIoControl(
+                    journal.volume,
+                    FSCTL_QUERY_USN_JOURNAL,
+                    std::ptr::null(),
+                    0,
+                    (&mut journal.data as *mut USN_JOURNAL_DATA_V0).cast::<c_void>(),
+                    std::mem::size_of::<USN_JOURNAL_DATA_V0>() as u32,
+                    &mut returned,
+                    std::ptr::null_mut(),
+                )
+            };
+            (ok != 0).then_some(journal)
+        }
+
+        pub fn position(&self) -> Position {
+            Position { journal_id: self.data.UsnJournalID, next_usn: self.data.NextUsn }
+        }
+
+        // Whether records from `usn` on are still in the journal
+        pub fn still_holds(&self, usn: i64) -> bool {
+            usn >= self.data.FirstUsn && usn <= self.data.NextUsn
+        }
+
+        // Paths of the entries created, deleted or renamed (both names) between two
+        // journal positions
+        pub fn changed_paths(&self, from: i64, to: i64) -> Option<Vec<PathBuf>> {
+            let mut parents: HashMap<u64, Option<PathBuf>> = HashMap::new();
+            let mut paths = Vec::new();
+            let mut buffer = vec![0u64; BUFFER_SIZE / 8]; // u64s keep the records aligned
+            let mut request = READ_USN_JOURNAL_DATA_V0 {
+                StartUsn: from,
+                ReasonMask: REASONS,
+                ReturnOnlyOnClose: 0,
+                Timeout: 0,
+                BytesToWaitFor: 0,
+                UsnJournalID: self.data.UsnJournalID,
+            };
+
+            while request.StartUsn < to {
+                let mut returned = 0u32;
+                let ok = unsafe {
+                    DeviceIoControl(
+                        self.volume,
+                        FSCTL_READ_USN_JOURNAL,
+                        (&request as *const READ_USN_JOURNAL_DATA_V0).cast::<c_void>(),
+                        std::mem::size_of::<READ_USN_JOURNAL_DATA_V0>() as u32,
+                        buffer.as_mut_ptr().cast::<c_void>(),
+                        BUFFER_SIZE as u32,
+                        &mut returned,
+                        std::ptr::null_mut(),
+                    )
+                };
+                if ok == 0 {
+                    return None;
+                }
+                // The output starts with the USN to continue from, followed by records
+                let next_usn = buffer[0] as i64;
+                let bytes = buffer.as_ptr().cast::<u8>();
+                let mut offset = 8usize;
+                while offset < returned as usize {
+                    let record = unsafe { &*(bytes.add(offset) as *const USN_RECORD_V2) };
+                    if record.RecordLength == 0 {
+                        break;
+                    }
+                    if record.Usn < to && record.MajorVersion == 2 {
+                        let name = unsafe {
+                            let start = bytes.add(offset + record.FileNameOffset as usize) as *const u16;
+                            std::slice::from_raw_parts(start, record.FileNameLength as usize / 2)
+                        };
+                        let parent = parents
+                            .entry(record.ParentFileReferenceNumber)
+                            .or_insert_with(|| self.path_of(record.ParentFileReferenceNumber));
+                        if let Some(parent) = parent {
+                            paths.push(parent.join(std::ffi::OsString::from_wide(name)));
+                        }
+                    }
+                    offset += record.RecordLength as usize;
+                }
+                if next_usn <= request.StartUsn {
+                    break; // Nothing more to read
+                }
+                request.StartUsn = next_usn;
+            }
+            paths.sort();
+            paths.dedup();
+            Some(paths)
+        }
+
+        // Current path of the file or folder with reference number `id`
+        fn path_of(&self, id: u64) -> Option<PathBuf> {
+            let descriptor = FILE_ID_DESCRIPTOR {
+                dwSize: std::mem::size_of::<FILE_ID_DESCRIPTOR>() as u32,
+                Type: FileIdType,
+                Anonymous: FILE_ID_DESCRIPTOR_0 { FileId: id as i64 },
+            };
+            let handle = unsafe {
+                OpenFileById(
+                    self.volume,
+                    &descriptor,
+                    FILE_READ_ATTRIBUTES,
+                    FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE,
+                    std::ptr::null(),
+                    FILE_FLAG_BACKUP_SEMANTICS, // Needed to open folders
+                )
+            };
+            if handle == INVALID_HANDLE_VALUE {
+                return None; // Deleted since
+            }
+            let mut buffer = vec![0u16; 1024];
+            let len = unsafe {
+                GetFinalPathNameByHandleW(
+                    handle,
+                    buffer.as_mut_ptr(),
+                    buffer.len() as u32,
+                    FILE_NAME_NORMALIZED | VOLUME_NAME_DOS,
+                )
+            };
+            unsafe { CloseHandle(handle) };
+            if len == 0 || len as usize >= buffer.len() {
+                return None;
+            }
+            let path = String::from_utf16_lossy(&buffer[..len as usize]);
+            // Reported as \\?\C:\...; the index stores plain paths
+            Some(PathBuf::from(path.trim_start_matches(r"\\?\")))
+        }
+    }
+
+    impl Drop for Journal {
+        fn drop(&mut self) {
+            unsafe { CloseHandle(self.volume) };
+        }
+    }
+
+    fn is_ntfs(volume_root: &str) -> bool {
+        let root = wide(volume_root);
+        let mut name = [0u16; 32];
+        let ok = unsafe {
+            GetVolumeInformationW(
+                root.as_ptr(),
+                std::ptr::null_mut(),
+                0,
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                std::ptr::null_mut(),
+                name.as_mut_ptr(),
+                name.len() as u32,
+            )
+        };
+        let len = name.iter().position(|&c| c == 0).unwrap_or(name.len());
+        ok != 0 && String::from_utf16_lossy(&name[..len]).eq_ignore_ascii_case("ntfs")
+    }
+
+    fn wide(text: &str) -> Vec<u16> {
+        std::ffi::OsStr::new(text).encode_wide().chain(std::iter::once(0)).collect()
+    }
+}
+
+// Other systems have no change journal: roots are always walked
+#[cfg(not(windows))]
+mod journal {
+    use std::path::{Path, PathBuf};
+
+    use super::Position;
+
+    pub enum Journal {}
+
+    impl Journal {
+        pub fn open(_root: &Path) -> Option<Journal> {
+            None
+        }
+
+        pub fn position(&self) -> Position {
+            match *self {}
+        }
+
+        pub fn still_holds(&self, _usn: i64) -> bool {
+            match *self {}
+        }
+
+        pub fn changed_paths(&self, _from: i64, _to: i64) -> Option<Vec<PathBuf>> {
+            match *self {}
+        }
+    }
+}
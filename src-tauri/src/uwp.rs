@@ -0,0 +1,55 @@
+// Microsoft Store (UWP and other packaged) apps. They have no program file to find on
+// disk; they are listed in the AppsFolder shell namespace under their Application User
+// Model ID (AUMID), and started through that folder, as the Start menu does.
+
+use std::os::windows::process::CommandExt;
+use std::process::Command;
+
+use serde::Deserialize;
+
+// Prefix of the paths packaged apps are stored under in `applications`
+pub const APPS_FOLDER: &str = r"shell:AppsFolder\";
+
+// Keeps PowerShell from opening a console window
+const CREATE_NO_WINDOW: u32 = 0x0800_0000;
+
+#[derive(Deserialize)]
+struct StartApp {
+    #[serde(rename = "Name")]
+    name: String,
+    #[serde(rename = "AppID")]
+    app_id: String,
+}
+
+// The packaged apps on the Start menu, as (name, path to store and launch)
+pub fn installed() -> Result<Vec<(String, String)>, String> {
+    // Get-StartApps lists the AppsFolder; packaged apps are the ones whose AUMID has a
+    // "!" between package family and app. Desktop programs come from their shortcuts.
+    let output = Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            "[Console]::OutputEncoding = [Text.Encoding]::UTF8; \
+             @(Get-StartApps | Where-Object { $_.AppID -like '*!*' }) | ConvertTo-Json -Compress",
+        ])
+        .creation_flags(CREATE_NO_WINDOW)
+        .output()
+        .map_err(|e| e.to_string())?;
+    if !output.status.success() {
+        return Err(String::from_utf8_lossy(&output.stderr).into_owned());
+    }
+
+    let text = String::from_utf8_lossy(&output.stdout);
+    let text = text.trim();
+    if text.is_empty() {
+        return Ok(Vec::new());
+    }
+    // ConvertTo-Json writes a single app as an object rather than a list of one
+    let apps: Vec<StartApp> = if text.starts_with('[') {
+        serde_json::from_str(text).map_err(|e| e.to_string())?
+    } else {
+        vec![serde_json::from_str(text).map_err(|e| e.to_string())?]
+    };
+    Ok(apps.into_iter().map(|app| (app.name, format!("{}{}", APPS_FOLDER, app.app_id))).collect())
+}
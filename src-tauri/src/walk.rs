@@ -0,0 +1,141 @@
+// The file walk behind index_files: folders are read on a thread pool of the walk's own,
+// one top-level folder per task, and everything found goes through a bounded channel to
+// the single thread writing to the database, which adds it with multi-row INSERTs. Reading
+// the disk is what takes time, so it no longer waits for the writes and vice versa.
+// Walkers block on that channel while indexing is paused, which is why they don't use
+// rayon's global pool: searches run on that one and have to keep working meanwhile.
+
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+use std::time::UNIX_EPOCH;
+
+use crossbeam_channel::Sender;
+use rayon::prelude::*;
+use rayon::ThreadPool;
+use rusqlite::types::ToSql;
+use rusqlite::{Connection, params_from_iter};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::is_app_file;
+use crate::settings::Excludes;
+
+// Rows added per INSERT statement
+const ROWS_PER_INSERT: usize = 100;
+
+// Threads reading folders, made on the first walk; None when they could not be started
+static WALKERS: OnceLock<Option<ThreadPool>> = OnceLock::new();
+
+// An entry found by the walk, as stored
+pub struct Found {
+    pub path: String,
+    pub name: String,
+    pub is_file: bool,
+    pub is_app: bool,
+    pub size: Option<i64>,
+    pub modified: Option<i64>,
+}
+
+impl Found {
+    fn of(entry: &DirEntry) -> Found {
+        let is_file = entry.file_type().is_file();
+        // Windows has the metadata from reading the folder; elsewhere it costs a stat
+        let (size, modified) = entry.metadata().map_or((None, None), |meta| details(&meta));
+        Found {
+            path: entry.path().to_string_lossy().into_owned(),
+            name: entry.file_name().to_string_lossy().into_owned(),
+            is_file,
+            is_app: is_file && is_app_file(entry.path()),
+            size,
+            modified,
+        }
+    }
+}
+
+// The size (of files only) and the modification time, in seconds since 1970, as stored
+pub fn details(meta: &Metadata) -> (Option<i64>, Option<i64>) {
+    let size = meta.is_file().then(|| meta.len() as i64);
+    let modified = meta.modified().ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_secs() as i64);
+    (size, modified)
+}
+
+// Walk `root` down to `max_depth`, leaving out excluded entries, and send everything found.
+// Stops early once the receiving side hangs up.
+pub fn walk(root: &Path, max_depth: usize, excludes: &Excludes, found: Sender<Found>) {
+    // The root and its direct children here, the folders below them in parallel
+    let mut folders = Vec::new();
+    for entry in WalkDir::new(root)
+        .max_depth(1.min(max_depth))
+        .into_iter()
+        .filter_entry(|e| !excludes.matches(e.path()))
+        .filter_map(|e| e.ok())
+    {
+        if entry.depth() == 1 && entry.file_type().is_dir() {
+            folders.push(entry.path().to_path_buf());
+        }
+        if found.send(Found::of(&entry)).is_err() {
+            return;
+        }
+    }
+    if max_depth <= 1 {
+        return;
+    }
+
+    let walk_folder = |folder: &PathBuf| {
+        for entry in WalkDir::new(folder)
+            .min_depth(1)
+            .max_depth(max_depth - 1)
+            .into_iter()
+            .filter_entry(|e| !excludes.matches(e.path()))
+            .filter_map(|e| e.ok())
+        {
+            if found.send(Found::of(&entry)).is_err() {
+                return;
+            }
+        }
+    };
+    match walkers() {
+        Some(pool) => pool.install(|| folders.par_iter().for_each(walk_folder)),
+        // One folder after the other on this thread, which is slower but still complete
+        None => folders.iter().for_each(walk_folder),
+    }
+}
+
+fn walkers() -> Option<&'static ThreadPool> {
+    WALKERS
+        .get_or_init(|| {
+            rayon::ThreadPoolBuilder::new()
+                .thread_name(|i| format!("index-walker-{}", i))
+                .build()
+                .map_err(|e| log::warn!("Could not start the indexing threads: {}", e))
+                .ok()
+        })
+        .as_ref()
+}
+
+// Add or update the rows of `found`
+pub fn insert(conn: &Connection, found: &[Found]) -> Result<(), String> {
+    for chunk in found.chunks(ROWS_PER_INSERT) {
+        // Statements are cached by their text, so there is one per chunk size in use
+        let mut stmt = conn.prepare_cached(&insert_sql(chunk.len())).map_err(|e| e.to_string())?;
+        let values = chunk
+            .iter()
+            .flat_map(|f| [&f.path as &dyn ToSql, &f.name, &f.is_file, &f.is_app, &f.size, &f.modified]);
+        stmt.execute(params_from_iter(values)).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
+
+fn insert_sql(rows: usize) -> String {
+    let values = vec!["(?, ?, ?, ?, ?, ?, strftime('%s','now'))"; rows].join(", ");
+    format!(
+        "INSERT INTO files (path, name, is_file, is_app, size, modified, last_accessed)
+         VALUES {}
+         ON CONFLICT(path) DO UPDATE SET
+             name = excluded.name, is_file = excluded.is_file, is_app = excluded.is_app,
+             size = excluded.size, modified = excluded.modified",
+        values
+    )
+}
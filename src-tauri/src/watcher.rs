@@ -0,0 +1,241 @@
+// Keeps the `files` table current between full indexing runs: watches the indexed roots
+// for created, renamed and deleted entries and applies them in batches. Events are
+// collected until the file system has been quiet for DEBOUNCE (or MAX_DELAY has passed),
+// so an unzip or a build writing thousands of files costs one transaction, not thousands.
+
+use std::collections::HashSet;
+use std::fs::Metadata;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::time::{Duration, Instant};
+
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use rusqlite::{Connection, OptionalExtension, params};
+use tauri::Manager;
+use walkdir::WalkDir;
+
+use crate::settings::{self, IndexConfig};
+use crate::{AppState, is_app_file, name_index, walk};
+
+// Quiet time that ends a batch of events
+const DEBOUNCE: Duration = Duration::from_millis(500);
+// Longest a change waits before it is applied, even while events keep coming
+const MAX_DELAY: Duration = Duration::from_secs(5);
+
+// The running watcher and the folders it watches, kept in app state so they can follow
+// the index configuration
+pub struct Watching(Mutex<(RecommendedWatcher, Vec<PathBuf>)>);
+
+// Watch the configured roots on a background thread for as long as the app runs. Entries
+// the configuration leaves out (too deep, excluded) are ignored, like the indexer ignores
+// them.
+pub fn start(app: tauri::AppHandle) {
+    let (tx, rx) = mpsc::channel();
+    let watcher = match notify::recommended_watcher(tx) {
+        Ok(watcher) => watcher,
+        Err(e) => {
+            log::warn!("File watching is unavailable, the index only updates on re-index: {}", e);
+            return;
+        }
+    };
+    app.manage(Watching(Mutex::new((watcher, Vec::new()))));
+    let roots = match app.state::<AppState>().db.lock() {
+        Ok(conn) => settings::load::<IndexConfig>(&conn, settings::INDEX_CONFIG).roots,
+        Err(_) => return,
+    };
+    watch(&app, &roots);
+
+    std::thread::spawn(move || {
+        loop {
+            // Wait for the first change, then gather everything that follows it closely
+            let mut changed = HashSet::new();
+            let Ok(first) = rx.recv() else {
+                return;
+            };
+            collect(first, &mut changed);
+            let started = Instant::now();
+            while started.elapsed() < MAX_DELAY {
+                match rx.recv_timeout(DEBOUNCE) {
+                    Ok(event) => collect(event, &mut changed),
+                    Err(RecvTimeoutError::Timeout) => break,
+                    Err(RecvTimeoutError::Disconnected) => return,
+                }
+            }
+            if changed.is_empty() {
+                continue;
+            }
+
+            let state = app.state::<AppState>();
+            let Ok(mut conn) = state.db.lock() else {
+                return;
+            };
+            let config: IndexConfig = settings::load(&conn, settings::INDEX_CONFIG);
+            match apply(&mut conn, &config, changed) {
+                Ok(applied) => log::debug!("Index updated from file events: {} entries", applied),
+                Err(e) => log::warn!("Could not update the index from file events: {}", e),
+            }
+        }
+    });
+}
+
+// Watch exactly `roots` from now on; nothing happens before the watcher has started
+pub fn watch(app: &tauri::AppHandle, roots: &[PathBuf]) {
+    let Some(watching) = app.try_state::<Watching>() else {
+        return;
+    };
+    let Ok(mut guard) = watching.0.lock() else {
+        return;
+    };
+    let (watcher, watched) = &mut *guard;
+    watched.retain(|root| {
+        if roots.contains(root) {
+            return true;
+        }
+        let _ = watcher.unwatch(root);
+        false
+    });
+    for root in roots {
+        if watched.contains(root) {
+            continue;
+        }
+        match watcher.watch(root, RecursiveMode::Recursive) {
+            Ok(()) => watched.push(root.clone()),
+            Err(e) => log::warn!("Could not watch {}: {}", root.display(), e),
+        }
+    }
+}
+
+// Paths touched by one event; opening or reading a file changes nothing in the index
+fn collect(event: notify::Result<notify::Event>, changed: &mut HashSet<PathBuf>) {
+    match event {
+        Ok(event) if !matches!(event.kind, EventKind::Access(_)) => changed.extend(event.paths),
+        Ok(_) => {}
+        Err(e) => log::debug!("File watcher error: {}", e),
+    }
+}
+
+// Bring the rows for `changed` paths in line with the disk in one transaction. A path
+// that is gone is removed with everything below it (a deleted or renamed-away folder);
+// a path that exists is added or updated, and a folder new to the index is walked so its
+// contents are added too. Also used for the changes read from the NTFS change journal.
+pub fn apply(conn: &mut Connection, config: &IndexConfig, changed: HashSet<PathBuf>) -> Result<usize, String> {
+    let excludes = config.excludes();
+    let tx = conn.transaction().map_err(|e| e.to_string())?;
+    let mut applied = 0;
+    for path in changed {
+        let Some(depth) = config.depth(&path) else {
+            continue;
+        };
+        if depth > config.max_depth || excludes.matches(&path) {
+            continue;
+        }
+        match std::fs::symlink_metadata(&path) {
+            Ok(meta) => {
+                let added = upsert(&tx, &path, &meta)?;
+                applied += 1;
+                if added && meta.is_dir() {
+                    for entry in WalkDir::new(&path)
+                        .min_depth(1)
+                        .max_depth(config.max_depth - depth)
+                        .into_iter()
+                        .filter_entry(|e| !excludes.matches(e.path()))
+                        .filter_map(|e| e.ok())
+                    {
+                        let Ok(meta) = entry.metadata() else {
+                            continue;
+                        };
+                        upsert(&tx, entry.path(), &meta)?;
+                        applied += 1;
+                    }
+                }
+            }
+            Err(_) => applied += remove(&tx, &path)?,
+        }
+    }
+    if applied > 0 {
+        // Cached answers may list removed entries or miss new ones
+        tx.execute("DELETE FROM search_cache", []).map_err(|e| e.to_string())?;
+    }
+    tx.commit().map_err(|e| e.to_string())?;
+    Ok(applied)
+}
+
+// Add or update the row for `path`; true if it wasn't in the index yet
+fn upsert(conn: &Connection, path: &Path, meta: &Metadata) -> Result<bool, String> {
+    let text = path.to_string_lossy().into_owned();
+    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_else(|| text.clone());
+    let is_file = meta.is_file();
+    let is_app = is_file && is_app_file(path);
+    let (size, modified) = walk::details(meta);
+
+    let existing: Option<(i64, String)> = conn.query_row(
+        "SELECT id, name FROM files WHERE path = ?1",
+        params![text],
+        |row| Ok((row.get(0)?, row.get(1)?)),
+    ).optional().map_err(|e| e.to_string())?;
+
+    match existing {
+        Some((id, old_name)) => {
+            conn.execute(
+                "UPDATE files SET name = ?2, is_file = ?3, is_app = ?4, size = ?5, modified = ?6 WHERE id = ?1",
+                params![id, name, is_file, is_app, size, modified],
+            ).map_err(|e| e.to_string())?;
+            if old_name != name {
+                name_index::forget(conn, "files", id, &old_name)?;
+                name_index::add(conn, "files", id, &name)?;
+            }
+            Ok(false)
+        }
+        None => {
+            conn.execute(
+                "INSERT INTO files (path, name, is_file, is_app, size, modified, last_accessed)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, strftime('%s','now'))",
+                params![text, name, is_file, is_app, size, modified],
+            ).map_err(|e| e.to_string())?;
+            name_index::add(conn, "files", conn.last_insert_rowid(), &name)?;
+            Ok(true)
+        }
+    }
+}
+
+// Remove the row for `path` and the rows of everything below it; returns how many
+pub fn remove(conn: &Connection, path: &Path) -> Result<usize, String> {
+    let (text, first, end) = below(path);
+    let mut stmt = conn.prepare(
+        "SELECT id, name FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)"
+    ).map_err(|e| e.to_string())?;
+    let rows = stmt
+        .query_map(params![text, first, end], |row| {
+            Ok((row.get::<_, i64>(0)?, row.get::<_, String>(1)?))
+        })
+        .map_err(|e| e.to_string())?
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| e.to_string())?;
+
+    for (id, name) in &rows {
+        name_index::forget(conn, "files", *id, name)?;
+        conn.execute("DELETE FROM files WHERE id = ?1", params![id]).map_err(|e| e.to_string())?;
+    }
+    Ok(rows.len())
+}
+
+// Number of rows for `path` and everything below it
+pub fn count_below(conn: &Connection, path: &Path) -> Result<usize, String> {
+    let (text, first, end) = below(path);
+    conn.query_row(
+        "SELECT COUNT(*) FROM files WHERE path = ?1 OR (path >= ?2 AND path < ?3)",
+        params![text, first, end],
+        |row| row.get(0),
+    ).map_err(|e| e.to_string())
+}
+
+// `path` as stored, and the bounds of the paths below it: those sort between
+// "<path><separator>" and "<path><separator + 1>", a range the index on `path` can answer
+fn below(path: &Path) -> (String, String, String) {
+    let text = path.to_string_lossy().into_owned();
+    let separator = std::path::MAIN_SEPARATOR;
+    let first = format!("{}{}", text, separator);
+    let end = format!("{}{}", text, char::from(separator as u8 + 1));
+    (text, first, end)
+}
@@ -0,0 +1,87 @@
+// Results that leave the computer: opening what the query names when it's a web address
+// ("github.com/tauri-apps", "https://…"), and searching the web for it with the search
+// engines in settings when nothing local matches.
+
+use serde::{Deserialize, Serialize};
+
+// Stands in for the query, URL-encoded, in a search engine's URL
+pub const QUERY: &str = "{query}";
+
+// Top-level domains a query without a scheme has to end in to be taken for an address,
+// so that "notes.txt" or "main.rs" stay file searches
+const DOMAINS: &[&str] = &[
+    "com", "org", "net", "io", "dev", "app", "edu", "gov", "info", "biz", "co", "me", "tv", "uk", "us", "ca", "de",
+    "fr", "nl", "se", "no", "dk", "fi", "es", "it", "ch", "at", "be", "pl", "ru", "jp", "cn", "in", "au", "nz",
+    "br", "eu", "xyz", "tech", "site", "online",
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SearchEngine {
+    pub name: String,
+    // Search page, with QUERY where the query goes
+    pub url: String,
+}
+
+impl SearchEngine {
+    pub fn validate(&self) -> Result<(), String> {
+        if self.name.trim().is_empty() {
+            return Err("A search engine needs a name".to_string());
+        }
+        if !is_web_url(&self.url) || !self.url.contains(QUERY) {
+            return Err(format!("The URL of {} has to be a web address containing {}", self.name, QUERY));
+        }
+        Ok(())
+    }
+
+    // The page of results for `query`
+    pub fn url_for(&self, query: &str) -> String {
+        self.url.replace(QUERY, &encode(query))
+    }
+}
+
+pub fn default_engines() -> Vec<SearchEngine> {
+    vec![SearchEngine { name: "Google".to_string(), url: format!("https://www.google.com/search?q={}", QUERY) }]
+}
+
+// Whether `path` is an http(s) address, opened in the browser rather than looked up
+pub fn is_web_url(path: &str) -> bool {
+    let lower = path.get(..8).unwrap_or(path).to_ascii_lowercase();
+    lower.starts_with("http://") || lower.starts_with("https://")
+}
+
+// The address `query` names, with a scheme added if it had none; None if it isn't one
+pub fn address(query: &str) -> Option<String> {
+    let query = query.trim();
+    if query.is_empty() || query.contains(char::is_whitespace) {
+        return None;
+    }
+    if is_web_url(query) {
+        return (query.len() > query.find("//")? + 2).then(|| query.to_string());
+    }
+    let host = query.split(['/', '?', '#']).next()?;
+    let host = host.rsplit_once(':').filter(|(_, port)| port.parse::<u16>().is_ok()).map_or(host, |(host, _)| host);
+    if host.eq_ignore_ascii_case("localhost") {
+        return Some(format!("http://{}", query));
+    }
+    let labels: Vec<&str> = host.split('.').collect();
+    let valid = labels.len() > 1
+        && labels.iter().all(|label| {
+            !label.is_empty()
+                && !label.starts_with('-')
+                && label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+        })
+        && DOMAINS.contains(&labels[labels.len() - 1].to_ascii_lowercase().as_str());
+    valid.then(|| format!("https://{}", query))
+}
+
+// `text` for a URL's query string: letters, digits and -_.~ as they are, the rest as %XX
+fn encode(text: &str) -> String {
+    let mut encoded = String::new();
+    for byte in text.trim().bytes() {
+        match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => encoded.push(byte as char),
+            _ => encoded.push_str(&format!("%{:02X}", byte)),
+        }
+    }
+    encoded
+}
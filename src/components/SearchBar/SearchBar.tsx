@@ -1,25 +1,53 @@
 import React, { useState, useRef, useEffect } from 'react';
 import { useKeyboardShortcut } from './useKeyboardShortcut';
-import { invoke } from '@tauri-apps/api/core';
+import { convertFileSrc, invoke } from '@tauri-apps/api/core';
+import { listen } from '@tauri-apps/api/event';
 import './SearchBar.css';
 import LightningIcon from './LightningIcon';
 
+// The settings read here; see `get_settings`
+type Settings = {
+  result_limit: number;
+  launch: { hide_after_open: boolean };
+};
+
 type SearchResult = {
   path: string;
   name: string;
-  type: 'file' | 'folder' | 'app';
+  // 'calc' is the answer to a sum or conversion, its value in `path`; 'web' opens the
+  // web address in `path` in the browser, as does 'bookmark'; 'window' switches to an
+  // open window; 'clip' is from the clipboard history, its path "clip:<id>"; 'command'
+  // runs the shell command in `path`; 'symbol' is an emoji or character, itself in `path`
+  type: 'file' | 'folder' | 'app' | 'calc' | 'web' | 'bookmark' | 'window' | 'clip' | 'command' | 'symbol';
   score?: number;
 };
 
+// What a command run with its output captured printed; see `run_command`
+type CommandOutput = {
+  exit_code: number | null;
+  stdout: string;
+  stderr: string;
+  timed_out: boolean;
+};
+
 const SearchBar: React.FC = () => {
   const [isVisible, setIsVisible] = useState(false);
   const [searchQuery, setSearchQuery] = useState('');
   const [results, setResults] = useState<SearchResult[]>([]);
   const [isSearching, setIsSearching] = useState(false);
   const [selectedIndex, setSelectedIndex] = useState(-1);
+  // Cached PNG icon of each app result, by path; null where the app has none
+  const [icons, setIcons] = useState<Record<string, string | null>>({});
+  // Output of the last command run with it captured, shown below the results
+  const [commandOutput, setCommandOutput] = useState<CommandOutput | null>(null);
   const searchInputRef = useRef<HTMLInputElement>(null);
   const resultsRef = useRef<HTMLDivElement>(null);
   const debounceTimer = useRef<number | null>(null);
+  const hasMore = useRef(false);
+  const loadingMore = useRef(false);
+  // Results per page, as returned by `search` and `search_more`
+  const pageSize = useRef(20);
+  const hideAfterOpen = useRef(true);
 
   const toggleVisibility = () => {
     setIsVisible(!isVisible);
@@ -30,12 +58,35 @@ const SearchBar: React.FC = () => {
 
   useKeyboardShortcut(' ', true, false, false, toggleVisibility);
 
+  // The global shortcut brought the window up: start from an empty search
+  useEffect(() => {
+    const unlisten = listen('launcher-shown', () => {
+      setIsVisible(true);
+      setSearchQuery('');
+      setSelectedIndex(-1);
+      searchInputRef.current?.focus();
+    });
+    return () => {
+      unlisten.then(stop => stop());
+    };
+  }, []);
+
   useEffect(() => {
     if (isVisible && searchInputRef.current) {
       searchInputRef.current.focus();
     }
+    // Settings may have changed while hidden
+    if (isVisible) {
+      invoke<Settings>('get_settings')
+        .then(settings => {
+          pageSize.current = settings.result_limit;
+          hideAfterOpen.current = settings.launch.hide_after_open;
+        })
+        .catch(error => console.error('Failed to load settings:', error));
+    }
   }, [isVisible]);
 
+  // Resolves to null when a newer search replaced this one; its results would be stale
   const performSearch = async (query: string) => {
     if (query.length < 2) return [];
     
@@ -44,6 +95,7 @@ const SearchBar: React.FC = () => {
         const results = await invoke<SearchResult[]>('search', { query });
         return results;
     } catch (error) {
+        if (String(error).includes('superseded')) return null;
         console.error('Search error:', error);
         return [];
     }
@@ -53,10 +105,18 @@ const SearchBar: React.FC = () => {
     if (debounceTimer.current) {
         clearTimeout(debounceTimer.current);
     }
+    setCommandOutput(null);
 
     if (searchQuery.trim() === '') {
         setResults([]);
         setSelectedIndex(-1);
+        hasMore.current = false;
+        // With nothing typed, show what was opened lately
+        if (isVisible) {
+            invoke<SearchResult[]>('get_recent', { limit: pageSize.current })
+                .then(setResults)
+                .catch(error => console.error('Failed to load recent items:', error));
+        }
         return;
     }
 
@@ -64,7 +124,10 @@ const SearchBar: React.FC = () => {
         setIsSearching(true);
         try {
             const searchResults = await performSearch(searchQuery);
-            setResults(searchResults);
+            if (searchResults !== null) {
+                setResults(searchResults);
+                hasMore.current = searchResults.length === pageSize.current;
+            }
         } catch (error) {
             console.error('Search failed:', error);
             setResults([]);
@@ -78,7 +141,16 @@ const SearchBar: React.FC = () => {
             clearTimeout(debounceTimer.current);
         }
     };
-  }, [searchQuery]);
+  }, [searchQuery, isVisible]);
+
+  useEffect(() => {
+    const missing = results.filter(result => result.type === 'app' && !(result.path in icons));
+    missing.forEach(result => {
+      invoke<string | null>('get_icon', { path: result.path })
+        .catch(() => null)
+        .then(icon => setIcons(prev => ({ ...prev, [result.path]: icon })));
+    });
+  }, [results]);
 
   useEffect(() => {
     const handleKeyDown = (event: KeyboardEvent) => {
@@ -92,8 +164,18 @@ const SearchBar: React.FC = () => {
       } else if (event.key === 'ArrowUp') {
         event.preventDefault();
         setSelectedIndex(prev => Math.max(prev - 1, -1));
+      } else if (event.key === 'Enter' && event.ctrlKey && !event.shiftKey && selectedIndex >= 0 && results[selectedIndex]
+          && ['file', 'folder', 'app'].includes(results[selectedIndex].type)) {
+        // Ctrl+Enter shows the selected result in its folder instead of opening it
+        handleReveal(results[selectedIndex]);
+      } else if (event.key === 'Enter' && event.shiftKey && !event.ctrlKey && selectedIndex >= 0 && results[selectedIndex]
+          && results[selectedIndex].type === 'command') {
+        // Shift+Enter runs the command hidden and shows what it printed
+        handleRunCommand(results[selectedIndex], true);
       } else if (event.key === 'Enter' && selectedIndex >= 0 && results[selectedIndex]) {
-        handleResultClick(results[selectedIndex]);
+        // Ctrl+Shift+Enter runs the selected app as administrator, as in the Start menu
+        const elevated = event.ctrlKey && event.shiftKey && results[selectedIndex].type === 'app';
+        handleResultClick(results[selectedIndex], elevated);
       }
     };
 
@@ -110,23 +192,98 @@ const SearchBar: React.FC = () => {
     }
   }, [selectedIndex]);
 
-  const handleResultClick = async (result: SearchResult) => {
+  // Fetch the next page once the list is scrolled near its end
+  const handleResultsScroll = async (event: React.UIEvent<HTMLDivElement>) => {
+    const list = event.currentTarget;
+    if (!hasMore.current || loadingMore.current) return;
+    if (list.scrollTop + list.clientHeight < list.scrollHeight - 40) return;
+
+    loadingMore.current = true;
+    try {
+        const more = await invoke<SearchResult[]>('search_more', { query: searchQuery, offset: results.length });
+        hasMore.current = more.length === pageSize.current;
+        setResults(prev => [...prev, ...more]);
+    } catch (error) {
+        if (!String(error).includes('superseded')) {
+            console.error('Search error:', error);
+        }
+    } finally {
+        loadingMore.current = false;
+    }
+  };
+
+  const handleResultClick = async (result: SearchResult, elevated = false) => {
+    // An answer or a character isn't opened; it's copied to paste elsewhere
+    if (result.type === 'calc' || result.type === 'symbol') {
+        try {
+            await invoke('copy_to_clipboard', { text: result.path });
+            if (hideAfterOpen.current) setIsVisible(false);
+        } catch (error) {
+            console.error('Failed to copy:', error);
+        }
+        return;
+    }
+    // A clip is pasted into the window the launcher was called up from
+    if (result.type === 'clip') {
+        try {
+            await invoke('paste_clip', { id: Number(result.path.slice('clip:'.length)) });
+            setIsVisible(false);
+        } catch (error) {
+            console.error('Failed to paste:', error);
+            alert(`Failed to paste: ${error}`);
+        }
+        return;
+    }
+    if (result.type === 'command') {
+        handleRunCommand(result);
+        return;
+    }
     try {
-        if (result.type === 'app') {
-            // For applications, use launch_app
-            await invoke('launch_app', { path: result.path });
+        // open_path launches apps too, and counts the use for ranking
+        if (elevated) {
+            await invoke('launch_app_with_args', { path: result.path, args: null, elevated: true });
         } else {
-            // For files/folders, use open_path
             await invoke('open_path', { path: result.path });
         }
-        setIsVisible(false);
+        if (hideAfterOpen.current) setIsVisible(false);
     } catch (error) {
         console.error('Failed to open:', error);
+        // A target that is gone has been removed from the index; drop it here too
+        if (String(error).includes('no longer exists')) {
+            setResults(results.filter(r => r.path !== result.path));
+            setSelectedIndex(-1);
+        }
         // Show error to user
         alert(`Failed to open: ${error}`);
     }
   };
 
+  // Run a command in a terminal, or with `capture` (or when the shell is set to run
+  // without one) hidden, its output shown in place
+  const handleRunCommand = async (result: SearchResult, capture?: boolean) => {
+    try {
+        const output = await invoke<CommandOutput | null>('run_command', { command: result.path, capture: capture ?? null });
+        if (output) {
+            setCommandOutput(output);
+        } else if (hideAfterOpen.current) {
+            setIsVisible(false);
+        }
+    } catch (error) {
+        console.error('Failed to run:', error);
+        alert(`Failed to run: ${error}`);
+    }
+  };
+
+  const handleReveal = async (result: SearchResult) => {
+    try {
+        await invoke('reveal_in_folder', { path: result.path });
+        setIsVisible(false);
+    } catch (error) {
+        console.error('Failed to show in folder:', error);
+        alert(`Failed to show in folder: ${error}`);
+    }
+  };
+
   return (
     <>      
       <div className={`search-container ${isVisible ? 'visible' : ''}`}>
@@ -158,6 +315,7 @@ const SearchBar: React.FC = () => {
           <div 
             className="search-results" 
             ref={resultsRef}
+            onScroll={handleResultsScroll}
             role="listbox"
             aria-label="Search results"
           >
@@ -173,12 +331,16 @@ const SearchBar: React.FC = () => {
                 aria-selected={selectedIndex === index}
               >
                 <div className="result-icon" aria-hidden="true">
-                  {getIconForType(result.type)}
+                  {result.type === 'symbol'
+                    ? <span className="symbol-glyph">{result.path}</span>
+                    : icons[result.path]
+                    ? <img src={convertFileSrc(icons[result.path]!)} alt="" />
+                    : getIconForType(result.type)}
                 </div>
                 <div className="result-details">
                   <div className="result-title">
                     {result.name}
-                    {result.score && (
+                    {result.score && result.type !== 'calc' && result.type !== 'web' && (
                       <span className="result-score">{Math.round(result.score * 100)}%</span>
                     )}
                   </div>
@@ -193,6 +355,17 @@ const SearchBar: React.FC = () => {
           </div>
         )}
 
+        {commandOutput && (
+          <div className="command-output" aria-label="Command output">
+            <div className="command-status">
+              {commandOutput.timed_out
+                ? 'Stopped: took too long'
+                : `Exited with ${commandOutput.exit_code ?? 'a signal'}`}
+            </div>
+            <pre>{commandOutput.stdout}{commandOutput.stderr && <span className="command-stderr">{commandOutput.stderr}</span>}</pre>
+          </div>
+        )}
+
         {!isSearching && results.length === 0 && searchQuery.length >= 2 && (
           <div className="search-result-item">
             No results found for "{searchQuery}"
@@ -203,7 +376,7 @@ const SearchBar: React.FC = () => {
   );
 };
 
-const getIconForType = (type: 'file' | 'folder' | 'app') => {
+const getIconForType = (type: SearchResult['type']) => {
   switch (type) {
     case 'file':
       return (
@@ -223,6 +396,42 @@ const getIconForType = (type: 'file' | 'folder' | 'app') => {
           <path d="M19 3H5c-1.1 0-2 .9-2 2v14c0 1.1.9 2 2 2h14c1.1 0 2-.9 2-2V5c0-1.1-.9-2-2-2zm0 16H5V5h14v14zM12 8c-1.1 0-2 .9-2 2s.9 2 2 2 2-.9 2-2-.9-2-2-2zm0 10c-2.2 0-4-1.8-4-4s1.8-4 4-4 4 1.8 4 4-1.8 4-4 4z" />
         </svg>
       );
+    case 'web':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M12 2C6.48 2 2 6.48 2 12s4.48 10 10 10 10-4.48 10-10S17.52 2 12 2zm-1 17.93c-3.95-.49-7-3.85-7-7.93 0-.62.08-1.21.21-1.79L9 15v1c0 1.1.9 2 2 2v1.93zm6.9-2.54c-.26-.81-1-1.39-1.9-1.39h-1v-3c0-.55-.45-1-1-1H8v-2h2c.55 0 1-.45 1-1V7h2c1.1 0 2-.9 2-2v-.41c2.93 1.19 5 4.06 5 7.41 0 2.08-.8 3.97-2.1 5.39z" />
+        </svg>
+      );
+    case 'bookmark':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M17 3H7c-1.1 0-1.99.9-1.99 2L5 21l7-3 7 3V5c0-1.1-.9-2-2-2zm0 15l-5-2.18L7 18V5h10v13z" />
+        </svg>
+      );
+    case 'window':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M19 4H5c-1.11 0-2 .9-2 2v12c0 1.1.89 2 2 2h14c1.1 0 2-.9 2-2V6c0-1.1-.89-2-2-2zm0 14H5V8h14v10z" />
+        </svg>
+      );
+    case 'clip':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M19 2h-4.18C14.4.84 13.3 0 12 0S9.6.84 9.18 2H5c-1.1 0-2 .9-2 2v16c0 1.1.9 2 2 2h14c1.1 0 2-.9 2-2V4c0-1.1-.9-2-2-2zm-7 0c.55 0 1 .45 1 1s-.45 1-1 1-1-.45-1-1 .45-1 1-1zm7 18H5V4h2v3h10V4h2v16z" />
+        </svg>
+      );
+    case 'command':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M20 4H4c-1.11 0-2 .9-2 2v12c0 1.1.89 2 2 2h16c1.1 0 2-.9 2-2V6c0-1.1-.89-2-2-2zm0 14H4V8h16v10zm-2-1h-6v-2h6v2zM7.5 17l-1.41-1.41L8.67 13l-2.59-2.59L7.5 9l4 4-4 4z" />
+        </svg>
+      );
+    case 'calc':
+      return (
+        <svg viewBox="0 0 24 24">
+          <path d="M19 3H5c-1.1 0-2 .9-2 2v14c0 1.1.9 2 2 2h14c1.1 0 2-.9 2-2V5c0-1.1-.9-2-2-2zm0 16H5V5h14v14zM6.25 7.72h5v1.5h-5zM13 15.75h5v1.5h-5zm0-2.5h5v1.5h-5zM8 18h1.5v-2h2v-1.5h-2v-2H8v2H6V16h2zm6.09-7.05l1.41-1.41 1.41 1.41 1.06-1.06-1.41-1.42 1.41-1.41L16.91 6 15.5 7.41 14.09 6l-1.06 1.06 1.41 1.41-1.41 1.42z" />
+        </svg>
+      );
   }
 };
 